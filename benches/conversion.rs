@@ -0,0 +1,68 @@
+//! Timings for unit lookup and conversion, printed as nanoseconds per call:
+//! `cargo bench`. Every case is checked against its expected result first,
+//! so a bench can't quietly end up timing an error path; `cargo test
+//! --benches` runs only those checks.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use converter::{Unit, convert, convert_units, find_unit};
+
+const BATCH: usize = 1_000_000;
+
+/// Runs `f` until about half a second has passed and prints the mean time per call.
+fn bench<T>(name: &str, mut f: impl FnMut() -> T) {
+    let mut calls = 1u64;
+    loop {
+        let start = Instant::now();
+        for _ in 0..calls {
+            black_box(f());
+        }
+        let elapsed = start.elapsed();
+        if elapsed.as_millis() >= 500 {
+            println!("{:<32} {:>12.1} ns/call", name, elapsed.as_nanos() as f64 / calls as f64);
+            return;
+        }
+        calls *= 2;
+    }
+}
+
+fn unit(name: &str) -> &'static Unit {
+    find_unit(name).unwrap()
+}
+
+/// Converts every value from km to mi, looking both units up once.
+fn batch_one_lookup(values: &[f64]) -> f64 {
+    let (km, mi) = (unit("km"), unit("mi"));
+    values.iter().map(|&v| convert_units(km, mi, v)).sum()
+}
+
+/// Converts every value from km to mi, looking the units up for each one.
+fn batch_per_item(values: &[f64]) -> f64 {
+    values.iter().map(|&v| convert(v, "km", "mi").unwrap()).sum()
+}
+
+fn check(values: &[f64]) {
+    assert_eq!(find_unit("km").map(|u| u.name), Some("km"));
+    assert_eq!(find_unit("kilometres").map(|u| u.name), Some("km"));
+    assert!(find_unit("furlong").is_none());
+    assert_eq!(convert(100.0, "C", "F"), Ok(212.0));
+    let (once, each) = (batch_one_lookup(values), batch_per_item(values));
+    assert!((once - each).abs() <= 1e-9 * once, "{} vs {}", once, each);
+    assert!((once - values.iter().sum::<f64>() / 1.609344).abs() <= 1e-6 * once);
+}
+
+fn main() {
+    let values: Vec<f64> = (0..BATCH).map(|i| i as f64 * 0.5).collect();
+    check(&values[..1000]);
+    // `cargo bench` passes --bench; under `cargo test` the checks are enough.
+    if !std::env::args().any(|arg| arg == "--bench") {
+        return;
+    }
+    bench("find_unit symbol hit", || find_unit(black_box("km")));
+    bench("find_unit alias hit", || find_unit(black_box("kilometres")));
+    bench("find_unit miss", || find_unit(black_box("furlong")));
+    bench("convert", || convert(black_box(100.0), black_box("C"), black_box("F")));
+    bench("1M km→mi, one lookup", || batch_one_lookup(black_box(&values)));
+    bench("1M km→mi, lookup per item", || batch_per_item(black_box(&values)));
+}
@@ -0,0 +1,195 @@
+//! Generates the built-in unit tables from `units.toml`: one file per
+//! category in `OUT_DIR`, each a slice of `unit!` invocations that
+//! `src/units/<category>.rs` includes. A row that can't become a unit fails
+//! the build with its line in `units.toml`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+#[allow(dead_code)]
+#[path = "src/toml.rs"]
+mod toml;
+
+use toml::Table;
+
+const SOURCE: &str = "units.toml";
+const CATEGORIES: [(&str, &str); 8] = [
+    ("length", "Length"),
+    ("temperature", "Temperature"),
+    ("mass", "Mass"),
+    ("time", "Time"),
+    ("volume", "Volume"),
+    ("speed", "Speed"),
+    ("data", "Data"),
+    ("angle", "Angle"),
+];
+/// The categories with a typed quantity in `src/typed.rs`.
+const TYPED: [(&str, &str); 3] = [("length", "Length"), ("mass", "Mass"), ("temperature", "Temperature")];
+const KEYS: [&str; 9] = ["name", "aliases", "category", "factor", "denominator", "offset", "singular", "plural", "case_sensitive"];
+/// Symbols this short of a case-sensitive unit may differ from each other
+/// only in case, like `mm` and `Mm`, as the runtime's lookup allows.
+const SYMBOL_MAX_LEN: usize = 3;
+
+struct Row {
+    line: usize,
+    name: String,
+    aliases: Vec<String>,
+    category: &'static str,
+    factor: (f64, f64),
+    offset: f64,
+    singular: String,
+    plural: String,
+    case_sensitive: bool,
+}
+
+fn main() {
+    println!("cargo::rerun-if-changed={}", SOURCE);
+    println!("cargo::rerun-if-changed=src/toml.rs");
+    let text = std::fs::read_to_string(SOURCE).unwrap_or_else(|e| panic!("can't read {}: {}", SOURCE, e));
+    let rows = rows(&text).unwrap_or_else(|(line, message)| panic!("{}:{}: {}", SOURCE, line, message));
+    let out = std::env::var("OUT_DIR").expect("cargo sets OUT_DIR");
+    for (file, variant) in CATEGORIES {
+        let mut table = String::from("&[\n");
+        for row in rows.iter().filter(|row| row.category == variant) {
+            let factor = match row.offset {
+                0.0 => format!("{:?} / {:?}", row.factor.0, row.factor.1),
+                offset => format!("affine {:?} / {:?}, offset {:?}", row.factor.0, row.factor.1, offset),
+            };
+            writeln!(
+                table,
+                "    unit!({}: {:?}{}, {}, {:?} / {:?}, [{}]),",
+                variant,
+                row.name,
+                if row.case_sensitive { "" } else { " any case" },
+                factor,
+                row.singular,
+                row.plural,
+                row.aliases.iter().map(|a| format!("{:?}", a)).collect::<Vec<_>>().join(", ")
+            )
+            .expect("writing to a String");
+        }
+        table.push(']');
+        std::fs::write(Path::new(&out).join(format!("{}.rs", file)), table).expect("writing to OUT_DIR");
+    }
+    for (file, variant) in TYPED {
+        let methods = accessors(variant, &rows).unwrap_or_else(|message| panic!("{}: {}", SOURCE, message));
+        std::fs::write(Path::new(&out).join(format!("typed_{}.rs", file)), methods).expect("writing to OUT_DIR");
+    }
+}
+
+/// The `from_`/`to_` methods of `converter::typed::<variant>`, one pair
+/// per unit under its symbol and one under its plural name, indexing the
+/// category's table.
+fn accessors(variant: &str, rows: &[Row]) -> Result<String, String> {
+    let rows: Vec<&Row> = rows.iter().filter(|row| row.category == variant).collect();
+    let base = rows
+        .iter()
+        .position(|row| row.factor.0 == row.factor.1 && row.offset == 0.0)
+        .ok_or_else(|| format!("{} has no unit with factor 1 to keep its values in", variant))?;
+    let noun = variant.to_ascii_lowercase();
+    let mut taken: Vec<(String, usize)> = Vec::new();
+    let mut code = format!("impl {} {{
+    const BASE: usize = {};
+", variant, base);
+    for (index, row) in rows.iter().enumerate() {
+        for word in [&row.name, &row.plural] {
+            let ident: String = word.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' }).collect();
+            match taken.iter().find(|(other, _)| *other == ident) {
+                Some((_, line)) if *line == row.line => continue,
+                Some((_, line)) => {
+                    return Err(format!("line {}: '{}' would name the same {} methods as the unit on line {}", row.line, word, variant, line));
+                }
+                None => {}
+            }
+            writeln!(
+                code,
+                "\n    /// A {noun} of `value` {plural}.\n    pub fn from_{ident}(value: f64) -> {variant} {{\n        {variant}::from_unit(value, {index})\n    }}\n\n    /// This {noun} in {plural}.\n    pub fn to_{ident}(self) -> f64 {{\n        self.to_unit({index})\n    }}",
+                plural = row.plural,
+            )
+            .expect("writing to a String");
+            taken.push((ident, row.line));
+        }
+    }
+    code.push_str("}\n");
+    Ok(code)
+}
+
+/// Every unit in `text`, or the line and message of the first bad row.
+fn rows(text: &str) -> Result<Vec<Row>, (usize, String)> {
+    let doc = toml::parse(text).map_err(|e| (e.line, e.message))?;
+    if let Some(entry) = doc.root().entries.first() {
+        return Err((entry.line, format!("unexpected key '{}' outside a [[unit]] table", entry.key)));
+    }
+    let mut rows: Vec<Row> = Vec::new();
+    let mut words: BTreeMap<String, Vec<(String, bool, usize)>> = BTreeMap::new();
+    for table in &doc.tables[1..] {
+        if table.name != "unit" || !table.array {
+            return Err((table.line, format!("unexpected table [{}]; units are [[unit]] tables", table.name)));
+        }
+        let row = row(table)?;
+        for word in std::iter::once(&row.name).chain(&row.aliases) {
+            let spellings = words.entry(word.to_ascii_lowercase()).or_default();
+            if let Some((_, _, line)) = spellings.iter().find(|(other, case, _)| collide((word, row.case_sensitive), (other, *case))) {
+                return Err((row.line, format!("'{}' is already taken by the unit on line {}", word, line)));
+            }
+            spellings.push((word.clone(), row.case_sensitive, row.line));
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Two words that lowercase alike, each with whether its unit is
+/// case-sensitive, unless one is a symbol matched only as spelled and they
+/// are spelled differently.
+fn collide((a, a_case): (&str, bool), (b, b_case): (&str, bool)) -> bool {
+    let spelled_only = |word: &str, case: bool| case && word.chars().count() <= SYMBOL_MAX_LEN;
+    a == b || !(spelled_only(a, a_case) || spelled_only(b, b_case))
+}
+
+fn row(table: &Table) -> Result<Row, (usize, String)> {
+    let at = |key: &str| table.entries.iter().find(|e| e.key == key).map_or(table.line, |e| e.line);
+    if let Some(entry) = table.entries.iter().find(|e| !KEYS.contains(&e.key.as_str())) {
+        return Err((entry.line, format!("unknown key '{}'", entry.key)));
+    }
+    let text = |key: &str| match table.get(key) {
+        Some(value) => value.as_str().map(|s| Some(s.to_string())).ok_or_else(|| (at(key), format!("'{}' must be a string", key))),
+        None => Ok(None),
+    };
+    let number = |key: &str, default: Option<f64>| match table.get(key) {
+        Some(value) => value.as_f64().filter(|n| n.is_finite()).ok_or_else(|| (at(key), format!("'{}' must be a finite number", key))),
+        None => default.ok_or_else(|| (table.line, format!("unit has no '{}'", key))),
+    };
+
+    let name = text("name")?.filter(|name| !name.trim().is_empty()).ok_or_else(|| (at("name"), "unit has no name".to_string()))?;
+    let category = text("category")?.ok_or_else(|| (table.line, format!("{} has no 'category'", name)))?;
+    let category = CATEGORIES
+        .iter()
+        .find(|(key, _)| *key == category)
+        .map(|(_, variant)| *variant)
+        .ok_or_else(|| (at("category"), format!("unknown category '{}'", category)))?;
+    let aliases = match table.get("aliases") {
+        None => Vec::new(),
+        Some(value) => value
+            .as_array()
+            .and_then(|items| items.iter().map(|item| item.as_str().map(str::to_string)).collect::<Option<Vec<_>>>())
+            .ok_or_else(|| (at("aliases"), "'aliases' must be a list of strings".to_string()))?,
+    };
+    let factor = (number("factor", None)?, number("denominator", Some(1.0))?);
+    if factor.0 <= 0.0 || factor.1 <= 0.0 {
+        let key = if factor.0 <= 0.0 { "factor" } else { "denominator" };
+        return Err((at(key), format!("{}'s {} must be positive", name, key)));
+    }
+    let offset = number("offset", Some(0.0))?;
+    let case_sensitive = match table.get("case_sensitive") {
+        None => true,
+        Some(value) => value.as_bool().ok_or_else(|| (at("case_sensitive"), "'case_sensitive' must be true or false".to_string()))?,
+    };
+    let (singular, plural) = match (text("singular")?, text("plural")?, aliases.as_slice()) {
+        (Some(singular), Some(plural), _) => (singular, plural),
+        (None, None, [singular, plural, ..]) => (singular.clone(), plural.clone()),
+        _ => return Err((table.line, format!("{} needs 'singular' and 'plural', or two aliases to take them from", name))),
+    };
+    Ok(Row { line: table.line, name, aliases, category, factor, offset, singular, plural, case_sensitive })
+}
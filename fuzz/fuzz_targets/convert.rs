@@ -0,0 +1,26 @@
+//! Any value between any two built-in units: a conversion that succeeds
+//! gives a finite result, and NaN or an infinity is refused.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use converter::UNITS;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    value: f64,
+    /// Indexes into `UNITS`, wrapped to its length.
+    from: u8,
+    to: u8,
+}
+
+fuzz_target!(|input: Input| {
+    let from = &UNITS[usize::from(input.from) % UNITS.len()];
+    let to = &UNITS[usize::from(input.to) % UNITS.len()];
+    let result = converter::convert(input.value, from.name, to.name);
+    if let Ok(result) = result {
+        assert!(result.is_finite(), "{:?}: {} {} is {} in {}", input, input.value, from.name, result, to.name);
+    }
+    assert!(input.value.is_finite() || result.is_err(), "{:?}: a non-finite value was converted", input);
+});
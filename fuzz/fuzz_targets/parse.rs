@@ -0,0 +1,17 @@
+//! Any text as a quantity, a value or a unit: parsing may fail but must not panic.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = converter::Quantity::parse(input);
+    let _ = converter::find_unit(input);
+    let _ = converter::duration::parse(input);
+    if let Some(ratio) = converter::rational::Ratio::parse_decimal(input) {
+        let _ = ratio.to_decimal();
+    }
+    let _ = converter::convert_checked(input, "km", "mi");
+    let _ = converter::convert_delta(input, "C", "F");
+    let _ = converter::convert_checked("1", input, "m");
+});
@@ -0,0 +1,104 @@
+#ifndef CONVERTER_H
+#define CONVERTER_H
+
+/* The declarations of src/ffi.rs; regenerate with cbindgen (see cbindgen.toml). */
+
+#include <stdint.h>
+
+#define CONVERTER_OK 0
+
+/**
+ * An argument was a null pointer.
+ */
+#define CONVERTER_NULL_POINTER -1
+
+/**
+ * A unit name was not valid UTF-8.
+ */
+#define CONVERTER_INVALID_UTF8 -2
+
+#define CONVERTER_INVALID_VALUE -3
+
+#define CONVERTER_UNKNOWN_UNIT -4
+
+#define CONVERTER_CATEGORY_MISMATCH -5
+
+#define CONVERTER_BELOW_ABSOLUTE_ZERO -6
+
+#define CONVERTER_NEGATIVE_UNCERTAINTY -7
+
+#define CONVERTER_OFFSET_IN_COMPOUND -8
+
+#define CONVERTER_DISABLED_UNIT -9
+
+#define CONVERTER_NO_PATH -10
+
+/**
+ * The result is too large for a double.
+ */
+#define CONVERTER_OVERFLOW -11
+
+/**
+ * A speed of zero was converted to a pace.
+ */
+#define CONVERTER_ZERO_SPEED -12
+
+/**
+ * A word such as "gallon" that means a different unit in the US and
+ * imperial systems.
+ */
+#define CONVERTER_AMBIGUOUS_UNIT -13
+
+/**
+ * A wire gauge outside 4/0 through 40, given or converted to.
+ */
+#define CONVERTER_NO_GAUGE -14
+
+/**
+ * A currency code the loaded rates don't list, or any code when none are loaded.
+ */
+#define CONVERTER_UNKNOWN_CURRENCY -15
+
+/**
+ * Converts `value` from the unit named `from` to the one named `to` and
+ * stores the result in `*out`. Returns `CONVERTER_OK` or a negative code,
+ * leaving `*out` untouched on failure. Wire gauges (`awg`) and paces
+ * convert as they do on the command line.
+ *
+ * # Safety
+ *
+ * `from` and `to` must each be null or point to a NUL-terminated string,
+ * and `out` must be null or valid for writing an `f64`.
+ */
+int32_t converter_convert(double value, const char *from, const char *to, double *out);
+
+/**
+ * The message for the last failed call on this thread, or null if none
+ * has failed. It stays valid until the next failure on the same thread.
+ */
+const char *converter_last_error_message(void);
+
+/**
+ * The units the last failed call on this thread suggested in place of an
+ * unknown one, best first, as a JSON array of names; empty when it wasn't
+ * an unknown unit or nothing is close. Release it with `converter_free_string`.
+ */
+char *converter_last_error_suggestions_json(void);
+
+/**
+ * Every available unit as a JSON array, in the `--list --json` format.
+ * Release it with `converter_free_string`.
+ */
+char *converter_list_units_json(void);
+
+/**
+ * Releases a string returned by this library; null is ignored.
+ *
+ * # Safety
+ *
+ * `text` must be null or a string returned by this library that hasn't
+ * been released yet.
+ */
+void converter_free_string(char *text);
+
+#endif  /* CONVERTER_H */
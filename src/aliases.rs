@@ -0,0 +1,161 @@
+//! Unit names in Spanish, French and German, so "5 millas metros" or
+//! "3 livres kg" parse. Only compiled with the `i18n-aliases` feature. The
+//! built-in English names and aliases are always tried first; a word no
+//! built-in unit claims is then looked up in these tables in `TABLES` order,
+//! so when two languages spell the same word the earlier table wins.
+
+/// Canonical unit names, each with its words in one language (lowercase).
+type Table = &'static [(&'static str, &'static [&'static str])];
+
+pub const TABLES: &[(&str, Table)] = &[("es", ES), ("fr", FR), ("de", DE)];
+
+/// The canonical name of the unit `input` names in any language, ignoring case.
+pub fn canonical(input: &str) -> Option<&'static str> {
+    resolve(TABLES, input)
+}
+
+fn resolve(tables: &[(&str, Table)], input: &str) -> Option<&'static str> {
+    let input = input.to_lowercase();
+    tables
+        .iter()
+        .flat_map(|(_, table)| table.iter())
+        .find(|(_, words)| words.contains(&input.as_str()))
+        .map(|(name, _)| *name)
+}
+
+const ES: Table = &[
+    ("km", &["kilómetro", "kilómetros", "kilometro", "kilometros"]),
+    ("m", &["metro", "metros"]),
+    ("cm", &["centímetro", "centímetros", "centimetro", "centimetros"]),
+    ("mm", &["milímetro", "milímetros", "milimetro", "milimetros"]),
+    ("mi", &["milla", "millas"]),
+    ("yd", &["yarda", "yardas"]),
+    ("ft", &["pie", "pies"]),
+    ("in", &["pulgada", "pulgadas"]),
+    ("C", &["centígrado", "centígrados", "centigrado", "centigrados"]),
+    ("kg", &["kilogramo", "kilogramos", "kilo", "kilos"]),
+    ("g", &["gramo", "gramos"]),
+    ("mg", &["miligramo", "miligramos"]),
+    ("lb", &["libra", "libras"]),
+    ("oz", &["onza", "onzas"]),
+    ("ton", &["tonelada", "toneladas"]),
+    ("s", &["segundo", "segundos", "seg"]),
+    ("ms", &["milisegundo", "milisegundos"]),
+    ("min", &["minuto", "minutos"]),
+    ("h", &["hora", "horas"]),
+    ("d", &["día", "días", "dia", "dias"]),
+    ("wk", &["semana", "semanas"]),
+];
+
+const FR: Table = &[
+    ("km", &["kilomètre", "kilomètres"]),
+    ("m", &["mètre", "mètres"]),
+    ("cm", &["centimètre", "centimètres"]),
+    ("mm", &["millimètre", "millimètres"]),
+    ("mi", &["mille", "milles"]),
+    ("ft", &["pied", "pieds"]),
+    ("in", &["pouce", "pouces"]),
+    ("kg", &["kilogramme", "kilogrammes", "kilo", "kilos"]),
+    ("g", &["gramme", "grammes"]),
+    ("mg", &["milligramme", "milligrammes"]),
+    ("lb", &["livre", "livres"]),
+    ("oz", &["once", "onces"]),
+    ("s", &["seconde", "secondes"]),
+    ("ms", &["milliseconde", "millisecondes"]),
+    ("h", &["heure", "heures"]),
+    ("d", &["jour", "jours"]),
+    ("wk", &["semaine", "semaines"]),
+];
+
+const DE: Table = &[
+    ("km", &["kilometern"]),
+    ("m", &["metern"]),
+    ("cm", &["zentimeter", "zentimetern"]),
+    ("mm", &["millimetern"]),
+    ("mi", &["meile", "meilen"]),
+    ("ft", &["fuß", "fuss"]),
+    ("in", &["zoll"]),
+    ("kg", &["kilogramm", "kilo"]),
+    ("g", &["gramm"]),
+    ("mg", &["milligramm"]),
+    ("lb", &["pfund"]),
+    ("oz", &["unze", "unzen"]),
+    ("ton", &["tonnen"]),
+    ("s", &["sekunde", "sekunden"]),
+    ("ms", &["millisekunde", "millisekunden"]),
+    ("min", &["minuten"]),
+    ("h", &["stunde", "stunden"]),
+    ("d", &["tag", "tage", "tagen"]),
+    ("wk", &["woche", "wochen"]),
+];
+
+#[cfg(all(test, feature = "mass", feature = "time"))]
+mod tests {
+    use super::*;
+    use crate::builtin_unit;
+
+    fn name(input: &str) -> Option<&'static str> {
+        builtin_unit(input).map(|u| u.name)
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature", feature = "time"))]
+    fn test_spanish_names() {
+        assert_eq!(name("metros"), Some("m"));
+        assert_eq!(name("millas"), Some("mi"));
+        assert_eq!(name("libras"), Some("lb"));
+        assert_eq!(name("Kilómetros"), Some("km"));
+        assert_eq!(name("centígrados"), Some("C"));
+        assert_eq!(name("horas"), Some("h"));
+        assert_eq!(name("días"), Some("d"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "time"))]
+    fn test_french_names() {
+        assert_eq!(name("livres"), Some("lb"));
+        assert_eq!(name("mètres"), Some("m"));
+        assert_eq!(name("pouces"), Some("in"));
+        assert_eq!(name("grammes"), Some("g"));
+        assert_eq!(name("heures"), Some("h"));
+        assert_eq!(name("semaines"), Some("wk"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "time"))]
+    fn test_german_names() {
+        assert_eq!(name("Meilen"), Some("mi"));
+        assert_eq!(name("FUSS"), Some("ft"));
+        assert_eq!(name("Fuß"), Some("ft"));
+        assert_eq!(name("Pfund"), Some("lb"));
+        assert_eq!(name("Stunden"), Some("h"));
+        assert_eq!(name("Tage"), Some("d"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "mass", feature = "time"))]
+    fn test_collisions_resolve_in_table_order() {
+        // Built-in English wins: French "minute" and German "Minute" are
+        // simply the English alias, and "tonne" never reaches the tables.
+        assert_eq!(name("Minute"), Some("min"));
+        assert_eq!(name("tonne"), Some("ton"));
+        // "kilo" is in every table and means kg in all of them.
+        assert_eq!(canonical("kilo"), Some("kg"));
+        // Where two languages disagree, the earlier table wins.
+        let es: Table = &[("mi", &["mil"])];
+        let fr: Table = &[("in", &["mil"])];
+        assert_eq!(resolve(&[("es", es), ("fr", fr)], "MIL"), Some("mi"));
+        assert_eq!(resolve(&[("fr", fr), ("es", es)], "mil"), Some("in"));
+        assert_eq!(canonical("mil"), None);
+    }
+
+    #[test]
+    fn test_no_word_is_claimed_twice_for_different_units() {
+        let entries: Vec<(&str, &str)> =
+            TABLES.iter().flat_map(|(_, table)| table.iter()).flat_map(|(name, words)| words.iter().map(move |w| (*w, *name))).collect();
+        for (word, name) in &entries {
+            assert!(entries.iter().all(|(w, n)| w != word || n == name), "'{}' names two units", word);
+            assert!(crate::UNITS.iter().all(|u| !u.matches(word) || u.name == *name), "'{}' shadows a built-in unit", word);
+        }
+    }
+}
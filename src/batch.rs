@@ -0,0 +1,443 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+
+use converter::record::{self, ConversionRecord};
+
+use crate::csv;
+use crate::json::Json;
+use crate::parallel;
+use crate::progress::{self, Progress};
+use crate::Options;
+use crate::i18n::{self, tr};
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Summary {
+    pub converted: usize,
+    pub errors: usize,
+}
+
+/// Runs `--batch`, returning the process exit code.
+pub fn run(path: &str, options: &Options) -> i32 {
+    let input: Box<dyn BufRead> = if path == "-" {
+        Box::new(io::stdin().lock())
+    } else {
+        match File::open(path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(e) => {
+                eprintln!("{}: cannot read '{}': {}", tr("error"), path, e);
+                return 1;
+            }
+        }
+    };
+
+    let stdout = io::stdout();
+    let mut out = io::BufWriter::new(stdout.lock());
+    let mut progress = progress::for_input(path, options);
+    let summary = match convert_batch(input, &mut out, &mut io::stderr(), options, progress.as_mut()) {
+        Ok(summary) => summary,
+        Err(e) => {
+            eprintln!("{}: {}", tr("error"), e);
+            return 1;
+        }
+    };
+    if out.flush().is_err() {
+        return 1;
+    }
+
+    if summary.errors > 0 && !options.skip_errors {
+        eprintln!("{}: {} of {} lines failed", tr("error"), summary.errors, summary.converted + summary.errors);
+        return 1;
+    }
+    0
+}
+
+/// Converts every "value from to" line; blank lines and `#` comments are skipped.
+/// Failing lines are reported on `errors` with their line number.
+pub fn convert_batch(
+    input: impl BufRead,
+    out: &mut impl Write,
+    errors: &mut impl Write,
+    options: &Options,
+    progress: &mut dyn Progress,
+) -> io::Result<Summary> {
+    let mut summary = Summary::default();
+    let mut json_items = Vec::new();
+    if options.csv && !options.json {
+        writeln!(out, "{}", record::FIELDS.join(","))?;
+    }
+
+    let jobs = parallel::jobs(options);
+    let mut lines = input.lines().enumerate();
+    let mut bytes = 0;
+    loop {
+        let chunk = lines.by_ref().take(parallel::CHUNK_SIZE).map(|(i, line)| Ok((i + 1, line?))).collect::<io::Result<Vec<_>>>()?;
+        if chunk.is_empty() {
+            break;
+        }
+        let outcomes = parallel::map_ordered(&chunk, jobs, |(number, line)| convert_line(*number, line, options));
+        for ((number, line), outcome) in chunk.iter().zip(outcomes) {
+            bytes += line.len() as u64 + 1;
+            progress.tick(*number, bytes);
+            errors.write_all(outcome.messages.as_bytes())?;
+            match outcome.output {
+                Output::Skipped => {}
+                Output::Failed => summary.errors += 1,
+                Output::Json(item) => {
+                    summary.converted += 1;
+                    json_items.push(item);
+                }
+                Output::Text(text) => {
+                    summary.converted += 1;
+                    out.write_all(text.as_bytes())?;
+                }
+            }
+        }
+    }
+
+    progress.finish();
+    if options.json {
+        writeln!(out, "{}", Json::Array(json_items))?;
+    }
+    Ok(summary)
+}
+
+enum Output {
+    /// Blank or a comment.
+    Skipped,
+    Failed,
+    Json(Json),
+    /// Ready to write, newline included.
+    Text(String),
+}
+
+/// What one batch line produces: its output and anything for stderr.
+struct LineOutcome {
+    output: Output,
+    messages: String,
+}
+
+fn convert_line(number: usize, line: &str, options: &Options) -> LineOutcome {
+    let text = line.trim();
+    if text.is_empty() || text.starts_with('#') {
+        return LineOutcome { output: Output::Skipped, messages: String::new() };
+    }
+    let failed = |message: String| LineOutcome { output: Output::Failed, messages: format!("{}: line {}: {}\n", tr("error"), number, message) };
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let [value, from, to] = words[..] else {
+        return failed(format!("expected '<value> <from_unit> <to_unit>', found '{}'", text));
+    };
+    let (converted, record) = match crate::convert_checked(value, from, to).and_then(|c| c.record(from, to).map(|record| (c, record))) {
+        Ok(pair) => pair,
+        Err(e) => return failed(e.localized(i18n::active()).replace('\n', "\n    ")),
+    };
+    if options.json || options.csv {
+        // The warnings are in the record.
+        let output = if options.json { Output::Json(crate::result_json(&record, options)) } else { Output::Text(csv_record(&record, options)) };
+        return LineOutcome { output, messages: String::new() };
+    }
+    let messages = converted.warnings.iter().map(|w| format!("{}: line {}: {}\n", tr("warning"), number, w.localized(i18n::active()))).collect();
+    let output = Output::Text(format!("{}\n", crate::format_record(&record, options)));
+    LineOutcome { output, messages }
+}
+
+/// `record` as a `--csv` line under the `record::FIELDS` header; a missing
+/// factor is empty and the warnings are joined with "; ".
+fn csv_record(record: &ConversionRecord, options: &Options) -> String {
+    let fields = [
+        record.input.to_string(),
+        record.from.clone(),
+        record.to.clone(),
+        crate::format_number(record.result, options),
+        record.category.name().to_lowercase(),
+        record.factor.map(|factor| factor.to_string()).unwrap_or_default(),
+        record.warnings.join("; "),
+    ];
+    let mut line = Vec::new();
+    csv::write_record(&mut line, &fields).expect("writing to a Vec can't fail");
+    String::from_utf8(line).expect("CSV records are UTF-8")
+}
+
+/// Runs `--filter`, returning the process exit code.
+pub fn run_filter(program: &str, from: &str, to: &str, options: &Options) -> i32 {
+    if let Err(e) = crate::convert_checked("0", from, to) {
+        return crate::report_error(program, &e, options);
+    }
+
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut out = io::BufWriter::new(stdout.lock());
+    let mut progress = progress::for_input("-", options);
+    let result = filter_stream(stdin.lock(), &mut out, &mut io::stderr(), from, to, options, progress.as_mut());
+    match result.and_then(|ok| out.flush().map(|_| ok)) {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        // A closed pipe downstream (e.g. `| head`) just means nobody wants more output.
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => 0,
+        Err(e) => {
+            eprintln!("{}: {}", tr("error"), e);
+            1
+        }
+    }
+}
+
+/// Converts the number on each input line and writes one result per line as it goes.
+/// Returns `Ok(false)` after the first non-numeric line unless `--passthrough` is set,
+/// in which case such lines are copied through unchanged.
+pub fn filter_stream(
+    input: impl BufRead,
+    out: &mut impl Write,
+    errors: &mut impl Write,
+    from: &str,
+    to: &str,
+    options: &Options,
+    progress: &mut dyn Progress,
+) -> io::Result<bool> {
+    let mut bytes = 0;
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        bytes += line.len() as u64 + 1;
+        progress.tick(index + 1, bytes);
+        match crate::convert_checked(line.trim(), from, to) {
+            Ok(converted) => {
+                for warning in &converted.warnings {
+                    writeln!(errors, "{}: line {}: {}", tr("warning"), index + 1, warning.localized(i18n::active()))?;
+                }
+                writeln!(out, "{}", crate::format_number(converted.result, options))?;
+            }
+            Err(crate::ConvertError::InvalidValue(_)) if options.passthrough => writeln!(out, "{}", line)?,
+            Err(e) => {
+                progress.finish();
+                writeln!(errors, "{}: line {}: {}", tr("error"), index + 1, e.localized(i18n::active()))?;
+                return Ok(false);
+            }
+        }
+    }
+    progress.finish();
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> BufReader<File> {
+        let path = format!("{}/tests/fixtures/batch/{}", env!("CARGO_MANIFEST_DIR"), name);
+        BufReader::new(File::open(path).unwrap())
+    }
+
+    fn run_fixture(name: &str, options: &Options) -> (String, String, Summary) {
+        let mut out = Vec::new();
+        let mut err = Vec::new();
+        let summary = convert_batch(fixture(name), &mut out, &mut err, options, &mut progress::Silent).unwrap();
+        (String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap(), summary)
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature"))]
+    fn test_comments_and_blank_lines_skipped() {
+        let (out, err, summary) = run_fixture("comments.txt", &Options::default());
+        assert_eq!(out, "5 km = 3.10685596118667 mi\n100 C = 212 F\n1 kg = 1000 g\n");
+        assert_eq!(err, "");
+        assert_eq!(summary, Summary { converted: 3, errors: 0 });
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature"))]
+    fn test_errors_mid_file_are_reported_and_skipped() {
+        let (out, err, summary) = run_fixture("errors.txt", &Options::default());
+        assert_eq!(out, "1 m = 100 cm\n2 kg = 4.40924524369755 lb\n10 C = 283.15 K\n");
+        let lines: Vec<&str> = err.lines().collect();
+        assert_eq!(lines[0], "Error: line 2: expected '<value> <from_unit> <to_unit>', found '5 km'");
+        assert_eq!(lines[1], "Error: line 3: 'abc' is not a valid number");
+        assert_eq!(lines[2], "Error: line 5: Cannot convert between different unit categories (L¹ vs M¹)");
+        assert_eq!(summary, Summary { converted: 3, errors: 3 });
+    }
+
+    #[test]
+    fn test_empty_file() {
+        let (out, err, summary) = run_fixture("empty.txt", &Options::default());
+        assert_eq!((out.as_str(), err.as_str()), ("", ""));
+        assert_eq!(summary, Summary::default());
+
+        let json = Options { json: true, ..Options::default() };
+        assert_eq!(run_fixture("empty.txt", &json).0, "[]\n");
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature"))]
+    fn test_json_array_output() {
+        let options = Options { json: true, precision: Some(2), ..Options::default() };
+        let (out, _, _) = run_fixture("errors.txt", &options);
+        let Json::Array(items) = crate::json::parse(out.trim()).unwrap() else {
+            panic!("expected an array");
+        };
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[1].get("result").and_then(Json::as_f64), Some(4.41));
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_filter_converts_each_line() {
+        let input: &[u8] = b"1\n 2.5 \n-4\n";
+        let mut out = Vec::new();
+        let mut err = Vec::new();
+        let options = Options { precision: Some(1), ..Options::default() };
+        assert!(filter_stream(input, &mut out, &mut err, "m", "cm", &options, &mut progress::Silent).unwrap());
+        assert_eq!(String::from_utf8(out).unwrap(), "100.0\n250.0\n-400.0\n");
+        assert_eq!(String::from_utf8(err).unwrap(), "Warning: line 3: Negative length doesn't make physical sense\n");
+    }
+
+    #[test]
+    #[cfg(feature = "mass")]
+    fn test_filter_non_numeric_lines() {
+        let input: &[u8] = b"1\ntotal:\n2\n";
+        let mut out = Vec::new();
+        let mut err = Vec::new();
+        assert!(!filter_stream(input, &mut out, &mut err, "kg", "g", &Options::default(), &mut progress::Silent).unwrap());
+        assert_eq!(String::from_utf8(out).unwrap(), "1000\n");
+        assert_eq!(String::from_utf8(err).unwrap(), "Error: line 2: 'total:' is not a valid number\n");
+
+        let mut out = Vec::new();
+        let options = Options { passthrough: true, ..Options::default() };
+        assert!(filter_stream(input, &mut out, &mut Vec::new(), "kg", "g", &options, &mut progress::Silent).unwrap());
+        assert_eq!(String::from_utf8(out).unwrap(), "1000\ntotal:\n2000\n");
+    }
+
+    /// Generates numbered lines on demand and records how much output had
+    /// been written when the second half of the input was first requested.
+    #[cfg(feature = "length")]
+    struct Lines {
+        next: usize,
+        total: usize,
+        pending: Vec<u8>,
+        written: std::rc::Rc<std::cell::Cell<usize>>,
+        written_at_half: Option<usize>,
+    }
+
+    #[cfg(feature = "length")]
+    impl io::Read for Lines {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.pending.is_empty() {
+                if self.next == self.total {
+                    return Ok(0);
+                }
+                if self.next == self.total / 2 {
+                    self.written_at_half = Some(self.written.get());
+                }
+                self.pending = format!("{}\n", self.next).into_bytes();
+                self.next += 1;
+            }
+            let n = buf.len().min(self.pending.len());
+            buf[..n].copy_from_slice(&self.pending[..n]);
+            self.pending.drain(..n);
+            Ok(n)
+        }
+    }
+
+    #[cfg(feature = "length")]
+    struct CountingWriter(std::rc::Rc<std::cell::Cell<usize>>);
+
+    #[cfg(feature = "length")]
+    impl Write for CountingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.set(self.0.get() + buf.len());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_filter_streams_large_input() {
+        let written = std::rc::Rc::new(std::cell::Cell::new(0));
+        let mut lines = Lines { next: 0, total: 100_000, pending: Vec::new(), written: written.clone(), written_at_half: None };
+        let mut out = io::BufWriter::new(CountingWriter(written.clone()));
+        let ok = filter_stream(BufReader::new(&mut lines), &mut out, &mut io::sink(), "km", "m", &Options::default(), &mut progress::Silent)
+            .unwrap();
+        out.flush().unwrap();
+        assert!(ok);
+        assert!(lines.written_at_half.unwrap() > 100_000, "output should be written while input is still being read");
+        assert_eq!(written.get(), (0..100_000).map(|i: usize| (i * 1000).to_string().len() + 1).sum::<usize>());
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_progress_ticks_every_line() {
+        let path = format!("{}/tests/fixtures/batch/comments.txt", env!("CARGO_MANIFEST_DIR"));
+        let (lines, size) = (std::fs::read_to_string(&path).unwrap().lines().count(), std::fs::metadata(&path).unwrap().len());
+        let mut recorder = progress::Recorder::default();
+        convert_batch(fixture("comments.txt"), &mut Vec::new(), &mut Vec::new(), &Options::default(), &mut recorder).unwrap();
+        assert_eq!(recorder.ticks.len(), lines);
+        assert_eq!(recorder.ticks.iter().map(|t| t.0).collect::<Vec<_>>(), (1..=lines).collect::<Vec<_>>());
+        assert_eq!(recorder.ticks.last().unwrap().1, size);
+        assert!(recorder.finished);
+
+        let mut recorder = progress::Recorder::default();
+        filter_stream(&b"1\n2\n3\n"[..], &mut Vec::new(), &mut Vec::new(), "m", "cm", &Options::default(), &mut recorder).unwrap();
+        assert_eq!(recorder.ticks, vec![(1, 2), (2, 4), (3, 6)]);
+    }
+
+    /// Numbered "value unit unit" lines with a bad line every 97th.
+    fn generated_input(lines: usize) -> String {
+        let units = [("km", "mi"), ("C", "F"), ("lb", "kg"), ("h", "s")];
+        (0..lines)
+            .map(|i| match i % 97 {
+                0 => format!("{} furlong m\n", i),
+                _ => format!("{}.5 {} {}\n", i, units[i % 4].0, units[i % 4].1),
+            })
+            .collect()
+    }
+
+    fn run_generated(input: &str, options: &Options) -> (Vec<u8>, Vec<u8>, Summary) {
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let summary = convert_batch(input.as_bytes(), &mut out, &mut err, options, &mut progress::Silent).unwrap();
+        (out, err, summary)
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature", feature = "time"))]
+    fn test_parallel_output_matches_serial() {
+        // More than two chunks, so chunk boundaries are crossed too.
+        let input = generated_input(parallel::CHUNK_SIZE * 2 + 123);
+        for format in [Options::default(), Options { json: true, ..Options::default() }, Options { csv: true, ..Options::default() }] {
+            let serial = run_generated(&input, &Options { jobs: Some(1), ..format.clone() });
+            let parallel = run_generated(&input, &Options { jobs: Some(8), ..format });
+            assert_eq!(serial, parallel);
+        }
+        let (_, err, summary) = run_generated(&input, &Options { jobs: Some(8), ..Options::default() });
+        let err = String::from_utf8(err).unwrap();
+        assert!(err.starts_with("Error: line 1: Unknown unit 'furlong'\nError: line 98: Unknown unit 'furlong'\n"));
+        assert!(err.contains(&format!("Error: line {}: Unknown unit 'furlong'\n", 97 * 169 + 1)));
+        assert_eq!(summary.errors, (parallel::CHUNK_SIZE * 2 + 123).div_ceil(97));
+    }
+
+    #[test]
+    fn test_parallel_output_is_deterministic() {
+        let input = generated_input(20_000);
+        let options = Options { jobs: Some(7), ..Options::default() };
+        let first = run_generated(&input, &options);
+        for _ in 0..3 {
+            assert_eq!(run_generated(&input, &options), first);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature"))]
+    fn test_csv_output() {
+        let options = Options { csv: true, ..Options::default() };
+        let (out, _, _) = run_fixture("comments.txt", &options);
+        let expected = "value,from,to,result,category,factor,warnings\n\
+                        5,km,mi,3.10685596118667,length,0.621371192237334,\n\
+                        100,C,F,212,temperature,,\n\
+                        1,kg,g,1000,mass,1000,\n";
+        assert_eq!(out, expected);
+        // Warnings go in their column rather than to stderr.
+        let (out, err, _) = run_generated("-1 m cm\n", &options);
+        assert_eq!(String::from_utf8(out).unwrap(), "value,from,to,result,category,factor,warnings\n-1,m,cm,-100,length,100,Negative length doesn't make physical sense\n");
+        assert!(err.is_empty());
+    }
+}
@@ -0,0 +1,253 @@
+//! `check FILE`: validates a units file before anyone loads it. Every
+//! `[[unit]]` table is parsed, then the `doctor` invariants run over the
+//! built-in units plus the file's, and every problem is reported with the line
+//! of the unit it concerns rather than stopping at the first.
+//!
+//! ```toml
+//! [[unit]]
+//! name = "furlong"
+//! aliases = ["furlongs"]
+//! category = "length"
+//! factor = 201.168    # base units (m, C, kg, s or m3) per unit
+//! offset = 0          # optional, added before scaling
+//! case_sensitive = true   # optional; false lets short symbols match in any case
+//! ```
+
+use std::fs;
+
+use crate::doctor;
+use crate::i18n::tr;
+use crate::toml::{self, Table, Value};
+use crate::registry::{BuildError, UnitBuilder, UnitDef};
+use crate::Unit;
+
+const KEYS: [&str; 8] = ["name", "aliases", "category", "factor", "offset", "singular", "plural", "case_sensitive"];
+
+/// A problem found in the file; `line` is `None` for one in the built-in table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Finding {
+    fn at(line: usize, message: String) -> Finding {
+        Finding { line: Some(line), message }
+    }
+}
+
+/// The units file parsed into units, each with the line of its `[[unit]]` header.
+struct Parsed {
+    units: Vec<(&'static Unit, usize)>,
+    findings: Vec<Finding>,
+}
+
+fn parse_units(text: &str) -> Parsed {
+    let doc = match toml::parse(text) {
+        Ok(doc) => doc,
+        Err(e) => return Parsed { units: Vec::new(), findings: vec![Finding::at(e.line, e.message)] },
+    };
+    let mut parsed = Parsed { units: Vec::new(), findings: Vec::new() };
+    for entry in &doc.root().entries {
+        parsed.findings.push(Finding::at(entry.line, format!("unexpected key '{}' outside a [[unit]] table", entry.key)));
+    }
+    for table in &doc.tables[1..] {
+        if table.name != "unit" || !table.array {
+            parsed.findings.push(Finding::at(table.line, format!("unexpected table [{}]; units are [[unit]] tables", table.name)));
+            continue;
+        }
+        let before = parsed.findings.len();
+        let unit = parse_unit(table, &mut parsed.findings);
+        if let Some(unit) = unit.filter(|_| parsed.findings.len() == before) {
+            parsed.units.push((unit.leak(), table.line));
+        }
+    }
+    parsed
+}
+
+/// Reads one `[[unit]]` table, recording every problem with it.
+pub fn parse_unit(table: &Table, findings: &mut Vec<Finding>) -> Option<UnitDef> {
+    let line = |key: &str| table.entries.iter().find(|e| e.key == key).map_or(table.line, |e| e.line);
+    let mut problem = |key: &str, message: String| findings.push(Finding::at(line(key), message));
+
+    for entry in &table.entries {
+        if !KEYS.contains(&entry.key.as_str()) {
+            problem(&entry.key, format!("unknown key '{}'", entry.key));
+        }
+    }
+    let text = |key: &str| table.get(key).map(|v| v.as_str().ok_or_else(|| format!("'{}' must be a string", key)));
+    let number = |key: &str| table.get(key).map(|v| v.as_f64().ok_or_else(|| format!("'{}' must be a number", key)));
+
+    let name = match text("name") {
+        Some(Ok(name)) if !name.trim().is_empty() => Some(name.to_string()),
+        Some(Ok(_)) => { problem("name", "'name' must not be empty".to_string()); None }
+        Some(Err(e)) => { problem("name", e); None }
+        None => { problem("name", "unit has no 'name'".to_string()); None }
+    };
+    let aliases: Vec<String> = match table.get("aliases") {
+        None => Vec::new(),
+        Some(value) => match value.as_array().and_then(|items| items.iter().map(Value::as_str).collect::<Option<Vec<_>>>()) {
+            Some(aliases) => aliases.into_iter().map(str::to_string).collect(),
+            None => { problem("aliases", "'aliases' must be a list of strings".to_string()); Vec::new() }
+        },
+    };
+    let category = match text("category") {
+        Some(Ok(category)) => category.parse().map_err(|e: converter::ParseCategoryError| problem("category", e.to_string())).ok(),
+        Some(Err(e)) => { problem("category", e); None }
+        None => { problem("category", "unit has no 'category'".to_string()); None }
+    };
+    let factor = match number("factor") {
+        Some(Ok(factor)) => Some(factor),
+        Some(Err(e)) => { problem("factor", e); None }
+        None => { problem("factor", "unit has no 'factor'".to_string()); None }
+    };
+    let offset = match number("offset") {
+        Some(Ok(offset)) => offset,
+        Some(Err(e)) => { problem("offset", e); 0.0 }
+        None => 0.0,
+    };
+    let mut label = |key: &str| match text(key) {
+        Some(Ok(label)) => Some(label.to_string()),
+        Some(Err(e)) => { problem(key, e); None }
+        None => None,
+    };
+    let (singular, plural) = (label("singular"), label("plural"));
+    let case_sensitive = match table.get("case_sensitive").map(Value::as_bool) {
+        Some(Some(case_sensitive)) => case_sensitive,
+        Some(None) => { problem("case_sensitive", "'case_sensitive' must be true or false".to_string()); true }
+        None => true,
+    };
+
+    let mut unit = UnitBuilder::new(name?).category(category?).factor(factor?).offset(offset).aliases(aliases).case_sensitive(case_sensitive);
+    unit = match (singular, plural) {
+        (Some(singular), Some(plural)) => unit.singular(singular).plural(plural),
+        (Some(singular), None) => unit.singular(singular),
+        (None, Some(plural)) => unit.plural(plural),
+        (None, None) => unit,
+    };
+    unit.build()
+        .map_err(|e| {
+            let key = match e {
+                BuildError::EmptyName => "name",
+                BuildError::MissingCategory { .. } => "category",
+                BuildError::MissingFactor { .. } | BuildError::NonFiniteFactor { .. } | BuildError::ZeroFactor { .. } => "factor",
+                BuildError::NonFiniteOffset { .. } | BuildError::OffsetOutsideTemperature { .. } => "offset",
+                BuildError::DuplicateAlias { .. } => "aliases",
+            };
+            problem(key, e.to_string())
+        })
+        .ok()
+}
+
+/// Everything wrong with the units file `text`, checked against `builtin`.
+pub fn check(text: &str, builtin: &[&'static Unit]) -> Vec<Finding> {
+    let parsed = parse_units(text);
+    let mut findings = parsed.findings;
+    let mut merged: Vec<&Unit> = builtin.to_vec();
+    merged.extend(parsed.units.iter().map(|(unit, _)| *unit));
+    for (_, check) in doctor::CHECKS {
+        for problem in check(&merged) {
+            // A duplicate is blamed on its second owner, which is the file's unit.
+            let line = parsed.units.iter().find(|(unit, _)| unit.name == problem.unit).map(|(_, line)| *line);
+            findings.push(Finding { line, message: problem.message });
+        }
+    }
+    findings.sort_by_key(|f| f.line);
+    findings
+}
+
+/// Runs the `check` subcommand, returning the process exit code.
+pub fn run(program: &str, args: &[String]) -> i32 {
+    let [path] = args else {
+        eprintln!("{}: {} check <units.toml>", tr("usage_label"), program);
+        return 1;
+    };
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("{}: cannot read '{}': {}", tr("error"), path, e);
+            return 1;
+        }
+    };
+    let builtin: Vec<&Unit> = crate::registry::active().units().collect();
+    let findings = check(&text, &builtin);
+    for finding in &findings {
+        match finding.line {
+            Some(line) => println!("{}:{}: {}", path, line, finding.message),
+            None => println!("{}: built-in table: {}", path, finding.message),
+        }
+    }
+    if findings.is_empty() {
+        println!("{}: ok", path);
+        0
+    } else {
+        println!("{}: {} problem{}", path, findings.len(), if findings.len() == 1 { "" } else { "s" });
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(name: &str) -> String {
+        fs::read_to_string(format!("{}/tests/fixtures/units/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+    }
+
+    fn fixture(name: &str) -> Vec<Finding> {
+        let builtin: Vec<&Unit> = converter::UNITS.iter().collect();
+        check(&read(name), &builtin)
+    }
+
+    fn at(line: usize, message: &str) -> Finding {
+        Finding::at(line, message.to_string())
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn test_clean_file() {
+        assert_eq!(fixture("clean.toml"), []);
+        let parsed = parse_units(&read("clean.toml"));
+        let (furlong, rankine) = (parsed.units[0].0, parsed.units[1].0);
+        assert_eq!((furlong.name, furlong.aliases, furlong.category), ("furlong", &["furlongs"][..], crate::UnitCategory::Length));
+        assert_eq!((rankine.display_plural, rankine.conversion.offset(), parsed.units[1].1), ("Rankine degrees", -491.67, 8));
+        assert!((rankine.to_base(491.67 + 180.0) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_duplicate_alias() {
+        assert_eq!(
+            fixture("duplicate_alias.toml"),
+            [at(8, "'furlongs' names both furlong and fur"), at(14, "'meters' names both m and metre-ish")]
+        );
+    }
+
+    #[test]
+    fn test_zero_factor() {
+        let findings = fixture("zero_factor.toml");
+        assert_eq!(findings, [at(4, "unit 'nothing' can't have a factor of 0")]);
+    }
+
+    #[test]
+    fn test_bad_category_and_every_problem_reported() {
+        assert_eq!(
+            fixture("bad_category.toml"),
+            [
+                at(3, "unknown category 'energy'; use length, temperature, mass, time, volume, speed, data, angle or currency"),
+                at(6, "unit has no 'name'"),
+                at(13, "'factor' must be a number"),
+                at(14, "unknown key 'offest'"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_syntax_error_and_stray_tables() {
+        assert_eq!(check("[[unit]]\nname = \"x\"\nfactor = \n", &[]), [at(3, "invalid value ''")]);
+        assert_eq!(
+            check("scale = 2\n[units]\n", &[]),
+            [at(1, "unexpected key 'scale' outside a [[unit]] table"), at(2, "unexpected table [units]; units are [[unit]] tables")]
+        );
+    }
+}
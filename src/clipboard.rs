@@ -0,0 +1,168 @@
+//! `--copy` / `--paste`. The system backend shells out to the platform's
+//! clipboard tool and is only compiled with the `clipboard` feature; every
+//! failure is reported as a warning so headless machines keep working.
+
+pub trait Clipboard {
+    fn get(&mut self) -> Result<String, String>;
+    fn set(&mut self, text: &str) -> Result<(), String>;
+}
+
+/// Puts `text` on the clipboard, returning a warning message on failure.
+pub fn copy(clipboard: &mut dyn Clipboard, text: &str) -> Option<String> {
+    match clipboard.set(text) {
+        Ok(()) => None,
+        Err(e) => Some(format!("could not copy to the clipboard: {}", e)),
+    }
+}
+
+/// Reads a value from the clipboard, trimmed of surrounding whitespace.
+pub fn paste(clipboard: &mut dyn Clipboard) -> Result<String, String> {
+    let text = clipboard.get()?;
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("the clipboard is empty".to_string());
+    }
+    Ok(text.to_string())
+}
+
+/// Stands in for the system clipboard when there is none; every call fails
+/// with the reason.
+pub struct Unavailable(pub String);
+
+impl Clipboard for Unavailable {
+    fn get(&mut self) -> Result<String, String> {
+        Err(self.0.clone())
+    }
+
+    fn set(&mut self, _text: &str) -> Result<(), String> {
+        Err(self.0.clone())
+    }
+}
+
+#[cfg(feature = "clipboard")]
+mod system {
+    use std::env;
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    pub struct CommandClipboard {
+        copy: &'static [&'static str],
+        paste: &'static [&'static str],
+    }
+
+    impl CommandClipboard {
+        pub fn detect() -> Result<CommandClipboard, String> {
+            let (copy, paste): (&[&str], &[&str]) = if cfg!(target_os = "macos") {
+                (&["pbcopy"], &["pbpaste"])
+            } else if cfg!(windows) {
+                (&["clip"], &["powershell", "-NoProfile", "-Command", "Get-Clipboard"])
+            } else if env::var_os("WAYLAND_DISPLAY").is_some() {
+                (&["wl-copy"], &["wl-paste", "--no-newline"])
+            } else if env::var_os("DISPLAY").is_some() {
+                if which("xclip") {
+                    (&["xclip", "-selection", "clipboard"], &["xclip", "-selection", "clipboard", "-o"])
+                } else {
+                    (&["xsel", "--clipboard", "--input"], &["xsel", "--clipboard", "--output"])
+                }
+            } else {
+                return Err("no graphical session (DISPLAY and WAYLAND_DISPLAY are unset)".to_string());
+            };
+            Ok(CommandClipboard { copy, paste })
+        }
+    }
+
+    fn which(program: &str) -> bool {
+        env::var_os("PATH").is_some_and(|paths| env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+    }
+
+    impl super::Clipboard for CommandClipboard {
+        fn get(&mut self) -> Result<String, String> {
+            let output = Command::new(self.paste[0])
+                .args(&self.paste[1..])
+                .stderr(Stdio::null())
+                .output()
+                .map_err(|e| format!("{}: {}", self.paste[0], e))?;
+            if !output.status.success() {
+                return Err(format!("{} exited with {}", self.paste[0], output.status));
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        }
+
+        fn set(&mut self, text: &str) -> Result<(), String> {
+            let mut child = Command::new(self.copy[0])
+                .args(&self.copy[1..])
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .map_err(|e| format!("{}: {}", self.copy[0], e))?;
+            child
+                .stdin
+                .take()
+                .expect("piped stdin")
+                .write_all(text.as_bytes())
+                .map_err(|e| e.to_string())?;
+            let status = child.wait().map_err(|e| e.to_string())?;
+            if !status.success() {
+                return Err(format!("{} exited with {}", self.copy[0], status));
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "clipboard")]
+pub fn system() -> Box<dyn Clipboard> {
+    match system::CommandClipboard::detect() {
+        Ok(clipboard) => Box::new(clipboard),
+        Err(reason) => Box::new(Unavailable(reason)),
+    }
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn system() -> Box<dyn Clipboard> {
+    Box::new(Unavailable("clipboard support is not compiled in (rebuild with `--features clipboard`)".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Mock {
+        contents: Option<String>,
+    }
+
+    impl Clipboard for Mock {
+        fn get(&mut self) -> Result<String, String> {
+            self.contents.clone().ok_or_else(|| "no clipboard".to_string())
+        }
+
+        fn set(&mut self, text: &str) -> Result<(), String> {
+            self.contents = Some(text.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_copy_sets_contents() {
+        let mut mock = Mock::default();
+        assert_eq!(copy(&mut mock, "3.11"), None);
+        assert_eq!(mock.contents.as_deref(), Some("3.11"));
+    }
+
+    #[test]
+    fn test_copy_failure_is_a_warning() {
+        let warning = copy(&mut Unavailable("no graphical session".to_string()), "3.11").unwrap();
+        assert_eq!(warning, "could not copy to the clipboard: no graphical session");
+    }
+
+    #[test]
+    fn test_paste_trims_value() {
+        let mut mock = Mock { contents: Some(" 42.5\n".to_string()) };
+        assert_eq!(paste(&mut mock), Ok("42.5".to_string()));
+        let mut empty = Mock { contents: Some("  ".to_string()) };
+        assert_eq!(paste(&mut empty), Err("the clipboard is empty".to_string()));
+        assert!(paste(&mut Mock::default()).is_err());
+    }
+}
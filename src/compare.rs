@@ -0,0 +1,223 @@
+//! `approx` checks whether two quantities agree within a tolerance; `compare`
+//! reports which is larger.
+
+use std::cmp::Ordering;
+
+use crate::{ConvertError, Options, UnitCategory};
+use crate::i18n::{self, tr, tr_with};
+
+const USAGE: &str = "approx <value> <unit> <value> <unit> [--tolerance T | T%]";
+const DEFAULT_TOLERANCE: Tolerance = Tolerance::Relative(0.01);
+
+/// `compare` treats quantities as equal when their relative difference is at
+/// most this, which absorbs rounding in the conversion factors.
+pub const EQUALITY_EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tolerance {
+    /// In the second quantity's unit.
+    Absolute(f64),
+    /// A fraction, so "1%" is 0.01.
+    Relative(f64),
+}
+
+impl Tolerance {
+    pub fn parse(input: &str) -> Result<Tolerance, String> {
+        let invalid = || tr_with("compare.invalid_tolerance", &[&input]);
+        let tolerance = match input.strip_suffix('%') {
+            Some(percent) => Tolerance::Relative(percent.trim().parse::<f64>().map_err(|_| invalid())? / 100.0),
+            None => Tolerance::Absolute(input.parse().map_err(|_| invalid())?),
+        };
+        match tolerance {
+            Tolerance::Absolute(t) | Tolerance::Relative(t) if t >= 0.0 => Ok(tolerance),
+            _ => Err(tr_with("compare.negative_tolerance", &[&input])),
+        }
+    }
+}
+
+/// Two quantities expressed in the second one's unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub a: f64,
+    pub b: f64,
+    pub difference: f64,
+    /// `|a - b| / |b|`. Temperatures are compared on the kelvin scale, since a
+    /// relative difference around 0 °C or 0 °F says nothing about the readings.
+    pub relative: f64,
+    /// `a / b`, also on the kelvin scale for temperatures.
+    pub ratio: f64,
+}
+
+pub fn compare(a: &str, a_unit: &str, b: &str, b_unit: &str) -> Result<Comparison, ConvertError> {
+    let a = crate::convert_checked(a, a_unit, b_unit)?.result;
+    let b = crate::convert_checked(b, b_unit, b_unit)?.value;
+    let unit = crate::find_unit(b_unit).ok_or_else(|| crate::unit_error(b_unit))?;
+    let (abs_a, abs_b) = if unit.category == UnitCategory::Temperature {
+        let kelvin = converter::builtin_unit("K").expect("kelvin is a built-in unit");
+        (crate::convert_units(unit, kelvin, a), crate::convert_units(unit, kelvin, b))
+    } else {
+        (a, b)
+    };
+    let relative = match (abs_a - abs_b).abs() {
+        0.0 => 0.0,
+        d => d / abs_b.abs(),
+    };
+    Ok(Comparison { a, b, difference: (a - b).abs(), relative, ratio: abs_a / abs_b })
+}
+
+pub fn within(comparison: &Comparison, tolerance: Tolerance) -> bool {
+    match tolerance {
+        Tolerance::Absolute(t) => comparison.difference <= t,
+        Tolerance::Relative(t) => comparison.relative <= t,
+    }
+}
+
+/// Runs `approx`: exit 0 when the quantities agree, 1 when they don't and 2 on errors.
+pub fn run_approx(program: &str, args: &[String], options: &Options) -> i32 {
+    let mut positional = Vec::new();
+    let mut tolerance = DEFAULT_TOLERANCE;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--tolerance" || arg == "-t" {
+            let parsed = iter.next().ok_or_else(|| tr_with("requires_value", &[arg])).and_then(|t| Tolerance::parse(t));
+            match parsed {
+                Ok(t) => tolerance = t,
+                Err(e) => {
+                    eprintln!("{}: {}", tr("error"), e);
+                    return 2;
+                }
+            }
+        } else {
+            positional.push(arg.as_str());
+        }
+    }
+    let [a, a_unit, b, b_unit] = positional[..] else {
+        eprintln!("{}: {} {}", tr("usage_label"), program, USAGE);
+        return 2;
+    };
+
+    let comparison = match compare(a, a_unit, b, b_unit) {
+        Ok(comparison) => comparison,
+        Err(e) => {
+            eprintln!("{}: {}", tr("error"), e.localized(i18n::active()));
+            return 2;
+        }
+    };
+    let ok = within(&comparison, tolerance);
+    let details = tr_with(
+        "compare.differs",
+        &[
+            &a,
+            &a_unit,
+            &crate::format_number(comparison.a, options),
+            &b_unit,
+            &b,
+            &crate::format_number(comparison.difference, options),
+            &format!("{:.3}", comparison.relative * 100.0),
+        ],
+    );
+    println!("{}: {}", tr(if ok { "compare.within" } else { "compare.outside" }), details);
+    if ok { 0 } else { 1 }
+}
+
+pub fn ordering(comparison: &Comparison) -> Ordering {
+    if comparison.relative <= EQUALITY_EPSILON {
+        Ordering::Equal
+    } else if comparison.a > comparison.b {
+        Ordering::Greater
+    } else {
+        Ordering::Less
+    }
+}
+
+/// Runs `compare`: exit 0 when the first quantity is larger, 1 when they are
+/// equal, 2 when it is smaller and 3 on errors.
+pub fn run_compare(program: &str, args: &[String], options: &Options) -> i32 {
+    let [a, a_unit, b, b_unit] = args else {
+        eprintln!("{}: {} compare <value> <unit> <value> <unit>", tr("usage_label"), program);
+        return 3;
+    };
+    // The difference in the first unit is converted the other way round, so an
+    // offset between temperature scales can't leak into it.
+    let reversed = compare(b, b_unit, a, a_unit);
+    let (comparison, reversed) = match compare(a, a_unit, b, b_unit).and_then(|c| Ok((c, reversed?))) {
+        Ok(pair) => pair,
+        Err(e) => {
+            eprintln!("{}: {}", tr("error"), e.localized(i18n::active()));
+            return 3;
+        }
+    };
+
+    let order = ordering(&comparison);
+    match order {
+        Ordering::Equal => println!("{}", tr_with("compare.equal", &[a, a_unit, b, b_unit])),
+        _ => {
+            let key = if order == Ordering::Greater { "compare.larger" } else { "compare.smaller" };
+            println!("{}", tr_with(key, &[a, a_unit, b, b_unit]));
+            let (difference, reversed) = (crate::format_number(comparison.difference, options), crate::format_number(reversed.difference, options));
+            println!("  {}", tr_with("compare.by", &[&difference, b_unit, &reversed, a_unit]));
+        }
+    }
+    println!("  {}", tr_with("compare.ratio", &[&crate::format_number(comparison.ratio, options)]));
+    match order {
+        Ordering::Greater => 0,
+        Ordering::Equal => 1,
+        Ordering::Less => 2,
+    }
+}
+
+#[cfg(all(test, any(feature = "length", feature = "temperature")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_relative_tolerance() {
+        let comparison = compare("5", "km", "3.1", "mi").unwrap();
+        assert!((comparison.relative - 0.00221).abs() < 1e-5);
+        assert!(within(&comparison, Tolerance::parse("1%").unwrap()));
+        assert!(!within(&comparison, Tolerance::parse("0.1%").unwrap()));
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_absolute_tolerance_uses_second_unit() {
+        let comparison = compare("1", "m", "99", "cm").unwrap();
+        assert!(within(&comparison, Tolerance::parse("1").unwrap()));
+        assert!(!within(&comparison, Tolerance::parse("0.5").unwrap()));
+    }
+
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn test_temperature_relative_to_kelvin() {
+        // 0.5 °C against 0 °C is "infinitely" different in °C but 0.18% in kelvin.
+        let comparison = compare("0.5", "C", "0", "C").unwrap();
+        assert!((comparison.relative - 0.5 / 273.15).abs() < 1e-12);
+        assert!(within(&comparison, Tolerance::Relative(0.01)));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn test_ordering_outcomes() {
+        let args = |list: [&str; 4]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let options = Options::default();
+        assert_eq!(run_compare("converter", &args(["5", "km", "3", "mi"]), &options), 0);
+        assert_eq!(run_compare("converter", &args(["1", "mi", "1.609344", "km"]), &options), 1);
+        assert_eq!(run_compare("converter", &args(["1", "ft", "1", "m"]), &options), 2);
+        assert_eq!(run_compare("converter", &args(["1", "ft", "1", "kg"]), &options), 3);
+
+        let comparison = compare("100", "C", "212", "F").unwrap();
+        assert_eq!(ordering(&comparison), Ordering::Equal);
+        assert!((compare("2", "m", "100", "cm").unwrap().ratio - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass"))]
+    fn test_errors_and_parsing() {
+        assert!(matches!(compare("5", "km", "5", "kg"), Err(ConvertError::CategoryMismatch { .. })));
+        assert!(Tolerance::parse("-1%").is_err());
+        assert!(Tolerance::parse("abc").is_err());
+        let args: Vec<String> = ["5", "km", "5", "kg"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(run_approx("converter", &args, &Options::default()), 2);
+    }
+}
@@ -0,0 +1,243 @@
+//! Compound units written as an expression over known units: "km/h",
+//! "g/cm^3", "kg·m/s^2". Terms are joined by '/', '*' or '·' from left to
+//! right, and each may carry an integer exponent after '^'. The factors
+//! compose into one linear unit whose dimension is the product of the terms'.
+//! A length may also be squared or cubed by a suffix: "m2", "cm3", "ft²".
+//!
+//! Temperatures and any unit with an offset can't be a term: "°C/s" has no
+//! single zero point, so it is rejected rather than guessed at.
+
+use std::sync::Mutex;
+
+use crate::dimension::Dimension;
+use crate::{Conversion, ConvertError, Unit, UnitCategory};
+
+const MAX_EXPONENT: u8 = 9;
+
+/// Every compound unit built so far, so each expression is leaked only once.
+static BUILT: Mutex<Vec<&'static Unit>> = Mutex::new(Vec::new());
+
+/// Whether `input` is written as an expression rather than a single name.
+pub fn is_expression(input: &str) -> bool {
+    input.contains(['/', '*', '·', '^']) || power_suffix(input).is_some()
+}
+
+/// "m2" or "ft³" split into the word and its power. Any digit counts here,
+/// so that "m4" is read as an attempt and rejected by `parse_term`.
+fn power_suffix(term: &str) -> Option<(&str, u32)> {
+    let last = term.chars().next_back()?;
+    let power = match last {
+        '²' => 2,
+        '³' => 3,
+        _ => last.to_digit(10)?,
+    };
+    let word = &term[..term.len() - last.len_utf8()];
+    word.ends_with(|c: char| c.is_alphabetic()).then_some((word, power))
+}
+
+/// The unit `input` describes, looking each term's unit up with `lookup`.
+/// A term that isn't a unit fails with `lookup`'s error for that term alone.
+pub fn parse(input: &str, lookup: impl Fn(&str) -> Result<&'static Unit, ConvertError>) -> Result<&'static Unit, ConvertError> {
+    let mut terms = Vec::new();
+    let mut name = String::new();
+    let mut rest = input.trim();
+    let mut divide = false;
+    loop {
+        let end = rest.find(['/', '*', '·']).unwrap_or(rest.len());
+        let term = rest[..end].trim();
+        let (unit, exponent) = parse_term(term, &lookup).map_err(|e| e.unwrap_or_else(|| unknown(input)))?;
+        if unit.category == UnitCategory::Temperature || unit.conversion.offset() != 0.0 {
+            return Err(ConvertError::OffsetInCompound { unit: unit.name, input: input.to_string() });
+        }
+        terms.push((unit, if divide { -exponent } else { exponent }));
+        name.push_str(unit.name);
+        if exponent != 1 {
+            name.push_str(&format!("^{}", exponent));
+        }
+
+        let Some(operator) = rest[end..].chars().next() else { break };
+        divide = operator == '/';
+        name.push(if divide { '/' } else { '·' });
+        rest = &rest[end + operator.len_utf8()..];
+    }
+    Ok(build(name, &terms))
+}
+
+/// The long name of `terms`, e.g. "kilometers per hour" or "grams per cubic
+/// centimeter"; `plural` inflects the last term before any "per".
+fn long_name(terms: &[(&Unit, i8)], plural: bool) -> String {
+    let word = |unit: &Unit, power: i8, plural: bool| {
+        let base = if plural { unit.display_plural } else { unit.display_singular };
+        match (power.abs(), unit.category) {
+            (1, _) => base.to_string(),
+            (2, UnitCategory::Length) => format!("square {}", base),
+            (3, UnitCategory::Length) => format!("cubic {}", base),
+            (2, _) => format!("{} squared", base),
+            (3, _) => format!("{} cubed", base),
+            (n, _) => format!("{}^{}", base, n),
+        }
+    };
+    let above: Vec<&(&Unit, i8)> = terms.iter().filter(|(_, power)| *power > 0).collect();
+    let numerator: Vec<String> =
+        above.iter().enumerate().map(|(i, (unit, power))| word(unit, *power, plural && i + 1 == above.len())).collect();
+    let per = terms.iter().filter(|(_, power)| *power < 0).map(|(unit, power)| format!("per {}", word(unit, *power, false)));
+    Some(numerator.join("-")).filter(|n| !n.is_empty()).into_iter().chain(per).collect::<Vec<_>>().join(" ")
+}
+
+/// A term's unit and exponent. `Err(None)` is a term too malformed to name,
+/// such as the empty one in "km/".
+fn parse_term(term: &str, lookup: impl Fn(&str) -> Result<&'static Unit, ConvertError>) -> Result<(&'static Unit, i8), Option<ConvertError>> {
+    if term.is_empty() {
+        return Err(None);
+    }
+    if let Some((word, exponent)) = term.split_once('^') {
+        if word.trim().is_empty() {
+            return Err(Some(ConvertError::MissingUnit(term.to_string())));
+        }
+        return match exponent.trim().parse::<i8>() {
+            Ok(exponent) if (1..=MAX_EXPONENT).contains(&exponent.unsigned_abs()) => Ok((lookup(word.trim()).map_err(Some)?, exponent)),
+            _ => Err(Some(unknown(term))),
+        };
+    }
+    match lookup(term) {
+        Ok(unit) => Ok((unit, 1)),
+        // Only area and volume are written with a suffix; "kg2" stays an unknown word.
+        Err(e) => match power_suffix(term) {
+            Some((word, power @ (2 | 3))) => match lookup(word) {
+                Ok(unit) if unit.category == UnitCategory::Length => Ok((unit, power as i8)),
+                _ => Err(Some(e)),
+            },
+            _ => Err(Some(e)),
+        },
+    }
+}
+
+fn unknown(input: &str) -> ConvertError {
+    ConvertError::UnknownUnit { input: input.to_string(), suggestions: Vec::new() }
+}
+
+fn build(name: String, terms: &[(&'static Unit, i8)]) -> &'static Unit {
+    let mut factor = (1.0, 1.0);
+    let mut dimension = Dimension::NONE;
+    for &(unit, power) in terms {
+        let (numerator, denominator) = unit.conversion.factor();
+        let (numerator, denominator) = if power < 0 { (denominator, numerator) } else { (numerator, denominator) };
+        let n = power.abs().into();
+        factor = (factor.0 * numerator.powi(n), factor.1 * denominator.powi(n));
+        dimension = dimension * unit.dimension().pow(power);
+    }
+    let category = UnitCategory::all().iter().copied().find(|c| c.dimension() == dimension).unwrap_or(UnitCategory::Derived(dimension));
+    let conversion = Conversion::Linear { factor };
+    let mut built = BUILT.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(unit) = built.iter().find(|u| u.name == name && u.category == category && u.conversion == conversion) {
+        return unit;
+    }
+    let leak = |text: String| -> &'static str { Box::leak(text.into_boxed_str()) };
+    let unit: &'static Unit = Box::leak(Box::new(Unit {
+        name: leak(name),
+        aliases: &[],
+        case_sensitive: true,
+        display_singular: leak(long_name(terms, false)),
+        display_plural: leak(long_name(terms, true)),
+        category,
+        conversion,
+    }));
+    built.push(unit);
+    unit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Result<&'static Unit, ConvertError> {
+        super::parse(input, |word| crate::find_unit(word).ok_or_else(|| crate::unit_error(word)))
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "time"))]
+    fn test_speed() {
+        let kmh = parse("km/h").unwrap();
+        assert_eq!((kmh.name, kmh.conversion.factor()), ("km/h", (1000.0, 3600.0)));
+        assert_eq!(kmh.category, UnitCategory::Speed);
+        assert_eq!(parse("m/s^2").unwrap().category, UnitCategory::Derived(Dimension::LENGTH / Dimension::TIME.pow(2)));
+        assert!(std::ptr::eq(kmh, parse("kilometers / hour").unwrap()));
+        assert_eq!((kmh.display_singular, kmh.display_plural), ("kilometer per hour", "kilometers per hour"));
+        assert!((crate::convert(90.0_f64, "km/h", "m/s").unwrap() - 25.0).abs() < 1e-12);
+        assert!((crate::convert(60.0_f64, "mi/h", "km/h").unwrap() - 96.56064).abs() < 1e-9);
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "time"))]
+    fn test_exponents() {
+        let density = parse("g/cm^3").unwrap();
+        assert_eq!(density.category.dimension(), Dimension::MASS / Dimension::LENGTH.pow(3));
+        assert!((crate::convert(1.0_f64, "g/cm^3", "kg/m^3").unwrap() - 1000.0).abs() < 1e-9);
+        assert!((crate::convert(1.0_f64, "m^2", "cm^2").unwrap() - 10_000.0).abs() < 1e-9);
+        assert!((crate::convert(1.0_f64, "m/s^2", "km/h^2").unwrap() - 12_960.0).abs() < 1e-9);
+        assert_eq!(parse("kg*m/s^2").unwrap().name, "kg·m/s^2");
+        assert_eq!(parse("kg*m/s^2").unwrap().display_plural, "kilogram-meters per second squared");
+        assert_eq!(density.display_plural, "grams per cubic centimeter");
+        assert_eq!(parse("s^-1").unwrap().display_singular, "per second");
+        assert_eq!(parse("s^-1").unwrap().category.dimension(), Dimension::TIME.pow(-1));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "time", feature = "volume"))]
+    fn test_area_and_volume_suffixes() {
+        assert!((crate::convert(2.0_f64, "m2", "ft2").unwrap() - 21.527820833419447).abs() < 1e-9);
+        assert!((crate::convert(1.0_f64, "in3", "cm3").unwrap() - 16.387064).abs() < 1e-9);
+        assert!((crate::convert(1.0_f64, "m3", "L").unwrap() - 1000.0).abs() < 1e-9);
+        assert!((crate::convert(250.0_f64, "cm³", "mL").unwrap() - 250.0).abs() < 1e-9);
+        assert_eq!(parse("ft2").unwrap().name, "ft^2");
+        assert_eq!(parse("cm3").unwrap().category, UnitCategory::Volume);
+        assert_eq!(parse("km2/h").unwrap().category.dimension(), Dimension::LENGTH.pow(2) / Dimension::TIME);
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass"))]
+    fn test_suffixes_need_a_length_squared_or_cubed() {
+        let error = crate::convert(1.0, "kg2", "g2").unwrap_err();
+        assert_eq!(error.to_string(), "Unknown unit 'kg2'; did you mean 'kg'?");
+        assert!(matches!(parse("m4"), Err(ConvertError::UnknownUnit { input, .. }) if input == "m4"));
+        assert!(matches!(parse("h2"), Err(ConvertError::UnknownUnit { input, .. }) if input == "h2"));
+        assert!(crate::find_unit("m^4").is_some());
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "time"))]
+    fn test_named_dimensions_keep_their_category() {
+        let unit = parse("m·s/s").unwrap();
+        assert_eq!(unit.category, UnitCategory::Length);
+        assert!((crate::convert(2.0_f64, "m·s/s", "cm").unwrap() - 200.0).abs() < 1e-12);
+    }
+
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn test_temperatures_are_rejected() {
+        assert_eq!(parse("C/s"), Err(ConvertError::OffsetInCompound { unit: "C", input: "C/s".to_string() }));
+        assert_eq!(
+            crate::convert(1.0, "F/min", "C/s").unwrap_err().to_string(),
+            "'F/min' can't include F: temperatures and other units with an offset only convert on their own"
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "time"))]
+    fn test_bad_terms_are_named() {
+        let error = crate::convert(1.0, "km/fortnight", "m/s").unwrap_err();
+        assert!(matches!(&error, ConvertError::UnknownUnit { input, .. } if input == "fortnight"), "{:?}", error);
+        assert_eq!(error.to_string(), "Unknown unit 'fortnight'");
+        assert_eq!(parse("km/hrs").unwrap().name, "km/h");
+        assert!(matches!(parse("m^x"), Err(ConvertError::UnknownUnit { input, .. }) if input == "m^x"));
+        assert!(matches!(parse("m^-128"), Err(ConvertError::UnknownUnit { input, .. }) if input == "m^-128"));
+        assert!(matches!(parse("km/"), Err(ConvertError::UnknownUnit { input, .. }) if input == "km/"));
+        assert!(matches!(crate::convert(1.0, "km/h", "kg"), Err(ConvertError::CategoryMismatch { .. })));
+    }
+
+    #[test]
+    fn test_exponent_needs_a_unit() {
+        assert_eq!(parse("^2"), Err(ConvertError::MissingUnit("^2".to_string())));
+        assert_eq!(parse(" ^3 ").unwrap_err().to_string(), "missing unit before '^' in '^3'");
+    }
+}
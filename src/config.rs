@@ -0,0 +1,418 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::registry::{Calendar, Registry, RegistryError, UnitDef, UnitSystem};
+use crate::{check, toml, UnitCategory};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub history: bool,
+    pub favorites: Vec<Favorite>,
+    /// Target system for the two-argument form, `converter 5 mi`.
+    pub system: Option<UnitSystem>,
+    /// Whose average year "year" and "month" mean.
+    pub calendar: Calendar,
+    /// Units defined by `[[unit]]` tables, in file order.
+    pub units: Vec<UnitDef>,
+    /// Units to hide from lookups and listings, by any of their names.
+    pub disabled_units: Vec<String>,
+    /// When set, only units in these categories are available.
+    pub enabled_categories: Option<Vec<UnitCategory>>,
+    /// What a bare `converter` on a terminal starts.
+    pub startup: Startup,
+    /// The currency rates file when `--rates` doesn't name one.
+    pub rates: Option<String>,
+    /// Fetch currency rates as if `--live` were always given.
+    pub live_rates: bool,
+    /// The rate service for `--live`, when not the default one.
+    pub rates_url: Option<String>,
+    /// How long fetched rates are reused before `--live` fetches again.
+    pub rates_ttl_hours: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Startup {
+    #[default]
+    Wizard,
+    Repl,
+    /// The usage error, as when input is piped.
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Favorite {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+    pub precision: Option<usize>,
+    pub format: Option<String>,
+}
+
+impl Favorite {
+    fn from_table(name: &str, table: &toml::Table) -> Result<Favorite, String> {
+        let field = |key: &str| -> Result<String, String> {
+            table
+                .get(key)
+                .and_then(toml::Value::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| format!("favorite '{}' needs a '{}' unit", name, key))
+        };
+        let precision = match table.get("precision") {
+            None => None,
+            Some(value) => match value.as_f64() {
+                Some(p) if p >= 0.0 && p.fract() == 0.0 => Some(p as usize),
+                _ => return Err(format!("favorite '{}' has an invalid precision", name)),
+            },
+        };
+        let format = match table.get("format") {
+            None => None,
+            Some(value) => Some(
+                value
+                    .as_str()
+                    .ok_or_else(|| format!("favorite '{}' format must be a string", name))?
+                    .to_string(),
+            ),
+        };
+        Ok(Favorite { name: name.to_string(), from: field("from")?, to: field("to")?, precision, format })
+    }
+
+    fn to_toml(&self) -> String {
+        let mut out = format!("[fav.{}]\nfrom = {}\nto = {}\n", self.name, toml::quote(&self.from), toml::quote(&self.to));
+        if let Some(p) = self.precision {
+            out.push_str(&format!("precision = {}\n", p));
+        }
+        if let Some(format) = &self.format {
+            out.push_str(&format!("format = {}\n", toml::quote(format)));
+        }
+        out
+    }
+}
+
+impl Config {
+    pub fn load() -> Config {
+        let Some(path) = config_path() else {
+            return Config::default();
+        };
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(_) => return Config::default(),
+        };
+        match Config::parse(&text) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("Warning: ignoring invalid config {}: {}", path.display(), e);
+                Config::default()
+            }
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Config, String> {
+        let doc = toml::parse(text).map_err(|e| e.to_string())?;
+        let mut config = Config::default();
+        if let Some(value) = doc.get("history") {
+            config.history = value.as_bool().ok_or("'history' must be true or false")?;
+        }
+        if let Some(value) = doc.get("system") {
+            let system = value.as_str().and_then(UnitSystem::parse);
+            config.system = Some(system.ok_or("'system' must be \"metric\", \"imperial\" or \"us\"")?);
+        }
+        if let Some(value) = doc.get("calendar") {
+            let calendar = value.as_str().and_then(Calendar::parse);
+            config.calendar = calendar.ok_or("'calendar' must be \"julian\", \"gregorian\" or \"civil\"")?;
+        }
+        let mut registry = Registry::default();
+        for table in doc.tables.iter().filter(|t| t.array && t.name == "unit") {
+            let mut findings = Vec::new();
+            let def = check::parse_unit(table, &mut findings);
+            if let Some(finding) = findings.first() {
+                return Err(format!("line {}: {}", finding.line.unwrap_or(table.line), finding.message));
+            }
+            let def = def.expect("no findings");
+            registry.register(def.clone()).map_err(|e| format!("line {}: {}", table.line, e))?;
+            config.units.push(def);
+        }
+        if let Some(value) = doc.get("disabled_units") {
+            for unit in string_list(value, "disabled_units")? {
+                if registry.find(unit).is_none() {
+                    return Err(format!("'disabled_units' names unknown unit '{}'", unit));
+                }
+                config.disabled_units.push(unit.to_string());
+            }
+        }
+        if let Some(value) = doc.get("enabled_categories") {
+            let categories = string_list(value, "enabled_categories")?
+                .into_iter()
+                .map(|c| c.parse().map_err(|_| format!("'enabled_categories' names unknown category '{}'", c)))
+                .collect::<Result<_, _>>()?;
+            config.enabled_categories = Some(categories);
+        }
+        if let Some(value) = doc.get("startup") {
+            config.startup = match value.as_str() {
+                Some("wizard") => Startup::Wizard,
+                Some("repl") => Startup::Repl,
+                Some("error") => Startup::Error,
+                _ => return Err("'startup' must be \"wizard\", \"repl\" or \"error\"".to_string()),
+            };
+        }
+        if let Some(value) = doc.get("rates") {
+            config.rates = Some(value.as_str().ok_or("'rates' must be the path of a rates file")?.to_string());
+        }
+        if let Some(value) = doc.get("live_rates") {
+            config.live_rates = value.as_bool().ok_or("'live_rates' must be true or false")?;
+        }
+        if let Some(value) = doc.get("rates_url") {
+            config.rates_url = Some(value.as_str().ok_or("'rates_url' must be the URL of a rate service")?.to_string());
+        }
+        if let Some(value) = doc.get("rates_ttl_hours") {
+            let hours = value.as_f64().filter(|h| *h > 0.0).ok_or("'rates_ttl_hours' must be a positive number of hours")?;
+            config.rates_ttl_hours = Some(hours);
+        }
+        for (name, table) in doc.subtables("fav") {
+            config.favorites.push(Favorite::from_table(name, table)?);
+        }
+        Ok(config)
+    }
+
+    pub fn favorite(&self, name: &str) -> Option<&Favorite> {
+        self.favorites.iter().find(|f| f.name == name)
+    }
+
+    /// Adds units defined elsewhere, such as a rates file's currencies, as
+    /// if the config had `[[unit]]` tables for them.
+    pub fn add_units(&mut self, defs: Vec<UnitDef>) -> Result<(), RegistryError> {
+        let mut registry = Registry::default();
+        for def in self.units.iter().chain(&defs) {
+            registry.register(def.clone())?;
+        }
+        self.units.extend(defs);
+        Ok(())
+    }
+
+    /// The built-in units plus the config's own, minus those it disables.
+    pub fn registry(&self) -> Registry {
+        let mut registry = Registry::default();
+        for def in &self.units {
+            registry.register(def.clone()).expect("checked when the config was parsed");
+        }
+        registry.restrict(&self.disabled_units, self.enabled_categories.clone());
+        registry.set_system(self.system);
+        registry.set_calendar(self.calendar);
+        registry
+    }
+}
+
+fn string_list<'a>(value: &'a toml::Value, key: &str) -> Result<Vec<&'a str>, String> {
+    let invalid = || format!("'{}' must be a list of strings", key);
+    value.as_array().ok_or_else(invalid)?.iter().map(|v| v.as_str().ok_or_else(invalid)).collect()
+}
+
+/// Adds or replaces a favorite, leaving the rest of the file untouched.
+pub fn save_favorite(path: &Path, favorite: &Favorite) -> io::Result<()> {
+    let text = read_existing(path)?;
+    let mut text = remove_section(&text, &format!("fav.{}", favorite.name)).unwrap_or(text);
+    if !text.is_empty() && !text.ends_with("\n\n") {
+        text.push_str(if text.ends_with('\n') { "\n" } else { "\n\n" });
+    }
+    text.push_str(&favorite.to_toml());
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, text)
+}
+
+/// Returns false if no favorite by that name was present.
+pub fn remove_favorite(path: &Path, name: &str) -> io::Result<bool> {
+    let text = read_existing(path)?;
+    match remove_section(&text, &format!("fav.{}", name)) {
+        Some(text) => fs::write(path, text).map(|_| true),
+        None => Ok(false),
+    }
+}
+
+fn read_existing(path: &Path) -> io::Result<String> {
+    match fs::read_to_string(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        other => other,
+    }
+}
+
+fn remove_section(text: &str, table: &str) -> Option<String> {
+    let header = format!("[{}]", table);
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.iter().position(|l| l.trim() == header)?;
+    let end = lines[start + 1..]
+        .iter()
+        .position(|l| l.trim_start().starts_with('['))
+        .map_or(lines.len(), |i| start + 1 + i);
+    let mut kept: Vec<&str> = lines[..start].to_vec();
+    kept.extend(&lines[end..]);
+    while kept.last().is_some_and(|l| l.trim().is_empty()) {
+        kept.pop();
+    }
+    let mut out = kept.join("\n");
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    Some(out)
+}
+
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME").filter(|h| !h.is_empty()).map(PathBuf::from)
+}
+
+fn xdg_dir(var: &str, fallback: &str) -> Option<PathBuf> {
+    env::var_os(var)
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|h| h.join(fallback)))
+        .map(|d| d.join("converter"))
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config").map(|d| d.join("config.toml"))
+}
+
+pub fn state_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_STATE_HOME", ".local/state")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_history_flag() {
+        assert!(Config::parse("history = true").unwrap().history);
+        assert!(!Config::parse("").unwrap().history);
+        assert!(Config::parse("history = \"yes\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_system() {
+        assert_eq!(Config::parse("system = \"metric\"").unwrap().system, Some(UnitSystem::Metric));
+        assert_eq!(Config::parse("system = \"Imperial\"").unwrap().system, Some(UnitSystem::Imperial));
+        assert_eq!(Config::parse("system = \"US\"").unwrap().system, Some(UnitSystem::Us));
+        assert_eq!(Config::parse("").unwrap().system, None);
+        assert!(Config::parse("system = \"nautical\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_calendar() {
+        assert_eq!(Config::parse("calendar = \"gregorian\"").unwrap().calendar, Calendar::Gregorian);
+        assert_eq!(Config::parse("calendar = \"Civil\"").unwrap().calendar, Calendar::Civil);
+        assert_eq!(Config::parse("").unwrap().calendar, Calendar::Julian);
+        assert!(Config::parse("calendar = \"lunar\"").unwrap_err().contains("'calendar' must be"));
+    }
+
+    #[test]
+    #[cfg(feature = "mass")]
+    fn test_parse_unit_restrictions() {
+        let config = Config::parse("disabled_units = [\"ton\", \"ounces\"]\nenabled_categories = [\"length\", \"Mass\"]").unwrap();
+        assert_eq!(config.disabled_units, vec!["ton", "ounces"]);
+        assert_eq!(config.enabled_categories, Some(vec![UnitCategory::Length, UnitCategory::Mass]));
+        assert!(Config::parse("disabled_units = [\"furlong\"]").unwrap_err().contains("unknown unit 'furlong'"));
+        assert!(Config::parse("disabled_units = \"ton\"").is_err());
+        assert!(Config::parse("enabled_categories = [\"energy\"]").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_parse_custom_units() {
+        let text = "disabled_units = [\"fur\"]\n\n[[unit]]\nname = \"furlong\"\naliases = [\"fur\"]\ncategory = \"length\"\nfactor = 201.168\n";
+        let config = Config::parse(text).unwrap();
+        assert_eq!(config.units.len(), 1);
+        assert_eq!((config.units[0].name.as_str(), config.units[0].factor), ("furlong", 201.168));
+        let registry = config.registry();
+        assert!(registry.find("furlong").is_none());
+        assert!(registry.disabled_error("furlong").is_some());
+        let registry = Config { disabled_units: Vec::new(), ..config }.registry();
+        assert_eq!(registry.find("fur").map(|u| u.to_base(10.0)), Some(2011.68));
+        assert!(registry.find("FUR").is_none(), "a short alias matches only as spelled");
+        let config = Config::parse(&text.replace("factor", "case_sensitive = false\nfactor")).unwrap();
+        let registry = Config { disabled_units: Vec::new(), ..config }.registry();
+        assert_eq!(registry.find("FUR").map(|u| u.name), Some("furlong"));
+        let error = Config::parse(&text.replace("factor", "case_sensitive = \"no\"\nfactor"));
+        assert_eq!(error, Err("line 7: 'case_sensitive' must be true or false".to_string()));
+
+        let error = Config::parse("[[unit]]\nname = \"klick\"\naliases = [\"km\"]\ncategory = \"length\"\nfactor = 1000\n");
+        assert_eq!(error, Err("line 1: 'km' already names km".to_string()));
+        let error = Config::parse("\n[[unit]]\nname = \"klick\"\ncategory = \"energy\"\nfactor = 1000\n");
+        assert_eq!(error, Err("line 4: unknown category 'energy'; use length, temperature, mass, time, volume, speed, data, angle or currency".to_string()));
+    }
+
+    #[test]
+    fn test_parse_rates_and_add_units() {
+        let mut config = Config::parse("rates = \"/srv/rates.toml\"\nenabled_categories = [\"currency\"]").unwrap();
+        assert_eq!(config.rates.as_deref(), Some("/srv/rates.toml"));
+        assert!(Config::parse("rates = true").is_err());
+        let usd = UnitDef { name: "USD".to_string(), aliases: Vec::new(), case_sensitive: true, category: UnitCategory::Currency, factor: 1.0, offset: 0.0, singular: None, plural: None };
+        config.add_units(vec![usd.clone()]).unwrap();
+        assert_eq!(config.registry().units().map(|u| u.name).collect::<Vec<_>>(), ["USD"]);
+        assert_eq!(config.add_units(vec![usd]).unwrap_err().to_string(), "'USD' already names USD");
+    }
+
+    #[test]
+    fn test_parse_live_rates() {
+        let config = Config::parse("live_rates = true\nrates_url = \"https://rates.example/latest\"\nrates_ttl_hours = 6").unwrap();
+        assert!(config.live_rates);
+        assert_eq!(config.rates_url.as_deref(), Some("https://rates.example/latest"));
+        assert_eq!(config.rates_ttl_hours, Some(6.0));
+        assert!(!Config::parse("").unwrap().live_rates);
+        assert!(Config::parse("live_rates = \"yes\"").is_err());
+        assert!(Config::parse("rates_ttl_hours = 0").is_err());
+        assert!(Config::parse("rates_ttl_hours = \"1d\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_startup() {
+        assert_eq!(Config::parse("").unwrap().startup, Startup::Wizard);
+        assert_eq!(Config::parse("startup = \"repl\"").unwrap().startup, Startup::Repl);
+        assert_eq!(Config::parse("startup = \"error\"").unwrap().startup, Startup::Error);
+        assert!(Config::parse("startup = \"tui\"").is_err());
+    }
+
+    fn temp_config(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("converter-config-{}-{}", std::process::id(), name)).join("config.toml");
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn body_temp() -> Favorite {
+        Favorite { name: "body-temp".to_string(), from: "F".to_string(), to: "C".to_string(), precision: Some(1), format: None }
+    }
+
+    #[test]
+    fn test_parse_favorites() {
+        let config = Config::parse("[fav.body-temp]\nfrom = \"F\"\nto = \"C\"\nprecision = 1\n").unwrap();
+        assert_eq!(config.favorite("body-temp"), Some(&body_temp()));
+        assert!(Config::parse("[fav.x]\nfrom = \"F\"\n").is_err());
+        assert!(Config::parse("[fav.x]\nfrom = \"F\"\nto = \"C\"\nprecision = -1\n").is_err());
+    }
+
+    #[test]
+    fn test_save_and_remove_favorite() {
+        let path = temp_config("favorites");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "# my settings\nhistory = true\n").unwrap();
+
+        save_favorite(&path, &body_temp()).unwrap();
+        let mut mile = body_temp();
+        mile.name = "run".to_string();
+        mile.format = Some("{result} {to}".to_string());
+        save_favorite(&path, &mile).unwrap();
+        let config = Config::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(config.history);
+        assert_eq!(config.favorites, vec![body_temp(), mile.clone()]);
+
+        mile.to = "K".to_string();
+        save_favorite(&path, &mile).unwrap();
+        assert!(remove_favorite(&path, "body-temp").unwrap());
+        assert!(!remove_favorite(&path, "body-temp").unwrap());
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("# my settings\nhistory = true\n"));
+        assert_eq!(Config::parse(&text).unwrap().favorites, vec![mile]);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,416 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+
+use crate::parallel;
+use crate::progress::{self, Progress};
+use crate::Options;
+use crate::i18n::{self, tr, tr_with};
+
+/// Streams records from RFC 4180 style CSV, including quoted fields with
+/// embedded commas, doubled quotes and newlines.
+pub struct Reader<R> {
+    input: R,
+    line: String,
+    bytes: u64,
+}
+
+impl<R: BufRead> Reader<R> {
+    pub fn new(input: R) -> Reader<R> {
+        Reader { input, line: String::new(), bytes: 0 }
+    }
+
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes
+    }
+
+    pub fn read_record(&mut self) -> io::Result<Option<Vec<String>>> {
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut started = false;
+
+        loop {
+            self.line.clear();
+            let read = self.input.read_line(&mut self.line)?;
+            self.bytes += read as u64;
+            if read == 0 {
+                if in_quotes {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, tr("csv.unterminated")));
+                }
+                if !started {
+                    return Ok(None);
+                }
+                fields.push(field);
+                return Ok(Some(fields));
+            }
+            started = true;
+
+            let mut chars = self.line.chars().peekable();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' if in_quotes && chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    '"' if in_quotes => in_quotes = false,
+                    '"' if field.is_empty() => in_quotes = true,
+                    ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+                    '\r' | '\n' if !in_quotes => {
+                        fields.push(field);
+                        return Ok(Some(fields));
+                    }
+                    c => field.push(c),
+                }
+            }
+        }
+    }
+}
+
+pub fn field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn write_record(out: &mut impl Write, fields: &[String]) -> io::Result<()> {
+    let line: Vec<String> = fields.iter().map(|f| field(f)).collect();
+    writeln!(out, "{}", line.join(","))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    Name(String),
+    /// 1-based, like `cut -f`.
+    Index(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Job {
+    pub column: Column,
+    pub from: String,
+    pub to: String,
+    pub rename: Option<String>,
+    pub error_on_missing: bool,
+    pub header: bool,
+    pub output: Option<String>,
+}
+
+const USAGE: &str = "csv <input.csv | -> --column <name|N> --from <unit> --to <unit> \
+    [--rename NAME] [--output FILE] [--no-header] [--missing blank|error]";
+
+fn parse_job(args: &[String]) -> Result<(String, Job), String> {
+    let mut input = None;
+    let (mut column, mut from, mut to, mut rename, mut output) = (None, None, None, None, None);
+    let mut error_on_missing = false;
+    let mut header = true;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().cloned().ok_or_else(|| tr_with("requires_value", &[arg]));
+        match arg.as_str() {
+            "--column" | "-c" => column = Some(value()?),
+            "--from" => from = Some(value()?),
+            "--to" => to = Some(value()?),
+            "--rename" => rename = Some(value()?),
+            "--output" | "-o" => output = Some(value()?),
+            "--no-header" => header = false,
+            "--missing" => {
+                error_on_missing = match value()?.as_str() {
+                    "blank" => false,
+                    "error" => true,
+                    other => return Err(tr_with("csv.bad_missing", &[&other])),
+                }
+            }
+            _ if input.is_none() => input = Some(arg.clone()),
+            _ => return Err(tr_with("unexpected_argument", &[arg])),
+        }
+    }
+
+    let column = column.ok_or(tr("csv.column_required"))?;
+    let column = match column.parse::<usize>() {
+        Ok(0) => return Err(tr("csv.column_from_one").to_string()),
+        Ok(n) => Column::Index(n),
+        Err(_) if header => Column::Name(column),
+        Err(_) => return Err(tr("csv.numeric_column").to_string()),
+    };
+    if rename.is_some() && !header {
+        return Err(tr("csv.rename_needs_header").to_string());
+    }
+    let job = Job {
+        column,
+        from: from.ok_or(tr("csv.from_required"))?,
+        to: to.ok_or(tr("csv.to_required"))?,
+        rename,
+        error_on_missing,
+        header,
+        output,
+    };
+    Ok((input.ok_or(tr("csv.input_required"))?, job))
+}
+
+/// Runs the `csv` subcommand, returning the process exit code.
+pub fn run(program: &str, args: &[String], options: &Options) -> i32 {
+    let (path, job) = match parse_job(args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{}: {}", tr("error"), e);
+            eprintln!("{}: {} {}", tr("usage_label"), program, USAGE);
+            return 1;
+        }
+    };
+    if let Err(e) = crate::convert_checked("0", &job.from, &job.to) {
+        eprintln!("{}: {}", tr("error"), e.localized(i18n::active()));
+        return 1;
+    }
+
+    let input: Box<dyn BufRead> = if path == "-" {
+        Box::new(io::stdin().lock())
+    } else {
+        match File::open(&path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(e) => {
+                eprintln!("{}: {}", tr("error"), tr_with("csv.cannot_read", &[&path, &e]));
+                return 1;
+            }
+        }
+    };
+    let mut out: Box<dyn Write> = match &job.output {
+        Some(output) => match File::create(output) {
+            Ok(file) => Box::new(io::BufWriter::new(file)),
+            Err(e) => {
+                eprintln!("{}: {}", tr("error"), tr_with("csv.cannot_write", &[output, &e]));
+                return 1;
+            }
+        },
+        None => Box::new(io::BufWriter::new(io::stdout().lock())),
+    };
+
+    let mut progress = progress::for_input(&path, options);
+    match convert_csv(input, &mut out, &job, options, progress.as_mut()).and_then(|rows| out.flush().map_err(|e| e.to_string()).map(|_| rows)) {
+        Ok(_) => 0,
+        Err(e) => {
+            eprintln!("{}: {}", tr("error"), e);
+            1
+        }
+    }
+}
+
+/// Converts one column row by row, copying every other field untouched.
+/// Rows are numbered as in a spreadsheet, so the header is row 1.
+pub fn convert_csv(
+    input: impl BufRead,
+    out: &mut impl Write,
+    job: &Job,
+    options: &Options,
+    progress: &mut dyn Progress,
+) -> Result<usize, String> {
+    let io_err = |e: io::Error| e.to_string();
+    let mut reader = Reader::new(input);
+    let mut row = 0;
+
+    let index = match (&job.column, job.header) {
+        (column, true) => {
+            row += 1;
+            let Some(mut header) = reader.read_record().map_err(io_err)? else {
+                return Ok(0);
+            };
+            let index = match column {
+                Column::Index(n) if *n <= header.len() => n - 1,
+                Column::Index(n) => return Err(tr_with("csv.column_out_of_range", &[n, &header.len()])),
+                Column::Name(name) => header
+                    .iter()
+                    .position(|h| h.trim() == name)
+                    .ok_or_else(|| tr_with("csv.no_such_column", &[name]))?,
+            };
+            if let Some(new_name) = &job.rename {
+                header[index] = new_name.clone();
+            }
+            write_record(out, &header).map_err(io_err)?;
+            index
+        }
+        (Column::Index(n), false) => n - 1,
+        (Column::Name(_), false) => unreachable!("parse_job rejects named columns without a header"),
+    };
+
+    let jobs = parallel::jobs(options);
+    let mut converted = 0;
+    loop {
+        // Each record is kept with its row number and the bytes read once it was.
+        let mut chunk = Vec::new();
+        while chunk.len() < parallel::CHUNK_SIZE {
+            let Some(record) = reader.read_record().map_err(io_err)? else {
+                break;
+            };
+            row += 1;
+            chunk.push((row, reader.bytes_read(), record));
+        }
+        if chunk.is_empty() {
+            break;
+        }
+        let results = parallel::map_ordered(&chunk, jobs, |(row, _, record)| convert_record(*row, record, index, job, options));
+        for ((row, bytes, _), result) in chunk.iter().zip(results) {
+            progress.tick(*row, *bytes);
+            let (record, changed) = result?;
+            converted += changed as usize;
+            write_record(out, &record).map_err(io_err)?;
+        }
+    }
+    progress.finish();
+    Ok(converted)
+}
+
+/// The record with the cell at `index` converted, and whether there was one to convert.
+fn convert_record(row: usize, record: &[String], index: usize, job: &Job, options: &Options) -> Result<(Vec<String>, bool), String> {
+    let mut record = record.to_vec();
+    let cell = record.get(index).map(|c| c.trim()).unwrap_or("");
+    if cell.is_empty() {
+        if job.error_on_missing {
+            return Err(tr_with("csv.missing_value", &[&row, &(index + 1)]));
+        }
+        return Ok((record, false));
+    }
+    let result = crate::convert_checked(cell, &job.from, &job.to).map_err(|e| tr_with("csv.row", &[&row, &e.localized(i18n::active())]))?;
+    let text = crate::format_number(result.result, options);
+    match record.get_mut(index) {
+        Some(slot) => *slot = text,
+        None => record.push(text),
+    }
+    Ok((record, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> BufReader<File> {
+        let path = format!("{}/tests/fixtures/csv/{}", env!("CARGO_MANIFEST_DIR"), name);
+        BufReader::new(File::open(path).unwrap())
+    }
+
+    fn job(column: Column) -> Job {
+        Job {
+            column,
+            from: "kg".to_string(),
+            to: "lb".to_string(),
+            rename: None,
+            error_on_missing: false,
+            header: true,
+            output: None,
+        }
+    }
+
+    fn run_job(name: &str, job: &Job) -> Result<String, String> {
+        let mut out = Vec::new();
+        let options = Options { precision: Some(2), ..Options::default() };
+        convert_csv(fixture(name), &mut out, job, &options, &mut progress::Silent).map(|_| String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_reader_handles_quotes() {
+        let input: &[u8] = b"a,\"b,c\",\"say \"\"hi\"\"\"\r\n\"multi\nline\",,x\n";
+        let mut reader = Reader::new(input);
+        assert_eq!(reader.read_record().unwrap().unwrap(), ["a", "b,c", "say \"hi\""]);
+        assert_eq!(reader.read_record().unwrap().unwrap(), ["multi\nline", "", "x"]);
+        assert_eq!(reader.read_record().unwrap(), None);
+        assert!(Reader::new(&b"\"open"[..]).read_record().is_err());
+    }
+
+    #[test]
+    fn test_field_quoting() {
+        assert_eq!(field("plain"), "plain");
+        assert_eq!(field("a,b"), "\"a,b\"");
+        assert_eq!(field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    #[cfg(feature = "mass")]
+    fn test_convert_named_column_with_rename() {
+        let mut job = job(Column::Name("weight_kg".to_string()));
+        job.rename = Some("weight_lb".to_string());
+        let out = run_job("weights.csv", &job).unwrap();
+        assert_eq!(
+            out,
+            "name,weight_lb,note\nAda,132.28,\"likes tea, not coffee\"\nBob,198.42,\"says \"\"hi\"\"\"\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "mass")]
+    fn test_progress_ticks_per_record() {
+        let size = std::fs::metadata(format!("{}/tests/fixtures/csv/weights.csv", env!("CARGO_MANIFEST_DIR"))).unwrap().len();
+        let mut recorder = progress::Recorder::default();
+        let job = job(Column::Name("weight_kg".to_string()));
+        convert_csv(fixture("weights.csv"), &mut Vec::new(), &job, &Options::default(), &mut recorder).unwrap();
+        assert_eq!(recorder.ticks.iter().map(|t| t.0).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(recorder.ticks.last().unwrap().1, size);
+        assert!(recorder.finished);
+    }
+
+    #[test]
+    #[cfg(feature = "mass")]
+    fn test_parallel_rows_match_serial() {
+        let input: String = std::iter::once("name,weight_kg\n".to_string())
+            .chain((0..parallel::CHUNK_SIZE + 500).map(|i| format!("row {},{}.25\n", i, i)))
+            .collect();
+        let convert = |jobs| {
+            let mut out = Vec::new();
+            let options = Options { jobs: Some(jobs), ..Options::default() };
+            let job = job(Column::Name("weight_kg".to_string()));
+            convert_csv(input.as_bytes(), &mut out, &job, &options, &mut progress::Silent).unwrap();
+            out
+        };
+        assert_eq!(convert(1), convert(6));
+
+        let bad = input.replace("row 8600,8600.25", "row 8600,heavy");
+        let options = Options { jobs: Some(6), ..Options::default() };
+        let err = convert_csv(bad.as_bytes(), &mut Vec::new(), &job(Column::Index(2)), &options, &mut progress::Silent).unwrap_err();
+        assert_eq!(err, "row 8602: 'heavy' is not a valid number");
+    }
+
+    #[test]
+    fn test_convert_indexed_column_without_header() {
+        let mut job = job(Column::Index(2));
+        job.header = false;
+        let out = run_job("weights.csv", &job).unwrap_err();
+        assert_eq!(out, "row 1: 'weight_kg' is not a valid number");
+    }
+
+    #[test]
+    #[cfg(feature = "mass")]
+    fn test_missing_value() {
+        let out = run_job("missing.csv", &job(Column::Name("weight_kg".to_string()))).unwrap();
+        assert_eq!(out, "name,weight_kg\nAda,132.28\nBob,\nCy,22.05\n");
+
+        let mut strict = job(Column::Index(2));
+        strict.error_on_missing = true;
+        assert_eq!(run_job("missing.csv", &strict).unwrap_err(), "row 3: missing value in column 2");
+    }
+
+    #[test]
+    #[cfg(feature = "mass")]
+    fn test_non_numeric_cell_reports_row() {
+        let err = run_job("bad.csv", &job(Column::Name("weight_kg".to_string()))).unwrap_err();
+        assert_eq!(err, "row 4: 'heavy' is not a valid number");
+    }
+
+    #[test]
+    fn test_unknown_column() {
+        let err = run_job("weights.csv", &job(Column::Name("mass".to_string()))).unwrap_err();
+        assert_eq!(err, "no column named 'mass' in the header");
+        assert!(run_job("weights.csv", &job(Column::Index(9))).is_err());
+    }
+
+    #[test]
+    fn test_parse_job() {
+        let args: Vec<String> = ["in.csv", "--column", "3", "--from", "kg", "--to", "lb", "--no-header"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let (input, job) = parse_job(&args).unwrap();
+        assert_eq!(input, "in.csv");
+        assert_eq!(job.column, Column::Index(3));
+        assert!(!job.header);
+        assert!(parse_job(&args[..6]).is_err());
+    }
+}
@@ -0,0 +1,115 @@
+//! `info`: everything about one unit, e.g. `info km`: its names, category,
+//! size in the category's base unit, measurement system, and what one of
+//! it is in a couple of other units.
+
+use converter::info::UnitInfo;
+use converter::{Unit, registry};
+
+use crate::i18n::{self, tr};
+
+/// Other units one `unit` is shown in: its counterpart in the other
+/// measurement system, the base unit, then the rest of its category.
+fn equivalents(unit: &Unit) -> Vec<&'static Unit> {
+    let registry = registry::active();
+    let counterparts = crate::COUNTERPARTS.iter().filter_map(|&(imperial, metric)| match unit.name {
+        name if name == imperial => Some(metric),
+        name if name == metric => Some(imperial),
+        _ => None,
+    });
+    let base = crate::base_unit(registry, unit.category).map(|base| base.name);
+    let mut shown: Vec<&'static Unit> = Vec::new();
+    for other in counterparts.chain(base).filter_map(|name| registry.find(name)).chain(registry.by_category(unit.category)) {
+        if other.name != unit.name && other.category == unit.category && !shown.iter().any(|u| u.name == other.name) {
+            shown.push(other);
+        }
+    }
+    shown.truncate(2);
+    shown
+}
+
+/// The `info` page for `unit`, one labeled line per fact.
+pub fn describe(unit: &Unit, options: &crate::Options) -> String {
+    let info = UnitInfo::of(unit);
+    let base = crate::base_unit(registry::active(), unit.category);
+    let relation = match crate::relation(unit, base) {
+        relation if relation.is_empty() => tr("info.is_base").to_string(),
+        relation => relation,
+    };
+    let mut equals = format!("1 {}", unit.name);
+    for other in equivalents(unit) {
+        equals.push_str(&format!(" = {} {}", crate::format_number(crate::convert_units(unit, other, 1.0), options), other.name));
+    }
+    let mut rows = vec![(tr("info.category"), unit.category.localized_name(i18n::active()).to_string())];
+    if !info.aliases.is_empty() {
+        rows.push((tr("info.aliases"), info.aliases.join(", ")));
+    }
+    rows.push((tr("info.base"), relation));
+    if !info.systems.is_empty() {
+        rows.push((tr("info.system"), info.systems.join(", ")));
+    }
+    rows.push((tr("info.equals"), equals));
+    let width = rows.iter().map(|(label, _)| label.chars().count()).max().unwrap_or(0) + 1;
+    let mut out = format!("{} ({})\n", unit.name, unit.display_singular);
+    for (label, value) in rows {
+        out.push_str(&format!("  {:width$} {}\n", format!("{}:", label), value));
+    }
+    out
+}
+
+/// Runs the `info` subcommand, returning the process exit code.
+pub fn run(program: &str, args: &[String], options: &crate::Options) -> i32 {
+    let [name] = args else {
+        eprintln!("{}: {} info <unit>", tr("usage_label"), program);
+        return 1;
+    };
+    let Some(unit) = converter::find_unit(name) else {
+        return crate::report_error(program, &converter::unit_error(name), options);
+    };
+    if options.json {
+        println!("{}", UnitInfo::of(unit).to_json());
+    } else {
+        print!("{}", describe(unit, options));
+    }
+    0
+}
+
+#[cfg(all(test, any(feature = "length", feature = "temperature", feature = "time")))]
+mod tests {
+    use super::*;
+    use crate::Options;
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_length_unit() {
+        let page = describe(converter::find_unit("km").unwrap(), &Options::default());
+        let expected = "km (kilometer)
+  Category: Length
+  Aliases:  kilometer, kilometers, kilometre, kilometres
+  Base:     1 km = 1000 m
+  System:   metric
+  Equals:   1 km = 0.621371192237334 mi = 1000 m
+";
+        assert_eq!(page, expected);
+        let page = describe(converter::find_unit("m").unwrap(), &Options::default());
+        assert!(page.contains("  Base:     base unit of its category\n"), "{}", page);
+        assert!(page.contains("  Equals:   1 m = 3.28083989501312 ft = 1.09361329833771 yd\n"), "{}", page);
+    }
+
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn test_temperature_unit_shows_the_formula() {
+        let page = describe(converter::find_unit("fahrenheit").unwrap(), &Options::default());
+        assert!(page.starts_with("F (degree Fahrenheit)\n"), "{}", page);
+        assert!(page.contains("  Base:     F = C × 9/5 + 32\n"), "{}", page);
+        assert!(page.contains("  System:   imperial\n"), "{}", page);
+        assert!(page.contains("  Equals:   1 F = -17.2222222222222 C = 255.927777777778 K\n"), "{}", page);
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn test_units_in_both_systems_have_no_system_line() {
+        let page = describe(converter::find_unit("s").unwrap(), &Options::default());
+        assert!(!page.contains("System:"), "{}", page);
+        assert!(page.contains("  Equals:   1 s = 1000 ms = "), "{}", page);
+    }
+}
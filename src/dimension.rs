@@ -0,0 +1,135 @@
+//! Physical dimensions as exponents over the seven SI base dimensions, so
+//! speed is L¹·T⁻¹ and area L², plus money, information and angle, which
+//! SI leaves out or calls dimensionless but which convert into nothing else
+//! either. Two units convert into each other exactly when their dimensions
+//! are equal.
+//!
+//! ```
+//! use converter::dimension::Dimension;
+//!
+//! let speed = Dimension::LENGTH / Dimension::TIME;
+//! assert_eq!(speed.to_string(), "L¹·T⁻¹");
+//! assert_eq!(speed * Dimension::TIME, Dimension::LENGTH);
+//! assert_eq!(Dimension::LENGTH.pow(2).to_string(), "L²");
+//! ```
+
+use core::fmt;
+use core::ops::{Div, Mul};
+
+/// Length, mass, time, temperature, current, amount of substance, luminous
+/// intensity, money, information and angle.
+const SYMBOLS: [&str; 10] = ["L", "M", "T", "Θ", "I", "N", "J", "¤", "D", "A"];
+
+const SUPERSCRIPTS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+
+/// The exponent of each base dimension, in `SYMBOLS` order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Dimension(pub [i8; 10]);
+
+impl Dimension {
+    pub const NONE: Dimension = Dimension([0; 10]);
+    pub const LENGTH: Dimension = Dimension::base(0);
+    pub const MASS: Dimension = Dimension::base(1);
+    pub const TIME: Dimension = Dimension::base(2);
+    pub const TEMPERATURE: Dimension = Dimension::base(3);
+    pub const CURRENT: Dimension = Dimension::base(4);
+    pub const AMOUNT: Dimension = Dimension::base(5);
+    pub const LUMINOSITY: Dimension = Dimension::base(6);
+    pub const CURRENCY: Dimension = Dimension::base(7);
+    pub const INFORMATION: Dimension = Dimension::base(8);
+    pub const ANGLE: Dimension = Dimension::base(9);
+
+    const fn base(index: usize) -> Dimension {
+        let mut exponents = [0; 10];
+        exponents[index] = 1;
+        Dimension(exponents)
+    }
+
+    pub fn is_dimensionless(self) -> bool {
+        self == Dimension::NONE
+    }
+
+    /// Every exponent times `n`: area is `LENGTH.pow(2)`, frequency `TIME.pow(-1)`.
+    pub fn pow(self, n: i8) -> Dimension {
+        Dimension(self.0.map(|e| e.saturating_mul(n)))
+    }
+
+    fn zip(self, other: Dimension, op: impl Fn(i8, i8) -> i8) -> Dimension {
+        Dimension(core::array::from_fn(|i| op(self.0[i], other.0[i])))
+    }
+}
+
+impl Mul for Dimension {
+    type Output = Dimension;
+
+    fn mul(self, other: Dimension) -> Dimension {
+        self.zip(other, i8::saturating_add)
+    }
+}
+
+impl Div for Dimension {
+    type Output = Dimension;
+
+    fn div(self, other: Dimension) -> Dimension {
+        self.zip(other, i8::saturating_sub)
+    }
+}
+
+/// "L¹·T⁻¹", with every nonzero exponent written out; "1" when dimensionless.
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_dimensionless() {
+            return f.write_str("1");
+        }
+        let factors = SYMBOLS.iter().zip(self.0).filter(|(_, exponent)| *exponent != 0);
+        for (i, (symbol, exponent)) in factors.enumerate() {
+            if i > 0 {
+                f.write_str("·")?;
+            }
+            f.write_str(symbol)?;
+            if exponent < 0 {
+                f.write_str("⁻")?;
+            }
+            write_superscript(f, exponent.unsigned_abs())?;
+        }
+        Ok(())
+    }
+}
+
+fn write_superscript(f: &mut fmt::Formatter, n: u8) -> fmt::Result {
+    if n >= 10 {
+        write_superscript(f, n / 10)?;
+    }
+    write!(f, "{}", SUPERSCRIPTS[usize::from(n % 10)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let speed = Dimension::LENGTH / Dimension::TIME;
+        assert_eq!(speed.0, [1, 0, -1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(speed * Dimension::TIME, Dimension::LENGTH);
+        let force = Dimension::MASS * speed / Dimension::TIME;
+        assert_eq!(force, Dimension::MASS * Dimension::LENGTH * Dimension::TIME.pow(-2));
+        let density = Dimension::MASS / Dimension::LENGTH.pow(3);
+        assert_eq!(density.0, [-3, 1, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert!((Dimension::LENGTH / Dimension::LENGTH).is_dimensionless());
+        assert_eq!(speed.pow(0), Dimension::NONE);
+        assert_eq!(speed.pow(2), speed * speed);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Dimension::LENGTH.to_string(), "L¹");
+        assert_eq!((Dimension::LENGTH / Dimension::TIME).to_string(), "L¹·T⁻¹");
+        assert_eq!((Dimension::MASS / Dimension::LENGTH.pow(3)).to_string(), "L⁻³·M¹");
+        assert_eq!(Dimension::TEMPERATURE.pow(12).to_string(), "Θ¹²");
+        assert_eq!(Dimension::NONE.to_string(), "1");
+        assert_eq!((Dimension::CURRENCY / Dimension::TIME).to_string(), "T⁻¹·¤¹");
+        assert_eq!((Dimension::INFORMATION / Dimension::TIME).to_string(), "T⁻¹·D¹");
+        assert_eq!((Dimension::ANGLE / Dimension::TIME).to_string(), "T⁻¹·A¹");
+    }
+}
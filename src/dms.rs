@@ -0,0 +1,125 @@
+//! Angles in degrees, minutes and seconds, such as `12°34'56"` or
+//! `45d30m15s`. They are read as a value in degrees (`converter 12°34'56"
+//! deg rad`) and written by `--dms`.
+
+/// The markers after each component, largest first. The longest that
+/// matches wins, so `''` reads as seconds rather than as minutes.
+const MARKERS: [&[&str]; 3] = [&["°", "d"], &["′", "’", "'", "m"], &["″", "”", "\"", "''", "s"]];
+
+/// Parses `12°34'56"`, `45d30m15s`, `-0°30′` or `1° 2′ 3.5″` into degrees.
+/// The components come largest first, each at most once; only the last may
+/// have a fraction, and minutes or seconds after another component must be
+/// below 60. A leading '-' negates the whole angle. Without any marker this
+/// is not DMS, so plain numbers are left to the usual parsing.
+pub fn parse(input: &str) -> Option<f64> {
+    let input = input.trim();
+    let (negative, mut rest) = match input.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, input),
+    };
+    let mut total = 0.0;
+    let mut next = 0;
+    let mut fractional = false;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+        let number: f64 = rest[..digits].parse().ok()?;
+        rest = rest[digits..].trim_start();
+        let (rank, marker) = (next..MARKERS.len())
+            .flat_map(|rank| MARKERS[rank].iter().map(move |marker| (rank, marker)))
+            .filter(|(_, marker)| rest.starts_with(*marker))
+            .max_by_key(|(_, marker)| marker.len())?;
+        if fractional || (next > 0 && number >= 60.0) {
+            return None;
+        }
+        fractional = number.fract() != 0.0;
+        total += number / 60f64.powi(rank as i32);
+        next = rank + 1;
+        rest = rest[marker.len()..].trim_start();
+    }
+    if next == 0 {
+        return None;
+    }
+    Some(if negative { -total } else { total })
+}
+
+/// Writes `degrees` as `57°17′44.81″`, with the seconds rounded to `places`
+/// decimals and their trailing zeros dropped. Rounding carries through the
+/// minutes and degrees (`0°59′59.999″` at two places is `1°00′00″`), and the
+/// sign goes on the degrees alone. `places` past 9 are taken as 9, about as
+/// far as an `f64` of degrees is exact.
+pub fn format(degrees: f64, places: usize) -> String {
+    let places = places.min(9);
+    let scale = 10u128.pow(places as u32);
+    let total = (degrees.abs() * 3600.0 * scale as f64).round() as u128;
+    let (whole, fraction) = (total / scale, total % scale);
+    let sign = if degrees < 0.0 && total > 0 { "-" } else { "" };
+    let fraction = format!("{:0width$}", fraction, width = places);
+    let fraction = fraction.trim_end_matches('0');
+    let point = if fraction.is_empty() { "" } else { "." };
+    format!("{}{}°{:02}′{:02}{}{}″", sign, whole / 3600, whole / 60 % 60, whole % 60, point, fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let close = |input: &str, expected: f64| {
+            let degrees = parse(input).unwrap_or_else(|| panic!("{}", input));
+            assert!((degrees - expected).abs() < 1e-12, "{}: {}", input, degrees);
+        };
+        close("12°34'56\"", 12.0 + 34.0 / 60.0 + 56.0 / 3600.0);
+        close("45d30m15s", 45.50416666666667);
+        close("45°30'15\"", 45.50416666666667);
+        close("45°30′15″", 45.50416666666667);
+        close("45° 30′ 15.5″", 45.0 + 30.0 / 60.0 + 15.5 / 3600.0);
+        close("45°30'15''", 45.50416666666667);
+        close("45°15''", 45.0 + 15.0 / 3600.0);
+        close("-12°30'", -12.5);
+        close("90'", 1.5);
+        close("1.5°", 1.5);
+        close("30″", 30.0 / 3600.0);
+        for bad in ["12", "", "-", "°", "12°34", "12'34°", "12°12°", "12.5°30'", "12°60'", "12°30'75\"", "12°x", "12 deg"] {
+            assert_eq!(parse(bad), None, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format(57.29577951308232, 2), "57°17′44.81″");
+        assert_eq!(format(1.0, 2), "1°00′00″");
+        assert_eq!(format(45.50416666666667, 0), "45°30′15″");
+        assert_eq!(format(0.5, 3), "0°30′00″");
+        assert_eq!(format(12.0 + 34.0 / 60.0 + 56.25 / 3600.0, 4), "12°34′56.25″");
+    }
+
+    #[test]
+    fn test_rounding_carries_through_minutes_and_degrees() {
+        let just_under = 59.0 / 60.0 + 59.999 / 3600.0;
+        assert_eq!(format(just_under, 2), "1°00′00″");
+        assert_eq!(format(just_under, 3), "0°59′59.999″");
+        assert_eq!(format(10.0 + 12.0 / 60.0 + 59.9996 / 3600.0, 3), "10°13′00″");
+        assert_eq!(format(-(29.0 + 59.0 / 60.0 + 59.96 / 3600.0), 1), "-30°00′00″");
+    }
+
+    #[test]
+    fn test_negative_angles_sign_only_the_degrees() {
+        assert_eq!(format(-12.5, 2), "-12°30′00″");
+        assert_eq!(format(-0.5, 2), "-0°30′00″");
+        assert_eq!(format(-1e-9, 2), "0°00′00″");
+        assert_eq!(parse("-0°30′"), Some(-0.5));
+    }
+
+    #[test]
+    fn test_round_trips() {
+        for degrees in [0.0, 1.0, -1.0, 12.582222, -45.504166, 179.999999, 359.5, -0.0001, 1234.56789] {
+            for places in [0, 2, 5] {
+                let text = format(degrees, places);
+                let back = parse(&text).unwrap_or_else(|| panic!("{}", text));
+                assert!((back - degrees).abs() <= 0.5 / 3600.0 / 10f64.powi(places as i32) + 1e-12, "{} at {}: {}", degrees, places, text);
+                assert_eq!(format(back, places), text);
+            }
+        }
+    }
+}
@@ -0,0 +1,237 @@
+//! `doctor`: checks the invariants the conversion code relies on against the
+//! active unit table and reports every violation. Each check is a plain
+//! function over a list of units so the tests can run it on broken fixtures.
+
+use std::fmt;
+
+use crate::i18n::tr;
+use crate::{Unit, UnitCategory};
+
+/// Largest round-trip error tolerated, relative to the value (or to the
+/// unit's offset when that is larger, since adding it drops the low digits).
+const ROUND_TRIP_TOLERANCE: f64 = 1e-12;
+const SAMPLE_MAGNITUDES: [f64; 7] = [1e-6, 1e-3, 0.5, 1.0, 1e3, 1e6, 1e9];
+
+/// The unit every other unit of a category converts through.
+const BASE_UNITS: [(UnitCategory, &str); 8] = [
+    (UnitCategory::Length, "m"),
+    (UnitCategory::Temperature, "C"),
+    (UnitCategory::Mass, "kg"),
+    (UnitCategory::Time, "s"),
+    (UnitCategory::Volume, "m3"),
+    (UnitCategory::Speed, "m/s"),
+    (UnitCategory::Data, "b"),
+    (UnitCategory::Angle, "deg"),
+];
+
+/// A violated invariant, attributed to the unit that breaks it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    pub unit: &'static str,
+    pub message: String,
+}
+
+impl Problem {
+    fn new(unit: &'static str, message: String) -> Problem {
+        Problem { unit, message }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+type Check = fn(&[&Unit]) -> Vec<Problem>;
+
+pub const CHECKS: [(&str, Check); 5] = [
+    ("finite, nonzero factors", finite_factors),
+    ("unique names and aliases", unique_names),
+    ("base units", base_units),
+    ("round trips", round_trips),
+    ("conversion invariants", invariants),
+];
+
+pub fn finite_factors(units: &[&Unit]) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    for unit in units {
+        let (numerator, denominator) = unit.conversion.factor();
+        if [numerator, denominator].iter().any(|f| !f.is_finite() || *f == 0.0) {
+            problems.push(Problem::new(unit.name, format!("{} has factor {}/{}", unit.name, numerator, denominator)));
+        }
+        if !unit.conversion.offset().is_finite() {
+            problems.push(Problem::new(unit.name, format!("{} has offset {}", unit.name, unit.conversion.offset())));
+        }
+    }
+    problems
+}
+
+/// Names and aliases are matched ignoring ASCII case, so that's how they
+/// must differ, unless a word is one of the intended `HOMONYMS`.
+pub fn unique_names(units: &[&Unit]) -> Vec<Problem> {
+    let clashes = converter::registry::name_clashes(units.iter().copied());
+    clashes.into_iter().map(|clash| Problem::new(clash.second, clash.to_string())).collect()
+}
+
+pub fn base_units(units: &[&Unit]) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    for (category, name) in BASE_UNITS {
+        if !units.iter().any(|u| u.category == category) {
+            continue;
+        }
+        let Some(base) = units.iter().find(|u| u.name == name) else {
+            problems.push(Problem::new(name, format!("{:?} has no base unit '{}'", category, name)));
+            continue;
+        };
+        if base.category != category {
+            problems.push(Problem::new(name, format!("base unit {} is {:?}, not {:?}", name, base.category, category)));
+        }
+        if base.to_base(1.0) != 1.0 || base.to_unit(1.0) != 1.0 {
+            let message = format!("base unit {} maps 1 to {} and back to {}", name, base.to_base(1.0), base.to_unit(1.0));
+            problems.push(Problem::new(name, message));
+        }
+    }
+    problems
+}
+
+pub fn round_trips(units: &[&Unit]) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    for unit in units {
+        let worst = SAMPLE_MAGNITUDES
+            .iter()
+            .flat_map(|&m| [m, -m])
+            .map(|value| {
+                let back = unit.to_unit(unit.to_base(value));
+                ((back - value).abs() / value.abs().max(unit.conversion.offset().abs()), value)
+            })
+            .filter(|(error, _)| error.is_nan() || *error > ROUND_TRIP_TOLERANCE)
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some((error, value)) = worst {
+            problems.push(Problem::new(unit.name, format!("{} round-trips {} with relative error {:e}", unit.name, value, error)));
+        }
+    }
+    problems
+}
+
+/// `verify_invariants` between every pair of units of a category. Units
+/// that already fail on their own are left out, so a bad factor is
+/// reported once rather than once per unit it is paired with.
+pub fn invariants(units: &[&Unit]) -> Vec<Problem> {
+    let sound: Vec<&Unit> = units.iter().copied().filter(|u| finite_factors(&[u]).is_empty() && round_trips(&[u]).is_empty()).collect();
+    converter::verify_invariants(&sound).into_iter().map(|v| Problem::new(v.to, v.message)).collect()
+}
+
+/// Runs every check, writing a line per check and one per problem, and
+/// returns whether all of them passed.
+pub fn report(units: &[&Unit], out: &mut String) -> bool {
+    let mut healthy = true;
+    for (name, check) in CHECKS {
+        let problems = check(units);
+        out.push_str(&format!("{} {}\n", if problems.is_empty() { "ok  " } else { "FAIL" }, name));
+        for problem in &problems {
+            out.push_str(&format!("       {}\n", problem));
+        }
+        healthy &= problems.is_empty();
+    }
+    healthy
+}
+
+/// Runs the `doctor` subcommand, returning the process exit code.
+pub fn run(program: &str, args: &[String]) -> i32 {
+    if !args.is_empty() {
+        eprintln!("{}: {} doctor", tr("usage_label"), program);
+        return 1;
+    }
+    let units: Vec<&Unit> = crate::registry::active().units().collect();
+    let mut out = String::new();
+    let healthy = report(&units, &mut out);
+    print!("{}", out);
+    println!("{} units checked", units.len());
+    if healthy { 0 } else { 1 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Conversion;
+
+    fn unit(name: &'static str, aliases: &'static [&'static str], factor: (f64, f64), offset: f64) -> Unit {
+        Unit {
+            name,
+            aliases,
+            case_sensitive: true,
+            display_singular: name,
+            display_plural: name,
+            category: UnitCategory::Length,
+            conversion: Conversion::new(factor, offset),
+        }
+    }
+
+    fn messages(problems: Vec<Problem>) -> Vec<String> {
+        problems.into_iter().map(|p| p.message).collect()
+    }
+
+    fn builtin() -> Vec<&'static Unit> {
+        converter::UNITS.iter().collect()
+    }
+
+    #[test]
+    fn test_builtin_table_is_healthy() {
+        let mut out = String::new();
+        assert!(report(&builtin(), &mut out), "{}", out);
+        assert!(out.lines().all(|line| line.starts_with("ok")));
+    }
+
+    #[test]
+    fn test_zero_and_infinite_factors_fail() {
+        let (zero, infinite) = (unit("zero", &[], (0.0, 1.0), 0.0), unit("inf", &[], (1.0, f64::INFINITY), 0.0));
+        let nan = unit("nan", &[], (1.0, 1.0), f64::NAN);
+        assert_eq!(messages(finite_factors(&[&zero, &infinite, &nan])), ["zero has factor 0/1", "inf has factor 1/inf", "nan has offset NaN"]);
+    }
+
+    #[test]
+    fn test_shared_names_fail() {
+        let (a, b) = (unit("a", &["thing"], (1.0, 1.0), 0.0), unit("b", &["THING", "b"], (1.0, 1.0), 0.0));
+        assert_eq!(messages(unique_names(&[&a, &b])), ["'THING' names both a and b", "b lists 'b' twice"]);
+    }
+
+    #[test]
+    fn test_scaled_base_unit_fails() {
+        let m = unit("m", &[], (2.0, 1.0), 0.0);
+        assert_eq!(messages(base_units(&[&m])), ["base unit m maps 1 to 2 and back to 0.5"]);
+        assert_eq!(messages(base_units(&[&unit("km", &[], (1000.0, 1.0), 0.0)])), ["Length has no base unit 'm'"]);
+    }
+
+    #[test]
+    fn test_lossy_round_trip_fails() {
+        // A factor that overflows to infinity on the way back can't return the value.
+        let huge = unit("huge", &[], (1e300, 1e-300), 0.0);
+        let problems = round_trips(&[&huge]);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].message.starts_with("huge round-trips"), "{}", problems[0]);
+        assert!(round_trips(&[&unit("odd", &[], (1.0, 3.0), 0.0)]).is_empty());
+    }
+
+    #[test]
+    fn test_broken_pair_is_blamed_on_the_later_unit() {
+        // Each converts to the base unit fine, but between them values underflow to 0.
+        let (tiny, vast) = (unit("tiny", &[], (1e-290, 1.0), 0.0), unit("vast", &[], (1e290, 1.0), 0.0));
+        assert!(round_trips(&[&tiny]).is_empty() && round_trips(&[&vast]).is_empty());
+        let problems = invariants(&[&tiny, &vast]);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].unit, "vast");
+        assert!(problems[0].message.starts_with("-9990000000000 tiny → vast → tiny returns -0 "), "{}", problems[0]);
+        let zero = unit("zero", &[], (0.0, 1.0), 0.0);
+        assert!(invariants(&[&tiny, &zero]).is_empty(), "zero is left to the other checks");
+    }
+
+    #[test]
+    fn test_report_marks_failures() {
+        let m = unit("m", &["m"], (1.0, 1.0), 0.0);
+        let mut out = String::new();
+        assert!(!report(&[&m], &mut out));
+        assert!(out.contains("FAIL unique names and aliases\n       m lists 'm' twice\n"), "{}", out);
+        assert!(out.contains("ok   round trips\n"));
+    }
+}
@@ -0,0 +1,113 @@
+//! Humanized durations such as "1d 2h 3m 4s", used through the `human`
+//! pseudo-unit (`converter 93784 s human`, `converter 1d2h3m human min`) or
+//! `--humanize`.
+
+use crate::UnitCategory;
+
+pub const HUMAN: &str = "human";
+
+/// Largest first, in milliseconds. In this notation "m" means minutes.
+const COMPONENTS: [(&str, u64); 5] = [("d", 86_400_000), ("h", 3_600_000), ("m", 60_000), ("s", 1000), ("ms", 1)];
+
+pub fn is_human(unit: &str) -> bool {
+    unit.eq_ignore_ascii_case(HUMAN)
+}
+
+/// Renders `seconds` using only its nonzero components, to the millisecond.
+/// With `max_components`, smaller components past the limit are dropped.
+pub fn humanize(seconds: f64, max_components: Option<usize>) -> String {
+    let mut remaining = (seconds.abs() * 1000.0).round() as u64;
+    let mut parts = Vec::new();
+    for (suffix, size) in COMPONENTS {
+        let count = remaining / size;
+        remaining %= size;
+        if count > 0 {
+            parts.push(format!("{}{}", count, suffix));
+        }
+    }
+    if let Some(max) = max_components {
+        parts.truncate(max.max(1));
+    }
+    if parts.is_empty() {
+        return "0s".to_string();
+    }
+    let sign = if seconds < 0.0 { "-" } else { "" };
+    format!("{}{}", sign, parts.join(" "))
+}
+
+/// Parses "1d2h3m", "1s 500ms" or "-1.5h" into seconds.
+pub fn parse(input: &str) -> Option<f64> {
+    let input = input.trim();
+    let (negative, mut rest) = match input.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, input),
+    };
+    if rest.is_empty() {
+        return None;
+    }
+    let mut total = 0.0;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        let digits = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+        let number: f64 = rest[..digits].parse().ok()?;
+        rest = rest[digits..].trim_start();
+        let letters = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+        let (_, size) = COMPONENTS.iter().find(|(suffix, _)| *suffix == &rest[..letters])?;
+        total += number * *size as f64 / 1000.0;
+        rest = &rest[letters..];
+    }
+    Some(if negative { -total } else { total })
+}
+
+/// The humanized form of a result in `unit`: always for `human`, and for any
+/// time unit when `humanize` is set (`--humanize`).
+pub fn humanized_result(result: f64, unit: &str, humanize_time: bool, max_components: Option<usize>) -> Option<String> {
+    let seconds = if is_human(unit) {
+        result
+    } else {
+        let unit = crate::find_unit(unit).filter(|u| humanize_time && u.category == UnitCategory::Time)?;
+        unit.to_base(result)
+    };
+    Some(humanize(seconds, max_components))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_humanize() {
+        assert_eq!(humanize(93784.0, None), "1d 2h 3m 4s");
+        assert_eq!(humanize(3600.0, None), "1h");
+        assert_eq!(humanize(1.5, None), "1s 500ms");
+        assert_eq!(humanize(-90.0, None), "-1m 30s");
+        assert_eq!(humanize(93784.0, Some(2)), "1d 2h");
+    }
+
+    #[test]
+    fn test_humanize_zero() {
+        assert_eq!(humanize(0.0, None), "0s");
+        assert_eq!(humanize(0.0001, None), "0s");
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("1d2h3m"), Some(93780.0));
+        assert_eq!(parse("1d 2h 3m 4s"), Some(93784.0));
+        assert_eq!(parse("1s500ms"), Some(1.5));
+        assert_eq!(parse("-1.5h"), Some(-5400.0));
+        assert_eq!(parse("5"), None);
+        assert_eq!(parse("5x"), None);
+        assert_eq!(parse(""), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for text in ["1d 2h 3m 4s", "1s 500ms", "59m 59s 999ms"] {
+            assert_eq!(humanize(parse(text).unwrap(), None), text);
+        }
+    }
+}
@@ -0,0 +1,458 @@
+//! A minimal line editor for the REPL: cursor movement, persistent history
+//! and tab completion. Raw terminal mode is toggled with `stty`, and reading
+//! falls back to plain buffered lines whenever that isn't available.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+const MAX_HISTORY: usize = 1000;
+
+pub trait Completer {
+    /// Returns the byte offset where the completed word starts and the candidates for it.
+    fn complete(&self, line: &str, pos: usize) -> (usize, Vec<String>);
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    entries: Vec<String>,
+    path: Option<PathBuf>,
+}
+
+impl History {
+    /// Loads history from `path`; a missing file or directory just means no history yet.
+    pub fn load(path: Option<PathBuf>) -> History {
+        let entries = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .map(|text| text.lines().filter(|l| !l.trim().is_empty()).map(str::to_string).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let skip = entries.len().saturating_sub(MAX_HISTORY);
+        History { entries: entries[skip..].to_vec(), path }
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Records a line, skipping blanks and immediate repeats. Failing to
+    /// persist it is silently ignored so a read-only home never breaks the REPL.
+    pub fn add(&mut self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() || self.entries.last().is_some_and(|l| l == line) {
+            return;
+        }
+        self.entries.push(line.to_string());
+        if let Some(path) = &self.path {
+            let _ = path
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| OpenOptions::new().create(true).append(true).open(path))
+                .and_then(|mut file| writeln!(file, "{}", line));
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Key {
+    Char(char),
+    Enter,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Home,
+    End,
+    Up,
+    Down,
+    Tab,
+    /// A lone Esc, only seen when reads time out (see `RawMode::enable_polling`).
+    Escape,
+    KillLine,
+    Interrupt,
+    EndOfFile,
+    Ignored,
+}
+
+/// Decodes one keypress from a raw-mode byte stream; `None` at end of input.
+pub fn read_key(input: &mut impl Read) -> io::Result<Option<Key>> {
+    let Some(byte) = read_byte(input)? else {
+        return Ok(None);
+    };
+    let key = match byte {
+        b'\r' | b'\n' => Key::Enter,
+        0x7f | 0x08 => Key::Backspace,
+        b'\t' => Key::Tab,
+        0x01 => Key::Home,
+        0x05 => Key::End,
+        0x03 => Key::Interrupt,
+        0x04 => Key::EndOfFile,
+        0x15 => Key::KillLine,
+        0x1b => match (read_byte(input)?, read_byte(input)?) {
+            (Some(b'[' | b'O'), Some(b'A')) => Key::Up,
+            (Some(b'[' | b'O'), Some(b'B')) => Key::Down,
+            (Some(b'[' | b'O'), Some(b'C')) => Key::Right,
+            (Some(b'[' | b'O'), Some(b'D')) => Key::Left,
+            (Some(b'[' | b'O'), Some(b'H')) => Key::Home,
+            (Some(b'[' | b'O'), Some(b'F')) => Key::End,
+            (Some(b'['), Some(b'3')) => {
+                read_byte(input)?;
+                Key::Delete
+            }
+            (None, _) => Key::Escape,
+            _ => Key::Ignored,
+        },
+        b if b < 0x20 => Key::Ignored,
+        b if b < 0x80 => Key::Char(b as char),
+        b => {
+            let len = if b >= 0xf0 { 4 } else if b >= 0xe0 { 3 } else { 2 };
+            let mut bytes = vec![b];
+            for _ in 1..len {
+                bytes.extend(read_byte(input)?);
+            }
+            match std::str::from_utf8(&bytes).ok().and_then(|s| s.chars().next()) {
+                Some(c) => Key::Char(c),
+                None => Key::Ignored,
+            }
+        }
+    };
+    Ok(Some(key))
+}
+
+fn read_byte(input: &mut impl Read) -> io::Result<Option<u8>> {
+    let mut buf = [0u8; 1];
+    match input.read(&mut buf)? {
+        0 => Ok(None),
+        _ => Ok(Some(buf[0])),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    Continue,
+    Submit(String),
+    EndOfFile,
+    Interrupted,
+}
+
+/// Editing state for one line, independent of the terminal so it can be tested.
+#[derive(Debug, Default)]
+pub struct LineState {
+    pub buffer: Vec<char>,
+    pub cursor: usize,
+    history_index: Option<usize>,
+    draft: Vec<char>,
+    /// Text to print above the prompt, e.g. a list of completion candidates.
+    pub message: Option<String>,
+}
+
+impl LineState {
+    pub fn line(&self) -> String {
+        self.buffer.iter().collect()
+    }
+
+    pub fn handle(&mut self, key: Key, history: &History, completer: &impl Completer) -> Action {
+        match key {
+            Key::Char(c) => {
+                self.buffer.insert(self.cursor, c);
+                self.cursor += 1;
+            }
+            Key::Enter => return Action::Submit(self.line()),
+            Key::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.buffer.remove(self.cursor);
+            }
+            Key::Delete if self.cursor < self.buffer.len() => {
+                self.buffer.remove(self.cursor);
+            }
+            Key::Left if self.cursor > 0 => self.cursor -= 1,
+            Key::Right if self.cursor < self.buffer.len() => self.cursor += 1,
+            Key::Home => self.cursor = 0,
+            Key::End => self.cursor = self.buffer.len(),
+            Key::KillLine => {
+                self.buffer.clear();
+                self.cursor = 0;
+            }
+            Key::Up => self.recall(history, true),
+            Key::Down => self.recall(history, false),
+            Key::Tab => self.complete(completer),
+            Key::Interrupt => return Action::Interrupted,
+            Key::EndOfFile if self.buffer.is_empty() => return Action::EndOfFile,
+            Key::EndOfFile => return self.handle(Key::Delete, history, completer),
+            _ => {}
+        }
+        Action::Continue
+    }
+
+    fn recall(&mut self, history: &History, older: bool) {
+        let entries = history.entries();
+        let index = match (self.history_index, older) {
+            (None, true) if !entries.is_empty() => {
+                self.draft = self.buffer.clone();
+                Some(entries.len() - 1)
+            }
+            (Some(i), true) => Some(i.saturating_sub(1)),
+            (Some(i), false) if i + 1 < entries.len() => Some(i + 1),
+            (Some(_), false) => None,
+            (None, _) => return,
+        };
+        self.buffer = match index {
+            Some(i) => entries[i].chars().collect(),
+            None => std::mem::take(&mut self.draft),
+        };
+        self.history_index = index;
+        self.cursor = self.buffer.len();
+    }
+
+    fn complete(&mut self, completer: &impl Completer) {
+        let line = self.line();
+        let byte_pos = line.char_indices().nth(self.cursor).map_or(line.len(), |(i, _)| i);
+        let (start, candidates) = completer.complete(&line, byte_pos);
+        let typed = line[start..byte_pos].chars().count();
+        let replacement = match candidates.as_slice() {
+            [] => return,
+            [only] => format!("{} ", only),
+            many => {
+                let prefix = common_prefix(many);
+                if prefix.chars().count() <= typed {
+                    self.message = Some(many.join("  "));
+                    return;
+                }
+                prefix
+            }
+        };
+        let start_char = line[..start].chars().count();
+        self.buffer.splice(start_char..self.cursor, replacement.chars());
+        self.cursor = start_char + replacement.chars().count();
+    }
+}
+
+fn common_prefix(words: &[String]) -> String {
+    let first: Vec<char> = words[0].chars().collect();
+    let len = words[1..].iter().fold(first.len(), |len, w| {
+        first
+            .iter()
+            .zip(w.chars())
+            .take(len)
+            .take_while(|(a, b)| a.eq_ignore_ascii_case(b))
+            .count()
+    });
+    first[..len].iter().collect()
+}
+
+pub struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    pub fn enable() -> Option<RawMode> {
+        RawMode::with_settings(&["min", "1"])
+    }
+
+    /// Raw mode where a read gives up after a tenth of a second, so a lone Esc
+    /// can be told apart from the start of an escape sequence.
+    #[cfg(feature = "tui")]
+    pub fn enable_polling() -> Option<RawMode> {
+        RawMode::with_settings(&["min", "0", "time", "1"])
+    }
+
+    fn with_settings(settings: &[&str]) -> Option<RawMode> {
+        let saved = Command::new("stty").arg("-g").stdin(Stdio::inherit()).output().ok()?;
+        if !saved.status.success() {
+            return None;
+        }
+        let status = Command::new("stty")
+            .args(["-icanon", "-echo", "-isig"])
+            .args(settings)
+            .stdin(Stdio::inherit())
+            .status()
+            .ok()?;
+        status.success().then(|| RawMode { saved: String::from_utf8_lossy(&saved.stdout).trim().to_string() })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = Command::new("stty").arg(&self.saved).stdin(Stdio::inherit()).status();
+    }
+}
+
+pub enum ReadLine {
+    Line(String),
+    EndOfFile,
+    Interrupted,
+}
+
+pub struct Editor<C: Completer> {
+    pub history: History,
+    completer: C,
+    interactive: bool,
+}
+
+impl<C: Completer> Editor<C> {
+    pub fn new(completer: C, history: History, interactive: bool) -> Editor<C> {
+        Editor { history, completer, interactive }
+    }
+
+    pub fn read_line(&mut self, prompt: &str) -> io::Result<ReadLine> {
+        let mut stdout = io::stdout();
+        write!(stdout, "{}", prompt)?;
+        stdout.flush()?;
+
+        let raw = if self.interactive { RawMode::enable() } else { None };
+        if raw.is_none() {
+            let mut line = String::new();
+            return match io::stdin().lock().read_line(&mut line) {
+                Ok(0) => Ok(ReadLine::EndOfFile),
+                Ok(_) => Ok(ReadLine::Line(line.trim_end_matches(['\r', '\n']).to_string())),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => Ok(ReadLine::Interrupted),
+                Err(e) => Err(e),
+            };
+        }
+
+        let mut state = LineState::default();
+        let stdin = io::stdin();
+        let mut input = stdin.lock();
+        loop {
+            let Some(key) = read_key(&mut input)? else {
+                writeln!(stdout, "\r")?;
+                return Ok(ReadLine::EndOfFile);
+            };
+            let action = state.handle(key, &self.history, &self.completer);
+            if let Some(message) = state.message.take() {
+                write!(stdout, "\r\n{}\r\n", message)?;
+            }
+            match action {
+                Action::Continue => {
+                    let tail = state.buffer.len() - state.cursor;
+                    write!(stdout, "\r{}{}\x1b[K", prompt, state.line())?;
+                    if tail > 0 {
+                        write!(stdout, "\x1b[{}D", tail)?;
+                    }
+                    stdout.flush()?;
+                }
+                Action::Submit(line) => {
+                    write!(stdout, "\r\n")?;
+                    return Ok(ReadLine::Line(line));
+                }
+                Action::EndOfFile => {
+                    write!(stdout, "\r\n")?;
+                    return Ok(ReadLine::EndOfFile);
+                }
+                Action::Interrupted => {
+                    write!(stdout, "^C\r\n")?;
+                    return Ok(ReadLine::Interrupted);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Words(&'static [&'static str]);
+
+    impl Completer for Words {
+        fn complete(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+            let start = line[..pos].rfind(' ').map_or(0, |i| i + 1);
+            let prefix = &line[start..pos];
+            (start, self.0.iter().filter(|w| w.starts_with(prefix)).map(|w| w.to_string()).collect())
+        }
+    }
+
+    fn type_text(state: &mut LineState, text: &str, history: &History) {
+        for c in text.chars() {
+            state.handle(Key::Char(c), history, &Words(&[]));
+        }
+    }
+
+    #[test]
+    fn test_read_key_sequences() {
+        let mut input: &[u8] = b"a\x1b[A\x1b[D\x7f\t\r\x03\x1b[3~\xc3\xa9";
+        let mut keys = Vec::new();
+        while let Some(key) = read_key(&mut input).unwrap() {
+            keys.push(key);
+        }
+        assert_eq!(
+            keys,
+            vec![Key::Char('a'), Key::Up, Key::Left, Key::Backspace, Key::Tab, Key::Enter, Key::Interrupt, Key::Delete, Key::Char('é')]
+        );
+    }
+
+    #[test]
+    fn test_editing() {
+        let history = History::default();
+        let mut state = LineState::default();
+        type_text(&mut state, "5 km m", &history);
+        state.handle(Key::Left, &history, &Words(&[]));
+        state.handle(Key::Backspace, &history, &Words(&[]));
+        type_text(&mut state, " ", &history);
+        assert_eq!(state.line(), "5 km m");
+        state.handle(Key::End, &history, &Words(&[]));
+        type_text(&mut state, "i", &history);
+        assert_eq!(state.handle(Key::Enter, &history, &Words(&[])), Action::Submit("5 km mi".to_string()));
+    }
+
+    #[test]
+    fn test_history_navigation() {
+        let mut history = History::default();
+        history.add("1 m cm");
+        history.add("2 kg lb");
+        history.add("2 kg lb");
+        assert_eq!(history.entries().len(), 2);
+        let mut state = LineState::default();
+        type_text(&mut state, "draft", &history);
+        state.handle(Key::Up, &history, &Words(&[]));
+        assert_eq!(state.line(), "2 kg lb");
+        state.handle(Key::Up, &history, &Words(&[]));
+        state.handle(Key::Up, &history, &Words(&[]));
+        assert_eq!(state.line(), "1 m cm");
+        state.handle(Key::Down, &history, &Words(&[]));
+        state.handle(Key::Down, &history, &Words(&[]));
+        assert_eq!(state.line(), "draft");
+    }
+
+    #[test]
+    fn test_tab_completion() {
+        let history = History::default();
+        let words = Words(&["kilometer", "kilometre", "kilogram"]);
+        let mut state = LineState::default();
+        type_text(&mut state, "5 kilom", &history);
+        state.handle(Key::Tab, &history, &words);
+        assert_eq!(state.line(), "5 kilomet");
+        state.handle(Key::Tab, &history, &words);
+        assert_eq!(state.message.take(), Some("kilometer  kilometre".to_string()));
+        type_text(&mut state, "e", &history);
+        state.handle(Key::Tab, &history, &words);
+        assert_eq!(state.line(), "5 kilometer ");
+    }
+
+    #[test]
+    fn test_ctrl_d_and_ctrl_c() {
+        let history = History::default();
+        let mut state = LineState::default();
+        assert_eq!(state.handle(Key::Interrupt, &history, &Words(&[])), Action::Interrupted);
+        assert_eq!(state.handle(Key::EndOfFile, &history, &Words(&[])), Action::EndOfFile);
+        type_text(&mut state, "x", &history);
+        state.handle(Key::Home, &history, &Words(&[]));
+        assert_eq!(state.handle(Key::EndOfFile, &history, &Words(&[])), Action::Continue);
+        assert_eq!(state.line(), "");
+    }
+
+    #[test]
+    fn test_history_file_tolerates_missing_directory() {
+        let dir = std::env::temp_dir().join(format!("converter-editor-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("nested").join("repl_history");
+        assert!(History::load(Some(path.clone())).entries().is_empty());
+
+        let mut history = History::load(Some(path.clone()));
+        history.add("5 km mi");
+        history.add("precision 2");
+        assert_eq!(History::load(Some(path)).entries(), ["5 km mi", "precision 2"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
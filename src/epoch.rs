@@ -0,0 +1,322 @@
+//! `time`: converts between Unix time and ISO-8601, whichever way the input
+//! calls for: `time 1700000000` prints 2023-11-14T22:13:20Z and
+//! `time 2023-11-14T22:13:20Z` prints 1700000000.
+//!
+//! Unix time has no leap seconds, so a leap second's "23:59:60" reads as
+//! the first second of the next day, which is where POSIX clocks put it.
+
+use crate::i18n::tr;
+use crate::tzif::Zone;
+
+const USAGE: &str = "time <seconds | datetime> [--from epoch|iso] [--millis] [--local]";
+const SECONDS_PER_DAY: i64 = 86_400;
+/// A number of four digits or fewer could be a year as easily as a time.
+const LARGEST_AMBIGUOUS: i64 = 9999;
+const YEARS: std::ops::RangeInclusive<i64> = 0..=9999;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum From {
+    Epoch,
+    Iso,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Job {
+    input: String,
+    from: Option<From>,
+    millis: bool,
+    local: bool,
+}
+
+fn parse_job(args: &[String]) -> Result<Job, String> {
+    let mut input = None;
+    let (mut from, mut millis, mut local) = (None, false, false);
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--from" => {
+                let value = iter.next().ok_or_else(|| format!("{} requires a value", arg))?;
+                from = Some(match value.as_str() {
+                    "epoch" => From::Epoch,
+                    "iso" => From::Iso,
+                    _ => return Err(format!("'{}' is not an input format; use epoch or iso", value)),
+                });
+            }
+            "--millis" => millis = true,
+            "--local" => local = true,
+            _ if input.is_none() => input = Some(arg.clone()),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+    Ok(Job { input: input.ok_or("a time to convert is required")?, from, millis, local })
+}
+
+/// Days since 1970-01-01 in the proleptic Gregorian calendar.
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The year, month and day `days` after 1970-01-01.
+pub fn civil(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 } as u32;
+    (year_of_era + era * 400 + if month <= 2 { 1 } else { 0 }, month, day)
+}
+
+/// 0 for Sunday through 6 for Saturday.
+pub fn weekday(days: i64) -> u32 {
+    // 1970-01-01 was a Thursday.
+    (days + 4).rem_euclid(7) as u32
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    let next = if month == 12 { days_from_civil(year + 1, 1, 1) } else { days_from_civil(year, month + 1, 1) };
+    (next - days_from_civil(year, month, 1)) as u32
+}
+
+/// "+05:30", or "-04:56:02" for an offset of odd seconds.
+fn format_offset(offset: i32) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.unsigned_abs();
+    let seconds = if offset.is_multiple_of(60) { String::new() } else { format!(":{:02}", offset % 60) };
+    format!("{}{:02}:{:02}{}", sign, offset / 3600, offset / 60 % 60, seconds)
+}
+
+/// `millis` since the epoch in ISO-8601: UTC with a Z, or `zone`'s local
+/// time with its offset. Milliseconds are shown when `show_millis` is set.
+pub fn to_iso(millis: i64, zone: Option<&Zone>, show_millis: bool) -> Result<String, String> {
+    let seconds = millis.div_euclid(1000);
+    let offset = zone.map_or(0, |zone| zone.offset_at(seconds));
+    let local = seconds + offset as i64;
+    let (year, month, day) = civil(local.div_euclid(SECONDS_PER_DAY));
+    if !YEARS.contains(&year) {
+        return Err(format!("{} is outside the years 0000 to 9999", if show_millis { format!("{} ms", millis) } else { format!("{} s", seconds) }));
+    }
+    let time = local.rem_euclid(SECONDS_PER_DAY);
+    let fraction = if show_millis { format!(".{:03}", millis.rem_euclid(1000)) } else { String::new() };
+    let zone = if zone.is_some() { format_offset(offset) } else { "Z".to_string() };
+    Ok(format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}{}", year, month, day, time / 3600, time / 60 % 60, time % 60, fraction, zone))
+}
+
+/// Reads exactly `len` digits from the front of `rest`.
+fn digits(rest: &mut &str, len: usize) -> Option<u32> {
+    let text = rest.get(..len).filter(|text| text.bytes().all(|b| b.is_ascii_digit()))?;
+    *rest = &rest[len..];
+    text.parse().ok()
+}
+
+/// Milliseconds since the epoch of an ISO-8601 date or datetime: "2023",
+/// "2023-11-14", "2023-11-14T22:13:20Z", "2023-11-14 17:13:20.5-05:00".
+/// Without an offset it is UTC, or `zone`'s local time if there is one.
+/// Digits past milliseconds are dropped.
+pub fn parse_iso(input: &str, zone: Option<&Zone>) -> Result<i64, String> {
+    let invalid = || format!("'{}' is not an ISO-8601 date or datetime", input);
+    let mut rest = input;
+    let year = digits(&mut rest, 4).ok_or_else(invalid)? as i64;
+    let mut field = |separator: char, range: std::ops::RangeInclusive<u32>| -> Result<Option<u32>, String> {
+        let Some(after) = rest.strip_prefix(separator) else { return Ok(None) };
+        rest = after;
+        digits(&mut rest, 2).filter(|value| range.contains(value)).map(Some).ok_or_else(invalid)
+    };
+    let month = field('-', 1..=12)?.unwrap_or(1);
+    let day = field('-', 1..=31)?.unwrap_or(1);
+    if day > days_in_month(year, month) {
+        return Err(format!("{:04}-{:02} has no day {}", year, month, day));
+    }
+    let mut seconds = days_from_civil(year, month, day) * SECONDS_PER_DAY;
+    let mut millis = 0;
+    let mut offset = None;
+    if let Some(after) = rest.strip_prefix(['T', 't', ' ']) {
+        rest = after;
+        let hour = digits(&mut rest, 2).filter(|hour| *hour <= 23).ok_or_else(invalid)?;
+        let mut field = |range: std::ops::RangeInclusive<u32>| -> Result<Option<u32>, String> {
+            let Some(after) = rest.strip_prefix(':') else { return Ok(None) };
+            rest = after;
+            digits(&mut rest, 2).filter(|value| range.contains(value)).map(Some).ok_or_else(invalid)
+        };
+        let minute = field(0..=59)?.ok_or_else(invalid)?;
+        // A leap second is 60; as a count of seconds it runs into the next minute.
+        let second = field(0..=60)?.unwrap_or(0);
+        seconds += (hour * 3600 + minute * 60 + second) as i64;
+        if let Some(after) = rest.strip_prefix(['.', ',']) {
+            let len = after.find(|c: char| !c.is_ascii_digit()).unwrap_or(after.len());
+            if len == 0 {
+                return Err(invalid());
+            }
+            millis = format!("{:0<3}", &after[..len.min(3)]).parse::<i64>().map_err(|_| invalid())?;
+            rest = &after[len..];
+        }
+        offset = match rest {
+            "" => None,
+            "Z" | "z" => Some(0),
+            _ => {
+                let sign = match rest.as_bytes()[0] {
+                    b'+' => 1,
+                    b'-' => -1,
+                    _ => return Err(invalid()),
+                };
+                rest = &rest[1..];
+                let hours = digits(&mut rest, 2).filter(|hours| *hours <= 23).ok_or_else(invalid)?;
+                rest = rest.strip_prefix(':').unwrap_or(rest);
+                let minutes = if rest.is_empty() { 0 } else { digits(&mut rest, 2).filter(|minutes| *minutes <= 59).ok_or_else(invalid)? };
+                if !rest.is_empty() {
+                    return Err(invalid());
+                }
+                Some(sign * (hours * 3600 + minutes * 60) as i64)
+            }
+        };
+    } else if !rest.is_empty() {
+        return Err(invalid());
+    }
+    let utc = match (offset, zone) {
+        (Some(offset), _) => seconds - offset,
+        (None, Some(zone)) => zone.to_utc(seconds),
+        (None, None) => seconds,
+    };
+    Ok(utc * 1000 + millis)
+}
+
+/// "1700000000", or "1700000000.5" for part of a second.
+fn format_seconds(millis: i64) -> String {
+    let sign = if millis < 0 { "-" } else { "" };
+    let (whole, part) = (millis.unsigned_abs() / 1000, millis.unsigned_abs() % 1000);
+    if part == 0 { format!("{}{}", sign, whole) } else { format!("{}{}.{}", sign, whole, format!("{:03}", part).trim_end_matches('0')) }
+}
+
+fn convert(job: &Job) -> Result<String, String> {
+    let zone = job.local.then(Zone::local);
+    let integer = job.input.parse::<i64>().ok();
+    let from = match (job.from, integer) {
+        (Some(from), _) => from,
+        (None, Some(number)) if number.abs() <= LARGEST_AMBIGUOUS => {
+            return Err(format!("'{}' could be a year or a time since the epoch; say which with --from epoch or --from iso", job.input));
+        }
+        (None, Some(_)) => From::Epoch,
+        (None, None) => From::Iso,
+    };
+    match from {
+        From::Epoch => {
+            let number = integer.ok_or_else(|| format!("'{}' is not a whole number of {}", job.input, if job.millis { "milliseconds" } else { "seconds" }))?;
+            let millis = if job.millis { Some(number) } else { number.checked_mul(1000) };
+            let millis = millis.ok_or_else(|| format!("{} s is outside the years 0000 to 9999", number))?;
+            to_iso(millis, zone.as_ref(), job.millis)
+        }
+        From::Iso => {
+            let millis = parse_iso(&job.input, zone.as_ref())?;
+            Ok(if job.millis { millis.to_string() } else { format_seconds(millis) })
+        }
+    }
+}
+
+/// Runs the `time` subcommand, returning the process exit code.
+pub fn run(program: &str, args: &[String]) -> i32 {
+    let result = parse_job(args).map_err(|e| (e, true)).and_then(|job| convert(&job).map_err(|e| (e, false)));
+    match result {
+        Ok(text) => {
+            println!("{}", text);
+            0
+        }
+        Err((e, usage)) => {
+            eprintln!("{}: {}", tr("error"), e);
+            if usage {
+                eprintln!("{}: {} {}", tr("usage_label"), program, USAGE);
+            }
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(args: &str) -> Result<String, String> {
+        let args: Vec<String> = args.split_whitespace().map(String::from).collect();
+        convert(&parse_job(&args)?)
+    }
+
+    #[test]
+    fn test_both_directions() {
+        assert_eq!(time("1700000000"), Ok("2023-11-14T22:13:20Z".to_string()));
+        assert_eq!(time("2023-11-14T22:13:20Z"), Ok("1700000000".to_string()));
+        assert_eq!(time("2023-11-14T17:13:20-05:00"), Ok("1700000000".to_string()));
+        assert_eq!(time("2023-11-14t22:13:20,25"), Ok("1700000000.25".to_string()));
+        assert_eq!(time("2023-11-14"), Ok("1699920000".to_string()));
+        assert_eq!(time("-86401"), Ok("1969-12-30T23:59:59Z".to_string()));
+        assert_eq!(time("1969-12-31T23:59:59.5Z"), Ok("-0.5".to_string()));
+        assert_eq!(time("2000-02-29T00:00:00+0130"), Ok("951777000".to_string()));
+    }
+
+    #[test]
+    fn test_millis() {
+        assert_eq!(time("1700000000123 --millis"), Ok("2023-11-14T22:13:20.123Z".to_string()));
+        assert_eq!(time("--millis 2023-11-14T22:13:20.123456Z"), Ok("1700000000123".to_string()));
+        assert_eq!(time("2023-11-14T22:13:20Z --millis"), Ok("1700000000000".to_string()));
+        assert_eq!(time("-1 --millis"), Err("'-1' could be a year or a time since the epoch; say which with --from epoch or --from iso".to_string()));
+        assert_eq!(time("-1 --millis --from epoch"), Ok("1969-12-31T23:59:59.999Z".to_string()));
+    }
+
+    #[test]
+    fn test_leap_second_neighbours() {
+        assert_eq!(time("1483228799"), Ok("2016-12-31T23:59:59Z".to_string()));
+        assert_eq!(time("1483228800"), Ok("2017-01-01T00:00:00Z".to_string()));
+        // The leap second itself has no number of its own.
+        assert_eq!(time("2016-12-31T23:59:60Z"), Ok("1483228800".to_string()));
+        assert_eq!(time("2016-12-31T23:59:60.5Z --millis"), Ok("1483228800500".to_string()));
+        assert_eq!(time("2016-12-31T23:59:61Z").unwrap_err(), "'2016-12-31T23:59:61Z' is not an ISO-8601 date or datetime");
+    }
+
+    #[test]
+    fn test_ambiguous_numbers() {
+        assert_eq!(time("2023"), Err("'2023' could be a year or a time since the epoch; say which with --from epoch or --from iso".to_string()));
+        assert_eq!(time("2023 --from epoch"), Ok("1970-01-01T00:33:43Z".to_string()));
+        assert_eq!(time("2023 --from iso"), Ok("1672531200".to_string()));
+        assert_eq!(time("10000"), Ok("1970-01-01T02:46:40Z".to_string()));
+        assert_eq!(time("2023-11-14 --from epoch").unwrap_err(), "'2023-11-14' is not a whole number of seconds");
+        assert_eq!(time("2023 --from unix").unwrap_err(), "'unix' is not an input format; use epoch or iso");
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        for input in ["2023-13-01", "2023-11-14T24:00Z", "2023-11-14T22", "2023-11-14T22:13+5", "23-11-14", "now", "2023-11-14T22:13:20.Z"] {
+            assert_eq!(time(input), Err(format!("'{}' is not an ISO-8601 date or datetime", input)), "{}", input);
+        }
+        assert_eq!(time("2023-02-29").unwrap_err(), "2023-02 has no day 29");
+        assert_eq!(time("253402300800").unwrap_err(), "253402300800 s is outside the years 0000 to 9999");
+        assert_eq!(time(&i64::MAX.to_string()).unwrap_err(), format!("{} s is outside the years 0000 to 9999", i64::MAX));
+    }
+
+    #[test]
+    fn test_calendar() {
+        for days in [-719_528, -1, 0, 59, 10_957, 11_016, 2_932_896] {
+            let (year, month, day) = civil(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+        assert_eq!(civil(0), (1970, 1, 1));
+        assert_eq!(civil(11_016), (2000, 2, 29));
+        assert_eq!(weekday(days_from_civil(2023, 11, 14)), 2);
+        assert_eq!(days_in_month(1900, 2), 28);
+        assert_eq!(days_in_month(2000, 2), 29);
+    }
+
+    #[test]
+    fn test_local_output() {
+        let new_york = Zone::parse_rule("EST5EDT,M3.2.0,M11.1.0").unwrap();
+        assert_eq!(to_iso(1700000000000, Some(&new_york), false), Ok("2023-11-14T17:13:20-05:00".to_string()));
+        assert_eq!(to_iso(1688212800000, Some(&new_york), false), Ok("2023-07-01T08:00:00-04:00".to_string()));
+        assert_eq!(parse_iso("2023-11-14T17:13:20", Some(&new_york)), Ok(1700000000000));
+        assert_eq!(parse_iso("2023-11-14T17:13:20Z", Some(&new_york)), Ok(1699982000000));
+        assert_eq!(format_offset(-17_762), "-04:56:02");
+    }
+}
@@ -0,0 +1,93 @@
+//! `factor`: prints the multiplier between two units, e.g. `factor km mi`.
+
+use crate::{Conversion, ConvertError, Unit};
+use crate::i18n::tr;
+
+/// The two units, when a single multiplier converts between them.
+fn units(from: &str, to: &str) -> Result<(&'static Unit, &'static Unit), String> {
+    crate::convert_checked("1", from, to).map_err(|e: ConvertError| e.to_string())?;
+    let (from_unit, to_unit) = (crate::find_unit(from).expect("checked"), crate::find_unit(to).expect("checked"));
+    let offset = [from_unit, to_unit].iter().any(|u| matches!(u.conversion, Conversion::Affine { .. }));
+    if offset && !std::ptr::eq(from_unit, to_unit) {
+        return Err(format!(
+            "{} and {} are offset from each other, so no single factor converts between them; try '1 {} {} --explain'",
+            from, to, from, to
+        ));
+    }
+    Ok((from_unit, to_unit))
+}
+
+pub fn factor(from: &str, to: &str) -> Result<f64, String> {
+    let (from, to) = units(from, to)?;
+    Ok(crate::slope(from, to))
+}
+
+/// The factor as an exact decimal or reduced fraction, when the definitions allow it.
+pub fn exact_factor(from: &str, to: &str) -> Result<Option<String>, String> {
+    let (from, to) = units(from, to)?;
+    Ok(crate::convert_exact("1", from, to, true).map(crate::format_exact))
+}
+
+/// Runs the `factor` subcommand, returning the process exit code.
+pub fn run(program: &str, args: &[String], options: &crate::Options) -> i32 {
+    let [from, to] = args else {
+        eprintln!("{}: {} factor <from_unit> <to_unit> [--exact]", tr("usage_label"), program);
+        return 1;
+    };
+    let result = if options.exact {
+        exact_factor(from, to).and_then(|exact| match exact {
+            Some(exact) => Ok(exact),
+            None => {
+                eprintln!("Note: this factor has no exact form; showing the floating-point value");
+                factor(from, to).map(|f| crate::format_number(f, options))
+            }
+        })
+    } else {
+        factor(from, to).map(|f| crate::format_number(f, options))
+    };
+    match result {
+        Ok(text) => {
+            println!("{}", text);
+            0
+        }
+        Err(e) => {
+            eprintln!("{}: {}", tr("error"), e);
+            1
+        }
+    }
+}
+
+#[cfg(all(test, any(feature = "length", feature = "mass")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_length_factor() {
+        assert!((factor("km", "mi").unwrap() - 0.621371192237334).abs() < 1e-15);
+        assert_eq!(factor("mi", "km").unwrap(), 1.609344);
+    }
+
+    #[test]
+    #[cfg(feature = "mass")]
+    fn test_mass_factor() {
+        assert_eq!(factor("lb", "kg").unwrap(), 0.45359237);
+        assert!((factor("kg", "g").unwrap() - 1000.0).abs() < 1e-12);
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature"))]
+    fn test_temperature_pairs_rejected() {
+        assert!(factor("C", "F").unwrap_err().contains("--explain"));
+        assert!(factor("K", "C").is_err());
+        assert_eq!(factor("F", "F").unwrap(), 1.0);
+        assert!(factor("km", "kg").unwrap_err().starts_with("Cannot convert"));
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_exact_factor() {
+        assert_eq!(exact_factor("km", "mi").unwrap().as_deref(), Some("15625/25146"));
+        assert_eq!(exact_factor("in", "cm").unwrap().as_deref(), Some("2.54"));
+    }
+}
@@ -0,0 +1,162 @@
+//! A C interface for programs in other languages, declared in
+//! `include/converter.h`. `cargo build --release --features ffi` builds the
+//! shared library, `target/release/libconverter.so` (`libconverter.dylib` on
+//! macOS, `converter.dll` on Windows).
+//!
+//! Calls return 0 or one of the negative codes below, which never change
+//! meaning; the message for the last failure on the calling thread is kept
+//! for `converter_last_error_message`, and its did-you-mean suggestions for
+//! `converter_last_error_suggestions_json`. Strings the library hands out are
+//! released with `converter_free_string`.
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+
+use crate::ConvertError;
+use crate::json::Json;
+
+pub const CONVERTER_OK: i32 = 0;
+/// An argument was a null pointer.
+pub const CONVERTER_NULL_POINTER: i32 = -1;
+/// A unit name was not valid UTF-8.
+pub const CONVERTER_INVALID_UTF8: i32 = -2;
+pub const CONVERTER_INVALID_VALUE: i32 = -3;
+pub const CONVERTER_UNKNOWN_UNIT: i32 = -4;
+pub const CONVERTER_CATEGORY_MISMATCH: i32 = -5;
+pub const CONVERTER_BELOW_ABSOLUTE_ZERO: i32 = -6;
+pub const CONVERTER_NEGATIVE_UNCERTAINTY: i32 = -7;
+pub const CONVERTER_OFFSET_IN_COMPOUND: i32 = -8;
+pub const CONVERTER_DISABLED_UNIT: i32 = -9;
+pub const CONVERTER_NO_PATH: i32 = -10;
+/// The result is too large for a double.
+pub const CONVERTER_OVERFLOW: i32 = -11;
+/// A speed of zero was converted to a pace.
+pub const CONVERTER_ZERO_SPEED: i32 = -12;
+/// A word such as "gallon" that means a different unit in the US and
+/// imperial systems.
+pub const CONVERTER_AMBIGUOUS_UNIT: i32 = -13;
+/// A wire gauge outside 4/0 through 40, given or converted to.
+pub const CONVERTER_NO_GAUGE: i32 = -14;
+/// A currency code the loaded rates don't list, or any code when none are loaded.
+pub const CONVERTER_UNKNOWN_CURRENCY: i32 = -15;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+    static LAST_SUGGESTIONS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn error_code(error: &ConvertError) -> i32 {
+    match error {
+        ConvertError::InvalidValue(_) | ConvertError::NotFinite(_) => CONVERTER_INVALID_VALUE,
+        ConvertError::UnknownUnit { .. } | ConvertError::MissingUnit(_) => CONVERTER_UNKNOWN_UNIT,
+        ConvertError::UnknownCurrency { .. } => CONVERTER_UNKNOWN_CURRENCY,
+        ConvertError::AmbiguousUnit { .. } => CONVERTER_AMBIGUOUS_UNIT,
+        ConvertError::NoGauge(_) => CONVERTER_NO_GAUGE,
+        ConvertError::CategoryMismatch { .. } => CONVERTER_CATEGORY_MISMATCH,
+        ConvertError::BelowAbsoluteZero { .. } => CONVERTER_BELOW_ABSOLUTE_ZERO,
+        ConvertError::NegativeUncertainty(_) => CONVERTER_NEGATIVE_UNCERTAINTY,
+        ConvertError::OffsetInCompound { .. } => CONVERTER_OFFSET_IN_COMPOUND,
+        ConvertError::DisabledUnit { .. } => CONVERTER_DISABLED_UNIT,
+        ConvertError::NoPath { .. } => CONVERTER_NO_PATH,
+        ConvertError::Overflow { .. } => CONVERTER_OVERFLOW,
+        ConvertError::ZeroSpeed(_) => CONVERTER_ZERO_SPEED,
+    }
+}
+
+/// `text` as a C string; an interior NUL can't cross the boundary, so it is dropped.
+fn c_string(text: String) -> CString {
+    CString::new(text.replace('\0', "")).unwrap_or_default()
+}
+
+fn fail(code: i32, message: String) -> i32 {
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(c_string(message)));
+    LAST_SUGGESTIONS.with(|last| last.borrow_mut().clear());
+    code
+}
+
+fn fail_with(error: ConvertError) -> i32 {
+    let code = fail(error_code(&error), error.to_string());
+    if let ConvertError::UnknownUnit { suggestions, .. } = error {
+        LAST_SUGGESTIONS.with(|last| *last.borrow_mut() = suggestions);
+    }
+    code
+}
+
+/// # Safety
+///
+/// `text` must be null or point to a NUL-terminated string.
+unsafe fn unit_name<'a>(text: *const c_char, which: &str) -> Result<&'a str, i32> {
+    if text.is_null() {
+        return Err(fail(CONVERTER_NULL_POINTER, format!("'{}' is a null pointer", which)));
+    }
+    // SAFETY: non-null, and NUL-terminated by the caller's contract.
+    let bytes = unsafe { CStr::from_ptr(text) };
+    bytes.to_str().map_err(|_| fail(CONVERTER_INVALID_UTF8, format!("'{}' is not valid UTF-8", which)))
+}
+
+/// Converts `value` from the unit named `from` to the one named `to` and
+/// stores the result in `*out`. Returns `CONVERTER_OK` or a negative code,
+/// leaving `*out` untouched on failure. Wire gauges (`awg`) and paces
+/// convert as they do on the command line.
+///
+/// # Safety
+///
+/// `from` and `to` must each be null or point to a NUL-terminated string,
+/// and `out` must be null or valid for writing an `f64`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn converter_convert(value: f64, from: *const c_char, to: *const c_char, out: *mut f64) -> i32 {
+    // SAFETY: passed on from the caller's contract.
+    let names = unsafe { unit_name(from, "from").and_then(|from| Ok((from, unit_name(to, "to")?))) };
+    let (from, to) = match names {
+        Ok(names) => names,
+        Err(code) => return code,
+    };
+    if out.is_null() {
+        return fail(CONVERTER_NULL_POINTER, "'out' is a null pointer".to_string());
+    }
+    match crate::convert_checked(&value.to_string(), from, to) {
+        Ok(converted) => {
+            // SAFETY: non-null, and valid for writes by the caller's contract.
+            unsafe { out.write(converted.result) };
+            CONVERTER_OK
+        }
+        Err(e) => fail_with(e),
+    }
+}
+
+/// The message for the last failed call on this thread, or null if none
+/// has failed. It stays valid until the next failure on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn converter_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(std::ptr::null(), |message| message.as_ptr()))
+}
+
+/// The units the last failed call on this thread suggested in place of an
+/// unknown one, best first, as a JSON array of names; empty when it wasn't
+/// an unknown unit or nothing is close. Release it with `converter_free_string`.
+#[unsafe(no_mangle)]
+pub extern "C" fn converter_last_error_suggestions_json() -> *mut c_char {
+    let names = LAST_SUGGESTIONS.with(|last| Json::Array(last.borrow().iter().map(|name| Json::from(name.as_str())).collect()));
+    c_string(names.to_string()).into_raw()
+}
+
+/// Every available unit as a JSON array, in the `--list --json` format.
+/// Release it with `converter_free_string`.
+#[unsafe(no_mangle)]
+pub extern "C" fn converter_list_units_json() -> *mut c_char {
+    c_string(crate::info::units_json(crate::registry::active().units()).to_string()).into_raw()
+}
+
+/// Releases a string returned by this library; null is ignored.
+///
+/// # Safety
+///
+/// `text` must be null or a string returned by this library that hasn't
+/// been released yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn converter_free_string(text: *mut c_char) {
+    if !text.is_null() {
+        // SAFETY: made by `CString::into_raw` and not freed, by the caller's contract.
+        drop(unsafe { CString::from_raw(text) });
+    }
+}
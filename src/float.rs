@@ -0,0 +1,29 @@
+//! The floating-point types conversions work in. Unit factors stay `f64`;
+//! a value is widened to `f64` for the arithmetic and narrowed back to its
+//! own type at the end, so an `f32` result is the `f64` one rounded once.
+
+/// `f32` or `f64`.
+pub trait Float: Copy {
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+}
+
+impl Float for f64 {
+    fn from_f64(value: f64) -> f64 {
+        value
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+impl Float for f32 {
+    fn from_f64(value: f64) -> f32 {
+        value as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+}
@@ -0,0 +1,140 @@
+//! American Wire Gauge numbers, used through the `awg` pseudo-unit: a gauge
+//! converts to its wire's diameter (`converter 14 awg mm`) or cross-section
+//! (`converter 12 awg mm2`), and a diameter or cross-section back to the
+//! nearest gauge (`converter 2 mm awg`).
+//!
+//! Gauges run from 0000, also written 4/0, to 40. The thick ones count down
+//! past zero as the formula has it: 00 is -1, 000 is -2 and 0000 is -3,
+//! which is also how a `--json` record writes them.
+
+use crate::dimension::Dimension;
+use crate::{ConvertError, Converted, UnitCategory, Warning};
+
+pub const AWG: &str = "awg";
+
+/// 0000, the thickest gauge, and the thinnest one that parses.
+const THICKEST: i32 = -3;
+const THINNEST: i32 = 40;
+/// A diameter within this many gauge steps of a gauge is that gauge: 0.01
+/// is about 0.1% of the diameter, more than tables round 2.053 mm by.
+const ON_GAUGE: f64 = 0.01;
+
+pub fn is_awg(unit: &str) -> bool {
+    unit.eq_ignore_ascii_case(AWG)
+}
+
+/// The gauge "14", "0000" or "4/0" names, from 4/0 through 40.
+pub fn parse(input: &str) -> Option<i32> {
+    let input = input.trim();
+    if !input.is_empty() && input.bytes().all(|b| b.is_ascii_digit()) {
+        // "0" is 0, "00" is -1 and so on; other numbers are themselves.
+        let gauge = if input.len() > 1 && input.bytes().all(|b| b == b'0') { 1 - input.len() as i32 } else { input.parse().ok()? };
+        return (THICKEST..=THINNEST).contains(&gauge).then_some(gauge);
+    }
+    let zeros: i32 = input.strip_suffix("/0")?.parse().ok()?;
+    (1..=1 - THICKEST).contains(&zeros).then_some(1 - zeros)
+}
+
+/// "14", or "4/0" for the gauges written with zeros.
+pub fn name(gauge: i32) -> String {
+    if gauge < 0 { format!("{}/0", 1 - gauge) } else { gauge.to_string() }
+}
+
+/// d = 0.127 mm × 92^((36 − n) / 39), so 36 AWG is 0.127 mm and 4/0 is 0.46 in.
+pub fn diameter_mm(gauge: f64) -> f64 {
+    0.127 * 92f64.powf((36.0 - gauge) / 39.0)
+}
+
+/// The inverse of `diameter_mm`: a gauge number, not yet rounded to one.
+pub fn gauge_of(diameter_mm: f64) -> f64 {
+    36.0 - 39.0 * (diameter_mm / 0.127).ln() / 92f64.ln()
+}
+
+/// Converts to or from `awg`. The other unit is a length, the wire's
+/// diameter, or an area, its cross-section. Converting to a gauge rounds to
+/// the nearest one, with `Warning::GaugeRounded` when that moved it.
+pub fn convert(value_arg: &str, from_unit: &str, to_unit: &str) -> Result<Converted, ConvertError> {
+    let area = Dimension::LENGTH.pow(2);
+    if is_awg(from_unit) {
+        let gauge = parse(value_arg).ok_or_else(|| ConvertError::NoGauge(value_arg.to_string()))?;
+        let value = gauge as f64;
+        if is_awg(to_unit) {
+            return Ok(Converted { value, result: value, sigma: None, warnings: Vec::new() });
+        }
+        let to = crate::lookup_unit(to_unit).ok_or_else(|| crate::unit_error(to_unit))?;
+        let diameter = diameter_mm(value) / 1000.0;
+        let result = match to.dimension() {
+            d if d == Dimension::LENGTH => to.to_unit(diameter),
+            d if d == area => to.to_unit(std::f64::consts::PI / 4.0 * diameter * diameter),
+            _ => return Err(mismatch(from_unit, to_unit, to.category, false)),
+        };
+        return Ok(Converted { value, result, sigma: None, warnings: Vec::new() });
+    }
+    let value = crate::Measurement::parse(value_arg)?.value;
+    let from = crate::lookup_unit(from_unit).ok_or_else(|| crate::unit_error(from_unit))?;
+    let diameter = match from.dimension() {
+        d if d == Dimension::LENGTH => from.to_base(value),
+        d if d == area => (4.0 / std::f64::consts::PI * from.to_base(value)).sqrt(),
+        _ => return Err(mismatch(from_unit, to_unit, from.category, true)),
+    };
+    let exact = gauge_of(diameter * 1000.0);
+    let gauge = exact.round();
+    // NaN, from a size of zero or less, fails this too.
+    if !(THICKEST as f64..=THINNEST as f64).contains(&gauge) {
+        return Err(ConvertError::NoGauge(format!("{} {}", value_arg, from_unit)));
+    }
+    let warnings = if (exact - gauge).abs() > ON_GAUGE { vec![Warning::GaugeRounded] } else { Vec::new() };
+    Ok(Converted { value, result: gauge, sigma: None, warnings })
+}
+
+/// A gauge measures a wire's thickness, so it stands in as a length.
+fn mismatch(from_unit: &str, to_unit: &str, other: UnitCategory, gauge_is_target: bool) -> ConvertError {
+    let (from, to) = if gauge_is_target { (other, UnitCategory::Length) } else { (UnitCategory::Length, other) };
+    ConvertError::CategoryMismatch { from, to, from_unit: from_unit.to_string(), to_unit: to_unit.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("14"), Some(14));
+        assert_eq!(parse("0"), Some(0));
+        assert_eq!(parse("1/0"), Some(0));
+        assert_eq!(parse("00"), Some(-1));
+        assert_eq!(parse("4/0"), Some(-3));
+        assert_eq!(parse("0000"), Some(-3));
+        assert_eq!(parse("40"), Some(40));
+        for bad in ["41", "00000", "5/0", "0/0", "-1", "12.5", "4/1", ""] {
+            assert_eq!(parse(bad), None, "{}", bad);
+        }
+        assert_eq!([-3, -1, 0, 14].map(name), ["4/0", "2/0", "0", "14"]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass"))]
+    fn test_gauge_to_size() {
+        let size = |gauge: &str, unit: &str| convert(gauge, AWG, unit).unwrap().result;
+        assert!((size("12", "mm") - 2.053).abs() < 1e-3, "{}", size("12", "mm"));
+        assert!((size("4/0", "in") - 0.46).abs() < 1e-9);
+        assert_eq!(size("36", "mm"), 0.127);
+        assert!((size("12", "mm2") - 3.309).abs() < 1e-3, "{}", size("12", "mm2"));
+        assert_eq!(convert("41", AWG, "mm").unwrap_err(), ConvertError::NoGauge("41".to_string()));
+        assert!(matches!(convert("12", AWG, "kg"), Err(ConvertError::CategoryMismatch { from: UnitCategory::Length, .. })));
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_size_to_gauge_rounds() {
+        let on_gauge = convert("2.053", "mm", AWG).unwrap();
+        assert_eq!((on_gauge.result, on_gauge.warnings), (12.0, vec![]));
+        let between = convert("2", "mm", AWG).unwrap();
+        assert_eq!((between.result, between.warnings), (12.0, vec![Warning::GaugeRounded]));
+        assert_eq!(convert("0.46", "in", AWG).unwrap().result, -3.0);
+        assert_eq!(convert("3.309", "mm2", AWG).unwrap().result, 12.0);
+        for size in ["20", "0", "-1"] {
+            assert_eq!(convert(size, "mm", AWG).unwrap_err(), ConvertError::NoGauge(format!("{} mm", size)));
+        }
+    }
+}
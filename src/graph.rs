@@ -0,0 +1,197 @@
+//! Conversions between things with no factor to a common base unit, such as
+//! currencies from a table of rates: every known factor is an edge between
+//! two names, usable both ways, and a breadth-first search finds the
+//! shortest chain from one name to the other. Two units of the registry
+//! that share a dimension skip the search and convert through their base
+//! unit as usual.
+//!
+//! Factors compose as exact fractions while they fit, so a long chain adds
+//! no rounding; a factor that isn't an exact decimal, or a product that
+//! overflows, falls back to floating point.
+
+use std::collections::VecDeque;
+
+use crate::rational::Ratio;
+use crate::{ConvertError, find_unit, slope};
+
+/// One `from` is `factor` of `to`.
+#[derive(Debug, Clone, PartialEq)]
+struct Edge {
+    from: String,
+    to: String,
+    factor: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Graph {
+    edges: Vec<Edge>,
+}
+
+/// Names are matched ignoring ASCII case, like unit names.
+fn same(a: &str, b: &str) -> bool {
+    a.eq_ignore_ascii_case(b)
+}
+
+impl Graph {
+    pub fn new() -> Graph {
+        Graph::default()
+    }
+
+    /// Records that one `from` is `factor` of `to`, and so one `to` is
+    /// 1/`factor` of `from`.
+    pub fn add(&mut self, from: &str, to: &str, factor: f64) {
+        self.edges.push(Edge { from: from.to_string(), to: to.to_string(), factor });
+    }
+
+    /// The edges leaving `name`, each as the name it reaches and the factor.
+    fn neighbors<'a>(&'a self, name: &'a str) -> impl Iterator<Item = (&'a str, Hop)> + 'a {
+        self.edges.iter().filter_map(move |edge| {
+            if same(&edge.from, name) {
+                Some((edge.to.as_str(), Hop::Times(edge.factor)))
+            } else if same(&edge.to, name) {
+                Some((edge.from.as_str(), Hop::Over(edge.factor)))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The shortest chain of names from `from` to `to`, both included, and
+    /// the step taken into each name after the first.
+    fn search(&self, from: &str, to: &str) -> Option<(Vec<String>, Vec<Hop>)> {
+        let mut previous: Vec<(String, Option<(usize, Hop)>)> = vec![(from.to_string(), None)];
+        let mut queue = VecDeque::from([0]);
+        while let Some(i) = queue.pop_front() {
+            if same(&previous[i].0, to) {
+                let (mut names, mut steps, mut at) = (Vec::new(), Vec::new(), i);
+                loop {
+                    names.push(previous[at].0.clone());
+                    let Some((before, step)) = previous[at].1 else { break };
+                    steps.push(step);
+                    at = before;
+                }
+                names.reverse();
+                steps.reverse();
+                return Some((names, steps));
+            }
+            let name = previous[i].0.clone();
+            for (next, step) in self.neighbors(&name) {
+                if !previous.iter().any(|(seen, _)| same(seen, next)) {
+                    previous.push((next.to_string(), Some((i, step))));
+                    queue.push_back(previous.len() - 1);
+                }
+            }
+        }
+        None
+    }
+
+    /// The names a conversion from `from` to `to` passes through, both
+    /// included, or `None` when no chain of edges connects them.
+    pub fn path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        self.search(from, to).map(|(names, _)| names)
+    }
+
+    /// How many `to` one `from` is. Registry units of one dimension convert
+    /// directly; anything else needs a chain of edges.
+    pub fn factor(&self, from: &str, to: &str) -> Result<f64, ConvertError> {
+        if let (Some(a), Some(b)) = (find_unit(from), find_unit(to))
+            && a.dimension() == b.dimension()
+            && a.conversion.offset() == 0.0
+            && b.conversion.offset() == 0.0
+        {
+            return Ok(slope(a, b));
+        }
+        let (_, steps) = self.search(from, to).ok_or_else(|| ConvertError::NoPath { from: from.to_string(), to: to.to_string() })?;
+        Ok(compose(&steps))
+    }
+
+    /// `value` in `from`, in `to`.
+    pub fn convert(&self, value: f64, from: &str, to: &str) -> Result<f64, ConvertError> {
+        self.factor(from, to).map(|factor| value * factor)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Hop {
+    Times(f64),
+    Over(f64),
+}
+
+/// The product of `steps`, exactly when every factor is an exact decimal
+/// and nothing overflows.
+fn compose(steps: &[Hop]) -> f64 {
+    exact_product(steps).map(Ratio::to_f64).unwrap_or_else(|| {
+        steps.iter().fold(1.0, |product, step| match *step {
+            Hop::Times(factor) => product * factor,
+            Hop::Over(factor) => product / factor,
+        })
+    })
+}
+
+fn exact_product(steps: &[Hop]) -> Option<Ratio> {
+    let mut product = Ratio::new(1, 1)?;
+    for step in steps {
+        product = match *step {
+            Hop::Times(factor) => product.checked_mul(Ratio::from_factor(factor)?)?,
+            Hop::Over(factor) => product.checked_div(Ratio::from_factor(factor)?)?,
+        };
+    }
+    Some(product)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a → b → c → d, plus a branch to e that leads nowhere.
+    fn chain() -> Graph {
+        let mut graph = Graph::new();
+        graph.add("a", "b", 2.0);
+        graph.add("b", "c", 3.0);
+        graph.add("C", "d", 0.1);
+        graph.add("a", "e", 5.0);
+        graph
+    }
+
+    #[test]
+    fn test_three_hops() {
+        let graph = chain();
+        assert_eq!(graph.path("a", "d").unwrap(), ["a", "b", "c", "d"]);
+        // Exact composition: 2 × 3 × 0.1 in floating point is 0.6000000000000001.
+        assert_eq!(graph.factor("a", "d"), Ok(0.6));
+        assert_eq!(graph.factor("D", "A"), Ok(1.0 / 0.6));
+        assert_eq!(graph.convert(10.0, "a", "c"), Ok(60.0));
+        assert_eq!(graph.factor("b", "b"), Ok(1.0));
+    }
+
+    #[test]
+    fn test_shortest_path_and_inexact_factors() {
+        let mut graph = chain();
+        graph.add("a", "d", 0.7);
+        assert_eq!(graph.path("a", "d").unwrap(), ["a", "d"]);
+        let mut thirds = Graph::new();
+        thirds.add("x", "y", 1.0 / 3.0);
+        thirds.add("y", "z", 3.0);
+        assert!((thirds.factor("x", "z").unwrap() - 1.0).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_no_path() {
+        let graph = chain();
+        assert_eq!(graph.path("d", "q"), None);
+        let error = graph.factor("e", "q").unwrap_err();
+        assert_eq!(error, ConvertError::NoPath { from: "e".to_string(), to: "q".to_string() });
+        assert_eq!(error.to_string(), "No chain of known factors leads from 'e' to 'q'");
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_units_of_one_dimension_convert_directly() {
+        let mut graph = Graph::new();
+        assert!((graph.factor("km", "mi").unwrap() - 0.621371192237334).abs() < 1e-15);
+        assert!(matches!(graph.factor("km", "kg"), Err(ConvertError::NoPath { .. })));
+        graph.add("kg", "USD", 4.5);
+        graph.add("km", "USD", 0.5);
+        assert_eq!(graph.factor("km", "kg"), Ok(0.5 / 4.5));
+    }
+}
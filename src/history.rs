@@ -0,0 +1,178 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::epoch;
+use crate::json::{self, Json};
+
+const HISTORY_FILE: &str = "history.jsonl";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub timestamp: String,
+    pub value: f64,
+    pub from: String,
+    pub to: String,
+    pub result: f64,
+}
+
+impl HistoryEntry {
+    pub fn now(value: f64, from: &str, to: &str, result: f64) -> HistoryEntry {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        HistoryEntry {
+            timestamp: format_timestamp(secs),
+            value,
+            from: from.to_string(),
+            to: to.to_string(),
+            result,
+        }
+    }
+
+    fn to_json(&self) -> Json {
+        Json::object(vec![
+            ("timestamp", Json::from(self.timestamp.as_str())),
+            ("value", Json::from(self.value)),
+            ("from", Json::from(self.from.as_str())),
+            ("to", Json::from(self.to.as_str())),
+            ("result", Json::from(self.result)),
+        ])
+    }
+
+    fn from_json(value: &Json) -> Option<HistoryEntry> {
+        Some(HistoryEntry {
+            timestamp: value.get("timestamp")?.as_str()?.to_string(),
+            value: value.get("value")?.as_f64()?,
+            from: value.get("from")?.as_str()?.to_string(),
+            to: value.get("to")?.as_str()?.to_string(),
+            result: value.get("result")?.as_f64()?,
+        })
+    }
+}
+
+pub fn append(dir: &Path, entry: &HistoryEntry) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(HISTORY_FILE))?;
+    writeln!(file, "{}", entry.to_json())
+}
+
+/// Returns up to `limit` of the most recent entries, oldest first.
+/// Lines that fail to parse are skipped rather than failing the whole read.
+pub fn read_last(dir: &Path, limit: usize) -> io::Result<Vec<HistoryEntry>> {
+    let file = match fs::File::open(dir.join(HISTORY_FILE)) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if let Some(entry) = json::parse(&line).ok().as_ref().and_then(HistoryEntry::from_json) {
+            entries.push(entry);
+        }
+    }
+    let skip = entries.len().saturating_sub(limit);
+    Ok(entries.split_off(skip))
+}
+
+pub fn clear(dir: &Path) -> io::Result<()> {
+    match fs::remove_file(dir.join(HISTORY_FILE)) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        other => other,
+    }
+}
+
+/// Formats seconds since the Unix epoch as an RFC 3339 UTC timestamp.
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let rem = secs % 86400;
+    let (year, month, day) = epoch::civil(days);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_state_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("converter-history-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn entry(value: f64) -> HistoryEntry {
+        HistoryEntry {
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            value,
+            from: "km".to_string(),
+            to: "mi".to_string(),
+            result: value * 0.621371,
+        }
+    }
+
+    #[test]
+    fn test_append_and_read() {
+        let dir = temp_state_dir("append");
+        append(&dir, &entry(1.0)).unwrap();
+        append(&dir, &entry(2.0)).unwrap();
+        let entries = read_last(&dir, 10).unwrap();
+        assert_eq!(entries, vec![entry(1.0), entry(2.0)]);
+        let text = fs::read_to_string(dir.join(HISTORY_FILE)).unwrap();
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.starts_with("{\"timestamp\":\"2024-01-01T00:00:00Z\",\"value\":1,"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_last_limit() {
+        let dir = temp_state_dir("limit");
+        for i in 0..5 {
+            append(&dir, &entry(i as f64)).unwrap();
+        }
+        let values: Vec<f64> = read_last(&dir, 2).unwrap().iter().map(|e| e.value).collect();
+        assert_eq!(values, vec![3.0, 4.0]);
+        assert_eq!(read_last(&dir, 0).unwrap().len(), 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_clear() {
+        let dir = temp_state_dir("clear");
+        append(&dir, &entry(1.0)).unwrap();
+        clear(&dir).unwrap();
+        assert!(read_last(&dir, 10).unwrap().is_empty());
+        clear(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_unwritable_dir_returns_error() {
+        // A regular file where the directory should be can't be written even as root.
+        let blocker = temp_state_dir("blocked");
+        fs::write(&blocker, "").unwrap();
+        assert!(append(&blocker.join("state"), &entry(1.0)).is_err());
+        fs::remove_file(&blocker).unwrap();
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(1700000000), "2023-11-14T22:13:20Z");
+        assert_eq!(format_timestamp(951782400), "2000-02-29T00:00:00Z");
+    }
+}
@@ -0,0 +1,226 @@
+//! `converter serve`: a small HTTP front end over the same request handling
+//! as `--serve-stdio`. tiny_http accepts the connections and parses the
+//! requests on its own threads; each is answered here in turn, since a
+//! conversion takes microseconds.
+//!
+//! `GET /convert?value=5&from=km&to=mi[&precision=2]` and `GET /units`.
+
+use tiny_http::{Header, Response as TinyResponse, Server};
+
+use crate::json::Json;
+use crate::protocol::{self, Request, Response};
+use crate::i18n::tr;
+
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: Json,
+}
+
+fn error(status: u16, code: &str, message: &str) -> HttpResponse {
+    HttpResponse {
+        status,
+        body: Json::object(vec![("ok", Json::from(false)), ("error", Json::from(code)), ("message", Json::from(message))]),
+    }
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let hex = |b: u8| (b as char).to_digit(16).map(|d| d as u8);
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => match (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                (Some(hi), Some(lo)) => {
+                    out.push(hi << 4 | lo);
+                    i += 2;
+                }
+                _ => out.push(b'%'),
+            },
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn query_params(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect()
+}
+
+fn convert(query: &str) -> HttpResponse {
+    let params = query_params(query);
+    let param = |key: &str| params.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+    let (Some(value), Some(from), Some(to)) = (param("value"), param("from"), param("to")) else {
+        return error(400, "bad_request", "'value', 'from' and 'to' query parameters are required");
+    };
+    let Ok(value) = value.trim().parse() else {
+        return error(422, "invalid_value", &format!("'{}' is not a valid number", value));
+    };
+    let precision = match param("precision").map(str::parse) {
+        None => None,
+        Some(Ok(p)) => Some(p),
+        Some(Err(_)) => return error(400, "bad_request", "'precision' must be a non-negative integer"),
+    };
+    let request = Request { id: None, value, from: from.to_string(), to: to.to_string(), precision };
+    let response = protocol::handle_request(&request);
+    let status = match &response {
+        Response::Ok { .. } => 200,
+        Response::Error { code: "invalid_value" | "below_absolute_zero" | "overflow", .. } => 422,
+        Response::Error { .. } => 400,
+    };
+    HttpResponse { status, body: response.to_json() }
+}
+
+/// Routes one request, such as `GET` of "/convert?value=5&from=km&to=mi".
+pub fn route(method: &str, target: &str) -> HttpResponse {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    match (method, path) {
+        ("GET", "/convert") => convert(query),
+        ("GET", "/units") => HttpResponse { status: 200, body: converter::info::units_json(crate::registry::active().units()) },
+        (_, "/convert" | "/units") => error(405, "method_not_allowed", "only GET is supported"),
+        _ => error(404, "not_found", &format!("no route for {}", path)),
+    }
+}
+
+/// Answers requests until the server is dropped; a client that goes away
+/// before its answer doesn't stop it.
+pub fn serve(server: Server) {
+    let content_type = Header::from_bytes("Content-Type", "application/json").expect("a valid header");
+    for request in server.incoming_requests() {
+        let response = route(request.method().as_str(), request.url());
+        let body = TinyResponse::from_string(response.body.to_string()).with_status_code(response.status).with_header(content_type.clone());
+        let _ = request.respond(body);
+    }
+}
+
+/// Runs the `serve` subcommand, returning the process exit code.
+pub fn run(program: &str, args: &[String]) -> i32 {
+    let mut host = "127.0.0.1".to_string();
+    let mut port: u16 = 8080;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let parsed = match (arg.as_str(), iter.next()) {
+            ("--port", Some(value)) => value.parse().map(|p| port = p).map_err(|_| format!("'{}' is not a valid port", value)),
+            ("--host", Some(value)) => {
+                host = value.clone();
+                Ok(())
+            }
+            _ => Err(format!("{}: {} serve [--host ADDR] [--port N]", tr("usage_label"), program)),
+        };
+        if let Err(e) = parsed {
+            eprintln!("{}: {}", tr("error"), e);
+            return 1;
+        }
+    }
+
+    let server = match Server::http((host.as_str(), port)) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("{}: cannot listen on {}:{}: {}", tr("error"), host, port, e);
+            return 1;
+        }
+    };
+    eprintln!("Listening on http://{}", server.server_addr());
+    serve(server);
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    fn start_server() -> std::net::SocketAddr {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let addr = server.server_addr().to_ip().unwrap();
+        std::thread::spawn(move || serve(server));
+        addr
+    }
+
+    fn get(addr: std::net::SocketAddr, target: &str) -> (u16, Json) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", target).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+        assert!(head.contains("Content-Type: application/json"));
+        (status, json::parse(body).unwrap())
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn test_convert_endpoint() {
+        let addr = start_server();
+        let (status, body) = get(addr, "/convert?value=5&from=km&to=mi&precision=3");
+        assert_eq!(status, 200);
+        assert_eq!(body.get("result").and_then(Json::as_f64), Some(3.107));
+
+        let (status, body) = get(addr, "/convert?value=100&from=C&to=F");
+        assert_eq!(status, 200);
+        assert_eq!(body.get("result").and_then(Json::as_f64), Some(212.0));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass"))]
+    fn test_convert_errors() {
+        let addr = start_server();
+        let (status, body) = get(addr, "/convert?value=5&from=km&to=parsec");
+        assert_eq!(status, 400);
+        assert_eq!(body.get("error").and_then(Json::as_str), Some("unknown_unit"));
+
+        let (status, body) = get(addr, "/convert?value=5&from=km&to=kg");
+        assert_eq!(status, 400);
+        assert_eq!(body.get("error").and_then(Json::as_str), Some("category_mismatch"));
+
+        let (status, body) = get(addr, "/convert?value=five&from=km&to=mi");
+        assert_eq!(status, 422);
+        assert_eq!(body.get("error").and_then(Json::as_str), Some("invalid_value"));
+
+        assert_eq!(get(addr, "/convert?from=km").0, 400);
+        assert_eq!(get(addr, "/nope").0, 404);
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_units_endpoint() {
+        let addr = start_server();
+        let (status, body) = get(addr, "/units");
+        assert_eq!(status, 200);
+        let Json::Array(units) = body else { panic!("expected an array") };
+        assert_eq!(units.len(), converter::UNITS.len());
+        assert_eq!(units[0].get("name").and_then(Json::as_str), Some("km"));
+        assert_eq!(units[0].get("category").and_then(Json::as_str), Some("length"));
+        assert_eq!(units[0].get("singular").and_then(Json::as_str), Some("kilometer"));
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_malformed_request_keeps_the_server_up() {
+        let addr = start_server();
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "nonsense\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 400"), "{}", response);
+        assert_eq!(get(addr, "/units").0, 200);
+    }
+
+    #[test]
+    fn test_route_and_decoding() {
+        assert_eq!(route("POST", "/convert").status, 405);
+        assert_eq!(route("GET", "/convert?value=1&from=%20&to=m").status, 400);
+        assert_eq!(percent_decode("metric%20ton+x%2"), "metric ton x%2");
+    }
+}
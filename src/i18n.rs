@@ -0,0 +1,759 @@
+//! Message catalogs for the command line's errors, warnings, help and unit
+//! listing. A message is looked up by key in the active language's catalog and
+//! falls back to English when that catalog lacks it. JSON output never goes
+//! through here, so it reads the same in every language.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Lang {
+    #[default]
+    En,
+    Es,
+    De,
+}
+
+static ACTIVE: OnceLock<Lang> = OnceLock::new();
+
+impl Lang {
+    /// Parses a language code or a locale such as "es_ES.UTF-8" or "de-AT".
+    pub fn parse(input: &str) -> Option<Lang> {
+        let code = input.split(['_', '-', '.', '@']).next().unwrap_or("");
+        match code.to_ascii_lowercase().as_str() {
+            "en" | "c" | "posix" => Some(Lang::En),
+            "es" => Some(Lang::Es),
+            "de" => Some(Lang::De),
+            _ => None,
+        }
+    }
+
+    /// The language named by LC_ALL, LC_MESSAGES or LANG, the first one set
+    /// winning, or English when that isn't one we have.
+    pub fn from_env() -> Lang {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Lang::parse(&value))
+            .unwrap_or_default()
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Lang::En => EN,
+            Lang::Es => ES,
+            Lang::De => DE,
+        }
+    }
+}
+
+/// Makes `lang` the language of every message. Only the first call has an effect.
+pub fn install(lang: Lang) {
+    let _ = ACTIVE.set(lang);
+}
+
+/// The installed language, or English.
+pub fn active() -> Lang {
+    ACTIVE.get().copied().unwrap_or_default()
+}
+
+/// The message for `key` in `lang`, falling back to English.
+pub fn message(lang: Lang, key: &str) -> &'static str {
+    lookup(lang.catalog(), key)
+}
+
+fn lookup(catalog: &[(&str, &'static str)], key: &str) -> &'static str {
+    let find = |catalog: &[(&str, &'static str)]| catalog.iter().find(|(k, _)| *k == key).map(|(_, text)| *text);
+    find(catalog).or_else(|| find(EN)).unwrap_or_else(|| panic!("no message for '{}'", key))
+}
+
+/// The message for `key` in the active language.
+pub fn tr(key: &str) -> &'static str {
+    message(active(), key)
+}
+
+/// `message` with "{0}", "{1}", ... replaced by `args`.
+pub fn fill(lang: Lang, key: &str, args: &[&dyn fmt::Display]) -> String {
+    let mut text = message(lang, key).to_string();
+    for (i, arg) in args.iter().enumerate() {
+        text = text.replace(&format!("{{{}}}", i), &arg.to_string());
+    }
+    text
+}
+
+/// `fill` in the active language.
+pub fn tr_with(key: &str, args: &[&dyn fmt::Display]) -> String {
+    fill(active(), key, args)
+}
+
+const EN: &[(&str, &str)] = &[
+    ("error", "Error"),
+    ("warning", "Warning"),
+    ("note", "Note"),
+    ("or", "or"),
+    ("invalid_value", "'{0}' is not a valid number"),
+    ("not_finite", "'{0}' is not allowed: the value must be a finite number"),
+    ("overflow", "{0} {1} in {2}: result too large to represent"),
+    ("zero_speed", "{0}: a speed of zero has no pace"),
+    ("missing_unit", "missing unit before '^' in '{0}'"),
+    ("unknown_unit", "Unknown unit '{0}'"),
+    ("did_you_mean", "; did you mean {0}?"),
+    ("category_mismatch", "Cannot convert between different unit categories ({0})"),
+    ("unit_of_category", "{0} is a {1} unit"),
+    ("hint_density", "hint: convert through a density, e.g. water ≈ 1 g/mL"),
+    ("hint_speed", "hint: convert through a speed, e.g. walking ≈ 5 km/h"),
+    ("hint_duration", "hint: convert through a duration, e.g. 1 h"),
+    ("below_absolute_zero", "{0} {1} is below absolute zero ({2} {1})"),
+    ("negative_uncertainty", "Uncertainty in '{0}' must not be negative"),
+    ("offset_in_compound", "'{1}' can't include {0}: temperatures and other units with an offset only convert on their own"),
+    ("no_path", "No chain of known factors leads from '{0}' to '{1}'"),
+    ("no_gauge", "'{0}' is not a wire gauge from 4/0 to 40"),
+    ("no_rates", "'{0}' looks like a currency code, but no rates are loaded; give a rates file with --rates or 'rates' in the config"),
+    ("unknown_currency", "currency '{0}' is not in the rates file"),
+    ("rates_dated", "rates from {0} dated {1}, {2} days old"),
+    ("data_symbols", "data symbols are case-sensitive: {0}"),
+    ("ambiguous_unit", "'{0}' means a different amount in the US and imperial systems; use {1}, or choose a system with --system us|imperial or 'system' in the config"),
+    ("disabled_unit", "unit '{0}' is disabled by your configuration"),
+    ("use_instead", "; use {0}"),
+    ("negative_length", "Negative length doesn't make physical sense"),
+    ("negative_mass", "Negative mass doesn't make physical sense"),
+    ("negative_volume", "Negative volume doesn't make physical sense"),
+    ("negative_amount", "A negative amount doesn't make physical sense here"),
+    ("underflow", "The result is too small for a double and came out as 0"),
+    ("precision_loss", "The number is too close to zero to keep full precision; trailing digits are unreliable"),
+    ("gauge_rounded", "The size falls between two wire gauges; the result is the nearer one"),
+    ("try_list", "Try '{0} --list' to see supported units"),
+    ("no_match", "no unit matches '{0}'"),
+    ("try_help", "Try '{0} --help' for more information"),
+    ("expected_arguments", "Expected 3 arguments, got {0}"),
+    ("usage", "Usage: {0} <value> <from_unit> <to_unit>"),
+    ("fraction_temperature", "--fraction only applies to linear quantities, not temperatures"),
+    ("no_exact_form", "this conversion has no exact form; showing the floating-point result"),
+    ("usage_label", "Usage"),
+    ("requires_value", "{0} requires a value"),
+    ("unexpected_argument", "unexpected argument '{0}'"),
+    ("csv.bad_missing", "--missing must be 'blank' or 'error', not '{0}'"),
+    ("csv.column_required", "--column is required"),
+    ("csv.column_from_one", "column indexes start at 1"),
+    ("csv.numeric_column", "--no-header needs a numeric --column"),
+    ("csv.rename_needs_header", "--rename needs a header row"),
+    ("csv.from_required", "--from is required"),
+    ("csv.to_required", "--to is required"),
+    ("csv.input_required", "an input file is required"),
+    ("csv.cannot_read", "cannot read '{0}': {1}"),
+    ("csv.cannot_write", "cannot write '{0}': {1}"),
+    ("csv.column_out_of_range", "column {0} is out of range; the header has {1} columns"),
+    ("csv.no_such_column", "no column named '{0}' in the header"),
+    ("csv.missing_value", "row {0}: missing value in column {1}"),
+    ("csv.row", "row {0}: {1}"),
+    ("csv.unterminated", "unterminated quoted field"),
+    ("compare.invalid_tolerance", "'{0}' is not a valid tolerance"),
+    ("compare.negative_tolerance", "tolerance must not be negative, got '{0}'"),
+    ("compare.within", "Within tolerance"),
+    ("compare.outside", "Outside tolerance"),
+    ("compare.differs", "{0} {1} = {2} {3}, which differs from {4} {3} by {5} {3} ({6}%)"),
+    ("compare.equal", "{0} {1} is equal to {2} {3}"),
+    ("compare.larger", "{0} {1} is larger than {2} {3}"),
+    ("compare.smaller", "{0} {1} is smaller than {2} {3}"),
+    ("compare.by", "by {0} {1} ({2} {3})"),
+    ("compare.ratio", "ratio {0}"),
+    ("big_needs_backend", "--big needs the high-precision backend; rebuild with `--features bigdecimal`"),
+    ("trace_needs_feature", "-vv needs the tracing instrumentation; rebuild with `--features tracing`"),
+    ("big_max_places", "--big supports at most {0} decimal places"),
+    ("no_tui", "this build has no TUI; rebuild with `--features tui`"),
+    ("no_http", "this build has no HTTP server; rebuild with `--features http`"),
+    ("no_angle", "this build has no angles; rebuild with `--features angle`"),
+    ("clipboard_unreadable", "could not read the clipboard: {0}"),
+    ("no_value", "no value given; pass one as the first argument"),
+    ("history_unwritable", "could not write conversion history: {0}"),
+    ("no_config_path", "cannot locate the config file (HOME is not set)"),
+    ("could_not_write", "could not write {0}: {1}"),
+    ("args.precision_required", "--precision requires a number of decimal places"),
+    ("args.invalid_precision", "'{0}' is not a valid precision"),
+    ("args.format_required", "--format requires a template"),
+    ("args.batch_required", "--batch requires a file (or '-' for stdin)"),
+    ("args.filter_required", "--filter requires <from_unit> <to_unit>"),
+    ("args.via_required", "--via requires a quantity, such as 1g/mL"),
+    ("args.system_required", "--system requires us, imperial or metric"),
+    ("args.unknown_system", "unknown system '{0}'; use us, imperial or metric"),
+    ("args.calendar_required", "--calendar requires julian, gregorian or civil"),
+    ("args.unknown_calendar", "unknown calendar '{0}'; use julian, gregorian or civil"),
+    ("args.rates_required", "--rates requires a rates file"),
+    ("args.lang_required", "--lang requires a language (en, es or de)"),
+    ("args.unsupported_lang", "unsupported language '{0}'; use en, es or de"),
+    ("args.jobs_required", "--jobs requires a number of threads"),
+    ("args.invalid_jobs", "'{0}' is not a valid number of jobs"),
+    ("args.max_components_required", "--max-components requires a number"),
+    ("args.invalid_max_components", "'{0}' is not a valid number of components"),
+    ("args.bad_denominator", "'{0}' is not a supported fraction denominator; use 2, 4, 8, 16, 32 or 64"),
+    ("no_target_unit", "no target unit given; pass one, or choose your preferred system with --system or system = \"metric\" or \"imperial\" in {0}"),
+    ("the_config_file", "the config file"),
+    ("no_counterpart", "'{0}' has no {1} counterpart; give a target unit"),
+    ("live_and_rates", "--live and --rates both name the rates; give one"),
+    ("rates_from", "rates from {0}: {1}"),
+    ("range_to", "{0} {1} to {2} {3}"),
+    ("exactly", "{0} {1} = {2} {3} exactly"),
+    ("rounded_down", " (rounded down by {0} {1})"),
+    ("rounded_up", " (rounded up by {0} {1})"),
+    ("mixed_no_subunit", "--mixed has no sub-unit for '{0}'; it works with {1}"),
+    ("dms_not_an_angle", "--dms writes angles; '{0}' is not one"),
+    ("fav.saved", "Saved favorite '{0}' ({1} -> {2})"),
+    ("fav.none", "No favorites saved"),
+    ("fav.precision", "precision {0}"),
+    ("fav.format", "format \"{0}\""),
+    ("fav.removed", "Removed favorite '{0}'"),
+    ("fav.missing", "no favorite named '{0}'"),
+    ("fav.try_list", "Try '{0} fav list' to see saved favorites"),
+    ("fav.invalid_name", "invalid favorite name '{0}': use letters, digits, '-' and '_'"),
+    ("history.no_state_dir", "cannot locate the state directory (HOME is not set)"),
+    ("history.clear_failed", "could not clear history: {0}"),
+    ("history.cleared", "History cleared"),
+    ("history.invalid_count", "'{0}' is not a valid number of entries"),
+    ("history.unreadable", "could not read history: {0}"),
+    ("roman.out_of_range", "{0} is out of range; Roman numerals run from 1 to {1}"),
+    ("roman.invalid", "'{0}' is not a valid Roman numeral"),
+    ("roman.not_standard", "'{0}' isn't the standard spelling of {1}, which is {2}; {3} accepts it"),
+    ("radix.not_a_base", "'{0}' is not a base; use bin, oct, dec, hex or a number from 2 to 36"),
+    ("radix.bad_group", "'{0}' is not a valid group size"),
+    ("radix.number_required", "a number to convert is required"),
+    ("radix.not_a_number", "'{0}' is not a number"),
+    ("radix.not_a_digit", "'{0}' is not a digit in base {1}"),
+    ("radix.too_large", "'{0}' is too large; the largest magnitude is 2^128 - 1 ({1})"),
+    ("paper.unknown", "unknown paper size '{0}'; use A0–A{1}, B0–B{1}, letter, legal or tabloid"),
+    ("paper.comparison", "{0} is {1} and {2} than {3}"),
+    ("paper.narrower", "{0} mm narrower"),
+    ("paper.wider", "{0} mm wider"),
+    ("paper.as_wide", "as wide"),
+    ("paper.shorter", "{0} mm shorter"),
+    ("paper.taller", "{0} mm taller"),
+    ("paper.as_tall", "as tall"),
+    ("shoe.outside_chart", "{0} is outside the chart, which runs from {1} to {2}"),
+    ("shoe.unknown_system", "unknown sizing system '{0}'; use {1}"),
+    ("shoe.invalid_size", "'{0}' is not a valid shoe size"),
+    ("category.length", "Length"),
+    ("category.temperature", "Temperature"),
+    ("category.mass", "Mass"),
+    ("category.time", "Time"),
+    ("category.volume", "Volume"),
+    ("category.speed", "Speed"),
+    ("category.data", "Data"),
+    ("category.angle", "Angle"),
+    ("category.currency", "Currency"),
+    ("category.derived", "Derived"),
+    ("list.header", "Supported units:"),
+    ("help.usage", "USAGE:"),
+    ("info.category", "Category"),
+    ("info.aliases", "Aliases"),
+    ("info.base", "Base"),
+    ("info.is_base", "base unit of its category"),
+    ("info.system", "System"),
+    ("info.equals", "Equals"),
+    ("help.examples", "EXAMPLES:"),
+    ("help.more_examples", "({0} shows examples for every category)"),
+    ("help.options", "OPTIONS:"),
+    ("help.needs_system", "needs system = \"metric\" or \"imperial\" in the config"),
+    ("help.needs_http", "requires the 'http' feature"),
+    ("help.needs_tui", "requires the 'tui' feature"),
+    ("help.from_clipboard", "value read from the clipboard"),
+    ("help.sum_example", "e.g. sum 5ft 3in --to m"),
+    ("help.note", "Note: Unit names ignore case, except short symbols such as mm and mg; common aliases work too"),
+    ("option.help", "Show this help message"),
+    ("option.version", "Show version information"),
+    ("option.list", "List all supported units"),
+    ("option.category", "With --list, only list this category:\n{0}"),
+    ("option.detailed", "With --list, show each unit's size in its category's base unit"),
+    ("option.markdown", "With --list, print a Markdown table per category"),
+    ("option.examples", "Show example conversions for every category, or only this one"),
+    ("option.precision", "Round the result to N decimal places"),
+    ("option.format", "Output template using {value}, {from}, {result}, {to},\n{from_name} and {to_name}"),
+    ("option.names", "Spell out unit names (\"5 kilometers = 3.11 miles\")"),
+    ("option.echo_input", "Show units as typed instead of by their symbols"),
+    ("option.explain", "Show the arithmetic behind the conversion"),
+    ("option.humanize", "Show time results as \"1d 2h 3m 4s\" (or convert to 'human')"),
+    ("option.max_components", "Limit --humanize output to the N largest components"),
+    ("option.mixed", "Split the result into lb + oz, ft + in, st + lb or yd + ft"),
+    ("option.dms", "Write an angle as degrees, minutes and seconds"),
+    ("option.fraction", "Show the result to the nearest 1/N (2, 4, 8, 16, 32 or 64; default 16)"),
+    ("option.delta", "Treat a temperature as a difference (10 C -> 18 F)"),
+    ("option.via", "Convert between categories through a quantity that connects them,\ne.g. --via 1g/mL from kg to L"),
+    ("option.rates", "Convert currencies with the rates in FILE (TOML or JSON)"),
+    ("option.system", "Read gallon, quart, pint and fl oz as US or imperial units (us, imperial or metric)"),
+    ("option.calendar", "Read year and month as averages of this calendar (julian, gregorian or civil)"),
+    ("option.live", "Fetch currency rates from the rate service, cached for a day"),
+    ("option.exact", "Convert with exact fractions when the factors allow it"),
+    ("option.big", "With -p N (up to 50), print exact digits (requires 'bigdecimal')"),
+    ("option.json", "Print the result as a JSON object"),
+    ("option.csv", "Print batch results as CSV"),
+    ("option.batch", "Convert every 'value from to' line of FILE ('-' for stdin)"),
+    ("option.skip_errors", "Exit successfully even if some batch lines fail"),
+    ("option.filter", "Convert one number per stdin line and print the results"),
+    ("option.passthrough", "With --filter, copy non-numeric lines through unchanged"),
+    ("option.jobs", "Threads for --batch and csv (default: one per CPU)"),
+    ("option.no_progress", "Don't show progress for --batch, --filter and csv on a terminal"),
+    ("option.serve_stdio", "Answer JSON requests, one per stdin line, until EOF"),
+    ("option.copy", "Also place the bare result on the clipboard"),
+    ("option.paste", "Read the value from the clipboard when none is given"),
+    ("option.log_history", "Append this conversion to the history log"),
+    ("option.strict", "Fail instead of warning about a physically meaningless value"),
+    ("option.end_of_options", "Treat every later argument as a value or unit, even one starting with '-'"),
+    ("option.trace", "Print the library's trace events to stderr (requires 'tracing')"),
+    ("option.lang", "Language of messages: en, es or de (default: from LANG)"),
+];
+
+const ES: &[(&str, &str)] = &[
+    ("error", "Error"),
+    ("warning", "Aviso"),
+    ("note", "Nota"),
+    ("or", "o"),
+    ("invalid_value", "'{0}' no es un número válido"),
+    ("not_finite", "'{0}' no está permitido: el valor debe ser un número finito"),
+    ("overflow", "{0} {1} en {2}: resultado demasiado grande para representarlo"),
+    ("zero_speed", "{0}: una velocidad de cero no tiene ritmo"),
+    ("missing_unit", "falta la unidad antes de '^' en '{0}'"),
+    ("unknown_unit", "Unidad desconocida '{0}'"),
+    ("did_you_mean", "; ¿quisiste decir {0}?"),
+    ("category_mismatch", "No se puede convertir entre categorías de unidades distintas ({0})"),
+    ("unit_of_category", "{0} es una unidad de {1}"),
+    ("hint_density", "pista: convierte a través de una densidad, p. ej. agua ≈ 1 g/mL"),
+    ("hint_speed", "pista: convierte a través de una velocidad, p. ej. caminando ≈ 5 km/h"),
+    ("hint_duration", "pista: convierte a través de una duración, p. ej. 1 h"),
+    ("below_absolute_zero", "{0} {1} está por debajo del cero absoluto ({2} {1})"),
+    ("negative_uncertainty", "La incertidumbre de '{0}' no puede ser negativa"),
+    ("offset_in_compound", "'{1}' no puede incluir {0}: las temperaturas y otras unidades con desplazamiento solo se convierten solas"),
+    ("no_path", "Ninguna cadena de factores conocidos lleva de '{0}' a '{1}'"),
+    ("no_gauge", "'{0}' no es un calibre de cable entre 4/0 y 40"),
+    ("no_rates", "'{0}' parece un código de moneda, pero no hay tipos de cambio cargados; indica un archivo con --rates o 'rates' en la configuración"),
+    ("unknown_currency", "la moneda '{0}' no está en el archivo de tipos de cambio"),
+    ("rates_dated", "tipos de cambio de {0} con fecha {1}, de hace {2} días"),
+    ("data_symbols", "los símbolos de datos distinguen mayúsculas: {0}"),
+    ("ambiguous_unit", "'{0}' es una cantidad distinta en los sistemas estadounidense e imperial; usa {1}, o elige un sistema con --system us|imperial o 'system' en la configuración"),
+    ("disabled_unit", "la unidad '{0}' está desactivada en tu configuración"),
+    ("use_instead", "; usa {0}"),
+    ("negative_length", "Una longitud negativa no tiene sentido físico"),
+    ("negative_mass", "Una masa negativa no tiene sentido físico"),
+    ("negative_volume", "Un volumen negativo no tiene sentido físico"),
+    ("negative_amount", "Una cantidad negativa no tiene sentido físico aquí"),
+    ("underflow", "El resultado es demasiado pequeño para un double y ha quedado en 0"),
+    ("precision_loss", "El número está demasiado cerca de cero para conservar toda su precisión; las últimas cifras no son fiables"),
+    ("gauge_rounded", "El tamaño cae entre dos calibres de cable; el resultado es el más cercano"),
+    ("try_list", "Prueba '{0} --list' para ver las unidades disponibles"),
+    ("no_match", "ninguna unidad coincide con '{0}'"),
+    ("try_help", "Prueba '{0} --help' para más información"),
+    ("expected_arguments", "Se esperaban 3 argumentos, se recibieron {0}"),
+    ("usage", "Uso: {0} <valor> <unidad_origen> <unidad_destino>"),
+    ("fraction_temperature", "--fraction solo se aplica a magnitudes lineales, no a temperaturas"),
+    ("no_exact_form", "esta conversión no tiene forma exacta; se muestra el resultado en coma flotante"),
+    ("usage_label", "Uso"),
+    ("requires_value", "{0} necesita un valor"),
+    ("unexpected_argument", "argumento inesperado '{0}'"),
+    ("csv.bad_missing", "--missing debe ser 'blank' o 'error', no '{0}'"),
+    ("csv.column_required", "falta --column"),
+    ("csv.column_from_one", "los índices de columna empiezan en 1"),
+    ("csv.numeric_column", "--no-header necesita un --column numérico"),
+    ("csv.rename_needs_header", "--rename necesita una fila de cabecera"),
+    ("csv.from_required", "falta --from"),
+    ("csv.to_required", "falta --to"),
+    ("csv.input_required", "se necesita un archivo de entrada"),
+    ("csv.cannot_read", "no se puede leer '{0}': {1}"),
+    ("csv.cannot_write", "no se puede escribir '{0}': {1}"),
+    ("csv.column_out_of_range", "la columna {0} está fuera de rango; la cabecera tiene {1} columnas"),
+    ("csv.no_such_column", "no hay ninguna columna llamada '{0}' en la cabecera"),
+    ("csv.missing_value", "fila {0}: falta el valor en la columna {1}"),
+    ("csv.row", "fila {0}: {1}"),
+    ("csv.unterminated", "campo entre comillas sin cerrar"),
+    ("compare.invalid_tolerance", "'{0}' no es una tolerancia válida"),
+    ("compare.negative_tolerance", "la tolerancia no puede ser negativa: '{0}'"),
+    ("compare.within", "Dentro de la tolerancia"),
+    ("compare.outside", "Fuera de la tolerancia"),
+    ("compare.differs", "{0} {1} = {2} {3}, que difiere de {4} {3} en {5} {3} ({6}%)"),
+    ("compare.equal", "{0} {1} es igual a {2} {3}"),
+    ("compare.larger", "{0} {1} es mayor que {2} {3}"),
+    ("compare.smaller", "{0} {1} es menor que {2} {3}"),
+    ("compare.by", "en {0} {1} ({2} {3})"),
+    ("compare.ratio", "razón {0}"),
+    ("big_needs_backend", "--big necesita el motor de alta precisión; recompila con `--features bigdecimal`"),
+    ("trace_needs_feature", "-vv necesita la instrumentación de trazas; recompila con `--features tracing`"),
+    ("big_max_places", "--big admite como máximo {0} decimales"),
+    ("no_tui", "esta compilación no tiene TUI; recompila con `--features tui`"),
+    ("no_http", "esta compilación no tiene servidor HTTP; recompila con `--features http`"),
+    ("no_angle", "esta compilación no tiene ángulos; recompila con `--features angle`"),
+    ("clipboard_unreadable", "no se pudo leer el portapapeles: {0}"),
+    ("no_value", "no se indicó ningún valor; pásalo como primer argumento"),
+    ("history_unwritable", "no se pudo escribir el historial de conversiones: {0}"),
+    ("no_config_path", "no se encuentra el archivo de configuración (HOME no está definido)"),
+    ("could_not_write", "no se pudo escribir {0}: {1}"),
+    ("args.precision_required", "--precision requiere un número de decimales"),
+    ("args.invalid_precision", "'{0}' no es una precisión válida"),
+    ("args.format_required", "--format requiere una plantilla"),
+    ("args.batch_required", "--batch requiere un archivo (o '-' para la entrada estándar)"),
+    ("args.filter_required", "--filter requiere <unidad_origen> <unidad_destino>"),
+    ("args.via_required", "--via requiere una cantidad, como 1g/mL"),
+    ("args.system_required", "--system requiere us, imperial o metric"),
+    ("args.unknown_system", "sistema desconocido '{0}'; usa us, imperial o metric"),
+    ("args.calendar_required", "--calendar requiere julian, gregorian o civil"),
+    ("args.unknown_calendar", "calendario desconocido '{0}'; usa julian, gregorian o civil"),
+    ("args.rates_required", "--rates requiere un archivo de tipos de cambio"),
+    ("args.lang_required", "--lang requiere un idioma (en, es o de)"),
+    ("args.unsupported_lang", "idioma no admitido '{0}'; usa en, es o de"),
+    ("args.jobs_required", "--jobs requiere un número de hilos"),
+    ("args.invalid_jobs", "'{0}' no es un número de hilos válido"),
+    ("args.max_components_required", "--max-components requiere un número"),
+    ("args.invalid_max_components", "'{0}' no es un número de componentes válido"),
+    ("args.bad_denominator", "'{0}' no es un denominador de fracción admitido; usa 2, 4, 8, 16, 32 o 64"),
+    ("no_target_unit", "no se indicó una unidad de destino; indica una, o elige tu sistema preferido con --system o system = \"metric\" o \"imperial\" en {0}"),
+    ("the_config_file", "el archivo de configuración"),
+    ("no_counterpart", "'{0}' no tiene equivalente en el sistema {1}; indica una unidad de destino"),
+    ("live_and_rates", "--live y --rates indican ambos los tipos de cambio; usa solo uno"),
+    ("rates_from", "tipos de cambio de {0}: {1}"),
+    ("range_to", "{0} {1} a {2} {3}"),
+    ("exactly", "{0} {1} = {2} {3} exactamente"),
+    ("rounded_down", " (redondeado hacia abajo en {0} {1})"),
+    ("rounded_up", " (redondeado hacia arriba en {0} {1})"),
+    ("mixed_no_subunit", "--mixed no tiene subunidad para '{0}'; funciona con {1}"),
+    ("dms_not_an_angle", "--dms escribe ángulos; '{0}' no es uno"),
+    ("fav.saved", "Favorito '{0}' guardado ({1} -> {2})"),
+    ("fav.none", "No hay favoritos guardados"),
+    ("fav.precision", "precisión {0}"),
+    ("fav.format", "formato \"{0}\""),
+    ("fav.removed", "Favorito '{0}' eliminado"),
+    ("fav.missing", "no hay ningún favorito llamado '{0}'"),
+    ("fav.try_list", "Prueba '{0} fav list' para ver los favoritos guardados"),
+    ("fav.invalid_name", "nombre de favorito no válido '{0}': usa letras, dígitos, '-' y '_'"),
+    ("history.no_state_dir", "no se encuentra el directorio de estado (HOME no está definido)"),
+    ("history.clear_failed", "no se pudo borrar el historial: {0}"),
+    ("history.cleared", "Historial borrado"),
+    ("history.invalid_count", "'{0}' no es un número de entradas válido"),
+    ("history.unreadable", "no se pudo leer el historial: {0}"),
+    ("roman.out_of_range", "{0} está fuera de rango; los números romanos van de 1 a {1}"),
+    ("roman.invalid", "'{0}' no es un número romano válido"),
+    ("roman.not_standard", "'{0}' no es la forma estándar de escribir {1}, que es {2}; {3} la acepta"),
+    ("radix.not_a_base", "'{0}' no es una base; usa bin, oct, dec, hex o un número de 2 a 36"),
+    ("radix.bad_group", "'{0}' no es un tamaño de grupo válido"),
+    ("radix.number_required", "falta el número que convertir"),
+    ("radix.not_a_number", "'{0}' no es un número"),
+    ("radix.not_a_digit", "'{0}' no es un dígito en base {1}"),
+    ("radix.too_large", "'{0}' es demasiado grande; la mayor magnitud es 2^128 - 1 ({1})"),
+    ("paper.unknown", "tamaño de papel desconocido '{0}'; usa A0–A{1}, B0–B{1}, letter, legal o tabloid"),
+    ("paper.comparison", "{0} es {1} y {2} que {3}"),
+    ("paper.narrower", "{0} mm más estrecho"),
+    ("paper.wider", "{0} mm más ancho"),
+    ("paper.as_wide", "igual de ancho"),
+    ("paper.shorter", "{0} mm más bajo"),
+    ("paper.taller", "{0} mm más alto"),
+    ("paper.as_tall", "igual de alto"),
+    ("shoe.outside_chart", "{0} está fuera de la tabla, que va de {1} a {2}"),
+    ("shoe.unknown_system", "sistema de tallas desconocido '{0}'; usa {1}"),
+    ("shoe.invalid_size", "'{0}' no es una talla de calzado válida"),
+    ("category.length", "Longitud"),
+    ("category.temperature", "Temperatura"),
+    ("category.mass", "Masa"),
+    ("category.time", "Tiempo"),
+    ("category.volume", "Volumen"),
+    ("category.speed", "Velocidad"),
+    ("category.data", "Datos"),
+    ("category.angle", "Ángulo"),
+    ("category.currency", "Moneda"),
+    ("category.derived", "magnitud derivada"),
+    ("list.header", "Unidades disponibles:"),
+    ("help.usage", "USO:"),
+    ("info.category", "Categoría"),
+    ("info.aliases", "Alias"),
+    ("info.base", "Base"),
+    ("info.is_base", "unidad base de su categoría"),
+    ("info.system", "Sistema"),
+    ("info.equals", "Equivale a"),
+    ("help.examples", "EJEMPLOS:"),
+    ("help.more_examples", "({0} muestra ejemplos de cada categoría)"),
+    ("help.options", "OPCIONES:"),
+    ("help.needs_system", "requiere system = \"metric\" o \"imperial\" en la configuración"),
+    ("help.needs_http", "requiere la característica 'http'"),
+    ("help.needs_tui", "requiere la característica 'tui'"),
+    ("help.from_clipboard", "valor leído del portapapeles"),
+    ("help.sum_example", "p. ej. sum 5ft 3in --to m"),
+    ("help.note", "Nota: Los nombres de unidades no distinguen mayúsculas, salvo símbolos cortos como mm y mg, y admiten alias comunes"),
+    ("option.help", "Muestra este mensaje de ayuda"),
+    ("option.version", "Muestra la versión"),
+    ("option.list", "Lista todas las unidades disponibles"),
+    ("option.category", "Con --list, lista solo esta categoría:\n{0}"),
+    ("option.detailed", "Con --list, muestra el tamaño de cada unidad en la unidad base de su categoría"),
+    ("option.markdown", "Con --list, imprime una tabla Markdown por categoría"),
+    ("option.examples", "Muestra conversiones de ejemplo para cada categoría, o solo para esta"),
+    ("option.precision", "Redondea el resultado a N decimales"),
+    ("option.format", "Plantilla de salida con {value}, {from}, {result}, {to},\n{from_name} y {to_name}"),
+    ("option.names", "Escribe los nombres de las unidades (\"5 kilometers = 3.11 miles\")"),
+    ("option.echo_input", "Muestra las unidades tal como se escribieron, no por su símbolo"),
+    ("option.explain", "Muestra la aritmética de la conversión"),
+    ("option.humanize", "Muestra los tiempos como \"1d 2h 3m 4s\" (o convierte a 'human')"),
+    ("option.max_components", "Limita --humanize a los N componentes mayores"),
+    ("option.mixed", "Divide el resultado en lb + oz, ft + in, st + lb o yd + ft"),
+    ("option.dms", "Escribe un ángulo en grados, minutos y segundos"),
+    ("option.fraction", "Muestra el resultado al 1/N más cercano (2, 4, 8, 16, 32 o 64; por defecto 16)"),
+    ("option.delta", "Trata una temperatura como una diferencia (10 C -> 18 F)"),
+    ("option.via", "Convierte entre categorías a través de una cantidad que las une,\np. ej. --via 1g/mL de kg a L"),
+    ("option.rates", "Convierte monedas con los tipos de cambio de FILE (TOML o JSON)"),
+    ("option.system", "Lee galón, cuarto, pinta y fl oz como unidades estadounidenses o imperiales (us, imperial o metric)"),
+    ("option.calendar", "Lee año y mes como promedios de este calendario (julian, gregorian o civil)"),
+    ("option.live", "Obtiene los tipos de cambio del servicio en línea y los guarda un día"),
+    ("option.exact", "Convierte con fracciones exactas cuando los factores lo permiten"),
+    ("option.big", "Con -p N (hasta 50), imprime dígitos exactos (requiere 'bigdecimal')"),
+    ("option.json", "Imprime el resultado como un objeto JSON"),
+    ("option.csv", "Imprime los resultados por lotes como CSV"),
+    ("option.batch", "Convierte cada línea 'valor origen destino' de FILE ('-' para stdin)"),
+    ("option.skip_errors", "Termina con éxito aunque fallen algunas líneas del lote"),
+    ("option.filter", "Convierte un número por línea de stdin e imprime los resultados"),
+    ("option.passthrough", "Con --filter, copia sin cambios las líneas no numéricas"),
+    ("option.jobs", "Hilos para --batch y csv (por defecto: uno por CPU)"),
+    ("option.no_progress", "No muestra el progreso de --batch, --filter y csv en una terminal"),
+    ("option.serve_stdio", "Responde peticiones JSON, una por línea de stdin, hasta EOF"),
+    ("option.copy", "Copia también el resultado al portapapeles"),
+    ("option.paste", "Lee el valor del portapapeles si no se indica ninguno"),
+    ("option.log_history", "Añade esta conversión al historial"),
+    ("option.strict", "Falla en lugar de avisar de un valor sin sentido físico"),
+    ("option.end_of_options", "Trata los argumentos siguientes como valores o unidades, aunque empiecen por '-'"),
+    ("option.trace", "Imprime los eventos de traza de la biblioteca en stderr (requiere 'tracing')"),
+    ("option.lang", "Idioma de los mensajes: en, es o de (por defecto: según LANG)"),
+];
+
+const DE: &[(&str, &str)] = &[
+    ("error", "Fehler"),
+    ("warning", "Warnung"),
+    ("note", "Hinweis"),
+    ("or", "oder"),
+    ("invalid_value", "'{0}' ist keine gültige Zahl"),
+    ("not_finite", "'{0}' ist nicht erlaubt: der Wert muss eine endliche Zahl sein"),
+    ("overflow", "{0} {1} in {2}: Ergebnis zu groß für die Darstellung"),
+    ("zero_speed", "{0}: eine Geschwindigkeit von null hat kein Tempo"),
+    ("missing_unit", "Einheit vor '^' fehlt in '{0}'"),
+    ("unknown_unit", "Unbekannte Einheit '{0}'"),
+    ("did_you_mean", "; meintest du {0}?"),
+    ("category_mismatch", "Zwischen verschiedenen Einheitenkategorien kann nicht umgerechnet werden ({0})"),
+    ("unit_of_category", "{0} ist eine Einheit der Kategorie {1}"),
+    ("hint_density", "Tipp: über eine Dichte umrechnen, z. B. Wasser ≈ 1 g/mL"),
+    ("hint_speed", "Tipp: über eine Geschwindigkeit umrechnen, z. B. Gehen ≈ 5 km/h"),
+    ("hint_duration", "Tipp: über eine Dauer umrechnen, z. B. 1 h"),
+    ("below_absolute_zero", "{0} {1} liegt unter dem absoluten Nullpunkt ({2} {1})"),
+    ("negative_uncertainty", "Die Unsicherheit in '{0}' darf nicht negativ sein"),
+    ("offset_in_compound", "'{1}' darf {0} nicht enthalten: Temperaturen und andere Einheiten mit Nullpunktversatz lassen sich nur allein umrechnen"),
+    ("no_path", "Keine Kette bekannter Faktoren führt von '{0}' nach '{1}'"),
+    ("no_gauge", "'{0}' ist keine Drahtstärke zwischen 4/0 und 40"),
+    ("no_rates", "'{0}' sieht wie ein Währungscode aus, aber es sind keine Kurse geladen; gib eine Kursdatei mit --rates oder 'rates' in der Konfiguration an"),
+    ("unknown_currency", "Währung '{0}' steht nicht in der Kursdatei"),
+    ("rates_dated", "Kurse aus {0} vom {1}, {2} Tage alt"),
+    ("data_symbols", "Datensymbole unterscheiden Groß- und Kleinschreibung: {0}"),
+    ("ambiguous_unit", "'{0}' ist im US- und im imperialen System eine andere Menge; verwende {1} oder wähle ein System mit --system us|imperial oder 'system' in der Konfiguration"),
+    ("disabled_unit", "Einheit '{0}' ist in deiner Konfiguration deaktiviert"),
+    ("use_instead", "; verwende {0}"),
+    ("negative_length", "Eine negative Länge ergibt physikalisch keinen Sinn"),
+    ("negative_mass", "Eine negative Masse ergibt physikalisch keinen Sinn"),
+    ("negative_volume", "Ein negatives Volumen ergibt physikalisch keinen Sinn"),
+    ("negative_amount", "Eine negative Menge ergibt hier physikalisch keinen Sinn"),
+    ("underflow", "Das Ergebnis ist für ein Double zu klein und wurde zu 0"),
+    ("precision_loss", "Die Zahl liegt zu nahe an null für volle Genauigkeit; die letzten Stellen sind unzuverlässig"),
+    ("gauge_rounded", "Die Größe liegt zwischen zwei Drahtstärken; das Ergebnis ist die nähere"),
+    ("try_list", "'{0} --list' zeigt die unterstützten Einheiten"),
+    ("no_match", "keine Einheit passt zu '{0}'"),
+    ("try_help", "'{0} --help' zeigt weitere Informationen"),
+    ("expected_arguments", "3 Argumente erwartet, {0} erhalten"),
+    ("usage", "Aufruf: {0} <Wert> <Ausgangseinheit> <Zieleinheit>"),
+    ("fraction_temperature", "--fraction gilt nur für lineare Größen, nicht für Temperaturen"),
+    ("no_exact_form", "diese Umrechnung hat keine exakte Form; das Gleitkommaergebnis wird angezeigt"),
+    ("usage_label", "Aufruf"),
+    ("requires_value", "{0} benötigt einen Wert"),
+    ("unexpected_argument", "unerwartetes Argument '{0}'"),
+    ("csv.bad_missing", "--missing muss 'blank' oder 'error' sein, nicht '{0}'"),
+    ("csv.column_required", "--column fehlt"),
+    ("csv.column_from_one", "Spaltennummern beginnen bei 1"),
+    ("csv.numeric_column", "--no-header braucht eine numerische --column"),
+    ("csv.rename_needs_header", "--rename braucht eine Kopfzeile"),
+    ("csv.from_required", "--from fehlt"),
+    ("csv.to_required", "--to fehlt"),
+    ("csv.input_required", "eine Eingabedatei ist erforderlich"),
+    ("csv.cannot_read", "'{0}' kann nicht gelesen werden: {1}"),
+    ("csv.cannot_write", "'{0}' kann nicht geschrieben werden: {1}"),
+    ("csv.column_out_of_range", "Spalte {0} liegt außerhalb des Bereichs; die Kopfzeile hat {1} Spalten"),
+    ("csv.no_such_column", "keine Spalte namens '{0}' in der Kopfzeile"),
+    ("csv.missing_value", "Zeile {0}: fehlender Wert in Spalte {1}"),
+    ("csv.row", "Zeile {0}: {1}"),
+    ("csv.unterminated", "nicht geschlossenes Feld in Anführungszeichen"),
+    ("compare.invalid_tolerance", "'{0}' ist keine gültige Toleranz"),
+    ("compare.negative_tolerance", "die Toleranz darf nicht negativ sein: '{0}'"),
+    ("compare.within", "Innerhalb der Toleranz"),
+    ("compare.outside", "Außerhalb der Toleranz"),
+    ("compare.differs", "{0} {1} = {2} {3}, weicht von {4} {3} um {5} {3} ab ({6}%)"),
+    ("compare.equal", "{0} {1} ist gleich {2} {3}"),
+    ("compare.larger", "{0} {1} ist größer als {2} {3}"),
+    ("compare.smaller", "{0} {1} ist kleiner als {2} {3}"),
+    ("compare.by", "um {0} {1} ({2} {3})"),
+    ("compare.ratio", "Verhältnis {0}"),
+    ("big_needs_backend", "--big benötigt das hochgenaue Backend; mit `--features bigdecimal` neu bauen"),
+    ("trace_needs_feature", "-vv benötigt die Tracing-Instrumentierung; mit `--features tracing` neu bauen"),
+    ("big_max_places", "--big unterstützt höchstens {0} Nachkommastellen"),
+    ("no_tui", "dieser Build hat keine TUI; mit `--features tui` neu bauen"),
+    ("no_http", "dieser Build hat keinen HTTP-Server; mit `--features http` neu bauen"),
+    ("no_angle", "dieser Build hat keine Winkel; mit `--features angle` neu bauen"),
+    ("clipboard_unreadable", "die Zwischenablage konnte nicht gelesen werden: {0}"),
+    ("no_value", "kein Wert angegeben; gib ihn als erstes Argument an"),
+    ("history_unwritable", "der Umrechnungsverlauf konnte nicht geschrieben werden: {0}"),
+    ("no_config_path", "die Konfigurationsdatei wurde nicht gefunden (HOME ist nicht gesetzt)"),
+    ("could_not_write", "{0} konnte nicht geschrieben werden: {1}"),
+    ("args.precision_required", "--precision benötigt eine Anzahl von Nachkommastellen"),
+    ("args.invalid_precision", "'{0}' ist keine gültige Genauigkeit"),
+    ("args.format_required", "--format benötigt eine Vorlage"),
+    ("args.batch_required", "--batch benötigt eine Datei (oder '-' für die Standardeingabe)"),
+    ("args.filter_required", "--filter benötigt <Ausgangseinheit> <Zieleinheit>"),
+    ("args.via_required", "--via benötigt eine Größe, etwa 1g/mL"),
+    ("args.system_required", "--system benötigt us, imperial oder metric"),
+    ("args.unknown_system", "unbekanntes System '{0}'; verwende us, imperial oder metric"),
+    ("args.calendar_required", "--calendar benötigt julian, gregorian oder civil"),
+    ("args.unknown_calendar", "unbekannter Kalender '{0}'; verwende julian, gregorian oder civil"),
+    ("args.rates_required", "--rates benötigt eine Kursdatei"),
+    ("args.lang_required", "--lang benötigt eine Sprache (en, es oder de)"),
+    ("args.unsupported_lang", "nicht unterstützte Sprache '{0}'; verwende en, es oder de"),
+    ("args.jobs_required", "--jobs benötigt eine Anzahl von Threads"),
+    ("args.invalid_jobs", "'{0}' ist keine gültige Anzahl von Threads"),
+    ("args.max_components_required", "--max-components benötigt eine Zahl"),
+    ("args.invalid_max_components", "'{0}' ist keine gültige Anzahl von Komponenten"),
+    ("args.bad_denominator", "'{0}' ist kein unterstützter Bruchnenner; verwende 2, 4, 8, 16, 32 oder 64"),
+    ("no_target_unit", "keine Zieleinheit angegeben; gib eine an oder wähle dein bevorzugtes System mit --system oder system = \"metric\" oder \"imperial\" in {0}"),
+    ("the_config_file", "der Konfigurationsdatei"),
+    ("no_counterpart", "'{0}' hat keine Entsprechung im System {1}; gib eine Zieleinheit an"),
+    ("live_and_rates", "--live und --rates geben beide die Kurse an; gib nur eines an"),
+    ("rates_from", "Kurse aus {0}: {1}"),
+    ("range_to", "{0} {1} bis {2} {3}"),
+    ("exactly", "{0} {1} = {2} {3} exakt"),
+    ("rounded_down", " (um {0} {1} abgerundet)"),
+    ("rounded_up", " (um {0} {1} aufgerundet)"),
+    ("mixed_no_subunit", "--mixed hat keine Untereinheit für '{0}'; es funktioniert mit {1}"),
+    ("dms_not_an_angle", "--dms schreibt Winkel; '{0}' ist keiner"),
+    ("fav.saved", "Favorit '{0}' gespeichert ({1} -> {2})"),
+    ("fav.none", "Keine Favoriten gespeichert"),
+    ("fav.precision", "Genauigkeit {0}"),
+    ("fav.format", "Format \"{0}\""),
+    ("fav.removed", "Favorit '{0}' entfernt"),
+    ("fav.missing", "kein Favorit namens '{0}'"),
+    ("fav.try_list", "'{0} fav list' zeigt die gespeicherten Favoriten"),
+    ("fav.invalid_name", "ungültiger Favoritenname '{0}': verwende Buchstaben, Ziffern, '-' und '_'"),
+    ("history.no_state_dir", "das Zustandsverzeichnis wurde nicht gefunden (HOME ist nicht gesetzt)"),
+    ("history.clear_failed", "der Verlauf konnte nicht gelöscht werden: {0}"),
+    ("history.cleared", "Verlauf gelöscht"),
+    ("history.invalid_count", "'{0}' ist keine gültige Anzahl von Einträgen"),
+    ("history.unreadable", "der Verlauf konnte nicht gelesen werden: {0}"),
+    ("roman.out_of_range", "{0} liegt außerhalb des Bereichs; römische Zahlen reichen von 1 bis {1}"),
+    ("roman.invalid", "'{0}' ist keine gültige römische Zahl"),
+    ("roman.not_standard", "'{0}' ist nicht die übliche Schreibweise von {1}, die {2} lautet; {3} akzeptiert sie"),
+    ("radix.not_a_base", "'{0}' ist keine Basis; verwende bin, oct, dec, hex oder eine Zahl von 2 bis 36"),
+    ("radix.bad_group", "'{0}' ist keine gültige Gruppengröße"),
+    ("radix.number_required", "eine umzurechnende Zahl fehlt"),
+    ("radix.not_a_number", "'{0}' ist keine Zahl"),
+    ("radix.not_a_digit", "'{0}' ist keine Ziffer zur Basis {1}"),
+    ("radix.too_large", "'{0}' ist zu groß; der größte Betrag ist 2^128 - 1 ({1})"),
+    ("paper.unknown", "unbekanntes Papierformat '{0}'; verwende A0–A{1}, B0–B{1}, letter, legal oder tabloid"),
+    ("paper.comparison", "{0} ist {1} und {2} als {3}"),
+    ("paper.narrower", "{0} mm schmaler"),
+    ("paper.wider", "{0} mm breiter"),
+    ("paper.as_wide", "gleich breit"),
+    ("paper.shorter", "{0} mm kürzer"),
+    ("paper.taller", "{0} mm höher"),
+    ("paper.as_tall", "gleich hoch"),
+    ("shoe.outside_chart", "{0} liegt außerhalb der Tabelle, die von {1} bis {2} reicht"),
+    ("shoe.unknown_system", "unbekanntes Größensystem '{0}'; verwende {1}"),
+    ("shoe.invalid_size", "'{0}' ist keine gültige Schuhgröße"),
+    ("category.length", "Länge"),
+    ("category.temperature", "Temperatur"),
+    ("category.mass", "Masse"),
+    ("category.time", "Zeit"),
+    ("category.volume", "Volumen"),
+    ("category.speed", "Geschwindigkeit"),
+    ("category.data", "Daten"),
+    ("category.angle", "Winkel"),
+    ("category.currency", "Währung"),
+    ("category.derived", "abgeleitete Größe"),
+    ("list.header", "Unterstützte Einheiten:"),
+    ("help.usage", "AUFRUF:"),
+    ("info.category", "Kategorie"),
+    ("info.aliases", "Aliase"),
+    ("info.base", "Basis"),
+    ("info.is_base", "Basiseinheit ihrer Kategorie"),
+    ("info.system", "System"),
+    ("info.equals", "Entspricht"),
+    ("help.examples", "BEISPIELE:"),
+    ("help.more_examples", "({0} zeigt Beispiele für jede Kategorie)"),
+    ("help.options", "OPTIONEN:"),
+    ("help.needs_system", "benötigt system = \"metric\" oder \"imperial\" in der Konfiguration"),
+    ("help.needs_http", "benötigt das Feature 'http'"),
+    ("help.needs_tui", "benötigt das Feature 'tui'"),
+    ("help.from_clipboard", "Wert aus der Zwischenablage"),
+    ("help.sum_example", "z. B. sum 5ft 3in --to m"),
+    ("help.note", "Hinweis: Groß- und Kleinschreibung von Einheiten ist egal, außer bei kurzen Symbolen wie mm und mg; gängige Aliase werden erkannt"),
+    ("option.help", "Zeigt diese Hilfe"),
+    ("option.version", "Zeigt die Version"),
+    ("option.list", "Listet alle unterstützten Einheiten auf"),
+    ("option.category", "Mit --list nur diese Kategorie auflisten:\n{0}"),
+    ("option.detailed", "Mit --list die Größe jeder Einheit in der Basiseinheit ihrer Kategorie zeigen"),
+    ("option.markdown", "Mit --list eine Markdown-Tabelle pro Kategorie ausgeben"),
+    ("option.examples", "Zeigt Beispielumrechnungen für jede Kategorie oder nur für diese"),
+    ("option.precision", "Rundet das Ergebnis auf N Nachkommastellen"),
+    ("option.format", "Ausgabevorlage mit {value}, {from}, {result}, {to},\n{from_name} und {to_name}"),
+    ("option.names", "Schreibt Einheitennamen aus (\"5 kilometers = 3.11 miles\")"),
+    ("option.echo_input", "Zeigt Einheiten wie eingegeben statt mit ihrem Symbol"),
+    ("option.explain", "Zeigt die Rechnung hinter der Umrechnung"),
+    ("option.humanize", "Zeigt Zeiten als \"1d 2h 3m 4s\" (oder rechnet in 'human' um)"),
+    ("option.max_components", "Beschränkt --humanize auf die N größten Komponenten"),
+    ("option.mixed", "Teilt das Ergebnis in lb + oz, ft + in, st + lb oder yd + ft"),
+    ("option.dms", "Schreibt einen Winkel in Grad, Minuten und Sekunden"),
+    ("option.fraction", "Zeigt das Ergebnis auf das nächste 1/N (2, 4, 8, 16, 32 oder 64; Standard 16)"),
+    ("option.delta", "Behandelt eine Temperatur als Differenz (10 C -> 18 F)"),
+    ("option.via", "Rechnet zwischen Kategorien über eine verbindende Größe um,\nz. B. --via 1g/mL von kg nach L"),
+    ("option.rates", "Rechnet Währungen mit den Kursen aus FILE um (TOML oder JSON)"),
+    ("option.system", "Liest Gallone, Quart, Pint und fl oz als US- oder imperiale Einheiten (us, imperial oder metric)"),
+    ("option.calendar", "Liest Jahr und Monat als Mittelwerte dieses Kalenders (julian, gregorian oder civil)"),
+    ("option.live", "Ruft Wechselkurse vom Kursdienst ab und speichert sie einen Tag"),
+    ("option.exact", "Rechnet mit exakten Brüchen, wenn die Faktoren es erlauben"),
+    ("option.big", "Mit -p N (bis 50) exakte Ziffern ausgeben (benötigt 'bigdecimal')"),
+    ("option.json", "Gibt das Ergebnis als JSON-Objekt aus"),
+    ("option.csv", "Gibt Stapelergebnisse als CSV aus"),
+    ("option.batch", "Rechnet jede Zeile 'Wert von nach' aus FILE um ('-' für stdin)"),
+    ("option.skip_errors", "Endet erfolgreich, auch wenn einige Zeilen fehlschlagen"),
+    ("option.filter", "Rechnet eine Zahl pro stdin-Zeile um und gibt die Ergebnisse aus"),
+    ("option.passthrough", "Mit --filter nicht-numerische Zeilen unverändert durchreichen"),
+    ("option.jobs", "Threads für --batch und csv (Standard: einer pro CPU)"),
+    ("option.no_progress", "Keinen Fortschritt für --batch, --filter und csv im Terminal anzeigen"),
+    ("option.serve_stdio", "Beantwortet JSON-Anfragen, eine pro stdin-Zeile, bis EOF"),
+    ("option.copy", "Legt das Ergebnis zusätzlich in die Zwischenablage"),
+    ("option.paste", "Liest den Wert aus der Zwischenablage, wenn keiner angegeben ist"),
+    ("option.log_history", "Hängt diese Umrechnung an den Verlauf an"),
+    ("option.strict", "Bricht ab, statt vor einem physikalisch sinnlosen Wert zu warnen"),
+    ("option.end_of_options", "Behandelt alle folgenden Argumente als Werte oder Einheiten, auch wenn sie mit '-' beginnen"),
+    ("option.trace", "Gibt die Trace-Ereignisse der Bibliothek auf stderr aus (benötigt 'tracing')"),
+    ("option.lang", "Sprache der Meldungen: en, es oder de (Standard: aus LANG)"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_locales() {
+        assert_eq!(Lang::parse("es"), Some(Lang::Es));
+        assert_eq!(Lang::parse("es_ES.UTF-8"), Some(Lang::Es));
+        assert_eq!(Lang::parse("de-AT"), Some(Lang::De));
+        assert_eq!(Lang::parse("C"), Some(Lang::En));
+        assert_eq!(Lang::parse("fr_FR"), None);
+    }
+
+    #[test]
+    fn test_fill_and_english_fallback() {
+        assert_eq!(fill(Lang::Es, "unknown_unit", &[&"furlong"]), "Unidad desconocida 'furlong'");
+        assert_eq!(fill(Lang::De, "expected_arguments", &[&1]), "3 Argumente erwartet, 1 erhalten");
+        assert_eq!(lookup(&[("error", "Fehler")], "error"), "Fehler");
+        assert_eq!(lookup(&[("error", "Fehler")], "note"), "Note");
+    }
+
+    #[test]
+    fn test_catalogs_are_complete() {
+        let missing = |catalog: &[(&str, &str)]| EN.iter().find(|(key, _)| !catalog.iter().any(|(k, _)| k == key)).map(|(k, _)| *k);
+        assert_eq!(missing(ES), None);
+        assert_eq!(missing(DE), None);
+    }
+}
@@ -0,0 +1,182 @@
+//! Unit metadata as JSON, for `--list --json` and other tools. A `UnitInfo`
+//! is a read-only view of a unit with its conversion flattened to a factor
+//! and an offset; its JSON reads back as a `UnitDef`, so an exported table
+//! can be registered again.
+
+use crate::json::Json;
+use crate::registry::UnitDef;
+use crate::{Unit, UnitCategory};
+
+/// Units that belong to a measurement system, for the `systems` tags.
+const METRIC: [&str; 13] = ["km", "m", "cm", "mm", "kg", "g", "mg", "ton", "C", "K", "m3", "L", "mL"];
+const IMPERIAL: [&str; 16] =
+    ["mi", "yd", "ft", "in", "lb", "oz", "st", "F", "floz-us", "floz-imp", "pt-us", "pt-imp", "qt-us", "qt-imp", "gal-us", "gal-imp"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitInfo {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub case_sensitive: bool,
+    pub category: UnitCategory,
+    /// Base units per unit, after adding `offset`.
+    pub factor: f64,
+    pub offset: f64,
+    pub singular: &'static str,
+    pub plural: &'static str,
+    /// "metric" and/or "imperial"; empty for units like seconds that are both.
+    pub systems: Vec<&'static str>,
+}
+
+impl UnitInfo {
+    pub fn of(unit: &Unit) -> UnitInfo {
+        let (numerator, denominator) = unit.conversion.factor();
+        let systems = [("metric", &METRIC[..]), ("imperial", &IMPERIAL[..])]
+            .into_iter()
+            .filter(|(_, names)| names.contains(&unit.name))
+            .map(|(system, _)| system)
+            .collect();
+        UnitInfo {
+            name: unit.name,
+            aliases: unit.aliases,
+            case_sensitive: unit.case_sensitive,
+            category: unit.category,
+            factor: numerator / denominator,
+            offset: unit.conversion.offset(),
+            singular: unit.display_singular,
+            plural: unit.display_plural,
+            systems,
+        }
+    }
+
+    pub fn to_json(&self) -> Json {
+        let strings = |items: &[&str]| Json::Array(items.iter().map(|s| Json::from(*s)).collect());
+        Json::object(vec![
+            ("name", Json::from(self.name)),
+            ("aliases", strings(self.aliases)),
+            ("case_sensitive", Json::from(self.case_sensitive)),
+            ("category", Json::from(self.category.name().to_lowercase())),
+            ("factor", Json::from(self.factor)),
+            ("offset", Json::from(self.offset)),
+            ("singular", Json::from(self.singular)),
+            ("plural", Json::from(self.plural)),
+            ("systems", strings(&self.systems)),
+        ])
+    }
+}
+
+/// Every unit of `units` as a JSON array.
+pub fn units_json<'a>(units: impl Iterator<Item = &'a Unit>) -> Json {
+    Json::Array(units.map(|u| UnitInfo::of(u).to_json()).collect())
+}
+
+impl UnitDef {
+    pub fn to_json(&self) -> Json {
+        let mut fields = vec![
+            ("name", Json::from(self.name.as_str())),
+            ("aliases", Json::Array(self.aliases.iter().map(|a| Json::from(a.as_str())).collect())),
+            ("category", Json::from(self.category.name().to_lowercase())),
+            ("factor", Json::from(self.factor)),
+            ("offset", Json::from(self.offset)),
+        ];
+        if !self.case_sensitive {
+            fields.insert(2, ("case_sensitive", Json::from(false)));
+        }
+        for (key, label) in [("singular", &self.singular), ("plural", &self.plural)] {
+            if let Some(label) = label {
+                fields.push((key, Json::from(label.as_str())));
+            }
+        }
+        Json::object(fields)
+    }
+
+    /// Reads a definition written by `to_json` or `UnitInfo::to_json`; keys
+    /// it doesn't know, like `systems`, are ignored.
+    pub fn from_json(value: &Json) -> Result<UnitDef, String> {
+        let text = |key: &str| value.get(key).map(|v| v.as_str().ok_or_else(|| format!("'{}' must be a string", key)));
+        let number = |key: &str| value.get(key).map(|v| v.as_f64().ok_or_else(|| format!("'{}' must be a number", key)));
+        let name = text("name").ok_or("unit has no 'name'")??.to_string();
+        let aliases = match value.get("aliases") {
+            None => Vec::new(),
+            Some(aliases) => aliases
+                .as_array()
+                .and_then(|items| items.iter().map(|a| a.as_str().map(str::to_string)).collect())
+                .ok_or("'aliases' must be a list of strings")?,
+        };
+        let category = text("category").ok_or("unit has no 'category'")??.parse().map_err(|e| format!("{}", e))?;
+        let case_sensitive = value.get("case_sensitive").map(|v| v.as_bool().ok_or("'case_sensitive' must be true or false")).transpose()?;
+        Ok(UnitDef {
+            name,
+            aliases,
+            case_sensitive: case_sensitive.unwrap_or(true),
+            category,
+            factor: number("factor").ok_or("unit has no 'factor'")??,
+            offset: number("offset").transpose()?.unwrap_or(0.0),
+            singular: text("singular").transpose()?.map(str::to_string),
+            plural: text("plural").transpose()?.map(str::to_string),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+    use crate::registry::Registry;
+
+    #[test]
+    #[cfg(all(feature = "mass", feature = "temperature", feature = "time"))]
+    fn test_unit_info() {
+        let fahrenheit = crate::find_unit("F").unwrap();
+        assert_eq!(
+            UnitInfo::of(fahrenheit).to_json().to_string(),
+            r#"{"name":"F","aliases":["fahrenheit"],"case_sensitive":false,"category":"temperature","factor":0.5555555555555556,"offset":-32,"singular":"degree Fahrenheit","plural":"degrees Fahrenheit","systems":["imperial"]}"#
+        );
+        assert_eq!(UnitInfo::of(crate::find_unit("kg").unwrap()).systems, ["metric"]);
+        assert!(UnitInfo::of(crate::find_unit("h").unwrap()).systems.is_empty());
+    }
+
+    #[test]
+    fn test_builtin_table_round_trips() {
+        let exported = units_json(Registry::default().units()).to_string();
+        let Json::Array(items) = json::parse(&exported).unwrap() else { panic!("not an array") };
+        let mut rebuilt = Registry::empty();
+        for item in &items {
+            rebuilt.register(UnitDef::from_json(item).unwrap()).unwrap();
+        }
+        assert_eq!(rebuilt.units().count(), crate::UNITS.len());
+        for (original, copy) in crate::UNITS.iter().zip(rebuilt.units()) {
+            assert_eq!((original.name, original.aliases, original.category), (copy.name, copy.aliases, copy.category));
+            assert_eq!(original.case_sensitive, copy.case_sensitive, "{}", original.name);
+            assert_eq!((original.display_singular, original.display_plural), (copy.display_singular, copy.display_plural));
+            for value in [-40.0, 0.0, 1.0, 37.5, 1e6] {
+                let (a, b) = (original.to_base(value), copy.to_base(value));
+                assert!((a - b).abs() <= 1e-12 * a.abs().max(1.0), "{} {}: {} vs {}", original.name, value, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_unit_def_json() {
+        let def = UnitDef {
+            name: "furlong".to_string(),
+            aliases: vec!["furlongs".to_string()],
+            case_sensitive: true,
+            category: UnitCategory::Length,
+            factor: 201.168,
+            offset: 0.0,
+            singular: None,
+            plural: Some("furlongs".to_string()),
+        };
+        let text = def.to_json().to_string();
+        assert_eq!(text, r#"{"name":"furlong","aliases":["furlongs"],"category":"length","factor":201.168,"offset":0,"plural":"furlongs"}"#);
+        assert_eq!(UnitDef::from_json(&json::parse(&text).unwrap()), Ok(def));
+        let parse = |text: &str| UnitDef::from_json(&json::parse(text).unwrap());
+        assert_eq!(parse(r#"{"name":"x","category":"mass","factor":2}"#).unwrap().offset, 0.0);
+        let any_case = parse(r#"{"name":"x","case_sensitive":false,"category":"mass","factor":2}"#).unwrap();
+        assert!(!any_case.case_sensitive && any_case.to_json().to_string().contains(r#""case_sensitive":false"#));
+        assert_eq!(parse(r#"{"name":"x","category":"mass"}"#), Err("unit has no 'factor'".to_string()));
+        assert_eq!(parse(r#"{"name":"x","category":"energy","factor":1}"#).unwrap_err(), "unknown category 'energy'; use length, temperature, mass, time, volume, speed, data, angle or currency");
+        assert_eq!(parse(r#"{"name":"x","category":"mass","factor":"2"}"#), Err("'factor' must be a number".to_string()));
+        assert_eq!(parse(r#"{"name":"x","aliases":"y","category":"mass","factor":2}"#), Err("'aliases' must be a list of strings".to_string()));
+    }
+}
@@ -0,0 +1,117 @@
+//! Properties every pair of units of one category must have, checked over
+//! values from 1e-12 to 1e12 of both signs: converting there and back
+//! returns the value, larger values never become smaller, and 0 stays 0 between
+//! units without an offset. Units with an offset, like the temperatures,
+//! must instead map values affinely: f(x) − f(0) = slope × x.
+
+use crate::{Unit, convert_units, slope};
+
+/// Largest error tolerated, relative to the value or, between units with
+/// an offset, to the offsets when those are larger.
+const TOLERANCE: f64 = 1e-10;
+const MANTISSAS: [f64; 5] = [1.0, 1.5, 2.54, 3.7, 9.99];
+
+/// A broken property of converting `from` into `to`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub from: &'static str,
+    pub to: &'static str,
+    pub message: String,
+}
+
+/// Sample values in increasing order: each mantissa at every power of ten
+/// from 1e-12 to 1e12, negated and not.
+fn samples() -> Vec<f64> {
+    let positive: Vec<f64> = (-12..=12).flat_map(|e| MANTISSAS.map(|m| m * 10f64.powi(e))).collect();
+    positive.iter().rev().map(|x| -x).chain(positive.iter().copied()).collect()
+}
+
+/// The first violation for every pair of `units` of one category, blamed
+/// on the later unit of the pair; nothing when all properties hold.
+pub fn verify_invariants(units: &[&Unit]) -> Vec<Violation> {
+    let samples = samples();
+    let mut violations = Vec::new();
+    for (i, from) in units.iter().enumerate() {
+        for to in units[i + 1..].iter().filter(|to| to.category == from.category) {
+            if let Some(message) = check_pair(from, to, &samples) {
+                violations.push(Violation { from: from.name, to: to.name, message });
+            }
+        }
+    }
+    violations
+}
+
+fn check_pair(from: &Unit, to: &Unit, samples: &[f64]) -> Option<String> {
+    let (a, b) = (from.name, to.name);
+    let linear = from.conversion.offset() == 0.0 && to.conversion.offset() == 0.0;
+    let slope = slope(from, to);
+    let zero = convert_units(from, to, 0.0);
+    if linear && zero != 0.0 {
+        return Some(format!("0 {} is {} {}, not 0", a, zero, b));
+    }
+    let mut previous: Option<(f64, f64)> = None;
+    for &x in samples {
+        let y = convert_units(from, to, x);
+        let back = convert_units(to, from, y);
+        let offsets = from.conversion.offset().abs().max(to.conversion.offset().abs()).max((zero / slope).abs());
+        let scale = if linear { x.abs() } else { x.abs().max(offsets) };
+        let error = (back - x).abs() / scale;
+        if error.is_nan() || error > TOLERANCE {
+            return Some(format!("{} {} → {} → {} returns {} (relative error {:e})", x, a, b, a, back, error));
+        }
+        if !linear {
+            let expected = slope * x;
+            let error = (y - zero - expected).abs() / (slope.abs() * scale);
+            if error.is_nan() || error > TOLERANCE {
+                return Some(format!("{} {} is {} {}, off the line through {} with slope {}", x, a, y, b, zero, slope));
+            }
+        }
+        if let Some((px, py)) = previous
+            && slope > 0.0
+            && y < py
+        {
+            return Some(format!("{} {} → {} {} is below {} {} → {} {}", x, a, y, b, px, a, py, b));
+        }
+        previous = Some((x, y));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Conversion, UNITS, UnitCategory};
+
+    fn unit(name: &'static str, factor: (f64, f64), offset: f64) -> Unit {
+        let conversion = Conversion::new(factor, offset);
+        Unit { name, aliases: &[], case_sensitive: true, display_singular: name, display_plural: name, category: UnitCategory::Length, conversion }
+    }
+
+    #[test]
+    fn test_builtin_units_hold() {
+        let units: Vec<&Unit> = UNITS.iter().collect();
+        assert_eq!(verify_invariants(&units), []);
+        assert_eq!(samples().len(), 250);
+        assert!(samples().windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_broken_pairs_are_reported() {
+        let (m, huge, infinite) = (unit("m", (1.0, 1.0), 0.0), unit("huge", (1e300, 1e-300), 0.0), unit("nil", (1.0, f64::INFINITY), 0.0));
+        let violations = verify_invariants(&[&m, &huge, &infinite]);
+        let pairs: Vec<(&str, &str)> = violations.iter().map(|v| (v.from, v.to)).collect();
+        assert_eq!(pairs, [("m", "huge"), ("m", "nil"), ("huge", "nil")]);
+        assert!(violations[0].message.starts_with("-9990000000000 m → huge → m returns"), "{}", violations[0].message);
+        assert_eq!(violations[1].message, "0 m is NaN nil, not 0");
+    }
+
+    #[test]
+    fn test_offsets_must_be_affine() {
+        let (c, k) = (unit("c", (1.0, 1.0), 0.0), unit("k", (1.0, 1.0), -273.15));
+        assert_eq!(verify_invariants(&[&c, &k]), []);
+        let mirrored = unit("mirrored", (-1.0, 1.0), 10.0);
+        assert!(verify_invariants(&[&c, &mirrored]).is_empty(), "a decreasing map is still affine");
+        let kg = Unit { category: UnitCategory::Mass, ..unit("kg", (1e300, 1e-300), 0.0) };
+        assert!(verify_invariants(&[&c, &kg]).is_empty(), "units of different categories aren't paired");
+    }
+}
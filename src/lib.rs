@@ -0,0 +1,1753 @@
+//! Conversion between units of length, temperature, mass, time, volume,
+//! speed, data and angle, and compound units built from them.
+//!
+//! Units are looked up by symbol or alias, ignoring case, in the active
+//! [`registry`], which is every built-in unit unless a program installs a
+//! restricted one.
+//!
+//! Without the default `std` feature the crate is `no_std`: what remains is
+//! the unit table, `Unit`, `Conversion`, `Dimension`, lookups over the
+//! built-in table and `convert_units`. Each category of built-in units is a
+//! feature too, so a build can leave out the ones it doesn't need.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+// The cdylib needs a panic handler. Where there is an operating system, std
+// supplies it even for a `no_std` build; bare-metal targets skip the cdylib.
+#[cfg(all(not(any(feature = "std", test)), not(target_os = "none")))]
+extern crate std;
+
+#[cfg(feature = "i18n-aliases")]
+mod aliases;
+#[cfg(feature = "std")]
+pub mod compound;
+pub mod dimension;
+#[cfg(all(feature = "std", feature = "angle"))]
+pub mod dms;
+#[cfg(feature = "std")]
+pub mod duration;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod float;
+#[cfg(feature = "std")]
+pub mod gauge;
+#[cfg(feature = "std")]
+pub mod graph;
+#[cfg(feature = "std")]
+pub mod i18n;
+#[cfg(feature = "std")]
+pub mod info;
+#[cfg(feature = "std")]
+pub mod invariants;
+#[cfg(feature = "std")]
+pub mod json;
+#[cfg(feature = "std")]
+pub mod pace;
+#[cfg(feature = "std")]
+pub mod quantity;
+#[cfg(feature = "std")]
+pub mod rational;
+#[cfg(feature = "std")]
+pub mod record;
+#[cfg(feature = "std")]
+pub mod registry;
+#[cfg(feature = "tracing")]
+pub mod trace;
+#[cfg(all(feature = "std", any(feature = "length", feature = "mass", feature = "temperature")))]
+pub mod typed;
+pub mod units;
+#[cfg(all(
+    feature = "uom",
+    any(feature = "length", feature = "mass", feature = "temperature", feature = "time", feature = "volume", feature = "speed", feature = "data", feature = "angle")
+))]
+pub mod uom;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+use core::fmt;
+#[cfg(feature = "std")]
+use core::str::FromStr;
+
+use dimension::Dimension;
+pub use float::Float;
+#[cfg(feature = "std")]
+use i18n::Lang;
+#[cfg(feature = "std")]
+use rational::Ratio;
+
+#[cfg(feature = "std")]
+pub use invariants::verify_invariants;
+#[cfg(feature = "std")]
+pub use quantity::Quantity;
+#[cfg(feature = "std")]
+pub use record::{ConversionRecord, convert_recorded};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnitCategory {
+    Length,
+    Temperature,
+    Mass,
+    Time,
+    Volume,
+    Speed,
+    /// Amounts of information: bits and bytes with SI and IEC prefixes.
+    Data,
+    /// Plane angles, in degrees, arcminutes, arcseconds and radians.
+    Angle,
+    /// Money. No unit of it is built in: the currencies come from a rates
+    /// file at runtime.
+    Currency,
+    /// A compound unit like km/h whose dimension no other category has.
+    Derived(Dimension),
+}
+
+impl UnitCategory {
+    const ALL: [UnitCategory; 9] = [
+        UnitCategory::Length,
+        UnitCategory::Temperature,
+        UnitCategory::Mass,
+        UnitCategory::Time,
+        UnitCategory::Volume,
+        UnitCategory::Speed,
+        UnitCategory::Data,
+        UnitCategory::Angle,
+        UnitCategory::Currency,
+    ];
+
+    /// Every category, in the order listings show them. `Derived` isn't one:
+    /// no unit in a table has it.
+    pub fn all() -> &'static [UnitCategory] {
+        &UnitCategory::ALL
+    }
+
+    /// The English name, which is also what `FromStr` accepts (in any case).
+    pub fn name(self) -> &'static str {
+        match self {
+            UnitCategory::Length => "Length",
+            UnitCategory::Temperature => "Temperature",
+            UnitCategory::Mass => "Mass",
+            UnitCategory::Time => "Time",
+            UnitCategory::Volume => "Volume",
+            UnitCategory::Speed => "Speed",
+            UnitCategory::Data => "Data",
+            UnitCategory::Angle => "Angle",
+            UnitCategory::Currency => "Currency",
+            UnitCategory::Derived(_) => "Derived",
+        }
+    }
+
+    pub fn dimension(self) -> Dimension {
+        match self {
+            UnitCategory::Length => Dimension::LENGTH,
+            UnitCategory::Temperature => Dimension::TEMPERATURE,
+            UnitCategory::Mass => Dimension::MASS,
+            UnitCategory::Time => Dimension::TIME,
+            UnitCategory::Volume => Dimension::LENGTH.pow(3),
+            UnitCategory::Speed => Dimension::LENGTH / Dimension::TIME,
+            UnitCategory::Data => Dimension::INFORMATION,
+            UnitCategory::Angle => Dimension::ANGLE,
+            UnitCategory::Currency => Dimension::CURRENCY,
+            UnitCategory::Derived(dimension) => dimension,
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn localized_name(self, lang: Lang) -> &'static str {
+        let key = match self {
+            UnitCategory::Length => "category.length",
+            UnitCategory::Temperature => "category.temperature",
+            UnitCategory::Mass => "category.mass",
+            UnitCategory::Time => "category.time",
+            UnitCategory::Volume => "category.volume",
+            UnitCategory::Speed => "category.speed",
+            UnitCategory::Data => "category.data",
+            UnitCategory::Angle => "category.angle",
+            UnitCategory::Currency => "category.currency",
+            UnitCategory::Derived(_) => "category.derived",
+        };
+        i18n::message(lang, key)
+    }
+    
+    /// Whether a negative amount means something: a temperature or a time
+    /// offset can be below zero, but a length, a mass or a volume can't.
+    pub fn allows_negative(self) -> bool {
+        !matches!(self, UnitCategory::Length | UnitCategory::Mass | UnitCategory::Volume | UnitCategory::Data)
+    }
+}
+
+impl fmt::Display for UnitCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(feature = "std")]
+impl FromStr for UnitCategory {
+    type Err = ParseCategoryError;
+
+    fn from_str(input: &str) -> Result<UnitCategory, ParseCategoryError> {
+        UnitCategory::all()
+            .iter()
+            .find(|c| c.name().eq_ignore_ascii_case(input))
+            .copied()
+            .ok_or_else(|| ParseCategoryError(input.to_string()))
+    }
+}
+
+/// A name that isn't a category; the message lists the ones that are.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseCategoryError(pub String);
+
+#[cfg(feature = "std")]
+impl fmt::Display for ParseCategoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names: Vec<String> = UnitCategory::all().iter().map(|c| c.name().to_lowercase()).collect();
+        let (last, rest) = names.split_last().expect("there are categories");
+        write!(f, "unknown category '{}'; use {} or {}", self.0, rest.join(", "), last)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseCategoryError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Unit {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    /// Whether its symbols, the name and aliases of up to three characters,
+    /// match only as spelled, so that "Mm" (megameter) needn't be "mm"
+    /// (millimeter), nor "B" (byte) "b" (bit). Longer words match in any case
+    /// either way; familiar symbols like km and ft turn this off so that "KM"
+    /// works.
+    pub case_sensitive: bool,
+    pub display_singular: &'static str,
+    pub display_plural: &'static str,
+    pub category: UnitCategory,
+    pub conversion: Conversion,
+}
+
+/// How a unit maps to the base unit of its category. Factors are kept as
+/// fractions so 5/9 stays exact and `--explain` can show them as written.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Conversion {
+    /// Base value = value × factor.0 / factor.1.
+    Linear { factor: (f64, f64) },
+    /// Base value = (value + offset) × factor.0 / factor.1.
+    Affine { factor: (f64, f64), offset: f64 },
+}
+
+impl Conversion {
+    /// `Linear` unless there is an offset.
+    pub fn new(factor: (f64, f64), offset: f64) -> Conversion {
+        if offset == 0.0 { Conversion::Linear { factor } } else { Conversion::Affine { factor, offset } }
+    }
+    
+    pub const fn factor(self) -> (f64, f64) {
+        match self {
+            Conversion::Linear { factor } | Conversion::Affine { factor, .. } => factor,
+        }
+    }
+    
+    pub const fn offset(self) -> f64 {
+        match self {
+            Conversion::Linear { .. } => 0.0,
+            Conversion::Affine { offset, .. } => offset,
+        }
+    }
+    
+    /// The steps from a value in the unit to the base unit.
+    pub fn steps(self) -> [Step; 2] {
+        let (numerator, denominator) = self.factor();
+        [Step::Add(self.offset()), Step::Scale(numerator, denominator)]
+    }
+    
+    /// The steps back from the base unit.
+    pub fn inverse_steps(self) -> [Step; 2] {
+        let (numerator, denominator) = self.factor();
+        [Step::Scale(denominator, numerator), Step::Add(-self.offset())]
+    }
+    
+    pub fn apply(self, value: f64) -> f64 {
+        self.steps().iter().fold(value, |x, step| step.apply(x))
+    }
+    
+    pub fn invert(self, base: f64) -> f64 {
+        self.inverse_steps().iter().fold(base, |x, step| step.apply(x))
+    }
+}
+
+/// One arithmetic step of a conversion, as shown by `--explain`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Step {
+    Add(f64),
+    Scale(f64, f64),
+}
+
+impl Step {
+    pub fn is_identity(self) -> bool {
+        match self {
+            Step::Add(offset) => offset == 0.0,
+            Step::Scale(numerator, denominator) => numerator == 1.0 && denominator == 1.0,
+        }
+    }
+    
+    pub fn apply(self, x: f64) -> f64 {
+        match self {
+            Step::Add(offset) if offset != 0.0 => x + offset,
+            Step::Add(_) => x,
+            Step::Scale(numerator, denominator) => {
+                let mut x = x;
+                if numerator != 1.0 {
+                    x *= numerator;
+                }
+                if denominator != 1.0 {
+                    x /= denominator;
+                }
+                x
+            }
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Step::Add(offset) if offset < 0.0 => write!(f, "− {}", -offset),
+            Step::Add(offset) => write!(f, "+ {}", offset),
+            Step::Scale(numerator, 1.0) => write!(f, "× {}", numerator),
+            Step::Scale(1.0, denominator) => write!(f, "/ {}", denominator),
+            Step::Scale(numerator, denominator) => write!(f, "× {}/{}", numerator, denominator),
+        }
+    }
+}
+
+impl Unit {
+    /// What the unit measures; two units convert into each other exactly
+    /// when their dimensions are equal.
+    pub fn dimension(&self) -> Dimension {
+        self.category.dimension()
+    }
+
+    pub fn matches(&self, input: &str) -> bool {
+        word_matches(self.name, input, self.case_sensitive) || self.aliases.iter().any(|a| word_matches(a, input, self.case_sensitive))
+    }
+    
+    pub fn to_base_steps(&self) -> [Step; 2] {
+        self.conversion.steps()
+    }
+    
+    pub fn to_unit_steps(&self) -> [Step; 2] {
+        self.conversion.inverse_steps()
+    }
+    
+    pub fn to_base(&self, value: f64) -> f64 {
+        self.conversion.apply(value)
+    }
+    
+    pub fn to_unit(&self, value: f64) -> f64 {
+        self.conversion.invert(value)
+    }
+    
+    /// Word form for a quantity printed as `number`: singular only for exactly "1" or "-1".
+    pub fn display_name(&self, number: &str) -> &'static str {
+        if number == "1" || number == "-1" { self.display_singular } else { self.display_plural }
+    }
+}
+
+/// The built-in units of every category compiled in, in the order of
+/// `UnitCategory::all`. Each category is a feature, and its units live in a
+/// module under [`units`].
+pub const UNITS: &[Unit] = &TABLE;
+
+const TABLES: &[&[Unit]] = &[
+    #[cfg(feature = "length")]
+    units::length::UNITS,
+    #[cfg(feature = "temperature")]
+    units::temperature::UNITS,
+    #[cfg(feature = "mass")]
+    units::mass::UNITS,
+    #[cfg(feature = "time")]
+    units::time::UNITS,
+    #[cfg(feature = "volume")]
+    units::volume::UNITS,
+    #[cfg(feature = "speed")]
+    units::speed::UNITS,
+    #[cfg(feature = "data")]
+    units::data::UNITS,
+    #[cfg(feature = "angle")]
+    units::angle::UNITS,
+];
+
+const TABLE: [Unit; table_len()] = table();
+
+const fn table_len() -> usize {
+    let (mut len, mut i) = (0, 0);
+    while i < TABLES.len() {
+        len += TABLES[i].len();
+        i += 1;
+    }
+    len
+}
+
+/// `TABLES` end to end; the placeholder only fills the array until it is overwritten.
+const fn table() -> [Unit; table_len()] {
+    let placeholder = Unit {
+        name: "",
+        aliases: &[],
+        case_sensitive: true,
+        display_singular: "",
+        display_plural: "",
+        category: UnitCategory::Length,
+        conversion: Conversion::Linear { factor: (1.0, 1.0) },
+    };
+    let mut table = [placeholder; table_len()];
+    let (mut n, mut i) = (0, 0);
+    while i < TABLES.len() {
+        let mut j = 0;
+        while j < TABLES[i].len() {
+            table[n] = TABLES[i][j];
+            n += 1;
+            j += 1;
+        }
+        i += 1;
+    }
+    table
+}
+
+/// Why a conversion failed. `Display` gives the English message; the binary
+/// shows `localized` and picks the exit code.
+#[cfg(feature = "std")]
+#[derive(Debug, PartialEq)]
+pub enum ConvertError {
+    InvalidValue(String),
+    /// NaN or an infinity, which parse as numbers but convert to nonsense.
+    NotFinite(String),
+    /// Not a unit at all; `suggestions` are the closest unit names, best
+    /// first: at most three, each within two edits of `input`.
+    UnknownUnit {
+        input: String,
+        suggestions: Vec<String>,
+    },
+    CategoryMismatch {
+        from: UnitCategory,
+        to: UnitCategory,
+        /// The units as the user wrote them.
+        from_unit: String,
+        to_unit: String,
+    },
+    BelowAbsoluteZero {
+        unit: &'static str,
+        value: f64,
+        /// Absolute zero in `unit`.
+        limit: f64,
+    },
+    NegativeUncertainty(String),
+    /// A temperature or other unit with an offset used as a term of a
+    /// compound unit such as "C/s".
+    OffsetInCompound {
+        unit: &'static str,
+        input: String,
+    },
+    /// Ruled out by `disabled_units` or `enabled_categories`.
+    DisabledUnit {
+        unit: String,
+        alternatives: Vec<&'static str>,
+    },
+    /// No chain of known factors in a `graph::Graph` leads from one to the other.
+    NoPath {
+        from: String,
+        to: String,
+    },
+    /// The result of converting `value` is too large for a float.
+    Overflow {
+        value: f64,
+        from: String,
+        to: String,
+    },
+    /// A wire gauge outside 4/0 through 40, as given or as a size converts to.
+    NoGauge(String),
+    /// A currency code no rates file lists; `known` are the codes it does,
+    /// and none when no rates are loaded.
+    UnknownCurrency {
+        code: String,
+        known: Vec<&'static str>,
+    },
+    /// A word such as "gallon" that names a different unit in the US and
+    /// the imperial systems, with no system chosen; `choices` are the US
+    /// unit and the imperial one.
+    AmbiguousUnit {
+        input: String,
+        choices: [&'static str; 2],
+    },
+    /// A speed of zero, or one so small its pace overflows, converted to a
+    /// pace; the speed as written.
+    ZeroSpeed(String),
+    /// A term of a compound unit with an exponent but no unit before its
+    /// '^', such as "^2"; the term as written.
+    MissingUnit(String),
+}
+
+#[cfg(feature = "std")]
+impl ConvertError {
+    /// The message in `lang`; `Display` is the English one, which is what
+    /// JSON output and the protocol carry.
+    pub fn localized(&self, lang: Lang) -> String {
+        match self {
+            ConvertError::InvalidValue(input) => i18n::fill(lang, "invalid_value", &[input]),
+            ConvertError::NotFinite(input) => i18n::fill(lang, "not_finite", &[input]),
+            ConvertError::UnknownUnit { input, suggestions } => {
+                let mut message = i18n::fill(lang, "unknown_unit", &[input]);
+                if !suggestions.is_empty() {
+                    message += &i18n::fill(lang, "did_you_mean", &[&choices(lang, suggestions)]);
+                }
+                message
+            }
+            ConvertError::CategoryMismatch { from, to, from_unit, to_unit } => {
+                let unit_of = |unit: &String, category: &UnitCategory| i18n::fill(lang, "unit_of_category", &[unit, &category.localized_name(lang)]);
+                // The source unit may have been meant as the unit of the
+                // target's category that shares its name.
+                let mut from_line = unit_of(from_unit, from);
+                let registry = registry::active();
+                let source = registry.find(from_unit).filter(|_| !matches!(to, UnitCategory::Derived(_)));
+                if let Some(word) = source.and_then(|unit| registry.cousin(unit, *to)) {
+                    from_line += &i18n::fill(lang, "did_you_mean", &[&format!("'{}' ({})", word, to.localized_name(lang))]);
+                }
+                let dimensions = format!("{} vs {}", from.dimension(), to.dimension());
+                let mut message = format!("{}\n  {}\n  {}", i18n::fill(lang, "category_mismatch", &[&dimensions]), from_line, unit_of(to_unit, to));
+                if let Some(hint) = bridge_hint(from.dimension(), to.dimension()) {
+                    message += &format!("\n  {}", i18n::message(lang, hint));
+                }
+                message
+            }
+            ConvertError::BelowAbsoluteZero { unit, value, limit } => i18n::fill(lang, "below_absolute_zero", &[value, unit, limit]),
+            ConvertError::NegativeUncertainty(input) => i18n::fill(lang, "negative_uncertainty", &[input]),
+            ConvertError::OffsetInCompound { unit, input } => i18n::fill(lang, "offset_in_compound", &[unit, input]),
+            ConvertError::DisabledUnit { unit, alternatives } => {
+                let mut message = i18n::fill(lang, "disabled_unit", &[unit]);
+                if !alternatives.is_empty() {
+                    message += &i18n::fill(lang, "use_instead", &[&choices(lang, alternatives)]);
+                }
+                message
+            }
+            ConvertError::NoPath { from, to } => i18n::fill(lang, "no_path", &[from, to]),
+            ConvertError::Overflow { value, from, to } => i18n::fill(lang, "overflow", &[&format!("{:e}", value), from, to]),
+            ConvertError::NoGauge(input) => i18n::fill(lang, "no_gauge", &[input]),
+            ConvertError::UnknownCurrency { code, known } if known.is_empty() => i18n::fill(lang, "no_rates", &[code]),
+            ConvertError::UnknownCurrency { code, known } => i18n::fill(lang, "unknown_currency", &[code]) + &i18n::fill(lang, "use_instead", &[&choices(lang, known)]),
+            ConvertError::AmbiguousUnit { input, choices: units } => i18n::fill(lang, "ambiguous_unit", &[input, &choices(lang, units)]),
+            ConvertError::ZeroSpeed(input) => i18n::fill(lang, "zero_speed", &[input]),
+            ConvertError::MissingUnit(term) => i18n::fill(lang, "missing_unit", &[term]),
+        }
+    }
+}
+
+/// The hint naming what connects two dimensions that don't convert, such
+/// as a density between a mass and a volume.
+#[cfg(feature = "std")]
+fn bridge_hint(from: Dimension, to: Dimension) -> Option<&'static str> {
+    let bridges = [
+        (Dimension::MASS / Dimension::LENGTH.pow(3), "hint_density"),
+        (Dimension::LENGTH / Dimension::TIME, "hint_speed"),
+        (Dimension::TIME, "hint_duration"),
+    ];
+    bridges.into_iter().find(|&(bridge, _)| from / to == bridge || to / from == bridge).map(|(_, key)| key)
+}
+
+/// "'a'", "'a' or 'b'", "'a', 'b' or 'c'".
+#[cfg(feature = "std")]
+fn choices(lang: Lang, names: &[impl AsRef<str>]) -> String {
+    let quoted: Vec<String> = names.iter().map(|n| format!("'{}'", n.as_ref())).collect();
+    match quoted.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} {} {}", rest.join(", "), i18n::message(lang, "or"), last),
+        _ => quoted.concat(),
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.localized(Lang::En))
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConvertError {}
+
+/// Something worth pointing out about a conversion that still succeeded.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Warning {
+    /// A negative amount of a category that doesn't allow one.
+    Negative(UnitCategory),
+    /// A nonzero value whose result is too small for a double and came out 0.
+    Underflow,
+    /// The value or the result is subnormal, so fewer digits are right than
+    /// are shown.
+    PrecisionLoss,
+    /// A size between two wire gauges, converted to the nearer one.
+    GaugeRounded,
+}
+
+#[cfg(feature = "std")]
+impl Warning {
+    pub fn localized(self, lang: Lang) -> &'static str {
+        match self {
+            Warning::Negative(category) => i18n::message(lang, match category {
+                UnitCategory::Length => "negative_length",
+                UnitCategory::Mass => "negative_mass",
+                UnitCategory::Volume => "negative_volume",
+                _ => "negative_amount",
+            }),
+            Warning::Underflow => i18n::message(lang, "underflow"),
+            Warning::PrecisionLoss => i18n::message(lang, "precision_loss"),
+            Warning::GaugeRounded => i18n::message(lang, "gauge_rounded"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.localized(Lang::En))
+    }
+}
+
+/// A value with an optional standard uncertainty, written "5.0±0.2" or "5.0+-0.2".
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub value: f64,
+    pub sigma: Option<f64>,
+}
+
+#[cfg(feature = "std")]
+impl Measurement {
+    pub fn parse(input: &str) -> Result<Measurement, ConvertError> {
+        let invalid = || ConvertError::InvalidValue(input.to_string());
+        let measurement = match input.split_once('±').or_else(|| input.split_once("+-")) {
+            None => Measurement { value: input.parse().map_err(|_| invalid())?, sigma: None },
+            Some((value, sigma)) => {
+                let value = value.trim().parse().map_err(|_| invalid())?;
+                let sigma: f64 = sigma.trim().parse().map_err(|_| invalid())?;
+                if sigma < 0.0 {
+                    return Err(ConvertError::NegativeUncertainty(input.to_string()));
+                }
+                Measurement { value, sigma: Some(sigma) }
+            }
+        };
+        // "NaN", "inf" and "1e400" all parse.
+        if !measurement.is_finite() {
+            return Err(ConvertError::NotFinite(input.to_string()));
+        }
+        Ok(measurement)
+    }
+    
+    fn is_finite(&self) -> bool {
+        self.value.is_finite() && self.sigma.is_none_or(f64::is_finite)
+    }
+    
+    /// Every unit is linear or affine, so the error scales by the slope and any
+    /// offset (e.g. the 32 in °F) drops out.
+    pub fn convert(&self, from: &Unit, to: &Unit) -> Measurement {
+        Measurement {
+            value: convert_units(from, to, self.value),
+            sigma: self.sigma.map(|s| s * slope(from, to).abs()),
+        }
+    }
+    
+    /// Converts a difference: only the scale applies, so 10 °C Δ is 18 °F Δ.
+    pub fn convert_delta(&self, from: &Unit, to: &Unit) -> Measurement {
+        let slope = slope(from, to);
+        Measurement { value: self.value * slope, sigma: self.sigma.map(|s| s * slope.abs()) }
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct Converted {
+    pub value: f64,
+    pub result: f64,
+    /// Uncertainty of `result`, when the input had one.
+    pub sigma: Option<f64>,
+    pub warnings: Vec<Warning>,
+}
+
+#[cfg(feature = "std")]
+pub fn convert_checked(value_arg: &str, from_unit: &str, to_unit: &str) -> Result<Converted, ConvertError> {
+    convert_value(value_arg, from_unit, to_unit, false)
+}
+
+/// `--delta`: treats temperatures as differences, skipping the offsets and the
+/// absolute-zero check. Every other category is linear, so there it is a no-op.
+#[cfg(feature = "std")]
+pub fn convert_delta(value_arg: &str, from_unit: &str, to_unit: &str) -> Result<Converted, ConvertError> {
+    convert_value(value_arg, from_unit, to_unit, true)
+}
+
+/// Converts `value` from one unit to another by symbol or alias, in `f64`
+/// or `f32`.
+///
+/// ```
+/// # #[cfg(all(feature = "length", feature = "mass", feature = "temperature"))] {
+/// use converter::{convert, ConvertError};
+///
+/// let miles: f64 = convert(5.0, "km", "mi").unwrap();
+/// assert!((miles - 3.106856).abs() < 1e-6);
+/// assert_eq!(convert(100.0, "C", "F"), Ok(212.0));
+/// assert_eq!(convert(100.0f32, "C", "F"), Ok(212.0f32));
+/// assert!(matches!(convert(1.0, "kg", "m"), Err(ConvertError::CategoryMismatch { .. })));
+/// assert_eq!(convert(-300.0, "C", "K"), Err(ConvertError::BelowAbsoluteZero { unit: "C", value: -300.0, limit: -273.15 }));
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub fn convert<T: Float>(value: T, from_unit: &str, to_unit: &str) -> Result<T, ConvertError> {
+    let value = value.to_f64();
+    let result = T::from_f64(convert_measurement(Measurement { value, sigma: None }, &value.to_string(), from_unit, to_unit, false)?.result);
+    // Finite in f64 but not necessarily in f32.
+    if !result.to_f64().is_finite() {
+        return Err(ConvertError::Overflow { value, from: from_unit.to_string(), to: to_unit.to_string() });
+    }
+    Ok(result)
+}
+
+/// `--via`: converts between two categories that `via`, a quantity such as
+/// "1g/mL", connects: the value is divided by it or multiplied by it,
+/// whichever gives `to_unit`'s dimension. Units of one category convert as
+/// usual; a `via` that doesn't connect them leaves the `CategoryMismatch`.
+///
+/// ```
+/// # #[cfg(all(feature = "length", feature = "mass", feature = "time", feature = "volume"))] {
+/// use converter::convert_via;
+///
+/// assert_eq!(convert_via(2.0, "kg", "L", "1g/mL"), Ok(2.0));
+/// assert_eq!(convert_via(10.0, "km", "min", "5km/h"), Ok(120.0));
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub fn convert_via(value: f64, from_unit: &str, to_unit: &str, via: &str) -> Result<f64, ConvertError> {
+    let (f, t) = (lookup_unit(from_unit).ok_or_else(|| unit_error(from_unit))?, lookup_unit(to_unit).ok_or_else(|| unit_error(to_unit))?);
+    if f.dimension() == t.dimension() {
+        return convert(value, from_unit, to_unit);
+    }
+    let bridge = Quantity::parse(via)?;
+    if let Some(unit) = [f, t, bridge.unit].into_iter().find(|u| u.conversion.offset() != 0.0) {
+        return Err(ConvertError::OffsetInCompound { unit: unit.name, input: via.to_string() });
+    }
+    let (base, step) = (f.to_base(value), bridge.unit.to_base(bridge.value));
+    let result = if f.dimension() / bridge.unit.dimension() == t.dimension() {
+        base / step
+    } else if f.dimension() * bridge.unit.dimension() == t.dimension() {
+        base * step
+    } else {
+        return convert(value, from_unit, to_unit);
+    };
+    let result = t.to_unit(result);
+    if !result.is_finite() {
+        return Err(ConvertError::Overflow { value, from: from_unit.to_string(), to: to_unit.to_string() });
+    }
+    Ok(result)
+}
+
+#[cfg(feature = "std")]
+fn convert_value(value_arg: &str, from_unit: &str, to_unit: &str, delta: bool) -> Result<Converted, ConvertError> {
+    if gauge::is_awg(from_unit) || gauge::is_awg(to_unit) {
+        return gauge::convert(value_arg, from_unit, to_unit);
+    }
+    if pace::is_clock_pace(from_unit) || pace::is_clock_pace(to_unit) {
+        return pace::convert(value_arg, from_unit, to_unit);
+    }
+    let input = if duration::is_human(from_unit) {
+        let seconds = duration::parse(value_arg).ok_or_else(|| ConvertError::InvalidValue(value_arg.to_string()))?;
+        Measurement { value: seconds, sigma: None }
+    } else if let Some(degrees) = dms_value(value_arg, from_unit) {
+        Measurement { value: degrees, sigma: None }
+    } else {
+        Measurement::parse(value_arg)?
+    };
+    convert_measurement(input, value_arg, from_unit, to_unit, delta)
+}
+
+/// A value such as `12°34'56"` given in degrees, in degrees.
+#[cfg(all(feature = "std", feature = "angle"))]
+fn dms_value(value_arg: &str, from_unit: &str) -> Option<f64> {
+    let degrees = dms::parse(value_arg)?;
+    find_unit(from_unit).filter(|u| u.name == "deg").map(|_| degrees)
+}
+
+#[cfg(all(feature = "std", not(feature = "angle")))]
+fn dms_value(_value_arg: &str, _from_unit: &str) -> Option<f64> {
+    None
+}
+
+/// `value_arg` is the value as written, for errors that echo it.
+#[cfg(feature = "std")]
+fn convert_measurement(input: Measurement, value_arg: &str, from_unit: &str, to_unit: &str, delta: bool) -> Result<Converted, ConvertError> {
+    let value = input.value;
+    if !input.is_finite() {
+        return Err(ConvertError::NotFinite(value.to_string()));
+    }
+    #[cfg(feature = "tracing")]
+    let span = trace::span("convert", || vec![("from", from_unit.to_string()), ("to", to_unit.to_string())]);
+    
+    let f = lookup_unit(from_unit).ok_or_else(|| unit_error(from_unit))?;
+    let t = lookup_unit(to_unit).ok_or_else(|| unit_error(to_unit))?;
+    #[cfg(feature = "tracing")]
+    span.record("category", || f.category.to_string());
+    
+    if pace::reciprocal(f, t) {
+        return pace::convert_units(value, value_arg, f, t, from_unit, to_unit);
+    }
+    if f.dimension() != t.dimension() {
+        return Err(ConvertError::CategoryMismatch {
+            from: f.category,
+            to: t.category,
+            from_unit: from_unit.to_string(),
+            to_unit: to_unit.to_string(),
+        });
+    }
+    
+    let mut warnings = Vec::new();
+    if value < 0.0 && !f.category.allows_negative() {
+        warnings.push(Warning::Negative(f.category));
+    }
+    
+    let output = if delta && f.category == UnitCategory::Temperature {
+        input.convert_delta(f, t)
+    } else {
+        if f.category == UnitCategory::Temperature {
+            above_absolute_zero(f, value)?;
+        }
+        input.convert(f, t)
+    };
+    if !output.value.is_finite() {
+        return Err(ConvertError::Overflow { value, from: from_unit.to_string(), to: to_unit.to_string() });
+    }
+    let linear = delta || (f.conversion.offset() == 0.0 && t.conversion.offset() == 0.0);
+    if linear && value != 0.0 && output.value == 0.0 {
+        warnings.push(Warning::Underflow);
+    } else if value.is_subnormal() || output.value.is_subnormal() {
+        warnings.push(Warning::PrecisionLoss);
+    }
+    #[cfg(feature = "tracing")]
+    for warning in &warnings {
+        trace::event(trace::Level::Warn, "warning", || vec![("message", warning.to_string())]);
+    }
+    Ok(Converted { value, result: output.value, sigma: output.sigma, warnings })
+}
+
+/// Absolute zero in degrees Celsius, the base unit of temperature.
+#[cfg(feature = "std")]
+const ABSOLUTE_ZERO: f64 = -273.15;
+
+/// Fails when `value` in the temperature `unit` is colder than absolute
+/// zero. The test is on the value in Celsius, so it holds for every scale,
+/// registered ones included, and a reading that lands exactly on absolute
+/// zero passes despite rounding; the error gives the limit in `unit`.
+#[cfg(feature = "std")]
+fn above_absolute_zero(unit: &'static Unit, value: f64) -> Result<(), ConvertError> {
+    const EPSILON: f64 = 1e-9;
+    if unit.to_base(value) >= ABSOLUTE_ZERO - EPSILON {
+        return Ok(());
+    }
+    // Rounded to nine places, so -459.66999999999996 °F reads -459.67; adding
+    // 0 turns a -0 into 0.
+    let limit = (unit.to_unit(ABSOLUTE_ZERO) * 1e9).round() / 1e9 + 0.0;
+    Err(ConvertError::BelowAbsoluteZero { unit: unit.name, value, limit })
+}
+
+/// `value` in a unit of `factor_from` base units, in one of `factor_to`
+/// base units. It is `const`, so with the factors in [`units`] a conversion
+/// can be a constant.
+pub const fn convert_linear(value: f64, factor_from: f64, factor_to: f64) -> f64 {
+    value * factor_from / factor_to
+}
+
+pub fn convert_units<T: Float>(from: &Unit, to: &Unit, value: T) -> T {
+    T::from_f64(to.to_unit(from.to_base(value.to_f64())))
+}
+
+/// `--exact`: the conversion in rational arithmetic, or `None` when the value or
+/// a factor isn't an exact decimal, or the arithmetic overflows.
+#[cfg(feature = "std")]
+pub fn convert_exact(value_arg: &str, from: &Unit, to: &Unit, delta: bool) -> Option<Ratio> {
+    let mut x = Ratio::parse_decimal(value_arg)?;
+    let steps = from.to_base_steps().into_iter().chain(to.to_unit_steps());
+    for step in steps.filter(|s| !s.is_identity()) {
+        x = match step {
+            Step::Add(_) if delta => x,
+            Step::Add(offset) => x.checked_add(Ratio::from_factor(offset)?)?,
+            Step::Scale(n, d) => x.checked_mul(Ratio::from_factor(n)?)?.checked_div(Ratio::from_factor(d)?)?,
+        };
+    }
+    Some(x)
+}
+
+/// How much the result changes per unit of input: the conversion without its offsets.
+pub fn slope(from: &Unit, to: &Unit) -> f64 {
+    from.to_base_steps()
+        .into_iter()
+        .chain(to.to_unit_steps())
+        .filter(|step| matches!(step, Step::Scale(..)))
+        .fold(1.0, |x, step| step.apply(x))
+}
+
+/// Looks a unit up in the full built-in table, ignoring the configuration.
+/// A single trailing period is ignored ("in.", "ft."), and a word that names
+/// nothing is retried without a trailing "s" ("lbs", "kms"); a word that
+/// already matches, like "celsius" or "ms", is never shortened, and neither
+/// is one that would leave a single letter ("Ms" is not meters).
+pub fn builtin_unit(input: &str) -> Option<&'static Unit> {
+    normalized(input, exact_unit)
+}
+
+/// Applies `builtin_unit`'s forgiving rules to any lookup.
+pub(crate) fn normalized(input: &str, exact: impl Fn(&str) -> Option<&'static Unit>) -> Option<&'static Unit> {
+    let input = input.strip_suffix('.').unwrap_or(input);
+    exact(input).or_else(|| input.strip_suffix(['s', 'S']).filter(|stem| stem.chars().count() > 1).and_then(exact))
+}
+
+/// English names come first, then the localized aliases when compiled in.
+fn exact_unit(input: &str) -> Option<&'static Unit> {
+    table_unit(input).or_else(|| localized_unit(input))
+}
+
+/// How lookups see a character of a unit word: ASCII case doesn't matter,
+/// and a hyphen or underscore stands for a space, so "metric-ton" and
+/// "Metric_Ton" both name "metric ton", which a shell would split in two.
+fn fold(c: char) -> char {
+    match c {
+        '-' | '_' => ' ',
+        c => c.to_ascii_lowercase(),
+    }
+}
+
+/// Whether `a` and `b` are the same unit word once folded.
+pub(crate) fn same_word(a: &str, b: &str) -> bool {
+    a.chars().map(fold).eq(b.chars().map(fold))
+}
+
+/// Words this short are symbols, which match only as spelled when their
+/// unit is `case_sensitive`. Longer names and aliases match in any case.
+const SYMBOL_MAX_LEN: usize = 3;
+
+/// Whether `word`, of a unit that is `case_sensitive` or not, only matches
+/// as spelled.
+pub(crate) fn spelled_only(word: &str, case_sensitive: bool) -> bool {
+    case_sensitive && word.chars().count() <= SYMBOL_MAX_LEN
+}
+
+/// The data symbol `input` is written with, such as "Mb" in "Mb/s", and
+/// its unit. Its case is all that tells bits from bytes, a factor of 8, so a
+/// program may want to say which it read.
+#[cfg(feature = "std")]
+pub fn data_symbol(input: &str) -> Option<(&str, &'static Unit)> {
+    let term = input.split('/').next()?.trim();
+    let unit = lookup_unit(term).filter(|u| u.category == UnitCategory::Data)?;
+    (unit.case_sensitive && term == unit.name).then_some((term, unit))
+}
+
+/// Whether `input` is the unit word `word`: exactly for a symbol of a
+/// case-sensitive unit, once folded for anything else.
+pub(crate) fn word_matches(word: &str, input: &str, case_sensitive: bool) -> bool {
+    if spelled_only(word, case_sensitive) { word == input } else { same_word(word, input) }
+}
+
+/// Whether two unit words, each with whether its unit is case-sensitive,
+/// can answer to the same input. An exact spelling is looked up first, so a
+/// symbol matched only as spelled collides only with itself.
+#[cfg(feature = "std")]
+pub(crate) fn words_collide((a, a_case): (&str, bool), (b, b_case): (&str, bool)) -> bool {
+    if spelled_only(a, a_case) || spelled_only(b, b_case) { a == b } else { same_word(a, b) }
+}
+
+/// `word` folded, for keying tables of unit words.
+#[cfg(feature = "std")]
+pub(crate) fn word_key(word: &str) -> String {
+    word.chars().map(fold).collect()
+}
+
+/// Words more than one unit in `UNITS` claims on purpose, each with the
+/// name of the unit it means. Any other shared word is a mistake, which
+/// `registry::name_clashes` reports.
+pub const HOMONYMS: &[(&str, &str)] = &[];
+
+/// Words the US and the imperial systems both use for a different amount,
+/// each with the US unit and the imperial one. None of them names a unit on
+/// its own: the registry resolves them by its system, if it has one.
+pub const US_IMPERIAL_WORDS: &[(&[&str], &str, &str)] = &[
+    (&["gal", "gallon"], "gal-us", "gal-imp"),
+    (&["qt", "quart"], "qt-us", "qt-imp"),
+    (&["pt", "pint"], "pt-us", "pt-imp"),
+    (&["floz", "fl oz", "fluid ounce"], "floz-us", "floz-imp"),
+];
+
+/// The US and the imperial unit `input` could mean, forgiving a trailing
+/// period and a plural "s" as `builtin_unit` does.
+pub fn us_imperial(input: &str) -> Option<(&'static str, &'static str)> {
+    forgiving(input, |word| US_IMPERIAL_WORDS.iter().find(|(words, _, _)| words.iter().any(|w| word_matches(w, word, true))).map(|&(_, us, imperial)| (us, imperial)))
+}
+
+/// Words for a year and a month, whose average length depends on the
+/// calendar, each with the Julian, the Gregorian and the civil unit. The
+/// registry resolves them by its calendar.
+pub const CALENDAR_WORDS: &[(&[&str], [&str; 3])] = &[
+    (&["yr", "year"], ["julian-year", "gregorian-year", "civil-year"]),
+    (&["mo", "month"], ["julian-month", "gregorian-month", "civil-month"]),
+];
+
+/// The Julian, the Gregorian and the civil unit `input` could mean,
+/// forgiving as `us_imperial` does.
+pub fn calendar_units(input: &str) -> Option<[&'static str; 3]> {
+    forgiving(input, |word| CALENDAR_WORDS.iter().find(|(words, _)| words.iter().any(|w| word_matches(w, word, true))).map(|&(_, units)| units))
+}
+
+/// `find` of `input`, or of `input` without a trailing period or plural "s".
+fn forgiving<T>(input: &str, find: impl Fn(&str) -> Option<T>) -> Option<T> {
+    let input = input.strip_suffix('.').unwrap_or(input);
+    find(input).or_else(|| input.strip_suffix(['s', 'S']).filter(|stem| stem.chars().count() > 1).and_then(&find))
+}
+
+/// The unit a `HOMONYMS` word means.
+fn homonym(input: &str) -> Option<&'static Unit> {
+    let (_, name) = HOMONYMS.iter().find(|(word, _)| same_word(word, input))?;
+    UNITS.iter().find(|u| u.name == *name)
+}
+
+/// Every name and alias in `UNITS` to the index of the unit it means, so a
+/// lookup is a hash or two instead of a scan: `exact` has every word as
+/// spelled, `folded` every word that matches in any case, folded.
+#[cfg(feature = "std")]
+struct Index {
+    exact: std::collections::HashMap<&'static str, usize>,
+    folded: std::collections::HashMap<String, usize>,
+}
+
+#[cfg(feature = "std")]
+static INDEX: std::sync::LazyLock<Index> = std::sync::LazyLock::new(|| {
+    let mut index = Index { exact: std::collections::HashMap::new(), folded: std::collections::HashMap::new() };
+    for (i, unit) in UNITS.iter().enumerate() {
+        for &word in core::iter::once(&unit.name).chain(unit.aliases) {
+            let i = homonym(word).and_then(|u| UNITS.iter().position(|other| other.name == u.name)).unwrap_or(i);
+            index.exact.entry(word).or_insert(i);
+            if !spelled_only(word, unit.case_sensitive) {
+                index.folded.entry(word_key(word)).or_insert(i);
+            }
+        }
+    }
+    index
+});
+
+/// The unit `input` names in `UNITS`, as `Unit::matches` decides: the
+/// `HOMONYMS` entry for a shared word, otherwise the only unit claiming it.
+/// A word spelled exactly as typed wins over one matched in another case.
+#[cfg(feature = "std")]
+fn table_unit(input: &str) -> Option<&'static Unit> {
+    let folded = || {
+        let key = if input.chars().any(|c| fold(c) != c) { word_key(input).into() } else { std::borrow::Cow::Borrowed(input) };
+        INDEX.folded.get(key.as_ref()).copied()
+    };
+    INDEX.exact.get(input).copied().or_else(folded).map(|i| &UNITS[i])
+}
+
+#[cfg(not(feature = "std"))]
+fn table_unit(input: &str) -> Option<&'static Unit> {
+    let spelled = |u: &&Unit| core::iter::once(&u.name).chain(u.aliases).any(|word| *word == input);
+    homonym(input).or_else(|| UNITS.iter().find(spelled)).or_else(|| UNITS.iter().find(|u| u.matches(input)))
+}
+
+#[cfg(feature = "i18n-aliases")]
+fn localized_unit(input: &str) -> Option<&'static Unit> {
+    let name = crate::aliases::canonical(input)?;
+    UNITS.iter().find(|u| u.name == name)
+}
+
+#[cfg(not(feature = "i18n-aliases"))]
+fn localized_unit(_input: &str) -> Option<&'static Unit> {
+    None
+}
+
+#[cfg(feature = "std")]
+pub fn find_unit(input: &str) -> Option<&'static Unit> {
+    registry::active().find(input)
+}
+
+/// Without `std` there is no registry, so lookups go straight to the table.
+#[cfg(not(feature = "std"))]
+pub fn find_unit(input: &str) -> Option<&'static Unit> {
+    builtin_unit(input)
+}
+
+/// Why `input` didn't resolve: disabled by the configuration, not a unit at
+/// all, or a compound unit with a bad term.
+#[cfg(feature = "std")]
+pub fn unit_error(input: &str) -> ConvertError {
+    registry::active().error(input)
+}
+
+/// Like `find_unit`, but also resolves the `human` pseudo-unit, which is a
+/// duration in seconds.
+#[cfg(feature = "std")]
+pub fn lookup_unit(input: &str) -> Option<&'static Unit> {
+    find_unit(if duration::is_human(input) { "s" } else { input })
+}
+
+/// The units of `category` in the active registry.
+#[cfg(feature = "std")]
+pub fn units_by_category(category: UnitCategory) -> impl Iterator<Item = &'static Unit> {
+    registry::active().by_category(category)
+}
+
+/// The categories the active registry has units for.
+#[cfg(feature = "std")]
+pub fn categories() -> Vec<UnitCategory> {
+    registry::active().categories()
+}
+
+/// The words besides its name that `find_unit` accepts for `unit`.
+pub fn aliases_of(unit: &Unit) -> &'static [&'static str] {
+    unit.aliases
+}
+
+/// Units in the active registry whose name, an alias or a display name
+/// contains `query`, ignoring case: "meter" finds km, m, cm and mm.
+#[cfg(feature = "std")]
+pub fn find_all_matching(query: &str) -> Vec<&'static Unit> {
+    registry::active().matching(query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn assert_approx_eq(a: f64, b: f64, epsilon: f64) {
+        assert!((a - b).abs() < epsilon, "{} != {} (epsilon: {})", a, b, epsilon);
+    }
+    
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_km_to_miles() {
+        let km = find_unit("km").unwrap();
+        let mi = find_unit("mi").unwrap();
+        let base = km.to_base(5.0);
+        let result = mi.to_unit(base);
+        assert_approx_eq(result, 3.10686, 0.00001);
+    }
+    
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn test_celsius_to_fahrenheit() {
+        let c = find_unit("C").unwrap();
+        let f = find_unit("F").unwrap();
+        let base = c.to_base(100.0);
+        let result = f.to_unit(base);
+        assert_approx_eq(result, 212.0, 0.00001);
+    }
+    
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn test_celsius_to_fahrenheit_freezing() {
+        let c = find_unit("C").unwrap();
+        let f = find_unit("F").unwrap();
+        let base = c.to_base(0.0);
+        let result = f.to_unit(base);
+        assert_approx_eq(result, 32.0, 0.00001);
+    }
+    
+    #[test]
+    #[cfg(feature = "mass")]
+    fn test_kg_to_pounds() {
+        let kg = find_unit("kg").unwrap();
+        let lb = find_unit("lb").unwrap();
+        let base = kg.to_base(10.0);
+        let result = lb.to_unit(base);
+        assert_approx_eq(result, 22.0462, 0.0001);
+    }
+    
+    #[test]
+    #[cfg(feature = "mass")]
+    fn test_mg_to_kg() {
+        let mg = find_unit("mg").unwrap();
+        let kg = find_unit("kg").unwrap();
+        let base = mg.to_base(1000000.0);
+        let result = kg.to_unit(base);
+        assert_approx_eq(result, 1.0, 0.00001);
+    }
+    
+    #[test]
+    #[cfg(feature = "mass")]
+    fn test_g_to_mg() {
+        let g = find_unit("g").unwrap();
+        let mg = find_unit("mg").unwrap();
+        let base = g.to_base(1.0);
+        let result = mg.to_unit(base);
+        assert_approx_eq(result, 1000.0, 0.00001);
+    }
+    
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn test_unit_matching_case_insensitive() {
+        assert!(find_unit("KM").is_some());
+        assert!(find_unit("MeTErs").is_some());
+        assert!(find_unit("FAHRENHEIT").is_some());
+    }
+    
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn test_unit_aliases() {
+        assert!(find_unit("kilometer").is_some());
+        assert!(find_unit("kilometres").is_some());
+        assert!(find_unit("celsius").is_some());
+    }
+    
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature", feature = "time", feature = "volume", feature = "speed", feature = "data"))]
+    fn test_short_symbols_match_case_sensitively() {
+        assert_eq!(find_unit("KM").map(|u| u.name), Some("km"));
+        assert_eq!(find_unit("k").map(|u| u.name), Some("K"));
+        assert_eq!((find_unit("c").map(|u| u.name), find_unit("C").map(|u| u.name)), (Some("c"), Some("C")));
+        assert_eq!(find_unit("Millimeters").map(|u| u.name), Some("mm"));
+        for (symbol, other_case) in [("mm", "Mm"), ("mg", "MG"), ("ms", "MS")] {
+            assert_eq!(find_unit(symbol).map(|u| u.name), Some(symbol));
+            assert!(find_unit(other_case).is_none(), "{}", other_case);
+        }
+        let unit = |name: &'static str| Unit { name, aliases: &[], case_sensitive: true, display_singular: name, display_plural: name, ..UNITS[0] };
+        let (bit, byte) = (unit("b"), unit("B"));
+        assert!(bit.matches("b") && !bit.matches("B"));
+        assert!(byte.matches("B") && !byte.matches("b"));
+        let any_case = Unit { case_sensitive: false, ..unit("b") };
+        assert!(any_case.matches("B") && any_case.matches("b"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "mass", feature = "volume"))]
+    fn test_multi_word_aliases_accept_hyphens_and_underscores() {
+        let ton = find_unit("ton").unwrap();
+        for word in ["metric ton", "metric-ton", "Metric_Ton"] {
+            assert!(ton.matches(word), "{}", word);
+            assert_eq!(find_unit(word).map(|u| u.name), Some("ton"), "{}", word);
+        }
+        assert_eq!(find_unit("metric_tons").map(|u| u.name), Some("ton"));
+        assert_eq!(find_unit("cubic_meters").map(|u| u.name), Some("m3"));
+        assert!(find_unit("metric--ton").is_none());
+        assert!(find_unit("metricton").is_none());
+    }
+    
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_same_unit_conversion() {
+        let m = find_unit("m").unwrap();
+        let base = m.to_base(100.0);
+        let result = m.to_unit(base);
+        assert_approx_eq(result, 100.0, 0.00001);
+    }
+    
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_negative_uncertainty_rejected() {
+        assert!(matches!(convert_checked("5±-0.2", "km", "mi"), Err(ConvertError::NegativeUncertainty(_))));
+        assert!(matches!(convert_checked("5±abc", "km", "mi"), Err(ConvertError::InvalidValue(_))));
+    }
+    
+    #[test]
+    #[cfg(all(feature = "std", feature = "length", feature = "mass", feature = "temperature"))]
+    fn test_errors_from_bad_input() {
+        assert_eq!(convert_checked("five", "km", "mi").unwrap_err(), ConvertError::InvalidValue("five".to_string()));
+        assert_eq!(
+            convert_checked("5", "kmz", "mi").unwrap_err(),
+            ConvertError::UnknownUnit { input: "kmz".to_string(), suggestions: vec!["km".to_string()] }
+        );
+        assert_eq!(
+            convert_checked("1", "kg", "ft").unwrap_err(),
+            ConvertError::CategoryMismatch {
+                from: UnitCategory::Mass,
+                to: UnitCategory::Length,
+                from_unit: "kg".to_string(),
+                to_unit: "ft".to_string(),
+            }
+        );
+        assert_eq!(convert_checked("-1", "K", "C").unwrap_err(), ConvertError::BelowAbsoluteZero { unit: "K", value: -1.0, limit: 0.0 });
+        assert_eq!(convert(-460.0, "F", "C"), Err(ConvertError::BelowAbsoluteZero { unit: "F", value: -460.0, limit: -459.67 }));
+    }
+    
+    #[test]
+    #[cfg(all(feature = "std", feature = "temperature"))]
+    fn test_absolute_zero_in_every_scale() {
+        assert!(matches!(convert(-460.0, "F", "K"), Err(ConvertError::BelowAbsoluteZero { unit: "F", .. })));
+        assert!(matches!(convert(-273.2, "C", "F"), Err(ConvertError::BelowAbsoluteZero { unit: "C", .. })));
+        assert_eq!(convert(0.0, "K", "C"), Ok(-273.15));
+        assert!(convert(-459.67, "F", "K").is_ok_and(|k: f64| k.abs() < 1e-9));
+        assert!(convert(-273.15, "C", "K").is_ok());
+        assert!(convert_delta("-500", "C", "F").is_ok(), "a difference may be any size");
+        // Matching the scale by name, the old check let any other scale through.
+        let rankine = registry::UnitDef {
+            name: "R".to_string(),
+            aliases: vec![],
+            case_sensitive: true,
+            category: UnitCategory::Temperature,
+            factor: 5.0 / 9.0,
+            offset: -491.67,
+            singular: None,
+            plural: None,
+        }
+        .leak();
+        assert_eq!(above_absolute_zero(rankine, -1.0), Err(ConvertError::BelowAbsoluteZero { unit: "R", value: -1.0, limit: 0.0 }));
+        assert_eq!(above_absolute_zero(rankine, 0.0), Ok(()));
+    }
+    
+    #[test]
+    #[cfg(all(feature = "std", feature = "length", feature = "mass", feature = "temperature", feature = "time", feature = "volume"))]
+    fn test_negative_amounts_warn_where_meaningless() {
+        let warnings = |value, from, to| convert_checked(value, from, to).unwrap().warnings;
+        assert_eq!(warnings("-5", "kg", "lb"), [Warning::Negative(UnitCategory::Mass)]);
+        assert_eq!(warnings("-1", "L", "mL"), [Warning::Negative(UnitCategory::Volume)]);
+        assert_eq!(warnings("-2", "km", "mi"), [Warning::Negative(UnitCategory::Length)]);
+        assert_eq!(warnings("-40", "C", "F"), []);
+        assert_eq!(warnings("-3", "h", "min"), []);
+        assert_eq!(warnings("0", "kg", "g"), []);
+        assert_eq!(Warning::Negative(UnitCategory::Mass).to_string(), "Negative mass doesn't make physical sense");
+        assert_eq!(Warning::Negative(UnitCategory::Mass).localized(Lang::Es), "Una masa negativa no tiene sentido físico");
+        let declared: Vec<UnitCategory> = UnitCategory::all().iter().copied().filter(|c| !c.allows_negative()).collect();
+        assert_eq!(declared, [UnitCategory::Length, UnitCategory::Mass, UnitCategory::Volume, UnitCategory::Data]);
+    }
+    
+    #[test]
+    #[cfg(all(feature = "std", feature = "length", feature = "mass", feature = "temperature", feature = "time"))]
+    fn test_extreme_magnitudes_warn() {
+        let warnings = |value, from, to| convert_checked(value, from, to).unwrap().warnings;
+        assert!(matches!(convert_checked("1e306", "km", "mm"), Err(ConvertError::Overflow { .. })));
+        assert_eq!(warnings("1e-308", "mg/d", "ton/ms"), [Warning::Underflow]);
+        assert_eq!(convert_checked("1e-308", "mg/d", "ton/ms").unwrap().result, 0.0);
+        assert_eq!(warnings("1e-300", "mg", "ton"), [Warning::PrecisionLoss]);
+        assert_eq!(warnings("1e-320", "km", "m"), [Warning::PrecisionLoss]);
+        assert_eq!(warnings("273.15", "K", "C"), [], "0 from an offset is no underflow");
+        assert_eq!(warnings("1e300", "km", "mm"), []);
+        assert_eq!(warnings("9007199254740993", "mm", "m"), []);
+        assert_eq!(warnings("1e-290", "mg", "ton"), []);
+        assert_eq!(Warning::Underflow.to_string(), "The result is too small for a double and came out as 0");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_non_finite_values_rejected() {
+        for input in ["NaN", "nan", "inf", "+inf", "-inf", "infinity", "1e400", "5±inf", "5±NaN"] {
+            assert_eq!(convert_checked(input, "km", "mi").unwrap_err(), ConvertError::NotFinite(input.to_string()));
+        }
+        assert_eq!(convert(f64::NAN, "C", "F"), Err(ConvertError::NotFinite("NaN".to_string())));
+        assert_eq!(convert(f64::NEG_INFINITY, "C", "F"), Err(ConvertError::NotFinite("-inf".to_string())));
+        assert_eq!(ConvertError::NotFinite("inf".to_string()).to_string(), "'inf' is not allowed: the value must be a finite number");
+    }
+    
+    #[test]
+    #[cfg(all(feature = "std", feature = "length", feature = "temperature"))]
+    fn test_overflow_reported() {
+        let overflow = ConvertError::Overflow { value: 1e308, from: "km".to_string(), to: "mm".to_string() };
+        assert_eq!(convert_checked("1e308", "km", "mm").unwrap_err(), overflow);
+        assert_eq!(overflow.to_string(), "1e308 km in mm: result too large to represent");
+        assert!(matches!(convert_delta("-1e308", "C", "F"), Err(ConvertError::Overflow { .. })));
+        assert!(convert(1e308, "mm", "km").is_ok_and(|km: f64| km.is_finite()));
+        assert!(convert(f64::MAX, "m", "m").is_ok());
+    }
+    
+    #[test]
+    #[cfg(all(feature = "std", feature = "length", feature = "mass"))]
+    fn test_error_messages() {
+        let unknown = |suggestions: &[&str]| ConvertError::UnknownUnit {
+            input: "mtr".to_string(),
+            suggestions: suggestions.iter().map(|s| s.to_string()).collect(),
+        };
+        assert_eq!(unknown(&[]).to_string(), "Unknown unit 'mtr'");
+        assert_eq!(unknown(&["m"]).to_string(), "Unknown unit 'mtr'; did you mean 'm'?");
+        assert_eq!(unknown(&["m", "mi", "mm"]).to_string(), "Unknown unit 'mtr'; did you mean 'm', 'mi' or 'mm'?");
+        assert_eq!(unknown(&["m"]).localized(Lang::Es), "Unidad desconocida 'mtr'; ¿quisiste decir 'm'?");
+        assert_eq!(ConvertError::InvalidValue("x".to_string()).to_string(), "'x' is not a valid number");
+        let cold = ConvertError::BelowAbsoluteZero { unit: "C", value: -300.5, limit: -273.15 };
+        assert_eq!(cold.to_string(), "-300.5 C is below absolute zero (-273.15 C)");
+        let mismatch = convert(1.0, "kg", "ft").unwrap_err();
+        assert_eq!(mismatch.to_string(), "Cannot convert between different unit categories (M¹ vs L¹)\n  kg is a Mass unit\n  ft is a Length unit");
+        let error: Box<dyn std::error::Error> = Box::new(mismatch);
+        assert!(error.source().is_none());
+    }
+    
+    #[test]
+    #[cfg(all(feature = "std", feature = "length", feature = "mass", feature = "time", feature = "volume"))]
+    fn test_mismatch_hints_at_a_bridge() {
+        let message = |from: &str, to: &str| convert(1.0, from, to).unwrap_err().to_string();
+        assert_eq!(
+            message("kg", "L"),
+            "Cannot convert between different unit categories (M¹ vs L³)\n  kg is a Mass unit\n  L is a Volume unit\n  hint: convert through a density, e.g. water ≈ 1 g/mL"
+        );
+        assert!(message("mi", "h").ends_with("\n  hint: convert through a speed, e.g. walking ≈ 5 km/h"));
+        assert!(message("kg*m^2/s^3", "kg*m^2/s^2").ends_with("\n  hint: convert through a duration, e.g. 1 h"));
+        assert!(message("kg", "m").ends_with("\n  m is a Length unit"));
+        let es = convert(1.0, "L", "g").unwrap_err().localized(Lang::Es);
+        assert!(es.ends_with("\n  pista: convierte a través de una densidad, p. ej. agua ≈ 1 g/mL"), "{}", es);
+    }
+    
+    #[test]
+    #[cfg(all(feature = "std", feature = "mass", feature = "volume"))]
+    fn test_mismatch_names_a_cousin() {
+        let message = |from: &str, to: &str| convert(5.0, from, to).unwrap_err().to_string();
+        let lines = message("oz", "floz-us");
+        assert!(lines.contains("\n  oz is a Mass unit; did you mean 'US fl oz' (Volume)?\n"), "{}", lines);
+        // Only the source unit gets a hint.
+        assert!(lines.contains("\n  floz-us is a Volume unit\n"), "{}", lines);
+        let lines = message("floz-us", "kg");
+        assert!(lines.contains("\n  floz-us is a Volume unit; did you mean 'oz' (Mass)?\n  kg is a Mass unit\n"), "{}", lines);
+        assert!(!message("L", "oz").contains("did you mean"));
+        assert!(message("ounces", "mL").contains("\n  ounces is a Mass unit; did you mean 'US fl oz' (Volume)?\n"));
+        assert!(!message("kg", "m").contains("did you mean"));
+        let de = convert(5.0, "oz", "L").unwrap_err().localized(Lang::De);
+        assert!(de.contains("oz ist eine Einheit der Kategorie Masse; meintest du 'US fl oz' (Volumen)?"), "{}", de);
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", feature = "length", feature = "time", feature = "data"))]
+    fn test_bits_and_bytes() {
+        assert_eq!(convert(1.0, "MB", "Mb"), Ok(8.0));
+        assert_eq!(convert(1.0, "KiB", "Kib"), Ok(8.0));
+        assert_eq!(convert(1.0, "GB", "MB"), Ok(1000.0));
+        assert_eq!(convert(1.0, "GiB", "MiB"), Ok(1024.0));
+        assert_eq!(convert(1.0, "TiB", "B"), Ok(1099511627776.0));
+        assert_eq!(convert(100.0, "Mb/s", "MB/s"), Ok(12.5));
+        // The symbols only match as spelled; the names in any case.
+        for (word, name) in [("Mb", "Mb"), ("MB", "MB"), ("Kib", "Kib"), ("KiB", "KiB"), ("b", "b"), ("B", "B")] {
+            assert_eq!(find_unit(word).map(|u| u.name), Some(name), "{}", word);
+        }
+        for word in ["mb", "mB", "KIB", "kib", "gb"] {
+            assert!(find_unit(word).is_none(), "{}", word);
+        }
+        for word in ["megabytes", "MEGABYTES", "MegaBytes", "megabyte"] {
+            assert_eq!(find_unit(word).map(|u| u.name), Some("MB"), "{}", word);
+        }
+        assert_eq!(find_unit("Megabits").map(|u| u.name), Some("Mb"));
+        assert_eq!(find_unit("BITS").map(|u| u.name), Some("b"));
+        assert!(matches!(convert(1.0, "MB", "m"), Err(ConvertError::CategoryMismatch { .. })));
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", feature = "data"))]
+    fn test_data_symbol() {
+        let symbol = |input| data_symbol(input).map(|(term, unit)| (term, unit.name));
+        assert_eq!(symbol("Mb"), Some(("Mb", "Mb")));
+        assert_eq!(symbol("KiB"), Some(("KiB", "KiB")));
+        assert_eq!(symbol("MB/s"), Some(("MB", "MB")));
+        for input in ["megabytes", "bit", "bits", "km", "mb", "Mbit"] {
+            assert_eq!(symbol(input), None, "{}", input);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", feature = "volume"))]
+    fn test_us_and_imperial_volumes() {
+        let close = |value: f64, from, to, expected: f64| assert!((convert(value, from, to).unwrap() - expected).abs() < 1e-9, "{} {} {}", value, from, to);
+        close(1.0, "gal-us", "L", 3.785411784);
+        close(1.0, "gal-imp", "L", 4.54609);
+        close(1.0, "imperial gallon", "US gallons", 1.200949925504855);
+        close(1.0, "pt-imp", "mL", 568.26125);
+        close(2.0, "pt-us", "qt-us", 1.0);
+        close(8.0, "pt-imp", "gal-imp", 1.0);
+        close(20.0, "floz-imp", "pt-imp", 1.0);
+        close(16.0, "floz-us", "pt-us", 1.0);
+        assert_eq!(us_imperial("Gallons"), Some(("gal-us", "gal-imp")));
+        assert_eq!(us_imperial("fl-oz"), Some(("floz-us", "floz-imp")));
+        assert_eq!(us_imperial("PT"), None, "a short symbol matches only as spelled");
+        // The default registry has no system, so the bare word names neither.
+        let error = convert(1.0, "gallon", "L").unwrap_err();
+        assert_eq!(error, ConvertError::AmbiguousUnit { input: "gallon".to_string(), choices: ["gal-us", "gal-imp"] });
+        assert_eq!(
+            error.to_string(),
+            "'gallon' means a different amount in the US and imperial systems; use 'gal-us' or 'gal-imp', or choose a system with --system us|imperial or 'system' in the config"
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", feature = "time"))]
+    fn test_calendar_years_and_months() {
+        let days = |unit| convert(1.0_f64, unit, "d").unwrap();
+        assert_eq!(days("julian-year"), 365.25);
+        assert_eq!(days("gregorian-year"), 365.2425);
+        assert_eq!(days("civil-year"), 365.0);
+        assert_eq!(convert(12.0, "julian-month", "julian-year"), Ok(1.0));
+        assert_eq!(convert(12.0, "gregorian-month", "gregorian-year"), Ok(1.0));
+        assert_eq!(convert(12.0, "civil-month", "civil-year"), Ok(1.0));
+        assert_eq!(calendar_units("Years"), Some(["julian-year", "gregorian-year", "civil-year"]));
+        assert_eq!(calendar_units("mos."), Some(["julian-month", "gregorian-month", "civil-month"]));
+        assert_eq!(calendar_units("MO"), None, "a short symbol matches only as spelled");
+        // Without a calendar chosen, the bare words are Julian.
+        assert_eq!(convert(1.0, "year", "s"), Ok(31557600.0));
+        assert_eq!(convert(2.0, "months", "s"), Ok(5259600.0));
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", feature = "volume"))]
+    fn test_dry_measures() {
+        assert_eq!(convert(1.0, "bushel", "pecks"), Ok(4.0));
+        assert_eq!(convert(1.0, "peck", "dry gallons"), Ok(2.0));
+        let close = |value: f64, from, to, expected: f64| assert!((convert(value, from, to).unwrap() - expected).abs() < 1e-9, "{} {} {}", value, from, to);
+        close(1.0, "bu", "L", 35.23907016688);
+        close(100.0, "L", "bushels", 2.837759325840175);
+        close(1.0, "dry-qt", "L", 1.101220942715);
+        // A dry quart is bigger than either liquid one, and no spelling of "quart" reaches it.
+        assert!(convert(1.0_f64, "dry quart", "US quart").unwrap() > 1.16);
+        assert_eq!(us_imperial("qt"), Some(("qt-us", "qt-imp")));
+    }
+    
+    #[test]
+    #[cfg(all(feature = "std", feature = "length", feature = "mass", feature = "temperature", feature = "time", feature = "volume"))]
+    fn test_convert_via() {
+        assert_eq!(convert_via(500.0, "mL", "g", "1g/mL"), Ok(500.0));
+        assert!((convert_via(1.0, "L", "lb", "0.92 g/mL").unwrap() - 2.0282528).abs() < 1e-6);
+        assert_eq!(convert_via(3.0, "h", "km", "5km/h"), Ok(15.0));
+        assert_eq!(convert_via(2.0, "km", "m", "5km/h"), Ok(2000.0));
+        assert!(matches!(convert_via(1.0, "kg", "m", "1g/mL"), Err(ConvertError::CategoryMismatch { .. })));
+        assert!(matches!(convert_via(1.0, "kg", "L", "1 furlong"), Err(ConvertError::UnknownUnit { .. })));
+        assert!(matches!(convert_via(1.0, "C", "m", "1m/K"), Err(ConvertError::OffsetInCompound { .. })));
+    }
+    
+    #[test]
+    #[cfg(all(feature = "std", feature = "length", feature = "temperature"))]
+    fn test_unknown_units_carry_suggestions() {
+        let suggestions = |from: &str| match convert(1.0, from, "F") {
+            Err(ConvertError::UnknownUnit { suggestions, .. }) => suggestions,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(suggestions("celcius"), ["C"]);
+        assert_eq!(suggestions("milimeter"), ["mm", "km"]);
+        assert!(suggestions("qwzxvbnpjk").is_empty());
+    }
+    
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_category_names_round_trip() {
+        for &category in UnitCategory::all() {
+            let name = category.to_string();
+            assert_eq!(name.parse(), Ok(category));
+            assert_eq!(name.to_uppercase().parse(), Ok(category));
+            assert_eq!(name.to_lowercase().parse(), Ok(category));
+        }
+        assert_eq!("temperature".parse(), Ok(UnitCategory::Temperature));
+        assert_eq!(UnitCategory::Mass.to_string(), "Mass");
+    }
+    
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_unknown_category() {
+        let error = "energy".parse::<UnitCategory>().unwrap_err();
+        assert_eq!(error, ParseCategoryError("energy".to_string()));
+        assert_eq!(error.to_string(), "unknown category 'energy'; use length, temperature, mass, time, volume, speed, data, angle or currency");
+        assert!("".parse::<UnitCategory>().is_err());
+        assert!(" mass".parse::<UnitCategory>().is_err());
+    }
+    
+    #[test]
+    #[cfg(all(feature = "std", feature = "length", feature = "mass", feature = "temperature", feature = "time", feature = "volume", feature = "speed", feature = "data", feature = "angle"))]
+    fn test_units_by_category() {
+        let count = |category| units_by_category(category).count();
+        assert_eq!(count(UnitCategory::Length), 8);
+        assert_eq!(count(UnitCategory::Temperature), 3);
+        assert_eq!(count(UnitCategory::Mass), 7);
+        assert_eq!(count(UnitCategory::Time), 12);
+        assert_eq!(count(UnitCategory::Volume), 15);
+        assert_eq!(count(UnitCategory::Speed), 4);
+        assert_eq!(count(UnitCategory::Data), 18);
+        assert_eq!(count(UnitCategory::Angle), 4);
+        assert_eq!(UnitCategory::all().iter().map(|&c| count(c)).sum::<usize>(), UNITS.len());
+        assert!(units_by_category(UnitCategory::Mass).all(|u| u.category == UnitCategory::Mass));
+        // No currency is built in.
+        assert_eq!(categories(), &UnitCategory::all()[..8]);
+        assert_eq!(aliases_of(find_unit("lb").unwrap()), ["pound", "pounds"]);
+    }
+    
+    #[test]
+    #[cfg(all(feature = "std", feature = "length", feature = "time", feature = "speed"))]
+    fn test_mach_and_the_speed_of_light() {
+        assert_eq!(convert(0.5, "c", "km/s"), Ok(149896.229));
+        assert!((convert(2.5_f64, "mach", "km/h").unwrap() - 3062.61).abs() < 1e-9);
+        assert_eq!(find_unit("speed of light").map(|u| u.name), Some("c"));
+        assert_eq!(find_unit("Lightspeed").map(|u| u.name), Some("c"));
+        assert_eq!(find_unit("MACH").map(|u| u.name), Some("mach"));
+        // c is 1.08e15 mm/h, so a round trip through it spans thirty orders
+        // of magnitude between the smallest and largest values here.
+        for value in [1e-300_f64, 1e-9, 1.0, 1e9, 1e290] {
+            let there = convert(value, "c", "mm/h").unwrap();
+            let back = convert(there, "mm/h", "c").unwrap();
+            assert!(((back - value) / value).abs() < 1e-15, "{} c -> {} mm/h -> {} c", value, there, back);
+        }
+        let walking = convert(5.0_f64, "km/h", "c").unwrap();
+        assert!((convert(walking, "c", "km/h").unwrap() - 5.0).abs() < 1e-12);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_dimensions_decide_what_converts() {
+        for &category in UnitCategory::all() {
+            assert_ne!(category.dimension(), Dimension::NONE);
+        }
+        assert_eq!(UnitCategory::Volume.dimension(), Dimension::LENGTH.pow(3));
+        assert_eq!(UnitCategory::Speed.dimension(), Dimension::LENGTH / Dimension::TIME);
+        for from in UNITS {
+            for to in UNITS {
+                let result = convert(1.0, from.name, to.name);
+                assert_eq!(result.is_ok(), from.dimension() == to.dimension(), "{} -> {}: {:?}", from.name, to.name, result);
+            }
+        }
+    }
+    
+    #[test]
+    #[cfg(all(feature = "std", feature = "length", feature = "temperature", feature = "volume", feature = "speed"))]
+    fn test_find_all_matching() {
+        let names = |query| find_all_matching(query).into_iter().map(|u| u.name).collect::<Vec<_>>();
+        assert_eq!(names("meter"), ["km", "m", "cm", "mm", "m3", "m/s"]);
+        assert_eq!(names("METRE"), names("meter"));
+        assert!(find_all_matching("meter").iter().all(|u| u.category != UnitCategory::Mass));
+        #[cfg(not(feature = "angle"))]
+        assert_eq!(names("degree"), ["C", "F"]);
+        #[cfg(feature = "angle")]
+        assert_eq!(names("degree"), ["C", "F", "deg"]);
+        assert!(names("furlong").is_empty());
+    }
+    
+    #[test]
+    fn test_conversions_invert() {
+        for unit in UNITS {
+            for x in [-1e6, -459.67, -1.0, 0.0, 0.1, 1.0, 98.6, 1e6] {
+                let back = unit.conversion.invert(unit.conversion.apply(x));
+                assert_approx_eq(back, x, 1e-9 * x.abs().max(1.0));
+            }
+        }
+    }
+    
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn test_conversion_kinds() {
+        assert_eq!(Conversion::new((1000.0, 1.0), 0.0), Conversion::Linear { factor: (1000.0, 1.0) });
+        let fahrenheit = Conversion::new((5.0, 9.0), -32.0);
+        assert_eq!(fahrenheit, Conversion::Affine { factor: (5.0, 9.0), offset: -32.0 });
+        assert_eq!((fahrenheit.factor(), fahrenheit.offset()), ((5.0, 9.0), -32.0));
+        assert_eq!(fahrenheit.apply(212.0), 100.0);
+        assert_eq!(fahrenheit.invert(100.0), 212.0);
+        let affine: Vec<&str> =
+            UNITS.iter().filter(|u| matches!(u.conversion, Conversion::Affine { .. })).map(|u| u.name).collect();
+        assert_eq!(affine, ["F", "K"]);
+    }
+    
+    #[test]
+    #[cfg(all(feature = "std", feature = "temperature"))]
+    fn test_temperature_delta() {
+        assert_approx_eq(convert_delta("10", "C", "F").unwrap().result, 18.0, 1e-9);
+        assert_approx_eq(convert_delta("18", "F", "C").unwrap().result, 10.0, 1e-9);
+        assert_approx_eq(convert_delta("10", "K", "C").unwrap().result, 10.0, 1e-9);
+        assert_approx_eq(convert_delta("-300", "C", "K").unwrap().result, -300.0, 1e-9);
+    }
+    
+    #[test]
+    #[cfg(all(feature = "std", feature = "length"))]
+    fn test_delta_is_a_no_op_for_linear_units() {
+        let delta = convert_delta("5", "km", "mi").unwrap();
+        let plain = convert_checked("5", "km", "mi").unwrap();
+        assert_eq!((delta.result, delta.warnings), (plain.result, plain.warnings));
+    }
+    
+    #[test]
+    #[cfg(all(feature = "std", feature = "length"))]
+    fn test_exact_falls_back_for_irrational_factors() {
+        let degree = Unit {
+            name: "deg",
+            aliases: &[],
+            case_sensitive: true,
+            display_singular: "degree",
+            display_plural: "degrees",
+            category: UnitCategory::Length,
+            conversion: Conversion::Linear { factor: (std::f64::consts::PI / 180.0, 1.0) },
+        };
+        let radian = Unit { name: "rad", conversion: Conversion::Linear { factor: (1.0, 1.0) }, ..degree };
+        assert_eq!(convert_exact("90", &degree, &radian, false), None);
+        assert_eq!(convert_exact("5±1", find_unit("m").unwrap(), find_unit("cm").unwrap(), false), None);
+    }
+    
+    #[test]
+    #[cfg(all(feature = "mass", feature = "temperature"))]
+    fn test_table_lookup_and_convert_units() {
+        let (lbs, kg) = (builtin_unit("lbs.").unwrap(), builtin_unit("KILOGRAM").unwrap());
+        assert_approx_eq(convert_units(lbs, kg, 10.0), 4.5359237, 1e-12);
+        assert_approx_eq(slope(builtin_unit("F").unwrap(), builtin_unit("C").unwrap()), 5.0 / 9.0, 1e-15);
+        assert!(builtin_unit("furlong").is_none());
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature", feature = "time"))]
+    fn test_f32_stays_within_epsilon_of_f64() {
+        let cases = [(5.0f32, "km", "mi"), (98.6, "F", "C"), (-40.0, "C", "F"), (0.3, "K", "F"), (1e6, "mg", "lb"), (7.25, "h", "ms")];
+        for (value, from, to) in cases {
+            let (from, to) = (builtin_unit(from).unwrap(), builtin_unit(to).unwrap());
+            let (single, double) = (convert_units(from, to, value), convert_units(from, to, f64::from(value)));
+            assert!((f64::from(single) - double).abs() <= f64::from(f32::EPSILON) * double.abs(), "{} {}: {} vs {}", value, from.name, single, double);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature"))]
+    fn test_convert_in_f32() {
+        assert_eq!(convert(100.0f32, "C", "F"), Ok(212.0f32));
+        assert_eq!(convert::<f32>(1.0, "mi", "km"), Ok(1.609344));
+        assert!(matches!(convert(f32::MAX, "km", "mm"), Err(ConvertError::Overflow { .. })), "finite in f64, not in f32");
+        assert!(matches!(convert(1.0f32, "kg", "m"), Err(ConvertError::CategoryMismatch { .. })));
+    }
+
+    #[test]
+    fn test_every_name_and_alias_resolves_to_its_unit() {
+        for word in UNITS.iter().flat_map(|u| core::iter::once(&u.name).chain(u.aliases)) {
+            let unit = homonym(word).or_else(|| UNITS.iter().find(|u| u.matches(word))).unwrap();
+            for spelling in [word.to_string(), word.to_lowercase(), word.to_uppercase()] {
+                let other_case = || UNITS.iter().find(|u| u.name == spelling).map(|u| u.name);
+                let expected = (spelling == *word || !spelled_only(word, unit.case_sensitive)).then_some(unit.name).or_else(other_case);
+                assert_eq!(table_unit(&spelling).map(|u| u.name), expected, "{}", spelling);
+            }
+        }
+        assert!(table_unit("Kilomètre").is_none());
+        assert!(table_unit("").is_none());
+    }
+
+    /// Smaller units divide by a whole power of ten instead of multiplying by
+    /// an inexact 0.01 or 0.000001, so whole powers of ten survive a round trip.
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "time", feature = "volume"))]
+    fn test_decimal_units_round_trip_exactly() {
+        for name in ["km", "cm", "mm", "g", "mg", "ton", "ms", "L", "mL"] {
+            let unit = find_unit(name).unwrap();
+            for exponent in -3..=15 {
+                let x = 10f64.powi(exponent);
+                assert_eq!(unit.to_unit(unit.to_base(x)), x, "{} {}", x, name);
+            }
+        }
+        let pair = |from, to| convert_units(find_unit(from).unwrap(), find_unit(to).unwrap(), 1.0);
+        assert_eq!(pair("m", "cm"), 100.0);
+        assert_eq!(pair("g", "mg"), 1000.0);
+        assert_eq!(pair("cm", "mm"), 10.0);
+        assert_eq!(pair("mL", "L"), 0.001);
+    }
+
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn test_kelvin_to_celsius() {
+        let k = find_unit("K").unwrap();
+        let c = find_unit("C").unwrap();
+        let base = k.to_base(273.15);
+        let result = c.to_unit(base);
+        assert_approx_eq(result, 0.0, 0.00001);
+    }
+
+    /// Run by tests/categories.rs in a build without the `mass` feature.
+    #[test]
+    #[cfg(all(feature = "std", not(feature = "mass")))]
+    fn test_absent_category() {
+        assert!(UNITS.iter().all(|u| u.category != UnitCategory::Mass));
+        assert!(units_by_category(UnitCategory::Mass).next().is_none());
+        assert!(!categories().contains(&UnitCategory::Mass));
+        assert!(matches!(convert(1.0, "kg", "g"), Err(ConvertError::UnknownUnit { .. })));
+        assert!(matches!(convert_checked("2", "lb", "km"), Err(ConvertError::UnknownUnit { .. })));
+        assert_eq!("mass".parse(), Ok(UnitCategory::Mass));
+    }
+}
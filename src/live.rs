@@ -0,0 +1,261 @@
+//! `--live`: currency rates fetched from a rate service instead of read from
+//! a file. The answer is cached in the state directory and reused until it
+//! is `rates_ttl_hours` old; when a fetch fails, the cached rates stand in
+//! with a warning however old they are. The system backend runs `curl` and
+//! is only compiled with the `online` feature, so nothing else ever touches
+//! the network.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use converter::json::{self, Json};
+
+use crate::rates::Rates;
+
+/// The service when the config has no `rates_url`. It answers in the shape of
+/// a JSON rates file: `{"base": "USD", "date": "...", "rates": {...}}`.
+pub const DEFAULT_URL: &str = "https://api.exchangerate.host/latest";
+pub const DEFAULT_TTL_HOURS: f64 = 24.0;
+const CACHE_FILE: &str = "rates.json";
+
+/// Why a fetch got no rates.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FetchError {
+    /// No answer at all: no network, an unknown host, a timeout.
+    Unreachable(String),
+    /// An answer with an error status. Only the `curl` backend sees one.
+    #[cfg_attr(not(feature = "online"), allow(dead_code))]
+    Status(u16),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FetchError::Unreachable(reason) => write!(f, "can't reach the rate service: {}", reason),
+            FetchError::Status(code) => write!(f, "the rate service answered with HTTP status {}", code),
+        }
+    }
+}
+
+pub trait Fetch {
+    /// The body of a GET of `url`.
+    fn get(&mut self, url: &str) -> Result<String, FetchError>;
+}
+
+/// Stands in for the network when there's no backend; every fetch fails
+/// with the reason.
+#[cfg_attr(feature = "online", allow(dead_code))]
+pub struct Unavailable(pub String);
+
+impl Fetch for Unavailable {
+    fn get(&mut self, _url: &str) -> Result<String, FetchError> {
+        Err(FetchError::Unreachable(self.0.clone()))
+    }
+}
+
+#[cfg(feature = "online")]
+mod system {
+    use std::process::Command;
+
+    use super::FetchError;
+
+    /// Seconds before a fetch gives up, so a dead network can't hang a conversion.
+    const TIMEOUT: &str = "10";
+
+    pub struct Curl;
+
+    impl super::Fetch for Curl {
+        fn get(&mut self, url: &str) -> Result<String, FetchError> {
+            // The status goes on a line of its own after the body.
+            let output = Command::new("curl")
+                .args(["--silent", "--show-error", "--location", "--max-time", TIMEOUT, "--write-out", "\n%{http_code}", url])
+                .output()
+                .map_err(|e| FetchError::Unreachable(format!("curl: {}", e)))?;
+            if !output.status.success() {
+                return Err(FetchError::Unreachable(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+            }
+            let text = String::from_utf8_lossy(&output.stdout);
+            let (body, status) = text.rsplit_once('\n').unwrap_or(("", &text));
+            match status.trim().parse::<u16>() {
+                Ok(200..=299) => Ok(body.to_string()),
+                Ok(code) => Err(FetchError::Status(code)),
+                Err(_) => Err(FetchError::Unreachable(format!("curl gave no status for {}", url))),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "online")]
+pub fn system() -> Box<dyn Fetch> {
+    Box::new(system::Curl)
+}
+
+#[cfg(not(feature = "online"))]
+pub fn system() -> Box<dyn Fetch> {
+    Box::new(Unavailable("live rates are not compiled in (rebuild with `--features online`)".to_string()))
+}
+
+/// Live rates, and the warning to print when they aren't fresh ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Live {
+    pub rates: Rates,
+    pub warning: Option<String>,
+}
+
+/// The rates at `url`: from the cache in `cache_dir` while it is younger
+/// than `ttl` seconds, else fetched and cached. `now` is in seconds since
+/// the epoch.
+pub fn rates(fetch: &mut dyn Fetch, url: &str, cache_dir: Option<&Path>, ttl: u64, now: u64) -> Result<Live, String> {
+    let cached = cache_dir.and_then(|dir| read_cache(&dir.join(CACHE_FILE), url));
+    if let Some((fetched, rates)) = &cached
+        && now.saturating_sub(*fetched) < ttl
+    {
+        return Ok(Live { rates: rates.clone(), warning: None });
+    }
+    let fetched = fetch.get(url).map_err(|e| e.to_string()).and_then(|body| parse(&body, url));
+    match (fetched, cached) {
+        (Ok((rates, payload)), _) => {
+            let warning = cache_dir.and_then(|dir| {
+                let path = dir.join(CACHE_FILE);
+                write_cache(&path, url, now, payload).err().map(|e| format!("can't cache the rates in {}: {}", path.display(), e))
+            });
+            Ok(Live { rates, warning })
+        }
+        (Err(e), Some((_, rates))) => {
+            let warning = format!("{}; using the cached rates dated {}", e, rates.date);
+            Ok(Live { rates, warning: Some(warning) })
+        }
+        (Err(e), None) => Err(e),
+    }
+}
+
+/// A service's answer as rates, and the JSON to cache. Services report
+/// their own errors in a 200 answer with `"success": false`.
+fn parse(body: &str, url: &str) -> Result<(Rates, Json), String> {
+    let malformed = |e: String| format!("malformed rates from {}: {}", url, e);
+    let payload = json::parse(body).map_err(malformed)?;
+    if let Some(Json::Bool(false)) = payload.get("success") {
+        let reason = payload.get("error").map(|error| error.get("info").and_then(Json::as_str).map_or_else(|| error.to_string(), str::to_string));
+        return Err(format!("the rate service refused: {}", reason.unwrap_or_else(|| "no reason given".to_string())));
+    }
+    let mut rates = Rates::from_json(&payload).map_err(malformed)?;
+    rates.path = url.to_string();
+    Ok((rates, payload))
+}
+
+/// When the cached rates were fetched, and the rates, if they are from `url`.
+fn read_cache(path: &Path, url: &str) -> Option<(u64, Rates)> {
+    let cache = json::parse(&fs::read_to_string(path).ok()?).ok()?;
+    let fetched = cache.get("fetched")?.as_f64()? as u64;
+    if cache.get("url")?.as_str()? != url {
+        return None;
+    }
+    let (rates, _) = parse(&cache.get("payload")?.to_string(), url).ok()?;
+    Some((fetched, rates))
+}
+
+fn write_cache(path: &Path, url: &str, now: u64, payload: Json) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let cache = Json::object(vec![("url", Json::from(url)), ("fetched", Json::from(now as f64)), ("payload", payload)]);
+    fs::write(path, format!("{}\n", cache))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const URL: &str = "https://rates.example/latest";
+    const BODY: &str = r#"{"success": true, "base": "USD", "date": "2024-05-01", "rates": {"EUR": 0.9342, "GBP": 0.7991}}"#;
+    const DAY: u64 = 86_400;
+    const NOW: u64 = 1_714_600_000;
+
+    /// Answers every fetch with `answer`, counting the fetches.
+    struct Fake {
+        answer: Result<String, FetchError>,
+        fetches: usize,
+    }
+
+    impl Fake {
+        fn answering(answer: Result<&str, FetchError>) -> Fake {
+            Fake { answer: answer.map(str::to_string), fetches: 0 }
+        }
+    }
+
+    impl Fetch for Fake {
+        fn get(&mut self, url: &str) -> Result<String, FetchError> {
+            assert_eq!(url, URL);
+            self.fetches += 1;
+            self.answer.clone()
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("converter-live-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_fetches_and_caches() {
+        let dir = temp_dir("fetch");
+        let mut fake = Fake::answering(Ok(BODY));
+        let live = rates(&mut fake, URL, Some(&dir), DAY, NOW).unwrap();
+        assert_eq!(live.warning, None);
+        assert_eq!((live.rates.base.as_str(), live.rates.date.as_str(), live.rates.path.as_str()), ("USD", "2024-05-01", URL));
+        assert_eq!(live.rates.rates, [("USD".to_string(), 1.0), ("EUR".to_string(), 0.9342), ("GBP".to_string(), 0.7991)]);
+        // An hour later the cache answers.
+        let again = rates(&mut fake, URL, Some(&dir), DAY, NOW + 3600).unwrap();
+        assert_eq!((again, fake.fetches), (live, 1));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_expired_cache_is_refetched() {
+        let dir = temp_dir("expiry");
+        rates(&mut Fake::answering(Ok(BODY)), URL, Some(&dir), DAY, NOW).unwrap();
+        let newer = BODY.replace("2024-05-01", "2024-05-02");
+        let mut fake = Fake::answering(Ok(&newer));
+        assert_eq!(rates(&mut fake, URL, Some(&dir), DAY, NOW + DAY - 1).unwrap().rates.date, "2024-05-01");
+        assert_eq!(rates(&mut fake, URL, Some(&dir), DAY, NOW + DAY).unwrap().rates.date, "2024-05-02");
+        assert_eq!(fake.fetches, 1);
+        // A cache of another service doesn't count.
+        let mut other = Fake::answering(Ok(BODY));
+        fs::write(dir.join(CACHE_FILE), fs::read_to_string(dir.join(CACHE_FILE)).unwrap().replace(URL, "https://other.example/")).unwrap();
+        rates(&mut other, URL, Some(&dir), DAY, NOW + DAY).unwrap();
+        assert_eq!(other.fetches, 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_falls_back_to_the_cache() {
+        let dir = temp_dir("fallback");
+        rates(&mut Fake::answering(Ok(BODY)), URL, Some(&dir), DAY, NOW).unwrap();
+        let mut offline = Fake::answering(Err(FetchError::Unreachable("Could not resolve host: rates.example".to_string())));
+        let live = rates(&mut offline, URL, Some(&dir), DAY, NOW + 30 * DAY).unwrap();
+        assert_eq!(live.rates.date, "2024-05-01");
+        assert_eq!(
+            live.warning.as_deref(),
+            Some("can't reach the rate service: Could not resolve host: rates.example; using the cached rates dated 2024-05-01")
+        );
+        let error = rates(&mut Fake::answering(Err(FetchError::Status(503))), URL, Some(&dir), DAY, NOW + 30 * DAY).unwrap();
+        assert_eq!(error.warning.as_deref(), Some("the rate service answered with HTTP status 503; using the cached rates dated 2024-05-01"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_errors_without_a_cache() {
+        let dir = temp_dir("errors");
+        let error = |answer| rates(&mut Fake::answering(answer), URL, Some(&dir), DAY, NOW).unwrap_err();
+        assert_eq!(error(Err(FetchError::Status(404))), "the rate service answered with HTTP status 404");
+        assert!(error(Ok("<html>busy</html>")).starts_with("malformed rates from https://rates.example/latest: "));
+        assert_eq!(error(Ok(r#"{"base": "USD", "rates": {}}"#)), "malformed rates from https://rates.example/latest: there is no 'date', so there's no telling how old the rates are");
+        assert_eq!(error(Ok(r#"{"success": false, "error": {"code": 101, "info": "You have not supplied an API Access Key."}}"#)), "the rate service refused: You have not supplied an API Access Key.");
+        assert!(!dir.exists());
+        let error = rates(&mut Unavailable("offline".to_string()), URL, None, DAY, NOW).unwrap_err();
+        assert_eq!(error, "can't reach the rate service: offline");
+    }
+}
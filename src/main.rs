@@ -1,365 +1,2044 @@
+mod batch;
+mod check;
+mod clipboard;
+mod compare;
+mod config;
+mod csv;
+mod describe;
+mod doctor;
+mod editor;
+mod epoch;
+mod factor;
+mod history;
+#[cfg(feature = "http")]
+mod http;
+mod progress;
+mod paper;
+mod parallel;
+mod protocol;
+mod radix;
+mod live;
+mod rates;
+mod repl;
+mod roman;
+mod shoe;
+mod sum;
+mod toml;
+mod tzif;
+#[cfg(feature = "tui")]
+mod tui;
+mod wizard;
+
+use std::io::IsTerminal;
+use std::path::Path;
 use std::process;
+use std::time::SystemTime;
+
+use converter::rational::Ratio;
+use converter::{convert_checked, convert_delta, convert_exact, convert_recorded, convert_units, convert_via, find_unit, lookup_unit, slope, unit_error};
+use converter::{compound, duration, gauge, i18n, json, pace, registry};
+use converter::{Conversion, ConversionRecord, ConvertError, Step, Unit, UnitCategory, Warning};
+use config::{Config, Favorite, Startup};
+use registry::{Calendar, Registry, UnitSystem};
+use history::HistoryEntry;
+use i18n::{tr, tr_with, Lang};
+use json::Json;
+use rates::Rates;
+
+const DEFAULT_HISTORY_LIMIT: usize = 20;
+/// `--big` places when no `--precision` is given, and the most it accepts.
+#[cfg(feature = "bigdecimal")]
+const BIG_DEFAULT_PLACES: usize = 20;
+const BIG_MAX_PLACES: usize = 50;
+const FRACTION_DENOMINATORS: [u32; 6] = [2, 4, 8, 16, 32, 64];
+const DEFAULT_FRACTION_DENOMINATOR: u32 = 16;
+
+/// `--mixed`: the conventional sub-unit each major unit's remainder is given in.
+const MIXED_UNITS: &[(&str, &str)] = &[("lb", "oz"), ("ft", "in"), ("st", "lb"), ("yd", "ft")];
+/// Decimal places of the minor part when no `--precision` is given.
+const MIXED_DEFAULT_PLACES: usize = 2;
+/// `--dms`: decimal places of the seconds when no `--precision` is given.
+#[cfg(feature = "angle")]
+const DMS_DEFAULT_PLACES: usize = 2;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum UnitCategory {
-    Length,
-    Temperature,
-    Mass,
-}
-
-#[derive(Debug)]
-struct Unit {
-    name: &'static str,
-    aliases: &'static [&'static str],
-    category: UnitCategory,
-    to_base: fn(f64) -> f64,
-    from_base: fn(f64) -> f64,
-}
-
-impl Unit {
-    fn matches(&self, input: &str) -> bool {
-        self.name.eq_ignore_ascii_case(input) || 
-        self.aliases.iter().any(|a| a.eq_ignore_ascii_case(input))
-    }
-}
-
-const UNITS: &[Unit] = &[
-    Unit { 
-        name: "km", 
-        aliases: &["kilometer", "kilometers", "kilometre", "kilometres"],
-        category: UnitCategory::Length,
-        to_base: |v| v * 1000.0,
-        from_base: |v| v / 1000.0,
-    },
-    Unit { 
-        name: "m", 
-        aliases: &["meter", "meters", "metre", "metres"],
-        category: UnitCategory::Length,
-        to_base: |v| v,
-        from_base: |v| v,
-    },
-    Unit { 
-        name: "cm", 
-        aliases: &["centimeter", "centimeters", "centimetre", "centimetres"],
-        category: UnitCategory::Length,
-        to_base: |v| v * 0.01,
-        from_base: |v| v / 0.01,
-    },
-    Unit { 
-        name: "mm", 
-        aliases: &["millimeter", "millimeters", "millimetre", "millimetres"],
-        category: UnitCategory::Length,
-        to_base: |v| v * 0.001,
-        from_base: |v| v / 0.001,
-    },
-    Unit { 
-        name: "mi", 
-        aliases: &["mile", "miles"],
-        category: UnitCategory::Length,
-        to_base: |v| v * 1609.344,
-        from_base: |v| v / 1609.344,
-    },
-    Unit { 
-        name: "yd", 
-        aliases: &["yard", "yards"],
-        category: UnitCategory::Length,
-        to_base: |v| v * 0.9144,
-        from_base: |v| v / 0.9144,
-    },
-    Unit { 
-        name: "ft", 
-        aliases: &["foot", "feet"],
-        category: UnitCategory::Length,
-        to_base: |v| v * 0.3048,
-        from_base: |v| v / 0.3048,
-    },
-    Unit { 
-        name: "in", 
-        aliases: &["inch", "inches"],
-        category: UnitCategory::Length,
-        to_base: |v| v * 0.0254,
-        from_base: |v| v / 0.0254,
-    },
-    Unit { 
-        name: "C", 
-        aliases: &["celsius", "centigrade"],
-        category: UnitCategory::Temperature,
-        to_base: |v| v,
-        from_base: |v| v,
-    },
-    Unit { 
-        name: "F", 
-        aliases: &["fahrenheit"],
-        category: UnitCategory::Temperature,
-        to_base: |v| (v - 32.0) * 5.0 / 9.0,
-        from_base: |v| v * 9.0 / 5.0 + 32.0,
-    },
-    Unit { 
-        name: "K", 
-        aliases: &["kelvin"],
-        category: UnitCategory::Temperature,
-        to_base: |v| v - 273.15,
-        from_base: |v| v + 273.15,
-    },
-    Unit { 
-        name: "kg", 
-        aliases: &["kilogram", "kilograms"],
-        category: UnitCategory::Mass,
-        to_base: |v| v,
-        from_base: |v| v,
-    },
-    Unit { 
-        name: "g", 
-        aliases: &["gram", "grams"],
-        category: UnitCategory::Mass,
-        to_base: |v| v * 0.001,
-        from_base: |v| v / 0.001,
-    },
-    Unit { 
-        name: "mg", 
-        aliases: &["milligram", "milligrams"],
-        category: UnitCategory::Mass,
-        to_base: |v| v * 0.000001,
-        from_base: |v| v / 0.000001,
-    },
-    Unit { 
-        name: "lb", 
-        aliases: &["pound", "pounds"],
-        category: UnitCategory::Mass,
-        to_base: |v| v * 0.45359237,
-        from_base: |v| v / 0.45359237,
-    },
-    Unit { 
-        name: "oz", 
-        aliases: &["ounce", "ounces"],
-        category: UnitCategory::Mass,
-        to_base: |v| v * 0.028349523125,
-        from_base: |v| v / 0.028349523125,
-    },
-    Unit { 
-        name: "ton", 
-        aliases: &["tons", "tonne", "tonnes", "metric ton"],
-        category: UnitCategory::Mass,
-        to_base: |v| v * 1000.0,
-        from_base: |v| v / 1000.0,
-    },
+/// (imperial, metric) counterparts for `converter 5 mi`. A unit converts to the
+/// first pair it appears in, so `m` goes to `ft` and `in` to `cm`.
+const COUNTERPARTS: &[(&str, &str)] = &[
+    ("mi", "km"),
+    ("ft", "m"),
+    ("yd", "m"),
+    ("in", "cm"),
+    ("in", "mm"),
+    ("lb", "kg"),
+    ("oz", "g"),
+    ("st", "kg"),
+    ("F", "C"),
+    ("F", "K"),
 ];
 
+/// The program's name when the way it was invoked doesn't give one.
+const PROGRAM: &str = "converter";
+
+const SUBCOMMANDS: &[&str] = &["history", "fav", "repl", "csv", "serve", "approx", "compare", "sum", "factor", "shoe", "paper", "base", "roman", "time", "doctor", "check", "search", "info", "tui"];
+
+const FAV_COMMANDS: &[&str] = &["add", "list", "remove"];
+
+#[derive(Debug, Clone, Default)]
+struct Options {
+    log_history: bool,
+    json: bool,
+    precision: Option<usize>,
+    format: Option<String>,
+    csv: bool,
+    batch: Option<String>,
+    skip_errors: bool,
+    filter: Option<(String, String)>,
+    passthrough: bool,
+    serve_stdio: bool,
+    copy: bool,
+    paste: bool,
+    names: bool,
+    echo_input: bool,
+    detailed: bool,
+    markdown: bool,
+    explain: bool,
+    humanize: bool,
+    max_components: Option<usize>,
+    fraction: Option<u32>,
+    mixed: bool,
+    dms: bool,
+    no_progress: bool,
+    jobs: Option<usize>,
+    delta: bool,
+    /// `--via`: a quantity such as "1g/mL" connecting two categories.
+    via: Option<String>,
+    /// `--rates`: the currency rates file, in place of the config's `rates`.
+    rates: Option<String>,
+    /// `--live`: currency rates from the rate service.
+    live: bool,
+    /// `--system`: in place of the config's `system`.
+    system: Option<UnitSystem>,
+    /// `--calendar`: in place of the config's `calendar`.
+    calendar: Option<Calendar>,
+    exact: bool,
+    big: bool,
+    strict: bool,
+    trace: bool,
+    lang: Option<Lang>,
+    /// `--` was given: everything after it is positional, even `-h`.
+    end_of_options: bool,
+}
+
+impl Options {
+    /// Applies a favorite's pinned settings unless overridden on the command line.
+    fn with_favorite(&self, favorite: &Favorite) -> Options {
+        Options {
+            precision: self.precision.or(favorite.precision),
+            format: self.format.clone().or_else(|| favorite.format.clone()),
+            ..self.clone()
+        }
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<(Options, Vec<String>), String> {
+    let mut options = Options::default();
+    let mut positional = Vec::new();
+    let mut iter = args.iter().peekable();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            arg if is_negative_value(arg) => positional.push(arg.to_string()),
+            "--" => {
+                options.end_of_options = true;
+                positional.extend(iter.by_ref().cloned());
+            }
+            "--log-history" => options.log_history = true,
+            "--json" => options.json = true,
+            "--precision" | "-p" => {
+                let value = iter.next().ok_or(tr("args.precision_required"))?;
+                let precision = value
+                    .parse()
+                    .map_err(|_| tr_with("args.invalid_precision", &[value]))?;
+                options.precision = Some(precision);
+            }
+            "--format" => {
+                let template = iter.next().ok_or(tr("args.format_required"))?;
+                options.format = Some(template.clone());
+            }
+            "--csv" => options.csv = true,
+            "--batch" => {
+                let path = iter.next().ok_or(tr("args.batch_required"))?;
+                options.batch = Some(path.clone());
+            }
+            "--skip-errors" => options.skip_errors = true,
+            "--filter" => {
+                let (Some(from), Some(to)) = (iter.next(), iter.next()) else {
+                    return Err(tr("args.filter_required").to_string());
+                };
+                options.filter = Some((from.clone(), to.clone()));
+            }
+            "--passthrough" => options.passthrough = true,
+            "--serve-stdio" => options.serve_stdio = true,
+            "--copy" => options.copy = true,
+            "--paste" => options.paste = true,
+            "--names" => options.names = true,
+            "--echo-input" => options.echo_input = true,
+            "--detailed" => options.detailed = true,
+            "--markdown" => options.markdown = true,
+            "--explain" => options.explain = true,
+            "--humanize" => options.humanize = true,
+            "--delta" => options.delta = true,
+            "--via" => {
+                let quantity = iter.next().ok_or(tr("args.via_required"))?;
+                options.via = Some(quantity.clone());
+            }
+            "--system" => {
+                let name = iter.next().ok_or(tr("args.system_required"))?;
+                let system = UnitSystem::parse(name).ok_or_else(|| tr_with("args.unknown_system", &[name]))?;
+                options.system = Some(system);
+            }
+            "--calendar" => {
+                let name = iter.next().ok_or(tr("args.calendar_required"))?;
+                let calendar = Calendar::parse(name).ok_or_else(|| tr_with("args.unknown_calendar", &[name]))?;
+                options.calendar = Some(calendar);
+            }
+            "--rates" => {
+                let path = iter.next().ok_or(tr("args.rates_required"))?;
+                options.rates = Some(path.clone());
+            }
+            "--live" => options.live = true,
+            "--exact" => options.exact = true,
+            "--big" => options.big = true,
+            "--strict" => options.strict = true,
+            "-vv" => options.trace = true,
+            "--mixed" => options.mixed = true,
+            "--dms" => options.dms = true,
+            "--no-progress" => options.no_progress = true,
+            "--lang" => {
+                let value = iter.next().ok_or(tr("args.lang_required"))?;
+                let lang = Lang::parse(value).ok_or_else(|| tr_with("args.unsupported_lang", &[value]))?;
+                options.lang = Some(lang);
+            }
+            "--jobs" | "-j" => {
+                let value = iter.next().ok_or(tr("args.jobs_required"))?;
+                let jobs = value.parse().ok().filter(|&n: &usize| n > 0);
+                options.jobs = Some(jobs.ok_or_else(|| tr_with("args.invalid_jobs", &[value]))?);
+            }
+            "--max-components" => {
+                let value = iter.next().ok_or(tr("args.max_components_required"))?;
+                let max = value
+                    .parse()
+                    .map_err(|_| tr_with("args.invalid_max_components", &[value]))?;
+                options.max_components = Some(max);
+            }
+            "--fraction" => {
+                // The denominator is optional, so it is only taken when nothing
+                // but other options follow it.
+                let mut denominator = DEFAULT_FRACTION_DENOMINATOR;
+                if let Some(next) = iter.peek()
+                    && next.parse::<u32>().is_ok()
+                    && iter.clone().nth(1).is_none_or(|after| after.starts_with("--"))
+                {
+                    let next = iter.next().expect("peeked");
+                    denominator = next.parse().expect("checked");
+                    if !FRACTION_DENOMINATORS.contains(&denominator) {
+                        return Err(tr_with("args.bad_denominator", &[next]));
+                    }
+                }
+                options.fraction = Some(denominator);
+            }
+            _ => positional.push(arg.clone()),
+        }
+    }
+    Ok((options, positional))
+}
+
+/// Whether `arg` is a negative value rather than an option: a minus sign
+/// followed by a digit or a decimal point, as in "-40", "-.5", "-1e3" or
+/// the range "-5--1".
+fn is_negative_value(arg: &str) -> bool {
+    arg.strip_prefix('-').and_then(|rest| rest.chars().next()).is_some_and(|c| c.is_ascii_digit() || c == '.')
+}
+
+/// The name to show for the program: how it was invoked, without the
+/// directory or extension, so "/usr/local/bin/converter" and
+/// "converter.exe" are both "converter".
+fn program_name(invoked: Option<&str>) -> String {
+    invoked.and_then(|path| Path::new(path).file_stem()).and_then(|stem| stem.to_str()).unwrap_or(PROGRAM).to_string()
+}
+
+/// The usage line and the hint about `--help` printed after a bad command line.
+fn usage_text(program: &str) -> String {
+    format!("{}\n{}\n", tr_with("usage", &[&program]), tr_with("try_help", &[&program]))
+}
+
 fn main() {
-    let args: Vec<String> = std::env::args().collect();
+    let raw_args: Vec<String> = std::env::args().collect();
+    
+    let parsed = parse_args(&raw_args);
+    i18n::install(parsed.as_ref().ok().and_then(|(options, _)| options.lang).unwrap_or_else(Lang::from_env));
+    let (options, mut args) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{}: {}", tr("error"), e);
+            process::exit(1);
+        }
+    };
+    // Every usage line and hint below names the program as `args[0]`.
+    let program = program_name(args.first().map(String::as_str));
+    match args.first_mut() {
+        Some(first) => *first = program,
+        None => args.push(program),
+    }
+    if options.trace && !cfg!(feature = "tracing") {
+        eprintln!("{}: {}", tr("error"), tr("trace_needs_feature"));
+        process::exit(1);
+    }
+    #[cfg(feature = "tracing")]
+    if options.trace {
+        converter::trace::set_global(std::sync::Arc::new(converter::trace::Stderr));
+    }
+    let mut config = Config::load();
+    if options.system.is_some() {
+        config.system = options.system;
+    }
+    if let Some(calendar) = options.calendar {
+        config.calendar = calendar;
+    }
+    match load_rates(&options, &config) {
+        Ok(Some(rates)) => {
+            let added = config.add_units(rates.units()).map_err(|e| tr_with("rates_from", &[&rates.path, &e]));
+            if let Err(e) = added {
+                eprintln!("{}: {}", tr("error"), e);
+                process::exit(1);
+            }
+            rates::install(rates);
+        }
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("{}: {}", tr("error"), e);
+            process::exit(1);
+        }
+    }
+    registry::install(config.registry());
+    
+    if options.big {
+        if !cfg!(feature = "bigdecimal") {
+            eprintln!("{}: {}", tr("error"), tr("big_needs_backend"));
+            process::exit(1);
+        }
+        if options.precision.is_some_and(|p| p > BIG_MAX_PLACES) {
+            eprintln!("{}: {}", tr("error"), tr_with("big_max_places", &[&BIG_MAX_PLACES]));
+            process::exit(1);
+        }
+    }
+    
+    if options.serve_stdio {
+        let stdout = std::io::stdout();
+        if let Err(e) = protocol::serve(std::io::stdin().lock(), &mut stdout.lock()) {
+            eprintln!("{}: {}", tr("error"), e);
+            process::exit(1);
+        }
+        return;
+    }
+    
+    if let Some(path) = &options.batch {
+        process::exit(batch::run(path, &options));
+    }
+    
+    if let Some((from, to)) = &options.filter {
+        process::exit(batch::run_filter(&args[0], from, to, &options));
+    }
+    
+    match args.get(1).map(String::as_str) {
+        Some("history") => { run_history(&args[0], &args[2..]); return; }
+        Some("fav") => { run_fav(&args[0], &args[2..], &options, &config); return; }
+        Some("repl") => { repl::run(&options, &config); return; }
+        Some("csv") => process::exit(csv::run(&args[0], &args[2..], &options)),
+        Some("approx") => process::exit(compare::run_approx(&args[0], &args[2..], &options)),
+        Some("compare") => process::exit(compare::run_compare(&args[0], &args[2..], &options)),
+        Some("sum") => process::exit(sum::run(&args[0], &args[2..], &options)),
+        Some("factor") => process::exit(factor::run(&args[0], &args[2..], &options)),
+        Some("shoe") => process::exit(shoe::run(&args[0], &args[2..], &options)),
+        Some("paper") => process::exit(paper::run(&args[0], &args[2..], &options)),
+        Some("base") => process::exit(radix::run(&args[0], &args[2..])),
+        Some("roman") => process::exit(roman::run(&args[0], &args[2..])),
+        Some("time") => process::exit(epoch::run(&args[0], &args[2..])),
+        Some("doctor") => process::exit(doctor::run(&args[0], &args[2..])),
+        Some("check") => process::exit(check::run(&args[0], &args[2..])),
+        Some("search") => process::exit(run_search(&args[0], &args[2..])),
+        Some("info") => process::exit(describe::run(&args[0], &args[2..], &options)),
+        #[cfg(feature = "tui")]
+        Some("tui") => {
+            if let Err(e) = tui::run(&options) {
+                eprintln!("{}: {}", tr("error"), e);
+                process::exit(1);
+            }
+            return;
+        }
+        #[cfg(not(feature = "tui"))]
+        Some("tui") => {
+            eprintln!("{}: {}", tr("error"), tr("no_tui"));
+            process::exit(1);
+        }
+        #[cfg(feature = "http")]
+        Some("serve") => process::exit(http::run(&args[0], &args[2..])),
+        #[cfg(not(feature = "http"))]
+        Some("serve") => {
+            eprintln!("{}: {}", tr("error"), tr("no_http"));
+            process::exit(1);
+        }
+        None if std::io::stdin().is_terminal() => match config.startup {
+            Startup::Wizard => {
+                if let Err(e) = wizard::run(&mut std::io::stdin().lock(), &mut std::io::stdout(), &options) {
+                    eprintln!("{}: {}", tr("error"), e);
+                    process::exit(1);
+                }
+                return;
+            }
+            Startup::Repl => { repl::run(&options, &config); return; }
+            Startup::Error => {}
+        },
+        _ => {}
+    }
     
-    if args.len() == 2 {
+    if args.len() == 2 && !options.end_of_options {
         match args[1].as_str() {
             "--help" | "-h" => { print_help(&args[0]); return; }
             "--version" | "-v" => { println!("Unit Converter v3.0.0"); return; }
-            "--list" | "-l" => { print_units(); return; }
+            "--list" | "-l" => { print_units(None, &options); return; }
             _ => {}
         }
     }
     
-    if args.len() != 4 {
-        eprintln!("Error: Expected 3 arguments, got {}", args.len() - 1);
-        eprintln!("Usage: {} <value> <from_unit> <to_unit>", args[0]);
-        eprintln!("Try '{} --help' for more information", args[0]);
+    if let [_, flag, rest @ ..] = args.as_slice() && !options.end_of_options && flag == "--examples" && rest.len() <= 1 {
+        match rest.first().map(|category| category.parse()).transpose() {
+            Ok(category) => print_examples(&args[0], category),
+            Err(e) => {
+                eprintln!("{}: {}", tr("error"), e);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+    
+    if let [_, list, flag, category] = args.as_slice() && !options.end_of_options && matches!(list.as_str(), "--list" | "-l") && flag == "--category" {
+        match category.parse() {
+            Ok(category) => print_units(Some(category), &options),
+            Err(e) => {
+                eprintln!("{}: {}", tr("error"), e);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+    
+    if options.paste && args.len() == 3 {
+        let value = match clipboard::paste(clipboard::system().as_mut()) {
+            Ok(value) => value,
+            Err(e) => {
+                eprintln!("{}: {}", tr("warning"), tr_with("clipboard_unreadable", &[&e]));
+                eprintln!("{}: {}", tr("error"), tr("no_value"));
+                process::exit(1);
+            }
+        };
+        run_conversion(&args[0], &value, &args[1], &args[2], &options, &config);
+        return;
+    }
+    
+    let args = join_unit_words(args);
+    if args.len() != 3 && args.len() != 4 {
+        eprintln!("{}: {}", tr("error"), tr_with("expected_arguments", &[&(args.len() - 1)]));
+        eprint!("{}", usage_text(&args[0]));
         process::exit(1);
     }
     
-    let value: f64 = match args[1].parse() {
-        Ok(v) => v,
-        Err(_) => {
-            eprintln!("Error: '{}' is not a valid number", args[1]);
+    let to_unit = target_unit(&args[1..], &config).unwrap_or_else(|e| {
+        eprintln!("{}: {}", tr("error"), e);
+        process::exit(1);
+    });
+    run_conversion(&args[0], &args[1], &args[2], to_unit, &options, &config);
+}
+
+/// Puts back together units the shell split at their spaces: `5 metric
+/// ton kg` becomes `5`, `metric ton`, `kg`. The first split of the words
+/// after the value where both sides name a unit wins; arguments that have
+/// no such split are left for the usual error.
+fn join_unit_words(args: Vec<String>) -> Vec<String> {
+    if args.len() <= 4 {
+        return args;
+    }
+    let words = &args[2..];
+    let split = (1..words.len()).map(|at| (words[..at].join(" "), words[at..].join(" "))).find(|(from, to)| names_unit(from) && names_unit(to));
+    match split {
+        Some((from, to)) => vec![args[0].clone(), args[1].clone(), from, to],
+        None => args,
+    }
+}
+
+/// Whether `word` names a unit, or would with a `--system`.
+fn names_unit(word: &str) -> bool {
+    lookup_unit(word).is_some() || converter::us_imperial(word).is_some()
+}
+
+/// The target of `<value> <from> [<to>]`: the one given, or the configured
+/// system's counterpart of `from`.
+fn target_unit<'a>(args: &'a [String], config: &Config) -> Result<&'a str, String> {
+    if let [_, _, to] = args {
+        return Ok(to);
+    }
+    let Some(system) = config.system else {
+        let path = config::config_path().map_or(tr("the_config_file").to_string(), |p| p.display().to_string());
+        return Err(tr_with("no_target_unit", &[&path]));
+    };
+    counterpart(&args[1], system)
+}
+
+fn counterpart(unit: &str, system: UnitSystem) -> Result<&'static str, String> {
+    let found = find_unit(unit).ok_or_else(|| unit_error(unit).to_string())?;
+    COUNTERPARTS
+        .iter()
+        .find_map(|&(imperial, metric)| match system {
+            UnitSystem::Metric if imperial == found.name => Some(metric),
+            UnitSystem::Imperial | UnitSystem::Us if metric == found.name => Some(imperial),
+            _ => None,
+        })
+        .ok_or_else(|| tr_with("no_counterpart", &[&unit, &system.name()]))
+}
+
+/// The currency rates for this run: `--rates`, else the rate service when
+/// `--live` or the config's `live_rates` asks for it, else the config's
+/// `rates` file. Only the rate service touches the network.
+fn load_rates(options: &Options, config: &Config) -> Result<Option<Rates>, String> {
+    if options.live && options.rates.is_some() {
+        return Err(tr("live_and_rates").to_string());
+    }
+    if let Some(path) = &options.rates {
+        return Rates::load(path).map(Some);
+    }
+    if options.live || config.live_rates {
+        let url = config.rates_url.as_deref().unwrap_or(live::DEFAULT_URL);
+        let ttl = config.rates_ttl_hours.unwrap_or(live::DEFAULT_TTL_HOURS) * 3600.0;
+        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+        let fetched = live::rates(live::system().as_mut(), url, config::state_dir().as_deref(), ttl as u64, now)?;
+        if let Some(warning) = fetched.warning {
+            eprintln!("{}: {}", tr("warning"), warning);
+        }
+        return Ok(Some(fetched.rates));
+    }
+    config.rates.as_deref().map(Rates::load).transpose()
+}
+
+/// Splits "10..20", "10-20" or "-5--1" into its endpoints. A dash only separates
+/// when it follows a digit or '.', so "-5", "1e-3" and "5±-1" stay single values.
+fn parse_range(input: &str) -> Option<(f64, f64)> {
+    if let Some((lo, hi)) = input.split_once("..") {
+        return Some((lo.trim().parse().ok()?, hi.trim().parse().ok()?));
+    }
+    input.char_indices().filter(|&(i, c)| c == '-' && i > 0).find_map(|(i, _)| {
+        let (lo, hi) = (&input[..i], &input[i + 1..]);
+        if !lo.ends_with(|c: char| c.is_ascii_digit() || c == '.') {
+            return None;
+        }
+        Some((lo.parse().ok()?, hi.parse().ok()?))
+    })
+}
+
+/// Converts both endpoints and returns them low to high, so a decreasing
+/// conversion still yields an ordered range.
+fn convert_range(lo: f64, hi: f64, from_unit: &str, to_unit: &str) -> Result<((f64, f64), Vec<Warning>), ConvertError> {
+    let a = convert_checked(&lo.to_string(), from_unit, to_unit)?;
+    let b = convert_checked(&hi.to_string(), from_unit, to_unit)?;
+    let mut warnings = a.warnings;
+    for warning in b.warnings {
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    }
+    Ok(((a.result.min(b.result), a.result.max(b.result)), warnings))
+}
+
+fn format_range(from_unit: &str, to_unit: &str, lo: f64, hi: f64, options: &Options) -> String {
+    if options.json {
+        let round = |n: f64| format_number(n, options).parse().unwrap_or(n);
+        return Json::object(vec![
+            ("from", Json::from(from_unit)),
+            ("to", Json::from(to_unit)),
+            ("lo", Json::from(round(lo))),
+            ("hi", Json::from(round(hi))),
+        ])
+        .to_string();
+    }
+    let (lo, hi) = (format_number(lo, options), format_number(hi, options));
+    tr_with("range_to", &[&lo, &unit_label(to_unit, &lo, options), &hi, &unit_label(to_unit, &hi, options)])
+}
+
+/// Prints a conversion error, pointing at `--list` when the unit is unknown
+/// and nothing close to it exists, and returns the exit code for it. With
+/// `--json` the error is one JSON object instead, from `protocol::error_json`.
+fn report_error(program: &str, e: &ConvertError, options: &Options) -> i32 {
+    if options.json {
+        eprintln!("{}", protocol::error_json(e));
+        return 1;
+    }
+    eprintln!("{}: {}", tr("error"), e.localized(i18n::active()));
+    if let ConvertError::UnknownUnit { suggestions, .. } = e && suggestions.is_empty() {
+        eprintln!("{}", tr_with("try_list", &[&program]));
+    }
+    1
+}
+
+fn exit_with_error(program: &str, e: &ConvertError, options: &Options) -> ! {
+    process::exit(report_error(program, e, options));
+}
+
+/// Prints `warnings`; under `--strict` the first is an error instead.
+fn report_warnings(warnings: &[Warning], options: &Options) {
+    for warning in warnings {
+        if options.strict {
+            eprintln!("{}: {}", tr("error"), warning.localized(i18n::active()));
             process::exit(1);
         }
-    };
+        eprintln!("{}: {}", tr("warning"), warning.localized(i18n::active()));
+    }
+}
+
+fn run_conversion(program: &str, value_arg: &str, from_unit: &str, to_unit: &str, options: &Options, config: &Config) {
+    if let Some((lo, hi)) = parse_range(value_arg) {
+        let ((lo, hi), warnings) = convert_range(lo, hi, from_unit, to_unit).unwrap_or_else(|e| exit_with_error(program, &e, options));
+        report_warnings(&warnings, options);
+        println!("{}", format_range(from_unit, to_unit, lo, hi, options));
+        return;
+    }
     
-    let from_unit = &args[2];
-    let to_unit = &args[3];
+    if let Some(via) = &options.via {
+        let value: f64 = value_arg.parse().unwrap_or_else(|_| exit_with_error(program, &ConvertError::InvalidValue(value_arg.to_string()), options));
+        let result = convert_via(value, from_unit, to_unit, via).unwrap_or_else(|e| exit_with_error(program, &e, options));
+        let category = lookup_unit(to_unit).expect("converted").category;
+        let record = ConversionRecord { input: value, from: from_unit.to_string(), to: to_unit.to_string(), result, category, factor: None, warnings: Vec::new() };
+        println!("{}", format_record(&record, options));
+        return;
+    }
+    
+    // A gauge isn't a unit and a pace is no factor from a speed, so --exact,
+    // --explain and the rest don't apply.
+    if gauge::is_awg(from_unit) || gauge::is_awg(to_unit) || pace::applies(from_unit, to_unit) {
+        let converted = convert_checked(value_arg, from_unit, to_unit).unwrap_or_else(|e| exit_with_error(program, &e, options));
+        if !options.json || options.strict {
+            report_warnings(&converted.warnings, options);
+        }
+        let record = converted.record(from_unit, to_unit).unwrap_or_else(|e| exit_with_error(program, &e, options));
+        println!("{}", format_record(&record, options));
+        return;
+    }
     
-    let from = find_unit(from_unit);
-    let to = find_unit(to_unit);
+    let convert = if options.delta { convert_delta } else { convert_checked };
+    let mut converted = convert(value_arg, from_unit, to_unit).unwrap_or_else(|e| exit_with_error(program, &e, options));
+    // Money is shown to the cent unless --precision says otherwise, rounded as banks round.
+    let rates = rates::active().filter(|_| lookup_unit(to_unit).is_some_and(|u| u.category == UnitCategory::Currency));
+    let currency_options;
+    let options = match rates {
+        Some(_) => {
+            let places = options.precision.unwrap_or(rates::PLACES);
+            converted.result = rates::round_half_even(converted.result, places);
+            currency_options = Options { precision: Some(places), ..options.clone() };
+            &currency_options
+        }
+        None => options,
+    };
+    // With --json the warnings are in the record instead.
+    if !options.json || options.strict {
+        report_warnings(&converted.warnings, options);
+    }
+    if options.fraction.is_some() && lookup_unit(to_unit).expect("checked").category == UnitCategory::Temperature {
+        eprintln!("{}: {}", tr("error"), tr("fraction_temperature"));
+        process::exit(1);
+    }
+    let fraction = options.fraction.map(|denominator| format_fraction(converted.result, denominator));
+    let mixed = options.mixed.then(|| {
+        format_mixed(converted.result, lookup_unit(to_unit).expect("checked"), options).unwrap_or_else(|e| {
+            eprintln!("{}: {}", tr("error"), e);
+            process::exit(1);
+        })
+    });
+    let dms = options.dms.then(|| {
+        format_dms(converted.result, lookup_unit(to_unit).expect("checked"), options).unwrap_or_else(|e| {
+            eprintln!("{}: {}", tr("error"), e);
+            process::exit(1);
+        })
+    });
+    let exact_ratio = || {
+        let (from, to) = (lookup_unit(from_unit).expect("checked"), lookup_unit(to_unit).expect("checked"));
+        let delta = options.delta && from.category == UnitCategory::Temperature;
+        let ratio = convert_exact(value_arg, from, to, delta);
+        if ratio.is_none() {
+            eprintln!("{}: {}", tr("note"), tr("no_exact_form"));
+        }
+        ratio
+    };
+    let exact = if options.exact { exact_ratio().map(format_exact) } else { None };
+    // `--big` prints the exact result to as many places as asked for.
+    #[cfg(feature = "bigdecimal")]
+    let big = if options.big { exact_ratio().map(|r| r.to_fixed(options.precision.unwrap_or(BIG_DEFAULT_PLACES))) } else { None };
+    #[cfg(not(feature = "bigdecimal"))]
+    let big: Option<String> = None;
+    let explanation = options.explain.then(|| {
+        let (from, to) = (lookup_unit(from_unit).expect("checked"), lookup_unit(to_unit).expect("checked"));
+        explain(from, to, converted.value, options)
+    });
+    if options.json {
+        let record = converted.record(from_unit, to_unit).unwrap_or_else(|e| exit_with_error(program, &e, options));
+        let mut json = result_json(&record, options);
+        if let Json::Object(fields) = &mut json {
+            if let Some(sigma) = converted.sigma {
+                fields.push(("uncertainty".to_string(), Json::from(sigma)));
+            }
+            if let Some(exact) = &exact {
+                fields.push(("exact".to_string(), Json::from(exact.as_str())));
+            }
+            if let Some(big) = &big {
+                fields.push(("decimal".to_string(), Json::from(big.as_str())));
+            }
+            if let Some((fraction, _)) = &fraction {
+                fields.push(("fraction".to_string(), Json::from(fraction.as_str())));
+            }
+            if let Some(mixed) = &mixed {
+                fields.push(("mixed".to_string(), Json::from(mixed.as_str())));
+            }
+            if let Some(dms) = &dms {
+                fields.push(("dms".to_string(), Json::from(dms.as_str())));
+            }
+            if let Some((_, steps)) = &explanation {
+                fields.push(("steps".to_string(), Json::Array(steps.iter().map(|s| Json::from(s.as_str())).collect())));
+            }
+            if let Some(rates) = rates {
+                fields.push(("rates_date".to_string(), Json::from(rates.date.as_str())));
+            }
+        }
+        println!("{}", json);
+    } else {
+        if let Some(exact) = &exact {
+            let value_text = converted.value.to_string();
+            println!(
+                "{}",
+                tr_with("exactly", &[&value_text, &unit_label(from_unit, &value_text, options), exact, &unit_label(to_unit, exact, options)])
+            );
+        } else if let Some(big) = &big {
+            let value_text = converted.value.to_string();
+            println!(
+                "{} {} = {} {}",
+                value_text,
+                unit_label(from_unit, &value_text, options),
+                big,
+                unit_label(to_unit, big, options)
+            );
+        } else if let Some((fraction, error)) = &fraction {
+            let value_text = converted.value.to_string();
+            let mut line = format!(
+                "{} {} = {} {}",
+                value_text,
+                unit_label(from_unit, &value_text, options),
+                fraction,
+                unit_label(to_unit, fraction, options)
+            );
+            // Snapping is off by at most half a step; mention it once it's over a quarter.
+            if error.abs() > 0.25 / options.fraction.expect("set") as f64 {
+                let key = if *error < 0.0 { "rounded_down" } else { "rounded_up" };
+                line += &tr_with(key, &[&format!("{:.*}", options.precision.unwrap_or(4), error.abs()), &to_unit]);
+            }
+            println!("{}", line);
+        } else if let Some(mixed) = &mixed {
+            let value_text = converted.value.to_string();
+            println!("{} {} = {}", value_text, unit_label(from_unit, &value_text, options), mixed);
+        } else if let Some(dms) = &dms {
+            let value_text = converted.value.to_string();
+            println!("{} {} = {}", value_text, unit_label(from_unit, &value_text, options), dms);
+        } else {
+            match converted.sigma {
+                Some(sigma) if options.format.is_none() => println!("{}", format_uncertain(converted.result, sigma, to_unit, options)),
+                _ => println!("{}", format_result(converted.value, from_unit, to_unit, converted.result, options)),
+            }
+        }
+        if let Some((formula, _)) = &explanation {
+            println!("{}", formula);
+        }
+    }
+    if options.copy
+        && let Some(warning) = clipboard::copy(clipboard::system().as_mut(), &format_number(converted.result, options))
+    {
+        eprintln!("{}: {}", tr("warning"), warning);
+    }
+    // "MB" and "Mb" differ by a factor of eight, so say which was read.
+    let symbols: Vec<String> = [from_unit, to_unit]
+        .into_iter()
+        .filter_map(converter::data_symbol)
+        .map(|(symbol, unit)| format!("{} = {}", symbol, unit.display_singular))
+        .collect();
+    if !symbols.is_empty() && !options.json {
+        eprintln!("{}: {}", tr("note"), tr_with("data_symbols", &[&symbols.join(", ")]));
+    }
+    if let Some(rates) = rates {
+        eprintln!("{}: {}", tr("note"), tr_with("rates_dated", &[&rates.path, &rates.date, &rates.age(rates::today())]));
+    }
     
-    match (from, to) {
-        (Some(f), Some(t)) => {
-            if f.category != t.category {
-                eprintln!("Error: Cannot convert between different unit categories");
-                eprintln!("  {} is a {:?} unit", from_unit, f.category);
-                eprintln!("  {} is a {:?} unit", to_unit, t.category);
+    if options.log_history || config.history {
+        let entry = HistoryEntry::now(converted.value, from_unit, to_unit, converted.result);
+        log_history(config::state_dir().as_deref(), &entry);
+    }
+}
+
+/// An exact decimal when there is one ("8.04672"), otherwise the fraction ("50/127").
+fn format_exact(ratio: Ratio) -> String {
+    ratio.to_decimal().unwrap_or_else(|| ratio.to_string())
+}
+
+/// The derivation of a conversion: the whole formula on one line, e.g.
+/// "(98.6 − 32) × 5/9 = 37", and each step with its intermediate result.
+fn explain(from: &Unit, to: &Unit, value: f64, options: &Options) -> (String, Vec<String>) {
+    let steps: Vec<Step> = from.to_base_steps().into_iter().chain(to.to_unit_steps()).filter(|s| !s.is_identity()).collect();
+    let formula = formula(value.to_string(), &steps);
+    let mut lines = Vec::new();
+    let mut x = value;
+    for (i, step) in steps.iter().enumerate() {
+        let next = step.apply(x);
+        let shown = if i + 1 == steps.len() { format_number(next, options) } else { next.to_string() };
+        lines.push(format!("{} {} = {}", x, step, shown));
+        x = next;
+    }
+    (format!("{} = {}", formula, format_number(x, options)), lines)
+}
+
+/// `start` with `steps` applied in writing: "(98.6 − 32) × 5/9". A sum is
+/// parenthesized before it is scaled.
+fn formula(start: String, steps: &[Step]) -> String {
+    let mut formula = start;
+    let mut open_sum = false;
+    for step in steps {
+        if let Step::Scale(..) = step
+            && open_sum
+        {
+            formula = format!("({})", formula);
+        }
+        open_sum = matches!(step, Step::Add(_));
+        formula = format!("{} {}", formula, step);
+    }
+    formula
+}
+
+/// `result` to `--precision` places, or by default to at most 15
+/// significant digits, the most a double always holds faithfully, and then
+/// as few as read back as the same number. Noise past those digits goes:
+/// 0.30000000000000004 prints as 0.3 and 434.99999999999994 as 435.
+fn format_number(result: f64, options: &Options) -> String {
+    match options.precision {
+        Some(p) => format!("{:.*}", p, result),
+        None => plain_or_scientific(significant(result)),
+    }
+}
+
+/// Magnitudes from which a number is written with an exponent, where as a
+/// plain decimal it would be mostly zeros: 1 c is 1.0792528488e15 mm/h.
+const SCIENTIFIC_FROM: f64 = 1e15;
+const SCIENTIFIC_BELOW: f64 = 1e-6;
+
+/// `x` as a plain decimal, or as "4.63e-9" when it is that large or small.
+fn plain_or_scientific(x: f64) -> String {
+    if x != 0.0 && x.is_finite() && !(SCIENTIFIC_BELOW..SCIENTIFIC_FROM).contains(&x.abs()) {
+        format!("{:e}", x)
+    } else {
+        x.to_string()
+    }
+}
+
+/// `x` rounded to `f64::DIGITS` significant digits, unless rounding up
+/// would take it past the largest double.
+fn significant(x: f64) -> f64 {
+    let rounded: f64 = format!("{:.*e}", f64::DIGITS as usize - 1, x).parse().unwrap_or(x);
+    if rounded.is_finite() { rounded } else { x }
+}
+
+/// `result` snapped to the nearest 1/`denominator` as a mixed number such as
+/// "3 9/16", with the rounding error (snapped minus exact).
+fn format_fraction(result: f64, denominator: u32) -> (String, f64) {
+    let denominator = denominator as u64;
+    let steps = (result.abs() * denominator as f64).round() as u64;
+    let snapped = (steps as f64 / denominator as f64).copysign(result);
+    let (whole, mut numerator, mut reduced) = (steps / denominator, steps % denominator, denominator);
+    while numerator != 0 && numerator % 2 == 0 {
+        numerator /= 2;
+        reduced /= 2;
+    }
+    let sign = if result < 0.0 && steps != 0 { "-" } else { "" };
+    let text = match (whole, numerator) {
+        (whole, 0) => format!("{}{}", sign, whole),
+        (0, numerator) => format!("{}{}/{}", sign, numerator, reduced),
+        (whole, numerator) => format!("{}{} {}/{}", sign, whole, numerator, reduced),
+    };
+    (text, snapped - result)
+}
+
+/// `result` in `to` split into whole major units and a remainder in the
+/// sub-unit, e.g. "8 lb 2.51 oz".
+fn format_mixed(result: f64, to: &Unit, options: &Options) -> Result<String, String> {
+    let Some(&(major, minor)) = MIXED_UNITS.iter().find(|(major, _)| *major == to.name) else {
+        let supported: Vec<&str> = MIXED_UNITS.iter().map(|(major, _)| *major).collect();
+        return Err(tr_with("mixed_no_subunit", &[&to.name, &supported.join(", ")]));
+    };
+    let minor_unit = converter::builtin_unit(minor).expect("mixed sub-units are built-in units");
+    let places = options.precision.unwrap_or(MIXED_DEFAULT_PLACES);
+    let round = |x: f64| (x * 10f64.powi(places as i32)).round() / 10f64.powi(places as i32);
+    let mut whole = result.abs().trunc();
+    let mut rest = round(convert_units(to, minor_unit, result.abs() - whole));
+    // A remainder that rounds to a full major unit carries over.
+    if rest >= round(convert_units(to, minor_unit, 1.0)) {
+        whole += 1.0;
+        rest = 0.0;
+    }
+    let mut rest_text = format!("{:.*}", places, rest);
+    if options.precision.is_none() && rest_text.contains('.') {
+        rest_text = rest_text.trim_end_matches('0').trim_end_matches('.').to_string();
+    }
+    let sign = if result < 0.0 { "-" } else { "" };
+    Ok(format!("{}{} {} {} {}", sign, whole, major, rest_text, minor))
+}
+
+/// `result` in `to` as degrees, minutes and seconds, e.g. "57°17′44.81″".
+#[cfg(feature = "angle")]
+fn format_dms(result: f64, to: &Unit, options: &Options) -> Result<String, String> {
+    if to.category != UnitCategory::Angle {
+        return Err(tr_with("dms_not_an_angle", &[&to.name]));
+    }
+    Ok(converter::dms::format(to.to_base(result), options.precision.unwrap_or(DMS_DEFAULT_PLACES)))
+}
+
+#[cfg(not(feature = "angle"))]
+fn format_dms(_result: f64, _to: &Unit, _options: &Options) -> Result<String, String> {
+    Err(tr("no_angle").to_string())
+}
+
+/// "3.11 ± 0.12 mi": the error is rounded to two significant figures and the
+/// value to the same decimal place, unless `--precision` says otherwise.
+fn format_uncertain(result: f64, sigma: f64, to_unit: &str, options: &Options) -> String {
+    let (result_text, sigma_text) = match options.precision {
+        Some(p) => (format!("{:.*}", p, result), format!("{:.*}", p, sigma)),
+        None if sigma == 0.0 => (significant(result).to_string(), "0".to_string()),
+        None => {
+            let place = 1 - sigma.log10().floor() as i32;
+            if place >= 0 {
+                let p = place as usize;
+                (format!("{:.*}", p, result), format!("{:.*}", p, sigma))
+            } else {
+                let step = 10f64.powi(-place);
+                (significant((result / step).round() * step).to_string(), significant((sigma / step).round() * step).to_string())
+            }
+        }
+    };
+    format!("{} ± {} {}", result_text, sigma_text, unit_label(to_unit, &result_text, options))
+}
+
+/// `record` as every structured output writes it, with its result rounded
+/// or humanized as `options` ask.
+fn result_json(record: &ConversionRecord, options: &Options) -> Json {
+    let result = match duration::humanized_result(record.result, &record.to, options.humanize, options.max_components) {
+        Some(text) => Json::from(text),
+        None => Json::from(format_number(record.result, options).parse().unwrap_or(record.result)),
+    };
+    let mut json = record.to_json();
+    if let Json::Object(fields) = &mut json
+        && let Some((_, slot)) = fields.iter_mut().find(|(key, _)| key == "result")
+    {
+        *slot = result;
+    }
+    json
+}
+
+/// A conversion as `--json` prints it, or as a line of text.
+fn format_record(record: &ConversionRecord, options: &Options) -> String {
+    if options.json {
+        return result_json(record, options).to_string();
+    }
+    format_result(record.input, &record.from, &record.to, record.result, options)
+}
+
+fn format_result(value: f64, from_unit: &str, to_unit: &str, result: f64, options: &Options) -> String {
+    let gauge_name = |unit: &str, number: f64| gauge::is_awg(unit).then(|| gauge::name(number as i32));
+    let clock = |unit: &str, number: f64| pace::is_clock_pace(unit).then(|| pace::format(number));
+    let clock_input = |unit: &str, number: f64| pace::is_clock_pace(unit).then(|| pace::format_input(number));
+    let value_text = gauge_name(from_unit, value).or_else(|| clock_input(from_unit, value)).unwrap_or_else(|| plain_or_scientific(value));
+    let result_text = gauge_name(to_unit, result).or_else(|| clock(to_unit, result)).unwrap_or_else(|| format_number(result, options));
+    let human_value = duration::is_human(from_unit).then(|| duration::humanize(value, None));
+    let human_result = duration::humanized_result(result, to_unit, options.humanize, options.max_components);
+    match &options.format {
+        Some(template) => template
+            .replace("{value}", human_value.as_deref().unwrap_or(&value_text))
+            .replace("{from_name}", long_name(from_unit, &value_text))
+            .replace("{to_name}", long_name(to_unit, &result_text))
+            .replace("{from}", unit_symbol(from_unit, options))
+            .replace("{to}", unit_symbol(to_unit, options))
+            .replace("{result}", human_result.as_deref().unwrap_or(&result_text)),
+        None => {
+            let value_side = human_value.unwrap_or_else(|| format!("{} {}", value_text, unit_label(from_unit, &value_text, options)));
+            let result_side = human_result.unwrap_or_else(|| format!("{} {}", result_text, unit_label(to_unit, &result_text, options)));
+            format!("{} = {}", value_side, result_side)
+        }
+    }
+}
+
+/// A unit as it follows a number: its symbol, whatever the user typed
+/// ("KILOMETRES" prints as km), or with `--names` its long name inflected
+/// to agree with the number. With `--echo-input`, symbols and compound
+/// units are echoed as typed and word forms ("miles", "Feet") re-inflected.
+fn unit_label<'a>(typed: &'a str, number: &str, options: &Options) -> &'a str {
+    let symbol = |unit: &Unit| unit.name.eq_ignore_ascii_case(typed) || compound::is_expression(typed);
+    match find_unit(typed) {
+        Some(unit) if options.names || (options.echo_input && !symbol(unit)) => unit.display_name(number),
+        _ => unit_symbol(typed, options),
+    }
+}
+
+/// The symbol of the unit `typed` names, or `typed` itself with
+/// `--echo-input` or when it names no unit.
+fn unit_symbol<'a>(typed: &'a str, options: &Options) -> &'a str {
+    match find_unit(typed) {
+        Some(unit) if !options.echo_input => unit.name,
+        _ => typed,
+    }
+}
+
+fn long_name<'a>(typed: &'a str, number: &str) -> &'a str {
+    find_unit(typed).map_or(typed, |unit| unit.display_name(number))
+}
+
+fn log_history(dir: Option<&Path>, entry: &HistoryEntry) {
+    let result = match dir {
+        Some(dir) => history::append(dir, entry),
+        None => Err(std::io::Error::other("no state directory (HOME is not set)")),
+    };
+    if let Err(e) = result {
+        eprintln!("{}: {}", tr("warning"), tr_with("history_unwritable", &[&e]));
+    }
+}
+
+fn validate_favorite_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(tr_with("fav.invalid_name", &[&name]));
+    }
+    if SUBCOMMANDS.contains(&name) || FAV_COMMANDS.contains(&name) {
+        return Err(format!("'{}' is a reserved command name and can't be used for a favorite", name));
+    }
+    Ok(())
+}
+
+fn run_fav(program: &str, args: &[String], options: &Options, config: &Config) {
+    let usage = || {
+        let label = tr("usage_label");
+        let indent = label.chars().count() + 2;
+        eprintln!("{}: {} fav add <name> <from_unit> <to_unit> [--precision N] [--format TEMPLATE]", label, program);
+        eprintln!("{:indent$}{} fav <name> <value>", "", program);
+        eprintln!("{:indent$}{} fav list", "", program);
+        eprintln!("{:indent$}{} fav remove <name>", "", program);
+        process::exit(1);
+    };
+    let Some(path) = config::config_path() else {
+        eprintln!("{}: {}", tr("error"), tr("no_config_path"));
+        process::exit(1);
+    };
+    
+    match args {
+        [cmd, name, from, to] if cmd == "add" => {
+            if let Err(e) = validate_favorite_name(name) {
+                eprintln!("{}: {}", tr("error"), e);
                 process::exit(1);
             }
-            
-            if f.category == UnitCategory::Length && value < 0.0 {
-                eprintln!("Warning: Negative length doesn't make physical sense");
+            let [f, t] = [from, to].map(|unit| find_unit(unit).unwrap_or_else(|| exit_with_error(program, &unit_error(unit), options)));
+            if f.dimension() != t.dimension() {
+                let e = ConvertError::CategoryMismatch { from: f.category, to: t.category, from_unit: from.clone(), to_unit: to.clone() };
+                exit_with_error(program, &e, options);
             }
-            
-            if f.category == UnitCategory::Temperature && f.name == "K" && value < 0.0 {
-                eprintln!("Error: Temperature below absolute zero");
+            let favorite = Favorite {
+                name: name.clone(),
+                from: from.clone(),
+                to: to.clone(),
+                precision: options.precision,
+                format: options.format.clone(),
+            };
+            if let Err(e) = config::save_favorite(&path, &favorite) {
+                eprintln!("{}: {}", tr("error"), tr_with("could_not_write", &[&path.display(), &e]));
                 process::exit(1);
             }
-            
-            if f.category == UnitCategory::Temperature && f.name == "C" && value < -273.15 {
-                eprintln!("Error: Temperature below absolute zero");
+            println!("{}", tr_with("fav.saved", &[name, from, to]));
+        }
+        [cmd] if cmd == "list" => {
+            if config.favorites.is_empty() {
+                println!("{}", tr("fav.none"));
+            }
+            for f in &config.favorites {
+                print!("  {}: {} -> {}", f.name, f.from, f.to);
+                if let Some(p) = f.precision {
+                    print!(" ({})", tr_with("fav.precision", &[&p]));
+                }
+                if let Some(format) = &f.format {
+                    print!(" ({})", tr_with("fav.format", &[format]));
+                }
+                println!();
+            }
+        }
+        [cmd, name] if cmd == "remove" => match config::remove_favorite(&path, name) {
+            Ok(true) => println!("{}", tr_with("fav.removed", &[name])),
+            Ok(false) => {
+                eprintln!("{}: {}", tr("error"), tr_with("fav.missing", &[name]));
                 process::exit(1);
             }
-            
-            if f.category == UnitCategory::Temperature && f.name == "F" && value < -459.67 {
-                eprintln!("Error: Temperature below absolute zero");
+            Err(e) => {
+                eprintln!("{}: {}", tr("error"), tr_with("could_not_write", &[&path.display(), &e]));
                 process::exit(1);
             }
-            
-            let base_value = (f.to_base)(value);
-            let result = (t.from_base)(base_value);
-            println!("{} {} = {} {}", value, from_unit, result, to_unit);
+        },
+        [name, value] if !FAV_COMMANDS.contains(&name.as_str()) => {
+            let Some(favorite) = config.favorite(name) else {
+                eprintln!("{}: {}", tr("error"), tr_with("fav.missing", &[name]));
+                eprintln!("{}", tr_with("fav.try_list", &[&program]));
+                process::exit(1);
+            };
+            let options = options.with_favorite(favorite);
+            run_conversion(program, value, &favorite.from, &favorite.to, &options, config);
+        }
+        _ => usage(),
+    }
+}
+
+fn run_history(program: &str, args: &[String]) {
+    let Some(dir) = config::state_dir() else {
+        eprintln!("{}: {}", tr("error"), tr("history.no_state_dir"));
+        process::exit(1);
+    };
+    
+    if let [flag] = args && flag == "--clear" {
+        if let Err(e) = history::clear(&dir) {
+            eprintln!("{}: {}", tr("error"), tr_with("history.clear_failed", &[&e]));
+            process::exit(1);
         }
-        (None, _) => {
-            eprintln!("Error: Unknown unit '{}'", from_unit);
-            eprintln!("Try '{} --list' to see supported units", args[0]);
+        println!("{}", tr("history.cleared"));
+        return;
+    }
+    
+    let limit = match args {
+        [] => DEFAULT_HISTORY_LIMIT,
+        [n] => match n.parse() {
+            Ok(n) => n,
+            Err(_) => {
+                eprintln!("{}: {}", tr("error"), tr_with("history.invalid_count", &[n]));
+                process::exit(1);
+            }
+        },
+        _ => {
+            eprintln!("{}: {} history [N | --clear]", tr("usage_label"), program);
             process::exit(1);
         }
-        (_, None) => {
-            eprintln!("Error: Unknown unit '{}'", to_unit);
-            eprintln!("Try '{} --list' to see supported units", args[0]);
+    };
+    
+    match history::read_last(&dir, limit) {
+        Ok(entries) => {
+            for e in entries {
+                println!("{}  {} {} = {} {}", e.timestamp, e.value, e.from, e.result, e.to);
+            }
+        }
+        Err(e) => {
+            eprintln!("{}: {}", tr("error"), tr_with("history.unreadable", &[&e]));
             process::exit(1);
         }
     }
 }
 
-fn find_unit(input: &str) -> Option<&'static Unit> {
-    UNITS.iter().find(|u| u.matches(input))
+fn print_help(program: &str) {
+    print!("{}", help_text(program));
 }
 
-fn print_help(program: &str) {
-    println!("Unit Converter v3.0.0");
-    println!();
-    println!("USAGE:");
-    println!("    {} <value> <from_unit> <to_unit>", program);
-    println!();
-    println!("EXAMPLES:");
-    println!("    {} 5 km mi", program);
-    println!("    {} 100 feet meters", program);
-    println!("    {} 100 C F", program);
-    println!("    {} 150 kg lb", program);
-    println!();
-    println!("OPTIONS:");
-    println!("    -h, --help       Show this help message");
-    println!("    -v, --version    Show version information");
-    println!("    -l, --list       List all supported units");
-    println!();
-    println!("Note: Unit names are case-insensitive and support common aliases");
+/// The `--help` page, with usage lines naming `program`.
+fn help_text(program: &str) -> String {
+    let mut out = String::new();
+    let (needs_system, needs_http, needs_tui) = (tr("help.needs_system"), tr("help.needs_http"), tr("help.needs_tui"));
+    out.push_str("Unit Converter v3.0.0\n");
+    out.push('\n');
+    out.push_str(&format!("{}\n", tr("help.usage")));
+    out.push_str(&format!("    {} <value> <from_unit> <to_unit>\n", program));
+    out.push_str(&format!("    {} <value> <from_unit>    ({})\n", program, needs_system));
+    out.push_str(&format!("    {} --batch <file> [--skip-errors]\n", program));
+    out.push_str(&format!("    {} --filter <from_unit> <to_unit> [--passthrough] < numbers.txt\n", program));
+    out.push_str(&format!("    {} csv <file> --column <name|N> --from <unit> --to <unit> [--rename NAME]\n", program));
+    out.push_str(&format!("    {} serve [--host ADDR] [--port N]    ({})\n", program, needs_http));
+    out.push_str(&format!("    {} --paste <from_unit> <to_unit>    ({})\n", program, tr("help.from_clipboard")));
+    out.push_str(&format!("    {} approx <value> <unit> <value> <unit> [--tolerance T | T%]\n", program));
+    out.push_str(&format!("    {} compare <value> <unit> <value> <unit>\n", program));
+    out.push_str(&format!("    {} sum <quantity>... [--to <unit>]    ({})\n", program, tr("help.sum_example")));
+    out.push_str(&format!("    {} factor <from_unit> <to_unit> [--exact]\n", program));
+    out.push_str(&format!("    {} shoe <size> <us-mens|us-womens|uk|eu|cm> <system> | shoe --foot-length <cm> [<system>]\n", program));
+    out.push_str(&format!("    {} paper <size> [--in <size>] [--json]\n", program));
+    out.push_str(&format!("    {} base <number> [--from <base>] [--to <base>] [--upper] [--group N]\n", program));
+    out.push_str(&format!("    {} roman <number | numeral> [--lenient]\n", program));
+    out.push_str(&format!("    {} time <seconds | datetime> [--from epoch|iso] [--millis] [--local]\n", program));
+    out.push_str(&format!("    {} doctor\n", program));
+    out.push_str(&format!("    {} check <units.toml>\n", program));
+    out.push_str(&format!("    {} search <text>\n", program));
+    out.push_str(&format!("    {} info <unit> [--json]\n", program));
+    out.push_str(&format!("    {} repl\n", program));
+    out.push_str(&format!("    {} tui    ({})\n", program, needs_tui));
+    out.push_str(&format!("    {} history [N | --clear]\n", program));
+    out.push_str(&format!("    {} fav add <name> <from_unit> <to_unit>\n", program));
+    out.push_str(&format!("    {} fav <name> <value>\n", program));
+    out.push_str(&format!("    {} fav list | fav remove <name>\n", program));
+    out.push('\n');
+    out.push_str(&format!("{}\n", tr("help.examples")));
+    out.push_str(&format!("    {} 5 km mi\n", program));
+    out.push_str(&format!("    {} \"5.0±0.2\" km mi\n", program));
+    out.push_str(&format!("    {} 10..20 C F\n", program));
+    out.push_str(&format!("    {}\n", tr_with("help.more_examples", &[&format!("{} --examples", program)])));
+    out.push('\n');
+    out.push_str(&format!("{}\n", tr("help.options")));
+    let categories: Vec<String> = converter::categories().iter().map(|c| c.name().to_lowercase()).collect();
+    for (flags, key) in HELP_OPTIONS {
+        let text = tr_with(key, &[&categories.join(", ")]).replace('\n', &format!("\n    {:21}", ""));
+        out.push_str(&format!("    {:20} {}\n", flags, text));
+    }
+    out.push('\n');
+    out.push_str(&format!("{}\n", tr("help.note")));
+    out
 }
 
-fn print_units() {
-    println!("Supported units:");
-    println!();
-    
-    let categories = [
-        (UnitCategory::Length, "Length"),
-        (UnitCategory::Temperature, "Temperature"),
-        (UnitCategory::Mass, "Mass"),
-    ];
-    
-    for (cat, name) in categories {
-        println!("{}:", name);
-        for unit in UNITS.iter().filter(|u| u.category == cat) {
-            print!("  {} ", unit.name);
-            if !unit.aliases.is_empty() {
-                print!("({})", unit.aliases.join(", "));
-            }
-            println!();
+/// The flags `--help` lists, each with the catalog key of its description.
+const HELP_OPTIONS: &[(&str, &str)] = &[
+    ("-h, --help", "option.help"),
+    ("-v, --version", "option.version"),
+    ("-l, --list", "option.list"),
+    ("--category NAME", "option.category"),
+    ("--detailed", "option.detailed"),
+    ("--markdown", "option.markdown"),
+    ("--examples [NAME]", "option.examples"),
+    ("-p, --precision N", "option.precision"),
+    ("--format TEMPLATE", "option.format"),
+    ("--names", "option.names"),
+    ("--echo-input", "option.echo_input"),
+    ("--explain", "option.explain"),
+    ("--humanize", "option.humanize"),
+    ("--max-components N", "option.max_components"),
+    ("--mixed", "option.mixed"),
+    ("--dms", "option.dms"),
+    ("--fraction [N]", "option.fraction"),
+    ("--delta", "option.delta"),
+    ("--via QUANTITY", "option.via"),
+    ("--rates FILE", "option.rates"),
+    ("--live", "option.live"),
+    ("--system NAME", "option.system"),
+    ("--calendar NAME", "option.calendar"),
+    ("--exact", "option.exact"),
+    ("--big", "option.big"),
+    ("--json", "option.json"),
+    ("--csv", "option.csv"),
+    ("--batch FILE", "option.batch"),
+    ("--skip-errors", "option.skip_errors"),
+    ("--filter FROM TO", "option.filter"),
+    ("--passthrough", "option.passthrough"),
+    ("--jobs N, -j N", "option.jobs"),
+    ("--no-progress", "option.no_progress"),
+    ("--serve-stdio", "option.serve_stdio"),
+    ("--copy", "option.copy"),
+    ("--paste", "option.paste"),
+    ("--strict", "option.strict"),
+    ("--log-history", "option.log_history"),
+    ("-vv", "option.trace"),
+    ("--lang LANG", "option.lang"),
+    ("--", "option.end_of_options"),
+];
+
+/// A unit as the listing shows it: "km (kilometer, kilometers, ...)".
+fn unit_line(unit: &Unit) -> String {
+    match converter::aliases_of(unit) {
+        [] => format!("{} ", unit.name),
+        aliases => format!("{} ({})", unit.name, aliases.join(", ")),
+    }
+}
+
+fn units_listing(registry: &Registry, filter: Option<UnitCategory>) -> String {
+    let mut out = String::new();
+    for cat in registry.categories().into_iter().filter(|&c| filter.is_none_or(|f| f == c)) {
+        out.push_str(&format!("{}:\n", cat.localized_name(i18n::active())));
+        for unit in registry.by_category(cat) {
+            out.push_str(&format!("  {}\n", unit_line(unit)));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// The listing of `--list --detailed`: every unit by symbol and name, and
+/// how it relates to its category's base unit ("1 mi = 1609.344 m", or a
+/// formula for units with an offset), smallest first within a category.
+fn units_detailed(registry: &Registry, filter: Option<UnitCategory>) -> String {
+    let mut out = String::new();
+    for cat in registry.categories().into_iter().filter(|&c| filter.is_none_or(|f| f == c)) {
+        let base = base_unit(registry, cat);
+        let mut units: Vec<&Unit> = registry.by_category(cat).collect();
+        if let Some(base) = base {
+            units.sort_by(|a, b| slope(a, base).total_cmp(&slope(b, base)));
+        }
+        let rows: Vec<[String; 3]> = units.iter().map(|unit| [unit.name.to_string(), unit.display_singular.to_string(), relation(unit, base)]).collect();
+        let width = |column: usize| rows.iter().map(|row| row[column].chars().count()).max().unwrap_or(0);
+        let (symbols, names) = (width(0), width(1));
+        out.push_str(&format!("{}:\n", cat.localized_name(i18n::active())));
+        for [symbol, name, relation] in &rows {
+            let line = format!("  {:symbols$}  {:names$}  {}", symbol, name, relation);
+            out.push_str(&format!("{}\n", line.trim_end()));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// `--list --markdown`: a table per category of each unit's symbol, name,
+/// aliases and factor to the base unit (or formula, when it has an offset).
+fn units_markdown(registry: &Registry, filter: Option<UnitCategory>) -> String {
+    let mut out = String::new();
+    for cat in registry.categories().into_iter().filter(|&c| filter.is_none_or(|f| f == c)) {
+        let base = base_unit(registry, cat);
+        out.push_str(&format!("## {}\n\n", cat.name()));
+        out.push_str("| Symbol | Name | Aliases | Factor to base |\n");
+        out.push_str("| --- | --- | --- | --- |\n");
+        for unit in registry.by_category(cat) {
+            let factor = match base {
+                Some(base) if unit.conversion.offset() != 0.0 => relation(unit, Some(base)),
+                Some(base) => significant(slope(unit, base)).to_string(),
+                None => String::new(),
+            };
+            let aliases: Vec<String> = converter::aliases_of(unit).iter().map(|a| markdown_escape(a)).collect();
+            let cells = [markdown_escape(unit.name), markdown_escape(unit.display_singular), aliases.join(", "), markdown_escape(&factor)];
+            out.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// `text` with a backslash before every character Markdown would read as
+/// markup, so a `|` in an alias can't split a table cell.
+fn markdown_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '|' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// The unit of `category` every other converts through, even if the
+/// configuration disabled it.
+fn base_unit(registry: &Registry, category: UnitCategory) -> Option<&'static Unit> {
+    converter::UNITS.iter().chain(registry.units()).find(|u| u.category == category && u.to_base_steps().iter().all(|s| s.is_identity()))
+}
+
+/// `unit` in terms of `base`: "1 mi = 1609.344 m", or "F = C × 9/5 + 32"
+/// when there is an offset; nothing for the base itself.
+fn relation(unit: &Unit, base: Option<&Unit>) -> String {
+    let Some(base) = base.filter(|base| base.name != unit.name) else { return String::new() };
+    if unit.conversion.offset() != 0.0 {
+        let steps: Vec<Step> = unit.to_unit_steps().into_iter().filter(|s| !s.is_identity()).collect();
+        return format!("{} = {}", unit.name, formula(base.name.to_string(), &steps));
+    }
+    format!("1 {} = {} {}", unit.name, significant(slope(unit, base)), base.name)
+}
+
+/// `--examples`: for each category, a conversion from a metric unit to its
+/// imperial counterpart, the reverse, and the same with the units spelled
+/// out. The units come from `registry`, so a disabled unit is never shown;
+/// a category without a counterpart pair uses its first two units.
+fn examples(registry: &Registry, filter: Option<UnitCategory>) -> Vec<(UnitCategory, Vec<String>)> {
+    let available = |name: &str| registry.find(name).filter(|u| u.name == name);
+    let mut out = Vec::new();
+    for cat in registry.categories().into_iter().filter(|&c| filter.is_none_or(|f| f == c)) {
+        let units: Vec<&Unit> = registry.by_category(cat).collect();
+        let pair = COUNTERPARTS
+            .iter()
+            .find_map(|&(imperial, metric)| Some((available(metric)?, available(imperial)?)).filter(|(m, _)| m.category == cat))
+            .or_else(|| Some((*units.first()?, *units.get(1)?)));
+        let Some((from, to)) = pair else { continue };
+        let value = if cat == UnitCategory::Temperature { "100" } else { "5" };
+        // A one-word alias the registry reads as the unit: "kilometers", "mph".
+        let alias = |unit: &Unit| {
+            std::iter::once(&unit.display_plural)
+                .chain(unit.aliases)
+                .find(|word| **word != unit.name && !word.contains(' ') && registry.find(word).is_some_and(|u| u.name == unit.name))
+                .copied()
+        };
+        let mut lines = vec![format!("{} {} {}", value, from.name, to.name), format!("{} {} {}", value, to.name, from.name)];
+        let spelled = format!("{} {} {}", value, alias(from).unwrap_or(from.name), alias(to).unwrap_or(to.name));
+        if !lines.contains(&spelled) {
+            lines.push(spelled);
+        }
+        out.push((cat, lines));
+    }
+    out
+}
+
+fn print_examples(program: &str, filter: Option<UnitCategory>) {
+    for (cat, lines) in examples(registry::active(), filter) {
+        println!("{}:", cat.localized_name(i18n::active()));
+        for line in lines {
+            println!("    {} {}", program, line);
         }
         println!();
     }
 }
 
+/// Runs the `search` subcommand, listing every unit whose names contain the
+/// text; finding nothing is an error.
+fn run_search(program: &str, args: &[String]) -> i32 {
+    let [query] = args else {
+        eprintln!("{}: {} search <text>", tr("usage_label"), program);
+        return 1;
+    };
+    let units = converter::find_all_matching(query);
+    if units.is_empty() {
+        eprintln!("{}: {}", tr("error"), tr_with("no_match", &[query]));
+        return 1;
+    }
+    for unit in units {
+        println!("{}", unit_line(unit));
+    }
+    0
+}
+
+fn print_units(filter: Option<UnitCategory>, options: &Options) {
+    if options.json {
+        let units = match filter {
+            Some(category) => converter::units_by_category(category).collect(),
+            None => registry::active().units().collect::<Vec<_>>(),
+        };
+        println!("{}", converter::info::units_json(units.into_iter()));
+        return;
+    }
+    if options.markdown {
+        print!("{}", units_markdown(registry::active(), filter));
+        return;
+    }
+    println!("{}", tr("list.header"));
+    println!();
+    if options.detailed {
+        print!("{}", units_detailed(registry::active(), filter));
+    } else {
+        print!("{}", units_listing(registry::active(), filter));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     
+    #[cfg(any(feature = "length", feature = "temperature"))]
     fn assert_approx_eq(a: f64, b: f64, epsilon: f64) {
         assert!((a - b).abs() < epsilon, "{} != {} (epsilon: {})", a, b, epsilon);
     }
     
     #[test]
-    fn test_km_to_miles() {
-        let km = find_unit("km").unwrap();
-        let mi = find_unit("mi").unwrap();
-        let base = (km.to_base)(5.0);
-        let result = (mi.from_base)(base);
-        assert_approx_eq(result, 3.10686, 0.00001);
+    fn test_parse_args_log_history() {
+        let args: Vec<String> = ["converter", "--log-history", "5", "km", "mi"]
+            .iter().map(|s| s.to_string()).collect();
+        let (options, positional) = parse_args(&args).unwrap();
+        assert!(options.log_history);
+        assert_eq!(positional, vec!["converter", "5", "km", "mi"]);
+    }
+    
+    #[test]
+    fn test_program_name_drops_directory_and_extension() {
+        for invoked in ["/usr/local/bin/converter", "target/debug/converter", "converter.exe", "converter"] {
+            assert_eq!(program_name(Some(invoked)), "converter");
+        }
+        assert_eq!(program_name(Some("/opt/tools/units")), "units");
+        assert_eq!(program_name(Some("")), PROGRAM);
+        assert_eq!(program_name(None), PROGRAM);
+    }
+    
+    #[test]
+    fn test_help_and_usage_name_the_program_briefly() {
+        let program = program_name(Some("/usr/local/bin/converter.exe"));
+        let help = help_text(&program);
+        assert!(help.contains("\n    converter 5 km mi\n"), "{}", help);
+        let usage = usage_text(&program);
+        assert_eq!(usage, "Usage: converter <value> <from_unit> <to_unit>\nTry 'converter --help' for more information\n");
+        for text in [help, usage] {
+            assert!(!text.contains("/usr/local/bin") && !text.contains(".exe"), "{}", text);
+        }
+    }
+    
+    #[test]
+    fn test_parse_args_negative_values_and_separator() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let (options, positional) = parse_args(&args(&["converter", "-40", "C", "F", "-p", "1"])).unwrap();
+        assert_eq!((positional, options.precision), (args(&["converter", "-40", "C", "F"]), Some(1)));
+        for value in ["-.5", "-1e3", "-5--1"] {
+            assert_eq!(parse_args(&args(&["converter", value, "km", "mi"])).unwrap().1[1], value);
+        }
+        let (options, positional) = parse_args(&args(&["converter", "--json", "--", "-p", "km", "-vv"])).unwrap();
+        assert!(options.json && options.end_of_options && !options.trace);
+        assert_eq!((positional, options.precision), (args(&["converter", "-p", "km", "-vv"]), None));
+        assert!(!is_negative_value("-vv") && !is_negative_value("-") && !is_negative_value("--"));
+    }
+    
+    fn body_temp() -> Favorite {
+        Favorite {
+            name: "body-temp".to_string(),
+            from: "F".to_string(),
+            to: "C".to_string(),
+            precision: Some(1),
+            format: None,
+        }
+    }
+    
+    #[test]
+    fn test_favorite_name_validation() {
+        assert!(validate_favorite_name("body-temp").is_ok());
+        assert!(validate_favorite_name("add").is_err());
+        assert!(validate_favorite_name("list").is_err());
+        assert!(validate_favorite_name("history").is_err());
+        assert!(validate_favorite_name("has space").is_err());
+        assert!(validate_favorite_name("").is_err());
+    }
+    
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn test_run_favorite() {
+        let fav = body_temp();
+        let options = Options::default().with_favorite(&fav);
+        let result = convert_units(find_unit(&fav.from).unwrap(), find_unit(&fav.to).unwrap(), 98.6);
+        assert_eq!(format_result(98.6, &fav.from, &fav.to, result, &options), "98.6 F = 37.0 C");
+    }
+    
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn test_run_favorite_with_json() {
+        let fav = body_temp();
+        let cli = Options { json: true, ..Options::default() };
+        let options = cli.with_favorite(&fav);
+        let record = convert_recorded("98.6", &fav.from, &fav.to).unwrap();
+        assert_eq!(
+            format_record(&record, &options),
+            r#"{"value":98.6,"from":"F","to":"C","result":37,"category":"temperature","factor":null,"warnings":[]}"#
+        );
+    }
+    
+    #[test]
+    fn test_command_line_overrides_favorite() {
+        let mut fav = body_temp();
+        fav.format = Some("{result}".to_string());
+        let cli = Options { precision: Some(3), ..Options::default() };
+        let options = cli.with_favorite(&fav);
+        assert_eq!(options.precision, Some(3));
+        assert_eq!(format_result(1.0, "F", "C", -17.222222, &options), "-17.222");
+    }
+    
+    #[test]
+    #[cfg(all(feature = "length", feature = "time", feature = "volume"))]
+    fn test_units_print_as_symbols() {
+        let options = Options::default();
+        assert_eq!(format_result(5.0, "KILOMETRES", "MILE", 3.10686, &options), "5 km = 3.10686 mi");
+        assert_eq!(format_result(1.0, "Foot", "inches", 12.0, &options), "1 ft = 12 in");
+        assert_eq!(format_result(36.0, "KM/H", "meters/second", 10.0, &options), "36 km/h = 10 m/s");
+        assert_eq!(format_result(2.0, "cubic_meters", "furlong", 1.0, &options), "2 m3 = 1 furlong");
+        let template = Options { format: Some("{value} {from} -> {result} {to}".to_string()), ..Options::default() };
+        assert_eq!(format_result(5.0, "kilometers", "miles", 3.1, &template), "5 km -> 3.1 mi");
+        let converted = convert_checked("05.000", "kilometres", "mile").unwrap();
+        assert_eq!(format_result(converted.value, "kilometres", "mile", 1.0, &options), "5 km = 1 mi");
+    }
+    
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_word_forms_are_pluralized() {
+        let options = Options { echo_input: true, ..Options::default() };
+        assert_eq!(format_result(1.0, "km", "miles", 0.5, &options), "1 km = 0.5 miles");
+        assert_eq!(format_result(1609.344, "m", "mi", 1.0, &options), "1609.344 m = 1 mi");
+        assert_eq!(format_result(3.0, "feet", "yards", 1.0, &options), "3 feet = 1 yard");
+        assert_eq!(format_result(1.0, "Foot", "inches", 12.0, &options), "1 foot = 12 inches");
+        assert_eq!(format_result(-1.0, "mile", "km", -1.609344, &options), "-1 mile = -1.609344 km");
+    }
+    
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_singular_only_when_displayed_as_one() {
+        let rounded = Options { precision: Some(0), echo_input: true, ..Options::default() };
+        assert_eq!(format_result(0.3048, "m", "feet", 0.99999, &rounded), "0.3048 m = 1 foot");
+        let padded = Options { precision: Some(2), echo_input: true, ..Options::default() };
+        assert_eq!(format_result(0.3048, "m", "feet", 1.0, &padded), "0.3048 m = 1.00 feet");
+        let echoed = Options { echo_input: true, ..Options::default() };
+        assert_eq!(format_result(0.0, "m", "feet", 0.0, &echoed), "0 m = 0 feet");
+        assert_eq!(format_result(36.0, "KM/H", "m/s", 10.0, &echoed), "36 KM/H = 10 m/s");
+    }
+    
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_names_option() {
+        let names = Options { names: true, precision: Some(2), ..Options::default() };
+        assert_eq!(format_result(5.0, "km", "mi", 3.10686, &names), "5 kilometers = 3.11 miles");
+        let names = Options { names: true, ..Options::default() };
+        assert_eq!(format_result(1.0, "ft", "in", 12.0, &names), "1 foot = 12 inches");
+        assert_eq!(format_result(3.0, "ft", "yd", 1.0, &names), "3 feet = 1 yard");
+    }
+    
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature", feature = "time", feature = "speed"))]
+    fn test_default_output_drops_floating_point_noise() {
+        let shown = |value: &str, from: &str, to: &str, options: &Options| {
+            let converted = convert_checked(value, from, to).unwrap();
+            format_result(converted.value, from, to, converted.result, options)
+        };
+        let cases = [
+            ("1.1", "m", "cm", "1.1 m = 110 cm"),
+            ("4.35", "m", "cm", "4.35 m = 435 cm"),
+            ("0.07", "m", "cm", "0.07 m = 7 cm"),
+            ("1.005", "km", "m", "1.005 km = 1005 m"),
+            ("3", "ft", "in", "3 ft = 36 in"),
+            ("0.1", "ft", "in", "0.1 ft = 1.2 in"),
+            ("14", "oz", "g", "14 oz = 396.89332375 g"),
+            ("300", "K", "C", "300 K = 26.85 C"),
+            ("100", "F", "C", "100 F = 37.7777777777778 C"),
+            ("5", "km", "mi", "5 km = 3.10685596118667 mi"),
+            ("0.1", "kg", "lb", "0.1 kg = 0.220462262184878 lb"),
+            ("1e-20", "m", "km", "1e-20 m = 1e-23 km"),
+            ("0.000001", "km", "mm", "0.000001 km = 1 mm"),
+            ("1", "c", "mm/h", "1 c = 1.0792528488e15 mm/h"),
+            ("5", "km/h", "c", "5 km/h = 4.63283465552989e-9 c"),
+        ];
+        for (value, from, to, expected) in cases {
+            assert_eq!(shown(value, from, to, &Options::default()), expected);
+        }
+        let exact = Options { precision: Some(17), ..Options::default() };
+        assert_eq!(shown("4.35", "m", "cm", &exact), "4.35 m = 434.99999999999994316 cm");
+        assert_eq!(format_number(0.1 + 0.2, &Options::default()), "0.3");
+        assert_eq!(format_number(f64::MAX, &Options::default()), "1.7976931348623157e308");
+        assert_eq!(format_number(-2.5e-7, &Options::default()), "-2.5e-7");
+        assert_eq!(format_number(999999999999999.0, &Options::default()), "999999999999999");
+    }
+    
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_name_placeholders_in_format() {
+        let options = Options {
+            names: true,
+            precision: Some(1),
+            format: Some("{value} {from_name} ({from}) is {result} {to_name}".to_string()),
+            ..Options::default()
+        };
+        assert_eq!(format_result(1.0, "mi", "km", 1.609344, &options), "1 mile (mi) is 1.6 kilometers");
+    }
+    
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_uncertainty_scales_with_length() {
+        let converted = convert_checked("5.0±0.2", "km", "mi").unwrap();
+        assert_approx_eq(converted.sigma.unwrap(), 0.2 / 1.609344, 1e-12);
+        let options = Options::default();
+        assert_eq!(format_uncertain(converted.result, converted.sigma.unwrap(), "mi", &options), "3.11 ± 0.12 mi");
+        assert_eq!(convert_checked("5+-0.2", "km", "mi").unwrap().sigma, converted.sigma);
+        assert_eq!(convert_checked("5", "km", "mi").unwrap().sigma, None);
+    }
+    
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn test_uncertainty_ignores_temperature_offset() {
+        let converted = convert_checked("10 ± 0.5", "C", "F").unwrap();
+        assert_approx_eq(converted.result, 50.0, 1e-9);
+        assert_approx_eq(converted.sigma.unwrap(), 0.9, 1e-9);
+        let options = Options::default();
+        assert_eq!(format_uncertain(1000.0, 124.0, "ft", &options), "1000 ± 120 ft");
+    }
+    
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn test_temperature_range() {
+        let ((lo, hi), _) = convert_range(10.0, 20.0, "C", "F").unwrap();
+        assert_approx_eq(lo, 50.0, 1e-9);
+        assert_approx_eq(hi, 68.0, 1e-9);
+        let options = Options { precision: Some(0), ..Options::default() };
+        assert_eq!(format_range("C", "F", lo, hi, &options), "50 F to 68 F");
+        let json = Options { json: true, ..options };
+        assert_eq!(format_range("C", "F", lo, hi, &json), r#"{"from":"C","to":"F","lo":50,"hi":68}"#);
+    }
+    
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_range_is_ordered() {
+        let ((lo, hi), warnings) = convert_range(2.0, -1.0, "m", "cm").unwrap();
+        assert_eq!((lo, hi), (-100.0, 200.0));
+        assert_eq!(warnings.len(), 1);
+    }
+    
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("10..20"), Some((10.0, 20.0)));
+        assert_eq!(parse_range("10-20"), Some((10.0, 20.0)));
+        assert_eq!(parse_range("-5-10"), Some((-5.0, 10.0)));
+        assert_eq!(parse_range("-5--1"), Some((-5.0, -1.0)));
+        assert_eq!(parse_range("1.5e-3-2"), Some((1.5e-3, 2.0)));
+        assert_eq!(parse_range("-5"), None);
+        assert_eq!(parse_range("1e-3"), None);
+        assert_eq!(parse_range("5±-1"), None);
+        assert_eq!(parse_range("a-b"), None);
+    }
+    
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn test_explain_temperature() {
+        let options = Options { precision: Some(1), ..Options::default() };
+        let (formula, steps) = explain(find_unit("F").unwrap(), find_unit("C").unwrap(), 98.6, &options);
+        assert_eq!(formula, "(98.6 − 32) × 5/9 = 37.0");
+        assert_eq!(steps.len(), 2);
+        assert!(steps[1].ends_with("× 5/9 = 37.0"));
+        
+        let (formula, _) = explain(find_unit("C").unwrap(), find_unit("F").unwrap(), 100.0, &Options::default());
+        assert_eq!(formula, "100 × 9/5 + 32 = 212");
+    }
+    
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_explain_length() {
+        let options = Options { precision: Some(3), ..Options::default() };
+        let (formula, steps) = explain(find_unit("mi").unwrap(), find_unit("km").unwrap(), 5.0, &options);
+        assert_eq!(formula, "5 × 1609.344 / 1000 = 8.047");
+        assert_eq!(steps, vec!["5 × 1609.344 = 8046.72", "8046.72 / 1000 = 8.047"]);
+        
+        let (formula, steps) = explain(find_unit("m").unwrap(), find_unit("m").unwrap(), 5.0, &Options::default());
+        assert_eq!((formula.as_str(), steps.len()), ("5 = 5", 0));
     }
     
     #[test]
-    fn test_celsius_to_fahrenheit() {
-        let c = find_unit("C").unwrap();
-        let f = find_unit("F").unwrap();
-        let base = (c.to_base)(100.0);
-        let result = (f.from_base)(base);
-        assert_approx_eq(result, 212.0, 0.00001);
+    #[cfg(feature = "time")]
+    fn test_human_duration_both_directions() {
+        let options = Options::default();
+        let converted = convert_checked("93784", "s", "human").unwrap();
+        assert_eq!(format_result(converted.value, "s", "human", converted.result, &options), "93784 s = 1d 2h 3m 4s");
+        let converted = convert_checked("1d2h3m", "human", "min").unwrap();
+        assert_eq!(format_result(converted.value, "human", "min", converted.result, &options), "1d 2h 3m = 1563 min");
+        
+        let humanize = Options { humanize: true, max_components: Some(1), ..Options::default() };
+        assert_eq!(format_result(26.0, "h", "d", 26.0 / 24.0, &humanize), "26 h = 1d");
     }
     
     #[test]
-    fn test_celsius_to_fahrenheit_freezing() {
-        let c = find_unit("C").unwrap();
-        let f = find_unit("F").unwrap();
-        let base = (c.to_base)(0.0);
-        let result = (f.from_base)(base);
-        assert_approx_eq(result, 32.0, 0.00001);
+    #[cfg(feature = "length")]
+    fn test_wire_gauges_print_as_gauges() {
+        let options = Options { precision: Some(3), ..Options::default() };
+        let converted = convert_checked("4/0", "awg", "in").unwrap();
+        assert_eq!(format_result(converted.value, "awg", "in", converted.result, &options), "4/0 awg = 0.460 in");
+        let converted = convert_checked("2", "mm", "awg").unwrap();
+        assert_eq!(format_result(converted.value, "mm", "awg", converted.result, &options), "2 mm = 12 awg");
+        assert_eq!(converted.warnings, [Warning::GaugeRounded]);
+    }
+    
+    #[cfg(any(feature = "length", feature = "mass", feature = "temperature"))]
+    fn exact(value: &str, from: &str, to: &str) -> Option<String> {
+        convert_exact(value, find_unit(from).unwrap(), find_unit(to).unwrap(), false).map(format_exact)
+    }
+    
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_exact_inches_and_centimeters() {
+        assert_eq!(exact("1", "in", "cm").as_deref(), Some("2.54"));
+        assert_eq!(exact("1", "cm", "in").as_deref(), Some("50/127"));
+        assert_eq!(exact("5", "mi", "km").as_deref(), Some("8.04672"));
+    }
+    
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn test_exact_fahrenheit_and_celsius() {
+        assert_eq!(exact("98.6", "F", "C").as_deref(), Some("37"));
     }
     
     #[test]
-    fn test_kg_to_pounds() {
-        let kg = find_unit("kg").unwrap();
-        let lb = find_unit("lb").unwrap();
-        let base = (kg.to_base)(10.0);
-        let result = (lb.from_base)(base);
-        assert_approx_eq(result, 22.0462, 0.0001);
+    #[cfg(feature = "mass")]
+    fn test_exact_pounds_and_kilograms() {
+        assert_eq!(exact("1", "lb", "kg").as_deref(), Some("0.45359237"));
+        assert_eq!(exact("1", "kg", "lb").as_deref(), Some("100000000/45359237"));
     }
     
+    #[cfg(all(feature = "bigdecimal", feature = "length"))]
     #[test]
-    fn test_mg_to_kg() {
-        let mg = find_unit("mg").unwrap();
-        let kg = find_unit("kg").unwrap();
-        let base = (mg.to_base)(1000000.0);
-        let result = (kg.from_base)(base);
-        assert_approx_eq(result, 1.0, 0.00001);
+    fn test_big_digits() {
+        let big = |value: &str, from: &str, to: &str, places: usize| {
+            convert_exact(value, find_unit(from).unwrap(), find_unit(to).unwrap(), false).unwrap().to_fixed(places)
+        };
+        assert_eq!(big("1", "mi", "mm", 30), format!("1609344.{}", "0".repeat(30)));
+        let inch = big("1", "cm", "in", 40);
+        assert_eq!(inch, "0.3937007874015748031496062992125984251969");
+        assert_eq!(big("1", "ft", "m", 40), format!("0.3048{}", "0".repeat(36)));
     }
     
     #[test]
-    fn test_g_to_mg() {
-        let g = find_unit("g").unwrap();
-        let mg = find_unit("mg").unwrap();
-        let base = (g.to_base)(1.0);
-        let result = (mg.from_base)(base);
-        assert_approx_eq(result, 1000.0, 0.00001);
+    #[cfg(feature = "length")]
+    fn test_fraction_snaps_to_sixteenths() {
+        let inches = convert_checked("91", "mm", "in").unwrap().result;
+        let (text, error) = format_fraction(inches, 16);
+        assert_eq!(text, "3 9/16");
+        assert!((error + 0.020177).abs() < 1e-6);
+        assert_eq!(format_fraction(3.5, 16), ("3 1/2".to_string(), 0.0));
+        assert_eq!(format_fraction(0.25, 64).0, "1/4");
+        assert_eq!(format_fraction(-1.99, 4).0, "-2");
     }
     
     #[test]
-    fn test_unit_matching_case_insensitive() {
-        assert!(find_unit("KM").is_some());
-        assert!(find_unit("MeTErs").is_some());
-        assert!(find_unit("FAHRENHEIT").is_some());
+    #[cfg(feature = "length")]
+    fn test_fraction_error_annotation_threshold() {
+        // 91 mm is a third of a sixteenth away from 3 9/16; 254 mm is 10 in on the nose.
+        let inches = convert_checked("91", "mm", "in").unwrap().result;
+        assert!(format_fraction(inches, 16).1.abs() > 0.25 / 16.0);
+        let inches = convert_checked("254", "mm", "in").unwrap().result;
+        assert!(format_fraction(inches, 16).1.abs() < 0.25 / 16.0);
     }
     
     #[test]
-    fn test_unit_aliases() {
-        assert!(find_unit("kilometer").is_some());
-        assert!(find_unit("kilometres").is_some());
-        assert!(find_unit("celsius").is_some());
+    fn test_fraction_denominator_validation() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let (options, positional) = parse_args(&args(&["converter", "91", "mm", "in", "--fraction"])).unwrap();
+        assert_eq!((options.fraction, positional.len()), (Some(16), 4));
+        let (options, _) = parse_args(&args(&["converter", "91", "mm", "in", "--fraction", "64"])).unwrap();
+        assert_eq!(options.fraction, Some(64));
+        let (options, positional) = parse_args(&args(&["converter", "--fraction", "91", "mm", "in"])).unwrap();
+        assert_eq!((options.fraction, positional.len()), (Some(16), 4));
+        let err = parse_args(&args(&["converter", "91", "mm", "in", "--fraction", "10"])).unwrap_err();
+        assert!(err.contains("'10' is not a supported fraction denominator"));
     }
     
     #[test]
-    fn test_same_unit_conversion() {
-        let m = find_unit("m").unwrap();
-        let base = (m.to_base)(100.0);
-        let result = (m.from_base)(base);
-        assert_approx_eq(result, 100.0, 0.00001);
+    #[cfg(all(feature = "mass", feature = "volume"))]
+    fn test_unit_words_split_by_the_shell_are_joined() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(join_unit_words(args(&["converter", "5", "metric", "ton", "kg"])), args(&["converter", "5", "metric ton", "kg"]));
+        assert_eq!(join_unit_words(args(&["converter", "2", "kg", "metric", "tons"])), args(&["converter", "2", "kg", "metric tons"]));
+        assert_eq!(join_unit_words(args(&["converter", "1", "cubic", "meter", "l"])), args(&["converter", "1", "cubic meter", "l"]));
+        assert_eq!(join_unit_words(args(&["converter", "5", "metric ton", "kg"])), args(&["converter", "5", "metric ton", "kg"]));
+        let unsplittable = args(&["converter", "5", "km", "mi", "furlong"]);
+        assert_eq!(join_unit_words(unsplittable.clone()), unsplittable);
     }
     
     #[test]
-    fn test_kelvin_to_celsius() {
-        let k = find_unit("K").unwrap();
-        let c = find_unit("C").unwrap();
-        let base = (k.to_base)(273.15);
-        let result = (c.from_base)(base);
-        assert_approx_eq(result, 0.0, 0.00001);
+    #[cfg(feature = "length")]
+    fn test_lang_option_translates_errors_but_not_json() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let (options, positional) = parse_args(&args(&["converter", "--lang", "es", "5", "furlong", "m"])).unwrap();
+        assert_eq!((options.lang, positional.len()), (Some(Lang::Es), 4));
+        assert!(parse_args(&args(&["converter", "--lang", "fr"])).unwrap_err().contains("unsupported language 'fr'"));
+        
+        let e = convert_checked("5", "furlong", "m").unwrap_err();
+        assert_eq!(e.localized(Lang::Es), "Unidad desconocida 'furlong'");
+        assert_eq!(e.to_string(), "Unknown unit 'furlong'");
+        let response = protocol::handle_line(r#"{"value":5,"from":"furlong","to":"m"}"#).to_json().to_string();
+        assert!(response.contains("Unknown unit 'furlong'"), "{}", response);
+        
+        let converted = convert_checked("-1", "m", "cm").unwrap();
+        assert_eq!(converted.warnings[0].localized(Lang::De), "Eine negative Länge ergibt physikalisch keinen Sinn");
+        let json = Options { json: true, lang: Some(Lang::De), ..Options::default() };
+        let record = converted.record("m", "cm").unwrap();
+        assert_eq!(
+            format_record(&record, &json),
+            r#"{"value":-1,"from":"m","to":"cm","result":-100,"category":"length","factor":100,"warnings":["Negative length doesn't make physical sense"]}"#
+        );
+    }
+    
+    #[cfg(any(feature = "length", feature = "mass"))]
+    fn mixed(value: &str, from: &str, to: &str) -> String {
+        let result = convert_checked(value, from, to).unwrap().result;
+        format_mixed(result, find_unit(to).unwrap(), &Options::default()).unwrap()
     }
-}
\ No newline at end of file
+    
+    #[test]
+    #[cfg(feature = "mass")]
+    fn test_mixed_pounds_and_ounces() {
+        assert_eq!(mixed("3.7", "kg", "lb"), "8 lb 2.51 oz");
+        assert_eq!(mixed("1", "lb", "lb"), "1 lb 0 oz");
+    }
+    
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_mixed_feet_and_inches() {
+        assert_eq!(mixed("2", "m", "ft"), "6 ft 6.74 in");
+        assert_eq!(mixed("-2", "m", "ft"), "-6 ft 6.74 in");
+    }
+    
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass"))]
+    fn test_mixed_stone_and_yards() {
+        assert_eq!(mixed("100", "kg", "st"), "15 st 10.46 lb");
+        assert_eq!(mixed("2", "m", "yd"), "2 yd 0.56 ft");
+    }
+    
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass"))]
+    fn test_mixed_carry_and_unsupported_unit() {
+        // 0.9999 lb is 15.9984 oz, which rounds to a whole pound.
+        assert_eq!(mixed("0.9999", "lb", "lb"), "1 lb 0 oz");
+        assert_eq!(mixed("35.9999", "in", "ft"), "3 ft 0 in");
+        assert!(format_mixed(2.0, find_unit("km").unwrap(), &Options::default()).unwrap_err().contains("no sub-unit for 'km'"));
+    }
+    
+    #[test]
+    #[cfg(feature = "angle")]
+    fn test_dms_output() {
+        let dms = |value: &str, from: &str, to: &str, precision: Option<usize>| {
+            let result = convert_checked(value, from, to).unwrap().result;
+            format_dms(result, find_unit(to).unwrap(), &Options { precision, ..Options::default() })
+        };
+        assert_eq!(dms("1", "rad", "rad", None), Ok("57°17′44.81″".to_string()));
+        assert_eq!(dms("1", "rad", "deg", Some(0)), Ok("57°17′45″".to_string()));
+        assert_eq!(dms("-5400", "arcsec", "arcmin", None), Ok("-1°30′00″".to_string()));
+        assert_eq!(dms("12°34'56\"", "deg", "deg", Some(4)), Ok("12°34′56″".to_string()));
+        assert_eq!(dms("45d30m15s", "deg", "arcsec", None), Ok("45°30′15″".to_string()));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "angle"))]
+    fn test_dms_needs_an_angle() {
+        let error = format_dms(1.0, find_unit("km").unwrap(), &Options::default()).unwrap_err();
+        assert_eq!(error, "--dms writes angles; 'km' is not one");
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+    
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature"))]
+    fn test_counterparts_for_each_system() {
+        let metric = Config { system: Some(UnitSystem::Metric), ..Config::default() };
+        let imperial = Config { system: Some(UnitSystem::Imperial), ..Config::default() };
+        assert_eq!(target_unit(&args(&["5", "mi"]), &metric), Ok("km"));
+        assert_eq!(target_unit(&args(&["5", "pounds"]), &metric), Ok("kg"));
+        assert_eq!(target_unit(&args(&["5", "kg"]), &imperial), Ok("lb"));
+        assert_eq!(target_unit(&args(&["20", "C"]), &imperial), Ok("F"));
+        assert_eq!(target_unit(&args(&["2", "m"]), &imperial), Ok("ft"));
+    }
+    
+    #[test]
+    #[cfg(feature = "time")]
+    fn test_counterpart_missing_or_unconfigured() {
+        let metric = Config { system: Some(UnitSystem::Metric), ..Config::default() };
+        assert_eq!(target_unit(&args(&["5", "s"]), &metric), Err("'s' has no metric counterpart; give a target unit".to_string()));
+        assert!(target_unit(&args(&["5", "km"]), &metric).is_err());
+        assert!(target_unit(&args(&["5", "mi"]), &Config::default()).unwrap_err().starts_with("no target unit given"));
+    }
+    
+    #[test]
+    fn test_explicit_target_ignores_system() {
+        let metric = Config { system: Some(UnitSystem::Metric), ..Config::default() };
+        assert_eq!(target_unit(&args(&["5", "mi", "yd"]), &metric), Ok("yd"));
+        assert_eq!(target_unit(&args(&["5", "mi", "yd"]), &Config::default()), Ok("yd"));
+    }
+    
+    #[test]
+    fn test_log_history_failure_is_not_fatal() {
+        let entry = HistoryEntry::now(5.0, "km", "mi", 3.1);
+        let blocker = std::env::temp_dir().join(format!("converter-main-blocked-{}", process::id()));
+        std::fs::write(&blocker, "").unwrap();
+        log_history(Some(&blocker.join("state")), &entry);
+        log_history(None, &entry);
+        std::fs::remove_file(&blocker).unwrap();
+    }
+    
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass"))]
+    fn test_listing_follows_config() {
+        let config = Config::parse("disabled_units = [\"ton\", \"mi\"]\nenabled_categories = [\"length\", \"mass\"]").unwrap();
+        let listing = units_listing(&config.registry(), None);
+        assert!(!listing.contains("  ton "));
+        assert!(!listing.contains("  mi "));
+        assert!(listing.contains("  km "));
+        assert!(listing.contains("Mass:") && !listing.contains("Temperature:"));
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_examples_convert_through_the_argument_pipeline() {
+        let examples = examples(&Registry::default(), None);
+        assert_eq!(examples.len(), converter::categories().len());
+        assert_eq!(examples[0].1, ["5 km mi", "5 mi km", "5 kilometers miles"]);
+        for line in examples.iter().flat_map(|(_, lines)| lines) {
+            let argv: Vec<String> = std::iter::once(PROGRAM).chain(line.split(' ')).map(str::to_string).collect();
+            let (_, args) = parse_args(&argv).unwrap();
+            let args = join_unit_words(args);
+            assert_eq!(args.len(), 4, "{}", line);
+            assert!(convert_checked(&args[1], &args[2], &args[3]).is_ok(), "{}", line);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_examples_skip_disabled_units() {
+        let registry = Registry::restricted(&["mi", "km"], None);
+        let length = &examples(&registry, Some(UnitCategory::Length))[0];
+        assert_eq!(length, &(UnitCategory::Length, vec!["5 m ft".to_string(), "5 ft m".to_string(), "5 meters feet".to_string()]));
+        assert!(examples(&Registry::restricted(&[] as &[&str], Some(vec![UnitCategory::Mass])), Some(UnitCategory::Length)).is_empty());
+    }
+
+    #[test]
+    #[cfg(all(feature = "volume", feature = "speed"))]
+    fn test_examples_spell_a_unit_by_an_alias_once() {
+        let registry = Registry::restricted(&["mi/h"], None);
+        let speed = &examples(&registry, Some(UnitCategory::Speed))[0].1;
+        assert_eq!(speed, &["5 m/s mach", "5 mach m/s", "5 m/s Ma"]);
+        for (category, lines) in examples(&Registry::default(), None) {
+            assert_eq!(lines.len(), 3, "{}", category);
+            assert!(lines.iter().enumerate().all(|(i, line)| !lines[..i].contains(line)), "{:?}", lines);
+        }
+        // Units with no one-word alias give no third line rather than a repeat.
+        let registry = Registry::restricted(&["m3", "L", "mL"], None);
+        assert_eq!(examples(&registry, Some(UnitCategory::Volume))[0].1, ["5 floz-us floz-imp", "5 floz-imp floz-us"]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "mass", feature = "temperature"))]
+    fn test_markdown_tables() {
+        let mass = units_markdown(&Registry::default(), Some(UnitCategory::Mass));
+        let expected = "## Mass
+
+| Symbol | Name | Aliases | Factor to base |
+| --- | --- | --- | --- |
+| kg | kilogram | kilogram, kilograms | 1 |
+| g | gram | gram, grams | 0.001 |
+| mg | milligram | milligram, milligrams | 0.000001 |
+| lb | pound | pound, pounds | 0.45359237 |
+| oz | ounce | ounce, ounces | 0.028349523125 |
+| st | stone | stone, stones | 6.35029318 |
+| ton | ton | tons, tonne, tonnes, metric ton | 1000 |
+
+";
+        assert_eq!(mass, expected);
+        let temperature = units_markdown(&Registry::default(), Some(UnitCategory::Temperature));
+        assert!(temperature.contains("| F | degree Fahrenheit | fahrenheit | F = C × 9/5 + 32 |\n"), "{}", temperature);
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_markdown_escapes_table_syntax() {
+        let config = Config::parse("[[unit]]\nname = \"pipe\"\naliases = [\"a|b\", \"*star*\", \"under_score\"]\ncategory = \"length\"\nfactor = 2\n").unwrap();
+        let length = units_markdown(&config.registry(), Some(UnitCategory::Length));
+        assert!(length.contains("| pipe | pipe | a\\|b, \\*star\\*, under\\_score | 2 |\n"), "{}", length);
+        assert_eq!(markdown_escape("[x]<y>#`\\"), "\\[x\\]\\<y\\>\\#\\`\\\\");
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn test_detailed_listing_orders_by_size() {
+        let listing = units_detailed(&Registry::default(), Some(UnitCategory::Length));
+        let expected = "Length:
+  mm  millimeter  1 mm = 0.001 m
+  cm  centimeter  1 cm = 0.01 m
+  in  inch        1 in = 0.0254 m
+  ft  foot        1 ft = 0.3048 m
+  yd  yard        1 yd = 0.9144 m
+  m   meter
+  km  kilometer   1 km = 1000 m
+  mi  mile        1 mi = 1609.344 m
+
+";
+        assert_eq!(listing, expected);
+        let all = units_detailed(&Registry::default(), None);
+        assert!(all.contains("  F  degree Fahrenheit  F = C × 9/5 + 32\n"), "{}", all);
+        assert!(all.contains("  K  kelvin             K = C + 273.15\n"), "{}", all);
+    }
+}
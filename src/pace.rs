@@ -0,0 +1,187 @@
+//! Running pace: time per distance, the inverse of a speed. `min/km` and
+//! `min/mi` take and print values as minutes and seconds
+//! (`converter 4:30 min/km min/mi`), and any pace converts to a speed and
+//! back by taking the reciprocal (`converter 4:30 min/km km/h`).
+
+use crate::dimension::Dimension;
+use crate::{ConvertError, Converted, Measurement, Unit};
+
+/// The paces written as minutes and seconds, "4:30", rather than a decimal.
+pub const CLOCK_PACES: &[&str] = &["min/km", "min/mi"];
+
+/// Time over length.
+fn pace_dimension() -> Dimension {
+    Dimension::TIME / Dimension::LENGTH
+}
+
+/// Whether `unit` is a pace written as minutes and seconds.
+pub fn is_clock_pace(unit: &str) -> bool {
+    CLOCK_PACES.iter().any(|pace| pace.eq_ignore_ascii_case(unit))
+}
+
+/// Whether one of `from` and `to` is a pace and the other a speed.
+pub fn reciprocal(from: &Unit, to: &Unit) -> bool {
+    from.dimension() == to.dimension().pow(-1) && (from.dimension() == pace_dimension() || to.dimension() == pace_dimension())
+}
+
+/// Whether converting from `from_unit` to `to_unit` needs this module: one
+/// of them is a clock pace, or one is a pace and the other a speed.
+pub fn applies(from_unit: &str, to_unit: &str) -> bool {
+    let units = || Some((crate::lookup_unit(from_unit)?, crate::lookup_unit(to_unit)?));
+    is_clock_pace(from_unit) || is_clock_pace(to_unit) || units().is_some_and(|(from, to)| reciprocal(from, to))
+}
+
+/// Minutes from "4:30" or "4:59.9", or from a plain number of minutes such
+/// as "4.5". The whole seconds take two digits and stay under 60.
+pub fn parse(input: &str) -> Option<f64> {
+    let input = input.trim();
+    let Some((minutes, seconds)) = input.split_once(':') else {
+        return input.parse().ok().filter(|m: &f64| m.is_finite() && *m >= 0.0);
+    };
+    let digits = |text: &str| !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit());
+    let (whole, tenths) = seconds.split_once('.').unwrap_or((seconds, "0"));
+    if !digits(minutes) || !digits(whole) || whole.len() != 2 || !digits(tenths) {
+        return None;
+    }
+    let seconds: f64 = seconds.parse().ok()?;
+    if seconds >= 60.0 {
+        return None;
+    }
+    Some(minutes.parse::<f64>().ok()? + seconds / 60.0)
+}
+
+/// "7:15" for 7.242 minutes: rounded to the second, so 7:59.6 is "8:00"
+/// rather than "7:60".
+pub fn format(minutes: f64) -> String {
+    let seconds = (minutes * 60.0).round() as u64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// A pace as given, to the tenth of a second when it has one: "4:59.9", but
+/// "4:30" rather than "4:30.0".
+pub fn format_input(minutes: f64) -> String {
+    let tenths = (minutes * 600.0).round() as u64;
+    match tenths % 10 {
+        0 => format(minutes),
+        tenth => format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenth),
+    }
+}
+
+/// Converts a pace to a pace or a speed, or a speed to a pace. A clock pace
+/// reads "4:30" as well as a number of minutes.
+pub fn convert(value_arg: &str, from_unit: &str, to_unit: &str) -> Result<Converted, ConvertError> {
+    let from = crate::lookup_unit(from_unit).ok_or_else(|| crate::unit_error(from_unit))?;
+    let to = crate::lookup_unit(to_unit).ok_or_else(|| crate::unit_error(to_unit))?;
+    let value = if is_clock_pace(from_unit) {
+        parse(value_arg).ok_or_else(|| ConvertError::InvalidValue(value_arg.to_string()))?
+    } else {
+        Measurement::parse(value_arg)?.value
+    };
+    convert_units(value, value_arg.trim(), from, to, from_unit, to_unit)
+}
+
+/// `value` of `from` in `to`, where both are paces or one is a speed;
+/// `value_arg`, `from_unit` and `to_unit` are as written, for errors.
+pub(crate) fn convert_units(value: f64, value_arg: &str, from: &Unit, to: &Unit, from_unit: &str, to_unit: &str) -> Result<Converted, ConvertError> {
+    let base = from.to_base(value);
+    let base = if from.dimension() == to.dimension() {
+        base
+    } else if reciprocal(from, to) {
+        1.0 / base
+    } else {
+        return Err(ConvertError::CategoryMismatch { from: from.category, to: to.category, from_unit: from_unit.to_string(), to_unit: to_unit.to_string() });
+    };
+    let result = to.to_unit(base);
+    // A speed of zero is no pace at all, and a subnormal one's overflows.
+    if !result.is_finite() {
+        return Err(ConvertError::ZeroSpeed(format!("{} {}", value_arg, from_unit)));
+    }
+    Ok(Converted { value, result, sigma: None, warnings: Vec::new() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("4:30"), Some(4.5));
+        assert_eq!(parse("0:45"), Some(0.75));
+        assert_eq!(parse("12:00"), Some(12.0));
+        assert_eq!(parse(" 5:06 "), Some(5.1));
+        assert_eq!(parse("4.5"), Some(4.5));
+        assert_eq!(parse("4:59.9"), Some(4.0 + 59.9 / 60.0));
+        assert_eq!(parse("0:07.25"), Some(7.25 / 60.0));
+        for bad in ["4:60", "4:5", "4:305", ":30", "4:", "-4:30", "4:-3", "4.5:30", "a:30", "-4", "", "4:30:00", "4:60.0", "4:5.9", "4:30.", "4:30.-1", "4:30.5.5", "4:.5"] {
+            assert_eq!(parse(bad), None, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_format_rounds_to_the_second() {
+        assert_eq!(format(4.5), "4:30");
+        assert_eq!(format(7.242048), "7:15");
+        assert_eq!(format(0.75), "0:45");
+        assert_eq!(format(12.0), "12:00");
+        // 7:59.7 rounds up into the next minute, never to "7:60".
+        assert_eq!(format(7.0 + 59.7 / 60.0), "8:00");
+        assert_eq!(format(7.0 + 59.4 / 60.0), "7:59");
+        for text in ["3:59", "4:00", "10:01", "59:59"] {
+            assert_eq!(format(parse(text).unwrap()), text);
+        }
+    }
+
+    #[test]
+    fn test_format_input_keeps_tenths() {
+        for text in ["4:59.9", "0:07.5", "4:30", "12:00"] {
+            assert_eq!(format_input(parse(text).unwrap()), text);
+        }
+        assert_eq!(format_input(parse("4:30.0").unwrap()), "4:30");
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "time"))]
+    fn test_pace_to_pace() {
+        let result = convert("4:30", "min/km", "min/mi").unwrap();
+        assert_eq!(result.value, 4.5);
+        assert!((result.result - 7.242048).abs() < 1e-9, "{}", result.result);
+        assert!((convert("8:00", "min/mi", "min/km").unwrap().result - 4.970969538).abs() < 1e-9);
+        assert!((convert("5:00", "min/km", "s/m").unwrap().result - 0.3).abs() < 1e-12);
+        assert_eq!(convert("4:61", "min/km", "min/mi").unwrap_err(), ConvertError::InvalidValue("4:61".to_string()));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "time", feature = "speed"))]
+    fn test_pace_and_speed_are_reciprocal() {
+        let speed = |value: &str, from, to| convert(value, from, to).unwrap().result;
+        assert!((speed("4:30", "min/km", "km/h") - 40.0 / 3.0).abs() < 1e-12);
+        assert!((speed("6:00", "min/km", "km/h") - 10.0).abs() < 1e-12);
+        assert!((speed("10", "km/h", "min/km") - 6.0).abs() < 1e-12);
+        assert!((speed("7.5", "mi/h", "min/mi") - 8.0).abs() < 1e-12);
+        assert!((speed("4:30", "min/km", "mph") - 8.284949229831).abs() < 1e-9);
+        assert!((speed("4:59.9", "min/km", "km/h") - 3600.0 / 299.9).abs() < 1e-9);
+        // There and back gives the pace again.
+        for pace in ["3:15", "4:30", "9:59"] {
+            let kmh = speed(pace, "min/km", "km/h");
+            assert!((speed(&kmh.to_string(), "km/h", "min/km") - parse(pace).unwrap()).abs() < 1e-9, "{}", pace);
+        }
+        // A pace is time over length whatever its units.
+        assert!((speed("4", "m/s", "s/m") - 0.25).abs() < 1e-12);
+        assert_eq!(convert("0", "km/h", "min/km").unwrap_err(), ConvertError::ZeroSpeed("0 km/h".to_string()));
+        assert_eq!(convert("0.0", "mph", "min/mi").unwrap_err(), ConvertError::ZeroSpeed("0.0 mph".to_string()));
+        assert_eq!(convert("1e-320", "m/s", "s/m").unwrap_err(), ConvertError::ZeroSpeed("1e-320 m/s".to_string()));
+        assert_eq!(convert("0", "km/h", "min/km").unwrap_err().to_string(), "0 km/h: a speed of zero has no pace");
+        assert!(matches!(convert("4:30", "min/km", "kg"), Err(ConvertError::CategoryMismatch { .. })));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "time"))]
+    fn test_applies() {
+        assert!(applies("min/km", "min/mi"));
+        assert!(applies("km/h", "MIN/KM"));
+        assert!(applies("s/m", "m/s"));
+        assert!(!applies("km/h", "m/s"));
+        assert!(!applies("s/m", "min/mile"));
+        assert!(!applies("km", "min"));
+    }
+}
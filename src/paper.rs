@@ -0,0 +1,198 @@
+//! `paper`: the dimensions of a paper size, e.g. `paper a4`, and how it
+//! differs from another, e.g. `paper a4 --in letter`.
+//!
+//! The ISO sizes aren't a table: A0 is the sheet of 1 m² with sides in the
+//! ratio √2, B0 has a short side of 1 m, and each next size halves the long
+//! side. As ISO 216 does, every halving rounds down to a whole millimeter,
+//! which is why A4 is 210 × 297 mm rather than 210.2 × 297.3.
+
+use converter::json::Json;
+use converter::units::{IN_TO_M, MM_TO_M};
+
+use crate::Options;
+use crate::i18n::{tr, tr_with};
+
+const COMPARE: &str = "--in";
+/// A0 through A10, and the same for B.
+const LAST_ISO: u32 = 10;
+/// The US sizes, in inches.
+const US: [(&str, f64, f64); 3] = [("Letter", 8.5, 11.0), ("Legal", 8.5, 14.0), ("Tabloid", 11.0, 17.0)];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Paper {
+    pub name: String,
+    /// Portrait: the width is the short side.
+    pub width_mm: f64,
+    pub height_mm: f64,
+}
+
+impl Paper {
+    /// "a4", "B5", "letter" and so on, ignoring case.
+    pub fn lookup(name: &str) -> Result<Paper, String> {
+        let unknown = || tr_with("paper.unknown", &[&name, &LAST_ISO]);
+        let lower = name.to_ascii_lowercase();
+        if let Some(&(us, width, height)) = US.iter().find(|(us, ..)| us.to_ascii_lowercase() == lower) {
+            let mm = |inches: f64| round(inches * IN_TO_M / MM_TO_M, 1);
+            return Ok(Paper { name: us.to_string(), width_mm: mm(width), height_mm: mm(height) });
+        }
+        let (series, number) = lower.split_at_checked(1).ok_or_else(unknown)?;
+        let number: u32 = number.parse().ok().filter(|&n| n <= LAST_ISO).ok_or_else(unknown)?;
+        // The long side of the size-0 sheet, unrounded, then its short side.
+        let long = match series {
+            "a" => 1000.0 * 2f64.powf(0.25),
+            "b" => 1000.0 * 2f64.sqrt(),
+            _ => return Err(unknown()),
+        };
+        let (mut width, mut height) = ((long / 2f64.sqrt()).round(), long.round());
+        for _ in 0..number {
+            (width, height) = ((height / 2.0).floor(), width);
+        }
+        Ok(Paper { name: format!("{}{}", series.to_ascii_uppercase(), number), width_mm: width, height_mm: height })
+    }
+
+    pub fn area_m2(&self) -> f64 {
+        self.width_mm * self.height_mm * MM_TO_M * MM_TO_M
+    }
+}
+
+/// `x` to `places` decimal places.
+fn round(x: f64, places: i32) -> f64 {
+    let scale = 10f64.powi(places);
+    (x * scale).round() / scale
+}
+
+/// Millimeters to inches, to two places unless `--precision` says otherwise.
+fn inches(mm: f64, options: &Options) -> f64 {
+    round(mm * MM_TO_M / IN_TO_M, options.precision.unwrap_or(2) as i32)
+}
+
+/// "A4: 210 × 297 mm (8.27 × 11.69 in)".
+fn dimensions(paper: &Paper, options: &Options) -> String {
+    format!(
+        "{}: {} × {} mm ({} × {} in)",
+        paper.name,
+        paper.width_mm,
+        paper.height_mm,
+        inches(paper.width_mm, options),
+        inches(paper.height_mm, options)
+    )
+}
+
+/// "5.9 mm narrower", "17.6 mm taller" or "as tall", from a difference in
+/// mm; the arguments are the message keys for each case.
+fn difference(mm: f64, smaller: &str, larger: &str, same: &str) -> String {
+    let mm = round(mm, 1);
+    match mm {
+        0.0 => tr(same).to_string(),
+        mm if mm < 0.0 => tr_with(smaller, &[&-mm]),
+        mm => tr_with(larger, &[&mm]),
+    }
+}
+
+/// "A4 is 5.9 mm narrower and 17.6 mm taller than Letter".
+fn comparison(paper: &Paper, other: &Paper) -> String {
+    let width = difference(paper.width_mm - other.width_mm, "paper.narrower", "paper.wider", "paper.as_wide");
+    let height = difference(paper.height_mm - other.height_mm, "paper.shorter", "paper.taller", "paper.as_tall");
+    tr_with("paper.comparison", &[&paper.name, &width, &height, &other.name])
+}
+
+fn paper_json(paper: &Paper, other: Option<&Paper>, options: &Options) -> Json {
+    let mut fields = vec![
+        ("size", Json::from(paper.name.as_str())),
+        ("width_mm", Json::from(paper.width_mm)),
+        ("height_mm", Json::from(paper.height_mm)),
+        ("width_in", Json::from(inches(paper.width_mm, options))),
+        ("height_in", Json::from(inches(paper.height_mm, options))),
+        ("area_m2", Json::from(round(paper.area_m2(), 6))),
+    ];
+    if let Some(other) = other {
+        fields.extend([
+            ("compared_to", Json::from(other.name.as_str())),
+            ("width_difference_mm", Json::from(round(paper.width_mm - other.width_mm, 1))),
+            ("height_difference_mm", Json::from(round(paper.height_mm - other.height_mm, 1))),
+        ]);
+    }
+    Json::object(fields)
+}
+
+/// Runs the `paper` subcommand, returning the process exit code.
+pub fn run(program: &str, args: &[String], options: &Options) -> i32 {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let (name, other) = match args.as_slice() {
+        [name] => (*name, None),
+        [name, COMPARE, other] => (*name, Some(*other)),
+        _ => {
+            eprintln!("{}: {} paper <size> [{} <size>] [--json]", tr("usage_label"), program, COMPARE);
+            return 1;
+        }
+    };
+    let papers = Paper::lookup(name).and_then(|paper| Ok((paper, other.map(Paper::lookup).transpose()?)));
+    let (paper, other) = match papers {
+        Ok(papers) => papers,
+        Err(e) => {
+            eprintln!("{}: {}", tr("error"), e);
+            return 1;
+        }
+    };
+    if options.json {
+        println!("{}", paper_json(&paper, other.as_ref(), options));
+        return 0;
+    }
+    println!("{}", dimensions(&paper, options));
+    if let Some(other) = &other {
+        println!("{}", dimensions(other, options));
+        println!("{}", comparison(&paper, other));
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn size(name: &str) -> (f64, f64) {
+        let paper = Paper::lookup(name).unwrap();
+        (paper.width_mm, paper.height_mm)
+    }
+
+    #[test]
+    fn test_iso_sizes() {
+        assert_eq!(size("a4"), (210.0, 297.0));
+        assert_eq!(size("A0"), (841.0, 1189.0));
+        assert_eq!(size("a1"), (594.0, 841.0));
+        assert_eq!(size("a10"), (26.0, 37.0));
+        assert_eq!(size("b0"), (1000.0, 1414.0));
+        assert_eq!(size("B4"), (250.0, 353.0));
+        assert!((Paper::lookup("a0").unwrap().area_m2() - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_us_sizes() {
+        assert_eq!(size("letter"), (215.9, 279.4));
+        assert_eq!(size("Legal"), (215.9, 355.6));
+        assert_eq!(size("TABLOID"), (279.4, 431.8));
+        assert_eq!(Paper::lookup("letter").unwrap().name, "Letter");
+    }
+
+    #[test]
+    fn test_unknown_sizes() {
+        for name in ["a11", "c4", "a", "", "a-1", "foolscap"] {
+            let error = Paper::lookup(name).unwrap_err();
+            assert_eq!(error, format!("unknown paper size '{}'; use A0–A10, B0–B10, letter, legal or tabloid", name));
+        }
+    }
+
+    #[test]
+    fn test_output() {
+        let options = Options::default();
+        let (a4, letter) = (Paper::lookup("a4").unwrap(), Paper::lookup("letter").unwrap());
+        assert_eq!(dimensions(&a4, &options), "A4: 210 × 297 mm (8.27 × 11.69 in)");
+        assert_eq!(dimensions(&letter, &options), "Letter: 215.9 × 279.4 mm (8.5 × 11 in)");
+        assert_eq!(comparison(&a4, &letter), "A4 is 5.9 mm narrower and 17.6 mm taller than Letter");
+        assert_eq!(comparison(&letter, &Paper::lookup("legal").unwrap()), "Letter is as wide and 76.2 mm shorter than Legal");
+        assert_eq!(
+            paper_json(&a4, Some(&letter), &options).to_string(),
+            r#"{"size":"A4","width_mm":210,"height_mm":297,"width_in":8.27,"height_in":11.69,"area_m2":0.06237,"compared_to":"Letter","width_difference_mm":-5.9,"height_difference_mm":17.6}"#
+        );
+    }
+}
@@ -0,0 +1,60 @@
+//! Order-preserving parallel map for `--batch` and `csv`, on a rayon pool
+//! with the `parallel` feature and serially without it.
+
+use std::num::NonZeroUsize;
+use std::thread;
+
+use crate::Options;
+
+/// Lines or records read and converted together; the output of one chunk is
+/// written before the next is read, so memory stays bounded.
+pub const CHUNK_SIZE: usize = 8192;
+
+/// `--jobs`, defaulting to the machine's available parallelism.
+pub fn jobs(options: &Options) -> usize {
+    options.jobs.unwrap_or_else(|| thread::available_parallelism().map_or(1, NonZeroUsize::get))
+}
+
+/// Applies `f` to every item on up to `jobs` threads, returning the results
+/// in the order of `items`.
+#[cfg(feature = "parallel")]
+pub fn map_ordered<T: Sync, R: Send>(items: &[T], jobs: usize, f: impl Fn(&T) -> R + Sync + Send) -> Vec<R> {
+    use rayon::prelude::*;
+
+    if jobs <= 1 || items.len() < 2 {
+        return items.iter().map(f).collect();
+    }
+    match rayon::ThreadPoolBuilder::new().num_threads(jobs).build() {
+        Ok(pool) => pool.install(|| items.par_iter().map(f).collect()),
+        Err(_) => items.iter().map(f).collect(),
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+pub fn map_ordered<T: Sync, R: Send>(items: &[T], _jobs: usize, f: impl Fn(&T) -> R + Sync + Send) -> Vec<R> {
+    items.iter().map(f).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_is_preserved() {
+        let items: Vec<usize> = (0..10_001).collect();
+        let serial: Vec<usize> = items.iter().map(|i| i * 3).collect();
+        for jobs in [1, 2, 3, 8, 64] {
+            assert_eq!(map_ordered(&items, jobs, |i| i * 3), serial);
+        }
+        assert_eq!(map_ordered(&[] as &[usize], 4, |i| *i), Vec::<usize>::new());
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_uses_up_to_jobs_threads() {
+        let items: Vec<usize> = (0..1000).collect();
+        let threads = std::sync::Mutex::new(std::collections::HashSet::new());
+        map_ordered(&items, 2, |_| threads.lock().unwrap().insert(thread::current().id()));
+        assert!(threads.lock().unwrap().len() <= 2);
+    }
+}
@@ -0,0 +1,133 @@
+//! Progress reporting for `--batch`, `--filter` and `csv`. The conversion loops
+//! report to a `Progress`; on a terminal that draws a status line on stderr,
+//! otherwise (or with `--no-progress`) nothing happens.
+
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+use crate::Options;
+
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+pub trait Progress {
+    /// Called after each line or record with the totals so far.
+    fn tick(&mut self, rows: usize, bytes: u64);
+
+    /// Called once the input is exhausted.
+    fn finish(&mut self) {}
+}
+
+/// Reports nothing.
+pub struct Silent;
+
+impl Progress for Silent {
+    fn tick(&mut self, _rows: usize, _bytes: u64) {}
+}
+
+/// A status line redrawn in place, e.g. "120000 rows, 35% (1.2 MB of 3.4 MB)".
+pub struct Bar<W: Write> {
+    out: W,
+    total_bytes: Option<u64>,
+    last_draw: Option<Instant>,
+}
+
+impl<W: Write> Bar<W> {
+    pub fn new(out: W, total_bytes: Option<u64>) -> Bar<W> {
+        Bar { out, total_bytes, last_draw: None }
+    }
+}
+
+impl<W: Write> Progress for Bar<W> {
+    fn tick(&mut self, rows: usize, bytes: u64) {
+        let now = Instant::now();
+        if self.last_draw.is_some_and(|last| now - last < REDRAW_INTERVAL) {
+            return;
+        }
+        self.last_draw = Some(now);
+        // Progress is best-effort; a failing stderr shouldn't stop the job.
+        let _ = write!(self.out, "\r{}\x1b[K", status(rows, bytes, self.total_bytes));
+        let _ = self.out.flush();
+    }
+
+    fn finish(&mut self) {
+        if self.last_draw.is_some() {
+            let _ = write!(self.out, "\r\x1b[K");
+            let _ = self.out.flush();
+        }
+    }
+}
+
+/// Keeps every tick instead of drawing anything, for tests of the loops.
+#[cfg(all(test, any(feature = "length", feature = "mass")))]
+#[derive(Debug, Default)]
+pub struct Recorder {
+    pub ticks: Vec<(usize, u64)>,
+    pub finished: bool,
+}
+
+#[cfg(all(test, any(feature = "length", feature = "mass")))]
+impl Progress for Recorder {
+    fn tick(&mut self, rows: usize, bytes: u64) {
+        self.ticks.push((rows, bytes));
+    }
+
+    fn finish(&mut self) {
+        self.finished = true;
+    }
+}
+
+pub fn status(rows: usize, bytes: u64, total_bytes: Option<u64>) -> String {
+    let noun = if rows == 1 { "row" } else { "rows" };
+    match total_bytes {
+        Some(total) if total > 0 => {
+            let percent = (bytes as f64 / total as f64 * 100.0).min(100.0);
+            format!("{} {}, {:.0}% ({} of {})", rows, noun, percent, size(bytes), size(total))
+        }
+        _ => format!("{} {}, {} read", rows, noun, size(bytes)),
+    }
+}
+
+fn size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 { format!("{} B", bytes) } else { format!("{:.1} {}", value, UNITS[unit]) }
+}
+
+/// The reporter for a job reading `path` ("-" for stdin): a bar when stderr is
+/// a terminal and `--no-progress` wasn't given, sized from the file if possible.
+pub fn for_input(path: &str, options: &Options) -> Box<dyn Progress> {
+    if options.no_progress || !io::stderr().is_terminal() {
+        return Box::new(Silent);
+    }
+    let total = (path != "-").then(|| fs::metadata(path).ok().map(|m| m.len())).flatten();
+    Box::new(Bar::new(io::stderr(), total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_line() {
+        assert_eq!(status(1, 10, None), "1 row, 10 B read");
+        assert_eq!(status(120_000, 1_234_567, Some(3_400_000)), "120000 rows, 36% (1.2 MB of 3.4 MB)");
+        assert_eq!(status(0, 0, Some(0)), "0 rows, 0 B read");
+    }
+
+    #[test]
+    fn test_bar_draws_and_clears() {
+        let mut out = Vec::new();
+        let mut bar = Bar::new(&mut out, Some(100));
+        bar.tick(1, 50);
+        // Too soon after the first draw to redraw.
+        bar.tick(2, 100);
+        bar.finish();
+        assert_eq!(String::from_utf8(out).unwrap(), "\r1 row, 50% (50 B of 100 B)\x1b[K\r\x1b[K");
+    }
+}
@@ -0,0 +1,258 @@
+//! JSON-lines protocol for `--serve-stdio`: one request object per input
+//! line, one response object per output line.
+//!
+//! Request:  `{"value": 5, "from": "km", "to": "mi", "precision": 2, "id": 1}`
+//! Response: `{"ok": true, "value": 5, "from": "km", "to": "mi", "result": 3.11, "category": "length",
+//!            "factor": 0.621371192237334, "warnings": [], "id": 1}`
+//! Failure:  `{"ok": false, "error": "unknown_unit", "message": "Unknown unit 'x'", "id": 1}`
+//!
+//! `precision` and `id` are optional; `id` is echoed back untouched.
+
+use std::io::{self, BufRead, Write};
+
+use crate::json::{self, Json};
+use crate::{ConvertError, Options};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub id: Option<Json>,
+    pub value: f64,
+    pub from: String,
+    pub to: String,
+    pub precision: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Response {
+    /// `record` is the conversion as `--json` prints it.
+    Ok {
+        id: Option<Json>,
+        record: Json,
+    },
+    Error {
+        id: Option<Json>,
+        code: &'static str,
+        message: String,
+    },
+}
+
+impl Request {
+    pub fn from_json(value: &Json) -> Result<Request, String> {
+        if !matches!(value, Json::Object(_)) {
+            return Err("request must be a JSON object".to_string());
+        }
+        let text = |key: &str| -> Result<String, String> {
+            value
+                .get(key)
+                .and_then(Json::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| format!("'{}' must be a unit name string", key))
+        };
+        let number = match value.get("value") {
+            Some(Json::Number(n)) => *n,
+            Some(Json::String(s)) => s.trim().parse().map_err(|_| format!("'{}' is not a valid number", s))?,
+            _ => return Err("'value' must be a number".to_string()),
+        };
+        let precision = match value.get("precision") {
+            None | Some(Json::Null) => None,
+            Some(Json::Number(p)) if *p >= 0.0 && p.fract() == 0.0 => Some(*p as usize),
+            Some(_) => return Err("'precision' must be a non-negative integer".to_string()),
+        };
+        Ok(Request {
+            id: value.get("id").cloned(),
+            value: number,
+            from: text("from")?,
+            to: text("to")?,
+            precision,
+        })
+    }
+}
+
+impl Response {
+    pub fn to_json(&self) -> Json {
+        let (mut fields, id) = match self {
+            Response::Ok { id, record } => {
+                let mut fields = vec![("ok", Json::from(true))];
+                if let Json::Object(record) = record {
+                    fields.extend(record.iter().map(|(key, value)| (key.as_str(), value.clone())));
+                }
+                (fields, id)
+            }
+            Response::Error { id, code, message } => (
+                vec![("ok", Json::from(false)), ("error", Json::from(*code)), ("message", Json::from(message.as_str()))],
+                id,
+            ),
+        };
+        if let Some(id) = id {
+            fields.push(("id", id.clone()));
+        }
+        Json::object(fields)
+    }
+}
+
+pub fn error_code(e: &ConvertError) -> &'static str {
+    match e {
+        ConvertError::InvalidValue(_) | ConvertError::NotFinite(_) | ConvertError::NegativeUncertainty(_) => "invalid_value",
+        ConvertError::NoGauge(_) => "no_gauge",
+        ConvertError::UnknownUnit { .. } => "unknown_unit",
+        ConvertError::UnknownCurrency { .. } => "unknown_currency",
+        ConvertError::DisabledUnit { .. } => "disabled_unit",
+        ConvertError::AmbiguousUnit { .. } => "ambiguous_unit",
+        ConvertError::CategoryMismatch { .. } => "category_mismatch",
+        ConvertError::BelowAbsoluteZero { .. } => "below_absolute_zero",
+        ConvertError::OffsetInCompound { .. } => "offset_in_compound",
+        ConvertError::NoPath { .. } => "no_path",
+        ConvertError::Overflow { .. } => "overflow",
+        ConvertError::ZeroSpeed(_) => "zero_speed",
+        ConvertError::MissingUnit(_) => "missing_unit",
+    }
+}
+
+/// `e` as the JSON object `--json` prints on stderr: `ok` is false, `error`
+/// is its code, `message` the English message, and the rest of the fields
+/// are what the error is about, named after the variant's fields.
+pub fn error_json(e: &ConvertError) -> Json {
+    let text = |s: &str| Json::from(s);
+    let names = |names: &[&str]| Json::Array(names.iter().map(|&n| text(n)).collect());
+    let mut fields = vec![("ok", Json::from(false)), ("error", text(error_code(e))), ("message", Json::from(e.to_string()))];
+    match e {
+        ConvertError::InvalidValue(input) | ConvertError::NotFinite(input) | ConvertError::NegativeUncertainty(input) | ConvertError::NoGauge(input) => {
+            fields.push(("input", text(input)))
+        }
+        ConvertError::UnknownUnit { input, suggestions } => {
+            let suggestions: Vec<&str> = suggestions.iter().map(String::as_str).collect();
+            fields.extend([("input", text(input)), ("suggestions", names(&suggestions))]);
+        }
+        ConvertError::CategoryMismatch { from, to, from_unit, to_unit } => fields.extend([
+            ("from", text(from_unit)),
+            ("to", text(to_unit)),
+            ("from_category", Json::from(from.name().to_lowercase())),
+            ("to_category", Json::from(to.name().to_lowercase())),
+            ("from_dimension", Json::from(from.dimension().to_string())),
+            ("to_dimension", Json::from(to.dimension().to_string())),
+        ]),
+        ConvertError::BelowAbsoluteZero { unit, value, limit } => fields.extend([("unit", text(unit)), ("value", Json::from(*value)), ("limit", Json::from(*limit))]),
+        ConvertError::OffsetInCompound { unit, input } => fields.extend([("unit", text(unit)), ("input", text(input))]),
+        ConvertError::DisabledUnit { unit, alternatives } => fields.extend([("unit", text(unit)), ("alternatives", names(alternatives))]),
+        ConvertError::NoPath { from, to } => fields.extend([("from", text(from)), ("to", text(to))]),
+        ConvertError::Overflow { value, from, to } => fields.extend([("value", Json::from(*value)), ("from", text(from)), ("to", text(to))]),
+        ConvertError::UnknownCurrency { code, known } => fields.extend([("input", text(code)), ("currencies", names(known))]),
+        ConvertError::AmbiguousUnit { input, choices } => fields.extend([("input", text(input)), ("choices", names(choices))]),
+        ConvertError::ZeroSpeed(input) | ConvertError::MissingUnit(input) => fields.push(("input", text(input))),
+    }
+    Json::object(fields)
+}
+
+pub fn handle_request(request: &Request) -> Response {
+    match crate::convert_recorded(&request.value.to_string(), &request.from, &request.to) {
+        Ok(record) => {
+            let options = Options { precision: request.precision, ..Options::default() };
+            Response::Ok { id: request.id.clone(), record: crate::result_json(&record, &options) }
+        }
+        Err(e) => Response::Error { id: request.id.clone(), code: error_code(&e), message: e.to_string() },
+    }
+}
+
+pub fn handle_line(line: &str) -> Response {
+    let parsed = match json::parse(line) {
+        Ok(parsed) => parsed,
+        Err(e) => return Response::Error { id: None, code: "bad_request", message: format!("invalid JSON: {}", e) },
+    };
+    match Request::from_json(&parsed) {
+        Ok(request) => handle_request(&request),
+        Err(message) => Response::Error { id: parsed.get("id").cloned(), code: "bad_request", message },
+    }
+}
+
+/// Answers requests until end of input, flushing after every response so
+/// callers can interleave writes and reads on one long-lived process.
+pub fn serve(input: impl BufRead, out: &mut impl Write) -> io::Result<()> {
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        writeln!(out, "{}", handle_line(&line).to_json())?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature"))]
+    fn test_serve_mixed_script() {
+        let script = concat!(
+            "{\"value\":5,\"from\":\"km\",\"to\":\"mi\",\"precision\":2,\"id\":1}\n",
+            "hello\n",
+            "\n",
+            "{\"value\":1,\"from\":\"km\",\"to\":\"furlong\",\"id\":\"b\"}\n",
+            "{\"value\":1,\"from\":\"km\",\"to\":\"kg\"}\n",
+            "{\"from\":\"km\",\"to\":\"mi\",\"id\":4}\n",
+            "{\"value\":-300,\"from\":\"C\",\"to\":\"F\"}\n",
+            "{\"value\":\"100\",\"from\":\"C\",\"to\":\"F\"}\n",
+        );
+        let mut out = Vec::new();
+        serve(script.as_bytes(), &mut out).unwrap();
+        let lines: Vec<String> = String::from_utf8(out).unwrap().lines().map(str::to_string).collect();
+        assert_eq!(
+            lines,
+            vec![
+                r#"{"ok":true,"value":5,"from":"km","to":"mi","result":3.11,"category":"length","factor":0.621371192237334,"warnings":[],"id":1}"#,
+                r#"{"ok":false,"error":"bad_request","message":"invalid JSON: unexpected character 'h' at position 0"}"#,
+                r#"{"ok":false,"error":"unknown_unit","message":"Unknown unit 'furlong'","id":"b"}"#,
+                r#"{"ok":false,"error":"category_mismatch","message":"Cannot convert between different unit categories (L¹ vs M¹)\n  km is a Length unit\n  kg is a Mass unit"}"#,
+                r#"{"ok":false,"error":"bad_request","message":"'value' must be a number","id":4}"#,
+                r#"{"ok":false,"error":"below_absolute_zero","message":"-300 C is below absolute zero (-273.15 C)"}"#,
+                r#"{"ok":true,"value":100,"from":"C","to":"F","result":212,"category":"temperature","factor":null,"warnings":[]}"#,
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_warnings_ride_along() {
+        let response = handle_line(r#"{"value":-1,"from":"m","to":"cm"}"#);
+        assert_eq!(
+            response.to_json().to_string(),
+            r#"{"ok":true,"value":-1,"from":"m","to":"cm","result":-100,"category":"length","factor":100,"warnings":["Negative length doesn't make physical sense"]}"#
+        );
+    }
+
+    #[test]
+    fn test_error_json_carries_the_variant_fields() {
+        let json = |e: ConvertError| error_json(&e).to_string();
+        assert_eq!(
+            json(ConvertError::DisabledUnit { unit: "ton".to_string(), alternatives: vec!["kg", "lb"] }),
+            r#"{"ok":false,"error":"disabled_unit","message":"unit 'ton' is disabled by your configuration; use 'kg' or 'lb'","unit":"ton","alternatives":["kg","lb"]}"#
+        );
+        assert_eq!(
+            json(ConvertError::BelowAbsoluteZero { unit: "K", value: -1.0, limit: 0.0 }),
+            r#"{"ok":false,"error":"below_absolute_zero","message":"-1 K is below absolute zero (0 K)","unit":"K","value":-1,"limit":0}"#
+        );
+        assert_eq!(
+            json(ConvertError::InvalidValue("abc".to_string())),
+            r#"{"ok":false,"error":"invalid_value","message":"'abc' is not a valid number","input":"abc"}"#
+        );
+        assert_eq!(
+            json(ConvertError::NoGauge("50".to_string())),
+            r#"{"ok":false,"error":"no_gauge","message":"'50' is not a wire gauge from 4/0 to 40","input":"50"}"#
+        );
+        assert_eq!(
+            json(ConvertError::UnknownCurrency { code: "XYZ".to_string(), known: vec!["EUR", "USD"] }),
+            r#"{"ok":false,"error":"unknown_currency","message":"currency 'XYZ' is not in the rates file; use 'EUR' or 'USD'","input":"XYZ","currencies":["EUR","USD"]}"#
+        );
+    }
+
+    #[test]
+    fn test_request_validation() {
+        let parse = |s: &str| Request::from_json(&json::parse(s).unwrap());
+        assert!(parse("[1]").is_err());
+        assert!(parse(r#"{"value":1,"from":"m"}"#).is_err());
+        assert!(parse(r#"{"value":1,"from":"m","to":"cm","precision":1.5}"#).is_err());
+        assert_eq!(parse(r#"{"value":1,"from":"m","to":"cm","precision":null}"#).unwrap().precision, None);
+    }
+}
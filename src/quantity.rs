@@ -0,0 +1,133 @@
+//! A value together with its unit, so library users can pass "5 km" around
+//! as one thing and convert it step by step.
+//!
+//! ```
+//! # #[cfg(feature = "length")] {
+//! use converter::Quantity;
+//!
+//! let feet = Quantity::parse("5 km")?.to("mi")?.to("ft")?;
+//! assert_eq!(feet.unit.name, "ft");
+//! assert!((feet.value - 16404.199).abs() < 1e-3);
+//! # }
+//! # Ok::<(), converter::ConvertError>(())
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{ConvertError, Unit};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Quantity {
+    pub value: f64,
+    pub unit: &'static Unit,
+}
+
+impl Quantity {
+    pub fn new(value: f64, unit: &str) -> Result<Quantity, ConvertError> {
+        let unit = crate::find_unit(unit).ok_or_else(|| crate::unit_error(unit))?;
+        Ok(Quantity { value, unit })
+    }
+
+    /// Reads "5 km", "5km" or "1.5e-3 metric ton". A value that isn't a
+    /// number, or no unit at all, is an `InvalidValue`.
+    pub fn parse(input: &str) -> Result<Quantity, ConvertError> {
+        let input = input.trim();
+        let invalid = || ConvertError::InvalidValue(input.to_string());
+        let (value, unit) = match input.split_once(char::is_whitespace) {
+            Some((value, unit)) => (value.parse().map_err(|_| invalid())?, unit.trim()),
+            None => split_quantity(input).ok_or_else(invalid)?,
+        };
+        Quantity::new(value, unit)
+    }
+
+    /// This quantity in `unit`, which must be of the same category.
+    pub fn to(&self, unit: &str) -> Result<Quantity, ConvertError> {
+        let value = crate::convert(self.value, self.unit.name, unit)?;
+        let unit = crate::lookup_unit(unit).expect("convert resolved it");
+        Ok(Quantity { value, unit })
+    }
+}
+
+/// Splits a glued quantity such as "5ft" or "1.5e-3kg" into value and unit,
+/// taking the longest numeric prefix.
+pub fn split_quantity(input: &str) -> Option<(f64, &str)> {
+    (1..input.len())
+        .rev()
+        .filter(|&i| input.is_char_boundary(i))
+        .filter(|&i| !input[i..].starts_with(|c: char| c.is_ascii_digit() || c == '.'))
+        .find_map(|i| Some((input[..i].parse().ok()?, input[i..].trim())))
+        .filter(|(_, unit)| !unit.is_empty())
+}
+
+/// Equal values in the same unit; 1 km and 1000 m are different quantities.
+impl PartialEq for Quantity {
+    fn eq(&self, other: &Quantity) -> bool {
+        self.value == other.value && self.unit.name == other.unit.name
+    }
+}
+
+impl FromStr for Quantity {
+    type Err = ConvertError;
+
+    fn from_str(input: &str) -> Result<Quantity, ConvertError> {
+        Quantity::parse(input)
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.value, self.unit.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_quantity() {
+        assert_eq!(split_quantity("5ft"), Some((5.0, "ft")));
+        assert_eq!(split_quantity("1.5e-3kg"), Some((1.5e-3, "kg")));
+        assert_eq!(split_quantity("-2m"), Some((-2.0, "m")));
+        assert_eq!(split_quantity("12"), None);
+        assert_eq!(split_quantity("ft"), None);
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature", feature = "time"))]
+    fn test_parse_round_trips() {
+        for input in ["5 km", "0.25 mi", "-40 F", "0.5 h", "3 ton"] {
+            assert_eq!(Quantity::parse(input).unwrap().to_string(), input);
+        }
+        let glued: Quantity = "5km".parse().unwrap();
+        assert_eq!(glued, Quantity::parse("  5   km ").unwrap());
+        assert_eq!(glued.to_string(), "5 km");
+        assert_eq!(Quantity::parse("2 metric ton").unwrap().to_string(), "2 ton");
+        assert_eq!(Quantity::parse("6 Pounds").unwrap(), Quantity::new(6.0, "lb").unwrap());
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn test_conversion_chains() -> Result<(), ConvertError> {
+        let feet = Quantity::parse("5 km")?.to("mi")?.to("ft")?;
+        assert_eq!(feet.unit.name, "ft");
+        assert!((feet.value - 16404.199475).abs() < 1e-6);
+        assert_eq!(Quantity::parse("100 C")?.to("F")?.to_string(), "212 F");
+        assert_ne!(Quantity::parse("1 km")?, Quantity::parse("1000 m")?);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature"))]
+    fn test_parse_failures() {
+        let invalid = |input: &str| Err(ConvertError::InvalidValue(input.to_string()));
+        assert_eq!(Quantity::parse("km"), invalid("km"));
+        assert_eq!(Quantity::parse("5"), invalid("5"));
+        assert_eq!(Quantity::parse(""), invalid(""));
+        assert_eq!(Quantity::parse("five km"), invalid("five km"));
+        assert!(matches!(Quantity::parse("5 furlong"), Err(ConvertError::UnknownUnit { .. })));
+        assert!(matches!(Quantity::parse("5 kg").unwrap().to("m"), Err(ConvertError::CategoryMismatch { .. })));
+        assert!(matches!(Quantity::parse("-5 K").unwrap().to("C"), Err(ConvertError::BelowAbsoluteZero { .. })));
+    }
+}
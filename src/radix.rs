@@ -0,0 +1,194 @@
+//! `base`: converts whole numbers between bases, e.g.
+//! `base 255 --from dec --to hex` prints `ff`. A base is bin, oct, dec, hex
+//! or any radix from 2 to 36; a 0x, 0o or 0b prefix names the input's base
+//! when `--from` doesn't. Numbers may be negative and go up to 2¹²⁸ − 1 either
+//! way, with `_` allowed between digits.
+
+use crate::i18n::{tr, tr_with};
+
+const USAGE: &str = "base <number> [--from BASE] [--to BASE] [--upper] [--group N]";
+const NAMED: [(&str, u32); 4] = [("bin", 2), ("oct", 8), ("dec", 10), ("hex", 16)];
+const PREFIXES: [(&str, u32); 3] = [("0x", 16), ("0o", 8), ("0b", 2)];
+const DIGIT_SEPARATOR: char = '_';
+
+#[derive(Debug, Clone, PartialEq)]
+struct Job {
+    number: String,
+    from: Option<u32>,
+    to: u32,
+    upper: bool,
+    group: Option<usize>,
+}
+
+/// "hex", "16" and the like; a radix from 2 to 36.
+fn base(name: &str) -> Result<u32, String> {
+    let named = NAMED.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, radix)| *radix);
+    named
+        .or_else(|| name.parse().ok().filter(|radix| (2..=36).contains(radix)))
+        .ok_or_else(|| tr_with("radix.not_a_base", &[&name]))
+}
+
+fn parse_job(args: &[String]) -> Result<Job, String> {
+    let mut number = None;
+    let (mut from, mut to, mut upper, mut group) = (None, 10, false, None);
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().cloned().ok_or_else(|| tr_with("requires_value", &[arg]));
+        match arg.as_str() {
+            "--from" => from = Some(base(&value()?)?),
+            "--to" => to = base(&value()?)?,
+            "--upper" => upper = true,
+            "--group" => {
+                let size = value()?;
+                group = Some(size.parse().ok().filter(|&n: &usize| n > 0).ok_or_else(|| tr_with("radix.bad_group", &[&size]))?);
+            }
+            _ if number.is_none() => number = Some(arg.clone()),
+            _ => return Err(tr_with("unexpected_argument", &[arg])),
+        }
+    }
+    Ok(Job { number: number.ok_or_else(|| tr("radix.number_required"))?, from, to, upper, group })
+}
+
+/// `input` in base `from`, or in the base its prefix names, as a sign and a
+/// magnitude. A prefix only counts in its own base, so "0b1" in hex is 0xb1.
+pub fn parse(input: &str, from: Option<u32>) -> Result<(bool, u128), String> {
+    let (negative, unsigned) = match input.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, input),
+    };
+    let prefixed = PREFIXES.iter().find(|(prefix, radix)| {
+        unsigned.len() > prefix.len() && unsigned[..prefix.len()].eq_ignore_ascii_case(prefix) && from.is_none_or(|from| from == *radix)
+    });
+    let (radix, digits) = match prefixed {
+        Some((prefix, radix)) => (*radix, &unsigned[prefix.len()..]),
+        None => (from.unwrap_or(10), unsigned),
+    };
+    if digits.is_empty() || digits.starts_with(DIGIT_SEPARATOR) || digits.ends_with(DIGIT_SEPARATOR) {
+        return Err(tr_with("radix.not_a_number", &[&input]));
+    }
+    let mut magnitude: u128 = 0;
+    for c in digits.chars().filter(|&c| c != DIGIT_SEPARATOR) {
+        let digit = c.to_digit(radix).ok_or_else(|| tr_with("radix.not_a_digit", &[&c, &radix]))?;
+        magnitude = magnitude
+            .checked_mul(radix as u128)
+            .and_then(|m| m.checked_add(digit as u128))
+            .ok_or_else(|| tr_with("radix.too_large", &[&input, &u128::MAX]))?;
+    }
+    Ok((negative && magnitude != 0, magnitude))
+}
+
+/// The digits of `magnitude` in `radix`, with `_` between groups of `group`
+/// counted from the right.
+pub fn format(negative: bool, magnitude: u128, radix: u32, upper: bool, group: Option<usize>) -> String {
+    let mut digits = Vec::new();
+    let mut rest = magnitude;
+    loop {
+        let digit = char::from_digit((rest % radix as u128) as u32, radix).expect("below the radix");
+        digits.push(if upper { digit.to_ascii_uppercase() } else { digit });
+        rest /= radix as u128;
+        if rest == 0 {
+            break;
+        }
+    }
+    let mut text = String::new();
+    for (i, digit) in digits.iter().enumerate().rev() {
+        text.push(*digit);
+        if group.is_some_and(|size| i > 0 && i % size == 0) {
+            text.push(DIGIT_SEPARATOR);
+        }
+    }
+    if negative { format!("-{}", text) } else { text }
+}
+
+fn convert(job: &Job) -> Result<String, String> {
+    let (negative, magnitude) = parse(&job.number, job.from)?;
+    Ok(format(negative, magnitude, job.to, job.upper, job.group))
+}
+
+/// Runs the `base` subcommand, returning the process exit code.
+pub fn run(program: &str, args: &[String]) -> i32 {
+    let result = parse_job(args).map_err(|e| (e, true)).and_then(|job| convert(&job).map_err(|e| (e, false)));
+    match result {
+        Ok(text) => {
+            println!("{}", text);
+            0
+        }
+        Err((e, usage)) => {
+            eprintln!("{}: {}", tr("error"), e);
+            if usage {
+                eprintln!("{}: {} {}", tr("usage_label"), program, USAGE);
+            }
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base_args(args: &str) -> Result<String, String> {
+        let args: Vec<String> = args.split_whitespace().map(String::from).collect();
+        convert(&parse_job(&args)?)
+    }
+
+    #[test]
+    fn test_named_and_numeric_bases() {
+        assert_eq!(base_args("255 --from dec --to hex"), Ok("ff".to_string()));
+        assert_eq!(base_args("255 --to bin"), Ok("11111111".to_string()));
+        assert_eq!(base_args("777 --from oct"), Ok("511".to_string()));
+        assert_eq!(base_args("zz --from 36 --to 10"), Ok("1295".to_string()));
+        assert_eq!(base_args("10 --to 37").unwrap_err(), "'37' is not a base; use bin, oct, dec, hex or a number from 2 to 36");
+    }
+
+    #[test]
+    fn test_round_trips() {
+        for value in [0, 1, 35, 255, 65535, 1 << 64, u128::MAX] {
+            for radix in [2, 3, 8, 10, 16, 36] {
+                for negative in [false, true] {
+                    let text = format(negative && value != 0, value, radix, radix % 2 == 0, None);
+                    assert_eq!(parse(&text, Some(radix)), Ok((negative && value != 0, value)), "{} in base {}", text, radix);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_prefixes() {
+        assert_eq!(parse("0xff", None), Ok((false, 255)));
+        assert_eq!(parse("-0B1010", None), Ok((true, 10)));
+        assert_eq!(parse("0o17", Some(8)), Ok((false, 15)));
+        // In hex, "0b1" is digits, not a prefix.
+        assert_eq!(parse("0b1", Some(16)), Ok((false, 0xb1)));
+        assert_eq!(parse("0x", None), Err("'x' is not a digit in base 10".to_string()));
+    }
+
+    #[test]
+    fn test_u128_boundary() {
+        let max = u128::MAX.to_string();
+        assert_eq!(parse(&max, None), Ok((false, u128::MAX)));
+        assert_eq!(format(false, u128::MAX, 16, false, None), "f".repeat(32));
+        assert_eq!(parse(&format!("-{}", max), None), Ok((true, u128::MAX)));
+        let over = "340282366920938463463374607431768211456";
+        assert_eq!(parse(over, None), Err(format!("'{}' is too large; the largest magnitude is 2^128 - 1 ({})", over, max)));
+        assert!(parse(&format!("1{}", "0".repeat(32)), Some(16)).is_err());
+    }
+
+    #[test]
+    fn test_invalid_digits() {
+        assert_eq!(parse("12a", None), Err("'a' is not a digit in base 10".to_string()));
+        assert_eq!(parse("102", Some(2)), Err("'2' is not a digit in base 2".to_string()));
+        assert_eq!(parse("0xfg", None), Err("'g' is not a digit in base 16".to_string()));
+        assert_eq!(parse("-", None), Err("'-' is not a number".to_string()));
+        assert_eq!(parse("_1", None), Err("'_1' is not a number".to_string()));
+    }
+
+    #[test]
+    fn test_output_formatting() {
+        assert_eq!(base_args("255 --to bin --group 4"), Ok("1111_1111".to_string()));
+        assert_eq!(base_args("1_000_000 --to hex --upper --group 2"), Ok("F_42_40".to_string()));
+        assert_eq!(base_args("-0xFF"), Ok("-255".to_string()));
+        assert_eq!(base_args("-0 --to hex"), Ok("0".to_string()));
+        assert_eq!(base_args("255 --group 0").unwrap_err(), "'0' is not a valid group size");
+    }
+}
@@ -0,0 +1,279 @@
+//! Currency rates: a file giving how much of each currency one unit of a
+//! base currency buys, which makes every listed currency a unit of the
+//! Currency category. In TOML:
+//!
+//! ```toml
+//! base = "USD"
+//! date = "2024-05-01"
+//!
+//! [rates]
+//! EUR = 0.9342
+//! JPY = 157.8
+//! ```
+//!
+//! or the same as JSON, `{"base": "USD", "date": "2024-05-01", "rates": {"EUR": 0.9342}}`.
+//! Every conversion goes through the base, so GBP to JPY is GBP to USD to JPY.
+
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+use converter::json::{self, Json};
+use converter::registry::{UnitDef, is_currency_code};
+use converter::UnitCategory;
+
+use crate::toml;
+
+/// Decimal places of a currency result when no `--precision` is given.
+pub const PLACES: usize = 2;
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rates {
+    /// The file as given, for messages.
+    pub path: String,
+    pub base: String,
+    /// The day the rates are for, "2024-05-01".
+    pub date: String,
+    /// Units of each currency one unit of the base buys, the base's own 1 first.
+    pub rates: Vec<(String, f64)>,
+}
+
+impl Rates {
+    /// Reads `path` as JSON when it ends in `.json` and as TOML otherwise.
+    pub fn load(path: &str) -> Result<Rates, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => format!("rates file {} not found", path),
+            _ => format!("can't read rates file {}: {}", path, e),
+        })?;
+        let json = Path::new(path).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let parsed = if json { Rates::parse_json(&text) } else { Rates::parse_toml(&text) };
+        let mut rates = parsed.map_err(|e| format!("rates file {}: {}", path, e))?;
+        rates.path = path.to_string();
+        Ok(rates)
+    }
+
+    pub fn parse_toml(text: &str) -> Result<Rates, String> {
+        let doc = toml::parse(text).map_err(|e| e.to_string())?;
+        let field = |key: &str| doc.get(key).map(|value| value.as_str().ok_or_else(|| format!("'{}' must be a string", key))).transpose();
+        let table = doc.tables.iter().find(|t| !t.array && t.name == "rates").ok_or("there is no [rates] table")?;
+        let rates = table
+            .entries
+            .iter()
+            .map(|entry| Ok((entry.key.as_str(), entry.value.as_f64().ok_or_else(|| format!("line {}: the rate of {} must be a number", entry.line, entry.key))?)))
+            .collect::<Result<Vec<_>, String>>()?;
+        Rates::new(field("base")?, field("date")?, rates)
+    }
+
+    pub fn parse_json(text: &str) -> Result<Rates, String> {
+        Rates::from_json(&json::parse(text)?)
+    }
+
+    /// Rates in the JSON shape, which is also what rate services answer with.
+    pub fn from_json(doc: &Json) -> Result<Rates, String> {
+        let field = |key: &str| doc.get(key).map(|value| value.as_str().ok_or_else(|| format!("'{}' must be a string", key))).transpose();
+        let Some(Json::Object(fields)) = doc.get("rates") else {
+            return Err("there is no \"rates\" object".to_string());
+        };
+        let rates = fields
+            .iter()
+            .map(|(code, rate)| Ok((code.as_str(), rate.as_f64().ok_or_else(|| format!("the rate of {} must be a number", code))?)))
+            .collect::<Result<Vec<_>, String>>()?;
+        Rates::new(field("base")?, field("date")?, rates)
+    }
+
+    fn new(base: Option<&str>, date: Option<&str>, listed: Vec<(&str, f64)>) -> Result<Rates, String> {
+        let base = base.ok_or("there is no 'base' currency")?;
+        let date = date.ok_or("there is no 'date', so there's no telling how old the rates are")?;
+        if date.len() != 10 || crate::epoch::parse_iso(date, None).is_err() {
+            return Err(format!("'{}' is not a date like 2024-05-01", date));
+        }
+        let mut rates = vec![(base.to_string(), 1.0)];
+        for (code, rate) in std::iter::once((base, 1.0)).chain(listed) {
+            if !is_currency_code(code) {
+                return Err(format!("'{}' is not a currency code such as EUR", code));
+            }
+            if !(rate.is_finite() && rate > 0.0) {
+                return Err(format!("the rate of {} must be a positive number, not {}", code, rate));
+            }
+            // The base may be listed too, at 1.
+            if code == base {
+                if rate != 1.0 {
+                    return Err(format!("the base currency {} must have a rate of 1, not {}", base, rate));
+                }
+                continue;
+            }
+            if rates.iter().any(|(seen, _)| seen == code) {
+                return Err(format!("{} is listed twice", code));
+            }
+            rates.push((code.to_string(), rate));
+        }
+        Ok(Rates { path: String::new(), base: base.to_string(), date: date.to_string(), rates })
+    }
+
+    /// A unit per currency, whose factor is the base's worth of one of it.
+    pub fn units(&self) -> Vec<UnitDef> {
+        let unit = |(code, rate): &(String, f64)| UnitDef {
+            name: code.clone(),
+            aliases: Vec::new(),
+            case_sensitive: true,
+            category: UnitCategory::Currency,
+            factor: 1.0 / rate,
+            offset: 0.0,
+            singular: None,
+            plural: None,
+        };
+        self.rates.iter().map(unit).collect()
+    }
+
+    /// Days from the rates' date to `today`, both in days since the epoch.
+    pub fn age(&self, today: i64) -> i64 {
+        let date = crate::epoch::parse_iso(&self.date, None).expect("checked when loaded");
+        today - date.div_euclid(MILLIS_PER_DAY)
+    }
+}
+
+/// Today in days since the epoch.
+pub fn today() -> i64 {
+    let millis = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_millis() as i64);
+    millis.div_euclid(MILLIS_PER_DAY)
+}
+
+static ACTIVE: OnceLock<Rates> = OnceLock::new();
+
+/// Makes `rates` the ones conversions report; the first call wins.
+pub fn install(rates: Rates) {
+    let _ = ACTIVE.set(rates);
+}
+
+pub fn active() -> Option<&'static Rates> {
+    ACTIVE.get()
+}
+
+/// `x` to `places` decimal places, a tie going to the even digit: 0.125
+/// is 0.12 and 0.135 is 0.14. Ties are judged on the decimal `x` prints as,
+/// so 2.675, a double just below it, still counts as one.
+pub fn round_half_even(x: f64, places: usize) -> f64 {
+    let x = crate::significant(x);
+    let text = x.to_string();
+    let (sign, unsigned) = match text.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", text.as_str()),
+    };
+    let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    if fraction.len() <= places {
+        return x;
+    }
+    let (kept, dropped) = fraction.split_at(places);
+    let mut digits: Vec<u8> = whole.bytes().chain(kept.bytes()).collect();
+    let odd = digits.last().is_some_and(|d| (d - b'0') % 2 == 1);
+    let up = match dropped.as_bytes()[0] {
+        b'5' => odd || dropped[1..].bytes().any(|d| d != b'0'),
+        d => d > b'5',
+    };
+    if up {
+        // Carry from the last kept digit, as by hand.
+        let mut i = digits.len();
+        loop {
+            if i == 0 {
+                digits.insert(0, b'1');
+                break;
+            }
+            i -= 1;
+            if digits[i] == b'9' {
+                digits[i] = b'0';
+            } else {
+                digits[i] += 1;
+                break;
+            }
+        }
+    }
+    let (whole, kept) = digits.split_at(digits.len() - places);
+    let text = format!("{}{}.{}", sign, String::from_utf8_lossy(whole), String::from_utf8_lossy(kept));
+    text.parse().expect("digits and a point")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use converter::registry::Registry;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/rates/usd.toml");
+
+    fn registry(rates: &Rates) -> Registry {
+        let mut registry = Registry::default();
+        for def in rates.units() {
+            registry.register(def).unwrap();
+        }
+        registry
+    }
+
+    fn convert(registry: &Registry, value: f64, from: &str, to: &str) -> f64 {
+        let (from, to) = (registry.find(from).unwrap(), registry.find(to).unwrap());
+        round_half_even(converter::convert_units(from, to, value), PLACES)
+    }
+
+    #[test]
+    fn test_conversion_through_the_base() {
+        let rates = Rates::load(FIXTURE).unwrap();
+        assert_eq!((rates.base.as_str(), rates.date.as_str()), ("USD", "2024-05-01"));
+        let registry = registry(&rates);
+        assert_eq!(convert(&registry, 100.0, "USD", "EUR"), 93.42);
+        assert_eq!(convert(&registry, 93.42, "EUR", "USD"), 100.0);
+        assert_eq!(convert(&registry, 1.0, "USD", "USD"), 1.0);
+    }
+
+    #[test]
+    fn test_cross_rates() {
+        let registry = registry(&Rates::load(FIXTURE).unwrap());
+        // 100 GBP is 100 / 0.7991 USD, and each of those buys 157.8 JPY.
+        assert_eq!(convert(&registry, 100.0, "GBP", "JPY"), 19747.22);
+        assert_eq!(convert(&registry, 19747.22, "JPY", "GBP"), 100.0);
+        assert_eq!(convert(&registry, 50.0, "EUR", "GBP"), 42.77);
+    }
+
+    #[test]
+    fn test_json_and_toml_agree() {
+        let json = Rates::parse_json(r#"{"base": "USD", "date": "2024-05-01", "rates": {"EUR": 0.9342, "GBP": 0.7991, "JPY": 157.8}}"#).unwrap();
+        let toml = Rates::load(FIXTURE).unwrap();
+        assert_eq!(json, Rates { path: String::new(), ..toml });
+    }
+
+    #[test]
+    fn test_staleness() {
+        let rates = Rates::load(FIXTURE).unwrap();
+        let may_first = crate::epoch::days_from_civil(2024, 5, 1);
+        assert_eq!(rates.age(may_first), 0);
+        assert_eq!(rates.age(crate::epoch::days_from_civil(2024, 6, 15)), 45);
+        assert!(rates.age(today()) > 0);
+    }
+
+    #[test]
+    fn test_invalid_files() {
+        let missing = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/rates/missing.toml");
+        assert_eq!(Rates::load(missing), Err(format!("rates file {} not found", missing)));
+        let toml = |text: &str| Rates::parse_toml(text).unwrap_err();
+        assert_eq!(toml("date = \"2024-05-01\"\n[rates]\nEUR = 0.9"), "there is no 'base' currency");
+        assert_eq!(toml("base = \"USD\"\n[rates]\nEUR = 0.9"), "there is no 'date', so there's no telling how old the rates are");
+        assert_eq!(toml("base = \"USD\"\ndate = \"May 1\"\n[rates]\nEUR = 0.9"), "'May 1' is not a date like 2024-05-01");
+        assert_eq!(toml("base = \"USD\"\ndate = \"2024-05-01\"\n[rates]\neuro = 0.9"), "'euro' is not a currency code such as EUR");
+        assert_eq!(toml("base = \"USD\"\ndate = \"2024-05-01\"\n[rates]\nEUR = 0"), "the rate of EUR must be a positive number, not 0");
+        assert_eq!(toml("base = \"USD\"\ndate = \"2024-05-01\"\n[rates]\nUSD = 2"), "the base currency USD must have a rate of 1, not 2");
+        assert_eq!(toml("base = \"USD\"\ndate = \"2024-05-01\"\n[rates]\nEUR = \"0.9\""), "line 4: the rate of EUR must be a number");
+        assert_eq!(toml("base = \"USD\"\ndate = \"2024-05-01\""), "there is no [rates] table");
+        assert!(Rates::parse_toml("base = \"USD\"\ndate = \"2024-05-01\"\n[rates]\nUSD = 1\nEUR = 0.9").is_ok());
+    }
+
+    #[test]
+    fn test_bankers_rounding() {
+        assert_eq!(round_half_even(0.125, 2), 0.12);
+        assert_eq!(round_half_even(0.135, 2), 0.14);
+        assert_eq!(round_half_even(2.675, 2), 2.68);
+        assert_eq!(round_half_even(2.665, 2), 2.66);
+        assert_eq!(round_half_even(-0.125, 2), -0.12);
+        assert_eq!(round_half_even(2.5, 0), 2.0);
+        assert_eq!(round_half_even(9.995, 2), 10.0);
+        assert_eq!(round_half_even(99.9951, 2), 100.0);
+        assert_eq!(round_half_even(1.5, 3), 1.5);
+    }
+}
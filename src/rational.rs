@@ -0,0 +1,198 @@
+//! Exact fractions for `--exact`. Overflow is reported as `None` so callers
+//! can fall back to floating point.
+
+use std::fmt;
+
+/// Decimal factors with more significant digits than this are taken to be
+/// rounded irrationals (π/180 and the like) rather than exact definitions.
+const MAX_EXACT_DIGITS: usize = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ratio {
+    num: i128,
+    den: i128,
+}
+
+fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+impl Ratio {
+    pub fn new(num: i128, den: i128) -> Option<Ratio> {
+        if den == 0 {
+            return None;
+        }
+        let g = gcd(num, den).max(1);
+        let sign = if den < 0 { -1 } else { 1 };
+        Some(Ratio { num: sign * num / g, den: sign * den / g })
+    }
+
+    /// Parses decimal text such as "98.6", "-5" or "1.5e-3".
+    pub fn parse_decimal(input: &str) -> Option<Ratio> {
+        let input = input.trim();
+        let (mantissa, exponent) = match input.split_once(['e', 'E']) {
+            Some((m, e)) => (m, e.parse::<i32>().ok()?),
+            None => (input, 0),
+        };
+        let (negative, digits) = match mantissa.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, mantissa.strip_prefix('+').unwrap_or(mantissa)),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        if whole.is_empty() && fraction.is_empty() || !(whole.chars().chain(fraction.chars())).all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let mut num: i128 = 0;
+        for c in whole.chars().chain(fraction.chars()) {
+            num = num.checked_mul(10)?.checked_add(c.to_digit(10)? as i128)?;
+        }
+        if negative {
+            num = -num;
+        }
+        let scale = exponent - fraction.len() as i32;
+        let power = 10i128.checked_pow(scale.unsigned_abs())?;
+        if scale >= 0 { Ratio::new(num.checked_mul(power)?, 1) } else { Ratio::new(num, power) }
+    }
+
+    /// The decimal a factor was written as, if it looks like an exact definition.
+    pub fn from_factor(value: f64) -> Option<Ratio> {
+        let text = value.to_string();
+        let significant = text.trim_start_matches(['-', '0', '.']).chars().filter(char::is_ascii_digit).count();
+        if significant > MAX_EXACT_DIGITS {
+            return None;
+        }
+        Ratio::parse_decimal(&text)
+    }
+
+    /// The nearest `f64`.
+    pub fn to_f64(self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+
+    pub fn checked_add(self, other: Ratio) -> Option<Ratio> {
+        let num = self.num.checked_mul(other.den)?.checked_add(other.num.checked_mul(self.den)?)?;
+        Ratio::new(num, self.den.checked_mul(other.den)?)
+    }
+
+    pub fn checked_mul(self, other: Ratio) -> Option<Ratio> {
+        // Cross-reduce first to keep the intermediates small.
+        let (a, b) = (gcd(self.num, other.den).max(1), gcd(other.num, self.den).max(1));
+        Ratio::new((self.num / a).checked_mul(other.num / b)?, (self.den / b).checked_mul(other.den / a)?)
+    }
+
+    pub fn checked_div(self, other: Ratio) -> Option<Ratio> {
+        self.checked_mul(Ratio::new(other.den, other.num)?)
+    }
+
+    /// The exact decimal expansion, when the denominator has no prime factors
+    /// other than 2 and 5.
+    pub fn to_decimal(self) -> Option<String> {
+        let mut den = self.den;
+        let mut places = 0u32;
+        while den % 10 == 0 {
+            den /= 10;
+            places += 1;
+        }
+        let mut extra = 0u32;
+        while den % 2 == 0 || den % 5 == 0 {
+            den /= if den % 2 == 0 { 2 } else { 5 };
+            extra += 1;
+        }
+        if den != 1 {
+            return None;
+        }
+        let places = places + extra;
+        let scaled = self.num.checked_mul(10i128.checked_pow(places)?)? / self.den;
+        let digits = scaled.unsigned_abs().to_string();
+        let sign = if scaled < 0 { "-" } else { "" };
+        if places == 0 {
+            return Some(format!("{}{}", sign, digits));
+        }
+        let digits = format!("{:0>width$}", digits, width = places as usize + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - places as usize);
+        Some(format!("{}{}.{}", sign, whole, fraction.trim_end_matches('0')))
+    }
+
+    /// Rounds to `places` decimals (half away from zero) by long division, so
+    /// every printed digit is correct.
+    #[cfg(feature = "bigdecimal")]
+    pub fn to_fixed(self, places: usize) -> String {
+        let den = self.den as u128;
+        let mut remainder = self.num.unsigned_abs() % den;
+        let mut digits: Vec<u8> = (self.num.unsigned_abs() / den).to_string().into_bytes();
+        for _ in 0..places {
+            remainder *= 10;
+            digits.push(b'0' + (remainder / den) as u8);
+            remainder %= den;
+        }
+        if remainder * 2 >= den {
+            let mut i = digits.len();
+            loop {
+                if i == 0 {
+                    digits.insert(0, b'1');
+                    break;
+                }
+                i -= 1;
+                if digits[i] == b'9' {
+                    digits[i] = b'0';
+                } else {
+                    digits[i] += 1;
+                    break;
+                }
+            }
+        }
+        let point = digits.len() - places;
+        let digits = String::from_utf8(digits).expect("ASCII digits");
+        let sign = if self.num < 0 && digits.bytes().any(|d| d != b'0') { "-" } else { "" };
+        if places == 0 {
+            return format!("{}{}", sign, digits);
+        }
+        format!("{}{}.{}", sign, &digits[..point], &digits[point..])
+    }
+}
+
+impl fmt::Display for Ratio {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.den == 1 { write!(f, "{}", self.num) } else { write!(f, "{}/{}", self.num, self.den) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_reduce() {
+        assert_eq!(Ratio::parse_decimal("0.25"), Ratio::new(1, 4));
+        assert_eq!(Ratio::parse_decimal("-1.5e-3"), Ratio::new(-3, 2000));
+        assert_eq!(Ratio::parse_decimal("12e2"), Ratio::new(1200, 1));
+        assert_eq!(Ratio::parse_decimal("abc"), None);
+        assert_eq!(Ratio::new(1000000, 1609344).unwrap().to_string(), "15625/25146");
+    }
+
+    #[test]
+    fn test_decimal_expansion() {
+        assert_eq!(Ratio::new(804672, 100000).unwrap().to_decimal().as_deref(), Some("8.04672"));
+        assert_eq!(Ratio::new(-1, 8).unwrap().to_decimal().as_deref(), Some("-0.125"));
+        assert_eq!(Ratio::new(1, 12).unwrap().to_decimal(), None);
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    #[test]
+    fn test_to_fixed() {
+        let third = Ratio::new(-2, 3).unwrap();
+        assert_eq!(third.to_fixed(5), "-0.66667");
+        assert_eq!(Ratio::new(999, 1000).unwrap().to_fixed(2), "1.00");
+        assert_eq!(Ratio::new(1, 127).unwrap().to_fixed(0), "0");
+        assert_eq!(Ratio::new(-1, 1000).unwrap().to_fixed(2), "0.00");
+    }
+
+    #[test]
+    fn test_factor_exactness() {
+        assert_eq!(Ratio::from_factor(0.45359237), Ratio::new(45359237, 100000000));
+        assert_eq!(Ratio::from_factor(std::f64::consts::PI / 180.0), None);
+    }
+}
@@ -0,0 +1,156 @@
+//! One conversion as data, in the shape every structured output shares:
+//! `--json`, `--serve-stdio` responses, the HTTP API and batch `--csv` all
+//! write a `ConversionRecord`, so a tool reading one reads them all.
+//!
+//! ```
+//! # #[cfg(feature = "length")] {
+//! use converter::{ConversionRecord, UnitCategory, convert_recorded};
+//!
+//! let record = convert_recorded("-1", "m", "cm")?;
+//! assert_eq!((record.result, record.category, record.factor), (-100.0, UnitCategory::Length, Some(100.0)));
+//! assert_eq!(record.warnings, ["Negative length doesn't make physical sense"]);
+//! assert_eq!(ConversionRecord::from_json(&record.to_json()), Ok(record));
+//! # }
+//! # Ok::<(), converter::ConvertError>(())
+//! ```
+
+use crate::json::Json;
+use crate::{ConvertError, Converted, UnitCategory, gauge};
+
+/// The keys of a record's JSON object, in order, which are also the
+/// columns of batch `--csv` output. `input` is written as `value`, the key
+/// `--json` has always used.
+pub const FIELDS: [&str; 7] = ["value", "from", "to", "result", "category", "factor", "warnings"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionRecord {
+    pub input: f64,
+    /// The units as the caller wrote them.
+    pub from: String,
+    pub to: String,
+    pub result: f64,
+    pub category: UnitCategory,
+    /// `to` units per `from` unit, when the conversion is a plain scaling;
+    /// `None` when an offset is involved, as between C and F.
+    pub factor: Option<f64>,
+    /// The English text of each warning the conversion raised.
+    pub warnings: Vec<String>,
+}
+
+impl ConversionRecord {
+    pub fn to_json(&self) -> Json {
+        let [value, from, to, result, category, factor, warnings] = FIELDS;
+        Json::object(vec![
+            (value, Json::from(self.input)),
+            (from, Json::from(self.from.as_str())),
+            (to, Json::from(self.to.as_str())),
+            (result, Json::from(self.result)),
+            (category, Json::from(self.category.name().to_lowercase())),
+            (factor, self.factor.map_or(Json::Null, Json::from)),
+            (warnings, Json::Array(self.warnings.iter().map(|w| Json::from(w.as_str())).collect())),
+        ])
+    }
+
+    /// Reads a record written by `to_json`. `result` may also be text, as
+    /// `--humanize` writes it; it reads back as NaN. A `derived` category is
+    /// worked out again from `to`.
+    pub fn from_json(value: &Json) -> Result<ConversionRecord, String> {
+        let field = |key: &str| value.get(key).ok_or_else(|| format!("record has no '{}'", key));
+        let text = |key: &str| field(key)?.as_str().map(str::to_string).ok_or_else(|| format!("'{}' must be a string", key));
+        let number = |key: &str| field(key)?.as_f64().ok_or_else(|| format!("'{}' must be a number", key));
+        let (input, from, to) = (number("value")?, text("from")?, text("to")?);
+        let result = match field("result")? {
+            Json::String(_) => f64::NAN,
+            _ => number("result")?,
+        };
+        let name = text("category")?;
+        let category = match name.as_str() {
+            // A compound unit's dimension is in the unit itself.
+            "derived" => crate::lookup_unit(&to)
+                .or_else(|| gauge::is_awg(&to).then(|| crate::lookup_unit(&from)).flatten())
+                .map(|unit| unit.category)
+                .filter(|c| matches!(c, UnitCategory::Derived(_))),
+            name => name.parse().ok(),
+        };
+        let category = category.ok_or_else(|| format!("'{}' isn't a category of '{}'", name, to))?;
+        let factor = match field("factor")? {
+            Json::Null => None,
+            _ => Some(number("factor")?),
+        };
+        let warnings = field("warnings")?
+            .as_array()
+            .and_then(|items| items.iter().map(|w| w.as_str().map(str::to_string)).collect())
+            .ok_or("'warnings' must be a list of strings")?;
+        Ok(ConversionRecord { input, from, to, result, category, factor, warnings })
+    }
+}
+
+impl Converted {
+    /// This conversion from `from` to `to` as a record. A wire gauge takes
+    /// the category of the size it converts with, and has no factor; nor
+    /// does a pace converted to a speed or back.
+    pub fn record(&self, from: &str, to: &str) -> Result<ConversionRecord, ConvertError> {
+        let unit = |name: &str| crate::lookup_unit(name).ok_or_else(|| crate::unit_error(name));
+        let (category, factor) = match (gauge::is_awg(from), gauge::is_awg(to)) {
+            (true, true) => (UnitCategory::Length, None),
+            (true, false) => (unit(to)?.category, None),
+            (false, true) => (unit(from)?.category, None),
+            (false, false) => {
+                let (from_unit, to_unit) = (unit(from)?, unit(to)?);
+                let linear = from_unit.conversion.offset() == 0.0 && to_unit.conversion.offset() == 0.0 && from_unit.dimension() == to_unit.dimension();
+                (to_unit.category, linear.then(|| crate::convert_units(from_unit, to_unit, 1.0)))
+            }
+        };
+        Ok(ConversionRecord {
+            input: self.value,
+            from: from.to_string(),
+            to: to.to_string(),
+            result: self.result,
+            category,
+            factor,
+            warnings: self.warnings.iter().map(ToString::to_string).collect(),
+        })
+    }
+}
+
+/// `convert_checked`, with the outcome as a record.
+pub fn convert_recorded(value_arg: &str, from: &str, to: &str) -> Result<ConversionRecord, ConvertError> {
+    crate::convert_checked(value_arg, from, to)?.record(from, to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature", feature = "time"))]
+    fn test_round_trips_through_json_text() {
+        let conversions = [("5", "km", "mi"), ("100", "C", "F"), ("-2", "kg", "lb"), ("90", "km/h", "m/s"), ("4/0", "awg", "mm2"), ("2", "mm2", "awg")];
+        for (value, from, to) in conversions {
+            let record = convert_recorded(value, from, to).unwrap();
+            let text = record.to_json().to_string();
+            assert_eq!(ConversionRecord::from_json(&json::parse(&text).unwrap()), Ok(record), "{}", text);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn test_fields() {
+        let record = convert_recorded("100", "C", "F").unwrap();
+        assert_eq!(record.to_json().to_string(), r#"{"value":100,"from":"C","to":"F","result":212,"category":"temperature","factor":null,"warnings":[]}"#);
+        let record = convert_recorded("1", "mi", "ft").unwrap();
+        assert_eq!((record.category, record.factor), (UnitCategory::Length, Some(5280.0)));
+        let Json::Object(fields) = record.to_json() else { panic!("not an object") };
+        assert_eq!(fields.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>(), FIELDS);
+    }
+
+    #[test]
+    fn test_bad_records() {
+        let parse = |text: &str| ConversionRecord::from_json(&json::parse(text).unwrap());
+        assert_eq!(parse(r#"{"value":1}"#), Err("record has no 'from'".to_string()));
+        let record = r#"{"value":1,"from":"m","to":"cm","result":100,"category":"length","factor":100,"warnings":"none"}"#;
+        assert_eq!(parse(record), Err("'warnings' must be a list of strings".to_string()));
+        assert_eq!(parse(&record.replace("length", "energy")), Err("'energy' isn't a category of 'cm'".to_string()));
+    }
+}
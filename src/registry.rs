@@ -0,0 +1,892 @@
+//! The units a run may use: the built-in table plus any units registered at
+//! runtime (the config's `[[unit]]` tables), minus whatever `disabled_units`
+//! and `enabled_categories` rule out. Lookups and listings go through the
+//! active registry, which a program installs once at startup.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::{builtin_unit, calendar_units, compound, normalized, same_word, us_imperial, words_collide, Conversion, ConvertError, Unit, UnitCategory, HOMONYMS, UNITS};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Registry {
+    /// Whether the built-in table is included.
+    builtin: bool,
+    /// Registered units, after the built-in ones and in registration order.
+    custom: Vec<&'static Unit>,
+    /// Canonical names of the disabled units.
+    disabled: Vec<&'static str>,
+    /// `None` allows every category.
+    categories: Option<Vec<UnitCategory>>,
+    /// Decides what "gallon" and the other `US_IMPERIAL_WORDS` mean; with
+    /// none, or metric, they are ambiguous.
+    system: Option<UnitSystem>,
+    /// Decides what "year", "month" and the other `CALENDAR_WORDS` mean.
+    calendar: Calendar,
+}
+
+/// A measurement system: what the words the US and imperial systems share
+/// mean, and which units a program converts into by preference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitSystem {
+    Metric,
+    Imperial,
+    /// US customary: imperial lengths and masses, but the US gallon, quart,
+    /// pint and fluid ounce.
+    Us,
+}
+
+impl UnitSystem {
+    pub fn parse(input: &str) -> Option<UnitSystem> {
+        match input.to_ascii_lowercase().as_str() {
+            "metric" => Some(UnitSystem::Metric),
+            "imperial" => Some(UnitSystem::Imperial),
+            "us" => Some(UnitSystem::Us),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            UnitSystem::Metric => "metric",
+            UnitSystem::Imperial => "imperial",
+            UnitSystem::Us => "US",
+        }
+    }
+}
+
+/// Whose average year the words "year" and "month" mean. A month is a
+/// twelfth of the year.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Calendar {
+    /// 365.25 days, the year of the light-year.
+    #[default]
+    Julian,
+    /// 365.2425 days, averaged over the 400-year cycle of leap years.
+    Gregorian,
+    /// 365 days, a year without a leap day.
+    Civil,
+}
+
+impl Calendar {
+    pub fn parse(input: &str) -> Option<Calendar> {
+        match input.to_ascii_lowercase().as_str() {
+            "julian" => Some(Calendar::Julian),
+            "gregorian" => Some(Calendar::Gregorian),
+            "civil" => Some(Calendar::Civil),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Calendar::Julian => "Julian",
+            Calendar::Gregorian => "Gregorian",
+            Calendar::Civil => "civil",
+        }
+    }
+}
+
+/// A unit to register: what a `[[unit]]` table in a units file describes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitDef {
+    pub name: String,
+    pub aliases: Vec<String>,
+    /// Whether its symbols match only as spelled, as for `Unit`.
+    pub case_sensitive: bool,
+    pub category: UnitCategory,
+    /// Base units per unit.
+    pub factor: f64,
+    /// Added before scaling, as for °F.
+    pub offset: f64,
+    /// Display names; the unit's name when not given.
+    pub singular: Option<String>,
+    pub plural: Option<String>,
+}
+
+impl UnitDef {
+    /// The unit this defines. `Unit` borrows its strings for the life of the
+    /// program, so they are leaked; units are defined once at startup.
+    pub fn leak(self) -> &'static Unit {
+        let leak = |text: String| -> &'static str { Box::leak(text.into_boxed_str()) };
+        let name = leak(self.name);
+        let aliases: Vec<&'static str> = self.aliases.into_iter().map(leak).collect();
+        Box::leak(Box::new(Unit {
+            name,
+            aliases: Box::leak(aliases.into_boxed_slice()),
+            case_sensitive: self.case_sensitive,
+            display_singular: self.singular.map_or(name, leak),
+            display_plural: self.plural.map_or(name, leak),
+            category: self.category,
+            conversion: Conversion::new((self.factor, 1.0), self.offset),
+        }))
+    }
+}
+
+impl UnitDef {
+    /// Everything `UnitBuilder::build` checks, for a definition put together
+    /// by hand.
+    pub fn validate(&self) -> Result<(), BuildError> {
+        let unit = || self.name.clone();
+        if self.name.trim().is_empty() {
+            return Err(BuildError::EmptyName);
+        }
+        if !self.factor.is_finite() {
+            return Err(BuildError::NonFiniteFactor { unit: unit(), factor: self.factor });
+        }
+        if self.factor == 0.0 {
+            return Err(BuildError::ZeroFactor { unit: unit() });
+        }
+        if !self.offset.is_finite() {
+            return Err(BuildError::NonFiniteOffset { unit: unit(), offset: self.offset });
+        }
+        if self.offset != 0.0 && self.category != UnitCategory::Temperature {
+            return Err(BuildError::OffsetOutsideTemperature { unit: unit(), category: self.category });
+        }
+        let words: Vec<&String> = std::iter::once(&self.name).chain(&self.aliases).collect();
+        for (i, word) in words.iter().enumerate() {
+            if words[..i].iter().any(|w| words_collide((w, self.case_sensitive), (word, self.case_sensitive))) {
+                return Err(BuildError::DuplicateAlias { unit: unit(), alias: word.to_string() });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A `UnitDef` put together step by step and checked as it's built:
+///
+/// ```
+/// use converter::UnitCategory;
+/// use converter::registry::{Registry, UnitBuilder};
+///
+/// let furlong = UnitBuilder::new("furlong").category(UnitCategory::Length).factor(201.168).alias("furlongs").build()?;
+/// let mut registry = Registry::default();
+/// registry.register(furlong)?;
+/// assert_eq!(registry.find("furlongs").map(|u| u.to_base(1.0)), Some(201.168));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitBuilder {
+    name: String,
+    aliases: Vec<String>,
+    case_sensitive: bool,
+    category: Option<UnitCategory>,
+    factor: Option<f64>,
+    offset: f64,
+    singular: Option<String>,
+    plural: Option<String>,
+}
+
+impl UnitBuilder {
+    pub fn new(name: impl Into<String>) -> UnitBuilder {
+        UnitBuilder { name: name.into(), aliases: Vec::new(), case_sensitive: true, category: None, factor: None, offset: 0.0, singular: None, plural: None }
+    }
+
+    pub fn category(mut self, category: UnitCategory) -> UnitBuilder {
+        self.category = Some(category);
+        self
+    }
+
+    /// Base units per unit.
+    pub fn factor(mut self, factor: f64) -> UnitBuilder {
+        self.factor = Some(factor);
+        self
+    }
+
+    /// Added before scaling, as for °F; only temperatures have one.
+    pub fn offset(mut self, offset: f64) -> UnitBuilder {
+        self.offset = offset;
+        self
+    }
+
+    pub fn alias(mut self, alias: impl Into<String>) -> UnitBuilder {
+        self.aliases.push(alias.into());
+        self
+    }
+
+    pub fn aliases(mut self, aliases: impl IntoIterator<Item = impl Into<String>>) -> UnitBuilder {
+        self.aliases.extend(aliases.into_iter().map(Into::into));
+        self
+    }
+
+    /// Whether its symbols match only as spelled; they do unless this
+    /// turns it off.
+    pub fn case_sensitive(mut self, case_sensitive: bool) -> UnitBuilder {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
+    /// Display names; the unit's name when not given.
+    pub fn singular(mut self, singular: impl Into<String>) -> UnitBuilder {
+        self.singular = Some(singular.into());
+        self
+    }
+
+    pub fn plural(mut self, plural: impl Into<String>) -> UnitBuilder {
+        self.plural = Some(plural.into());
+        self
+    }
+
+    /// The definition, or the first thing wrong with it. Whether its names
+    /// are free is up to the registry it's registered in.
+    pub fn build(self) -> Result<UnitDef, BuildError> {
+        if self.name.trim().is_empty() {
+            return Err(BuildError::EmptyName);
+        }
+        let category = self.category.ok_or_else(|| BuildError::MissingCategory { unit: self.name.clone() })?;
+        let factor = self.factor.ok_or_else(|| BuildError::MissingFactor { unit: self.name.clone() })?;
+        let def = UnitDef {
+            name: self.name,
+            aliases: self.aliases,
+            case_sensitive: self.case_sensitive,
+            category,
+            factor,
+            offset: self.offset,
+            singular: self.singular,
+            plural: self.plural,
+        };
+        def.validate()?;
+        Ok(def)
+    }
+}
+
+/// Why a `UnitBuilder` couldn't build its unit.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError {
+    EmptyName,
+    MissingCategory { unit: String },
+    MissingFactor { unit: String },
+    NonFiniteFactor { unit: String, factor: f64 },
+    ZeroFactor { unit: String },
+    NonFiniteOffset { unit: String, offset: f64 },
+    /// Only temperatures are measured from an arbitrary zero.
+    OffsetOutsideTemperature { unit: String, category: UnitCategory },
+    /// `alias` is listed twice, or repeats the name.
+    DuplicateAlias { unit: String, alias: String },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildError::EmptyName => write!(f, "a unit needs a name"),
+            BuildError::MissingCategory { unit } => write!(f, "unit '{}' has no category", unit),
+            BuildError::MissingFactor { unit } => write!(f, "unit '{}' has no factor", unit),
+            BuildError::NonFiniteFactor { unit, factor } => write!(f, "unit '{}' needs a finite factor, not {}", unit, factor),
+            BuildError::ZeroFactor { unit } => write!(f, "unit '{}' can't have a factor of 0", unit),
+            BuildError::NonFiniteOffset { unit, offset } => write!(f, "unit '{}' needs a finite offset, not {}", unit, offset),
+            BuildError::OffsetOutsideTemperature { unit, category } => {
+                write!(f, "unit '{}' has an offset, but only temperatures can, not {}", unit, category.name().to_lowercase())
+            }
+            BuildError::DuplicateAlias { unit, alias } => write!(f, "unit '{}' lists '{}' twice", unit, alias),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// Why a unit couldn't be registered.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryError {
+    /// `word` already names `owner`.
+    NameTaken { word: String, owner: String },
+    /// The definition itself is wrong, as `UnitDef::validate` finds.
+    Invalid(BuildError),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistryError::NameTaken { word, owner } => write!(f, "'{}' already names {}", word, owner),
+            RegistryError::Invalid(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+/// A word claimed twice, ignoring ASCII case: by two units, or listed twice
+/// by one (`first == second`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameClash {
+    pub word: &'static str,
+    pub first: &'static str,
+    pub second: &'static str,
+}
+
+impl fmt::Display for NameClash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.first == self.second {
+            write!(f, "{} lists '{}' twice", self.first, self.word)
+        } else {
+            write!(f, "'{}' names both {} and {}", self.word, self.first, self.second)
+        }
+    }
+}
+
+/// Every word of `units` claimed a second time, in table order, except the
+/// intended `HOMONYMS`.
+pub fn name_clashes<'a>(units: impl IntoIterator<Item = &'a Unit>) -> Vec<NameClash> {
+    clashes(units, HOMONYMS)
+}
+
+fn clashes<'a>(units: impl IntoIterator<Item = &'a Unit>, homonyms: &[(&str, &str)]) -> Vec<NameClash> {
+    let mut seen: Vec<(&'static str, bool, &'static str)> = Vec::new();
+    let mut clashes = Vec::new();
+    for unit in units {
+        for word in std::iter::once(unit.name).chain(unit.aliases.iter().copied()) {
+            match seen.iter().find(|(seen, case, _)| words_collide((seen, *case), (word, unit.case_sensitive))) {
+                Some((_, _, owner)) if *owner != unit.name && homonyms.iter().any(|(w, _)| same_word(w, word)) => {}
+                Some((_, _, owner)) => clashes.push(NameClash { word, first: owner, second: unit.name }),
+                None => seen.push((word, unit.case_sensitive, unit.name)),
+            }
+        }
+    }
+    clashes
+}
+
+/// Whether `word` is shaped like an ISO 4217 code: three capital letters.
+pub fn is_currency_code(word: &str) -> bool {
+    word.len() == 3 && word.bytes().all(|b| b.is_ascii_uppercase())
+}
+
+static ACTIVE: OnceLock<Registry> = OnceLock::new();
+
+impl Default for Registry {
+    fn default() -> Registry {
+        debug_assert!(name_clashes(UNITS).is_empty(), "{:?}", name_clashes(UNITS));
+        Registry { builtin: true, custom: Vec::new(), disabled: Vec::new(), categories: None, system: None, calendar: Calendar::Julian }
+    }
+}
+
+impl Registry {
+    /// No units at all until some are registered, e.g. to rebuild a table
+    /// from exported definitions.
+    pub fn empty() -> Registry {
+        Registry { builtin: false, ..Registry::default() }
+    }
+
+    /// Every built-in unit except `disabled_units`, and only those in
+    /// `categories` when given. Names that aren't units are ignored.
+    pub fn restricted(disabled_units: &[impl AsRef<str>], categories: Option<Vec<UnitCategory>>) -> Registry {
+        let mut registry = Registry::default();
+        registry.restrict(disabled_units, categories);
+        registry
+    }
+
+    /// Rules out `disabled_units`, and every category not in `categories`
+    /// when given. Registered units can be disabled like built-in ones.
+    pub fn restrict(&mut self, disabled_units: &[impl AsRef<str>], categories: Option<Vec<UnitCategory>>) {
+        self.disabled = disabled_units.iter().filter_map(|u| self.resolve(u.as_ref())).map(|u| u.name).collect();
+        self.categories = categories;
+    }
+
+    /// Resolves the words the US and imperial systems share, such as
+    /// "gallon", to `system`'s unit.
+    pub fn set_system(&mut self, system: Option<UnitSystem>) {
+        self.system = system;
+    }
+
+    /// Resolves "year", "month" and the other `CALENDAR_WORDS` to
+    /// `calendar`'s unit.
+    pub fn set_calendar(&mut self, calendar: Calendar) {
+        self.calendar = calendar;
+    }
+
+    /// Adds a unit after the built-in and previously registered ones. None of
+    /// its names may already name a unit, even a disabled one, or be read as
+    /// a built-in one ("lbs").
+    pub fn register(&mut self, def: UnitDef) -> Result<(), RegistryError> {
+        def.validate().map_err(RegistryError::Invalid)?;
+        for word in std::iter::once(&def.name).chain(&def.aliases) {
+            if let Some(owner) = self.owner(word) {
+                return Err(RegistryError::NameTaken { word: word.to_string(), owner: owner.name.to_string() });
+            }
+        }
+        self.custom.push(def.leak());
+        Ok(())
+    }
+
+    /// The unit a new unit can't take `word` from. Built-in lookups forgive
+    /// plurals and come first; registered units are matched exactly, because
+    /// an exact match beats a forgiven one among them.
+    fn owner(&self, word: &str) -> Option<&'static Unit> {
+        let builtin = if self.builtin { builtin_unit(word) } else { None };
+        builtin.or_else(|| self.custom.iter().copied().find(|u| u.matches(word)))
+    }
+
+    pub fn allows(&self, unit: &Unit) -> bool {
+        !self.disabled.contains(&unit.name) && self.categories.as_ref().is_none_or(|c| c.contains(&unit.category))
+    }
+
+    /// The available units: built-in ones first, then registered ones.
+    pub fn units(&self) -> impl Iterator<Item = &'static Unit> + '_ {
+        let builtin = if self.builtin { UNITS } else { &[] };
+        builtin.iter().chain(self.custom.iter().copied()).filter(|u| self.allows(u))
+    }
+
+    /// The available units of `category`, in `units` order.
+    pub fn by_category(&self, category: UnitCategory) -> impl Iterator<Item = &'static Unit> + '_ {
+        self.units().filter(move |u| u.category == category)
+    }
+
+    /// The categories with at least one available unit, in `UnitCategory::all` order.
+    pub fn categories(&self) -> Vec<UnitCategory> {
+        UnitCategory::all().iter().copied().filter(|&c| self.by_category(c).next().is_some()).collect()
+    }
+
+    /// Available units whose name, an alias or a display name contains
+    /// `query`, ignoring case.
+    pub fn matching(&self, query: &str) -> Vec<&'static Unit> {
+        let query = query.to_lowercase();
+        self.units()
+            .filter(|u| {
+                let mut words = [u.name, u.display_singular, u.display_plural].into_iter().chain(u.aliases.iter().copied());
+                words.any(|w| w.to_lowercase().contains(&query))
+            })
+            .collect()
+    }
+
+    /// The unit `input` names, or the compound unit it writes out ("km/h").
+    pub fn find(&self, input: &str) -> Option<&'static Unit> {
+        let compound = || {
+            let unit = self.compound(input).ok()?;
+            #[cfg(feature = "tracing")]
+            crate::trace::event(crate::trace::Level::Debug, "compound", || {
+                vec![("input", input.to_string()), ("dimension", unit.dimension().to_string())]
+            });
+            Some(unit)
+        };
+        self.find_named(input).or_else(|| compound::is_expression(input).then(compound).flatten())
+    }
+
+    fn find_named(&self, input: &str) -> Option<&'static Unit> {
+        let unit = self.resolve(input).filter(|u| self.allows(u))?;
+        #[cfg(feature = "tracing")]
+        if unit.name != input {
+            crate::trace::event(crate::trace::Level::Debug, "alias", || vec![("input", input.to_string()), ("unit", unit.name.to_string())]);
+        }
+        Some(unit)
+    }
+
+    fn compound(&self, input: &str) -> Result<&'static Unit, ConvertError> {
+        compound::parse(input, |word| self.find_named(word).ok_or_else(|| self.named_error(word)))
+    }
+
+    /// Why `input` doesn't resolve. For a compound unit that is the error for
+    /// its first bad term.
+    pub fn error(&self, input: &str) -> ConvertError {
+        if compound::is_expression(input) && let Err(e) = self.compound(input) {
+            return e;
+        }
+        self.named_error(input)
+    }
+
+    fn named_error(&self, input: &str) -> ConvertError {
+        if let Some((us, imperial)) = us_imperial(input)
+            && self.system_unit(input).is_none()
+        {
+            return ConvertError::AmbiguousUnit { input: input.to_string(), choices: [us, imperial] };
+        }
+        self.disabled_error(input).unwrap_or_else(|| {
+            let suggestions = self.suggestions(input);
+            let known: Vec<&'static str> = self.by_category(UnitCategory::Currency).map(|u| u.name).collect();
+            // A code like "CHF" is about the rates, unless it's a typo of a unit and there are none.
+            if is_currency_code(input) && (!known.is_empty() || suggestions.is_empty()) {
+                return ConvertError::UnknownCurrency { code: input.to_string(), known };
+            }
+            ConvertError::UnknownUnit { input: input.to_string(), suggestions: suggestions.into_iter().map(String::from).collect() }
+        })
+    }
+
+    /// Built-in units are tried first, so registering units doesn't slow
+    /// down lookups of the built-in ones.
+    fn resolve(&self, input: &str) -> Option<&'static Unit> {
+        let builtin = if self.builtin { builtin_unit(input) } else { None };
+        builtin.or_else(|| normalized(input, |word| self.custom.iter().copied().find(|u| u.matches(word)))).or_else(|| self.system_unit(input)).or_else(|| self.calendar_unit(input))
+    }
+
+    /// The unit of this registry's system that a shared word like "pint" means.
+    fn system_unit(&self, input: &str) -> Option<&'static Unit> {
+        let (us, imperial) = us_imperial(input)?;
+        match self.system? {
+            UnitSystem::Us => builtin_unit(us),
+            UnitSystem::Imperial => builtin_unit(imperial),
+            UnitSystem::Metric => None,
+        }
+    }
+
+    /// The unit of this registry's calendar that a word like "month" means.
+    fn calendar_unit(&self, input: &str) -> Option<&'static Unit> {
+        let [julian, gregorian, civil] = calendar_units(input)?;
+        builtin_unit(match self.calendar {
+            Calendar::Julian => julian,
+            Calendar::Gregorian => gregorian,
+            Calendar::Civil => civil,
+        })
+    }
+
+    /// The error for a unit that exists but is ruled out here, naming the
+    /// units of its category that are still available.
+    pub fn disabled_error(&self, input: &str) -> Option<ConvertError> {
+        let unit = self.resolve(input).filter(|u| !self.allows(u))?;
+        let alternatives = self.units().filter(|u| u.category == unit.category).map(|u| u.name).collect();
+        Some(ConvertError::DisabledUnit { unit: input.to_string(), alternatives })
+    }
+
+    /// Available units whose name or an alias is a small typo away from
+    /// `input`, closest first. Short words get no suggestions: one edit turns
+    /// "x" into half the table.
+    pub fn suggestions(&self, input: &str) -> Vec<&'static str> {
+        let input = input.to_lowercase();
+        let allowed = (input.chars().count() / 3).min(2);
+        let mut close: Vec<(usize, &'static str)> = self
+            .units()
+            .filter_map(|u| {
+                let distance = std::iter::once(&u.name).chain(u.aliases).map(|w| edit_distance(&input, &w.to_lowercase())).min()?;
+                (distance <= allowed).then_some((distance, u.name))
+            })
+            .collect();
+        close.sort_by_key(|(distance, _)| *distance);
+        close.into_iter().take(MAX_SUGGESTIONS).map(|(_, name)| name).collect()
+    }
+
+    /// The word for an available unit of `category` that goes by the same
+    /// name as `unit`, or a typo of one: "fl oz" in Volume for "oz". A unit
+    /// shares a name when one of its words, split at spaces, is one of
+    /// `unit`'s.
+    pub fn cousin(&self, unit: &Unit, category: UnitCategory) -> Option<&'static str> {
+        let names = |u: &Unit| std::iter::once(u.name).chain(u.aliases.iter().copied()).map(str::to_lowercase).collect::<Vec<_>>();
+        let own = names(unit);
+        let parts: Vec<&str> = own.iter().flat_map(|w| w.split_whitespace()).collect();
+        let related = |word: &str| {
+            word.split_whitespace().any(|part| parts.contains(&part))
+                || (word.chars().count() > COUSIN_MIN_LEN && own.iter().any(|w| edit_distance(word, w) <= 1))
+        };
+        self.by_category(category)
+            .filter(|u| u.category != unit.category)
+            .find_map(|u| std::iter::once(u.name).chain(u.aliases.iter().copied()).find(|w| related(&w.to_lowercase())))
+    }
+}
+
+/// Words this short are symbols, where one edit makes a different unit.
+const COUSIN_MIN_LEN: usize = 3;
+
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Levenshtein distance, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb { diagonal } else { 1 + diagonal.min(above).min(row[j]) };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Makes `registry` the one every lookup uses. Only the first call has an effect.
+pub fn install(registry: Registry) {
+    let _ = ACTIVE.set(registry);
+}
+
+/// The installed registry, or an unrestricted one.
+pub fn active() -> &'static Registry {
+    ACTIVE.get_or_init(Registry::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "mass")]
+    fn registry(disabled: &[&str], categories: Option<Vec<UnitCategory>>) -> Registry {
+        Registry::restricted(disabled, categories)
+    }
+
+    #[test]
+    #[cfg(feature = "mass")]
+    fn test_disabled_unit() {
+        let registry = registry(&["ton"], None);
+        assert!(registry.find("ton").is_none());
+        assert!(registry.find("tonnes").is_none());
+        assert!(registry.find("kg").is_some());
+        let error = registry.disabled_error("ton").unwrap().to_string();
+        assert_eq!(error, "unit 'ton' is disabled by your configuration; use 'kg', 'g', 'mg', 'lb', 'oz' or 'st'");
+        assert_eq!(registry.disabled_error("kg"), None);
+        assert_eq!(registry.disabled_error("furlong"), None);
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass"))]
+    fn test_units_omit_disabled_units() {
+        let registry = registry(&["ton", "mi", "furlong"], None);
+        assert!(registry.units().all(|u| u.name != "ton" && u.name != "mi"));
+        assert_eq!(registry.units().count(), UNITS.len() - 2);
+        assert_eq!(Registry::restricted(&[] as &[&str], None).units().count(), UNITS.len());
+    }
+
+    fn furlong() -> UnitDef {
+        UnitDef {
+            name: "furlong".to_string(),
+            aliases: vec!["furlongs".to_string(), "fur".to_string()],
+            case_sensitive: true,
+            category: UnitCategory::Length,
+            factor: 201.168,
+            offset: 0.0,
+            singular: None,
+            plural: None,
+        }
+    }
+
+    fn def(name: &str, aliases: &[&str]) -> UnitDef {
+        UnitDef { name: name.to_string(), aliases: aliases.iter().map(|a| a.to_string()).collect(), ..furlong() }
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_register_and_find() {
+        let mut registry = Registry::default();
+        registry.register(furlong()).unwrap();
+        let unit = registry.find("Furlongs").unwrap();
+        assert_eq!((unit.name, unit.display_plural, unit.category), ("furlong", "furlong", UnitCategory::Length));
+        assert_eq!(unit.to_base(1.0), 201.168);
+        assert_eq!(registry.find("fur.").map(|u| u.name), Some("furlong"));
+        assert_eq!(registry.find("km").map(|u| u.name), Some("km"));
+        assert!(Registry::default().find("furlong").is_none());
+        assert_eq!(registry.suggestions("furlog"), ["furlong"]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass"))]
+    fn test_register_rejects_collisions() {
+        let mut registry = Registry::default();
+        registry.register(furlong()).unwrap();
+        let taken = |word: &str, owner: &str| Err(RegistryError::NameTaken { word: word.to_string(), owner: owner.to_string() });
+        assert_eq!(registry.register(def("klick", &["KM"])), taken("KM", "km"));
+        assert_eq!(registry.register(def("klick", &["lbs"])), taken("lbs", "lb"));
+        assert_eq!(registry.register(def("FURLONGS", &[])), taken("FURLONGS", "furlong"));
+        assert_eq!(registry.register(def("MM", &[])), Ok(()), "short symbols are case-sensitive");
+        assert_eq!(registry.find("MM").map(|u| u.name), Some("MM"));
+        assert_eq!(registry.find("mm").map(|u| u.name), Some("mm"));
+        let invalid = |error| Err(RegistryError::Invalid(error));
+        assert_eq!(registry.register(def("klick", &["klicks", "Klick"])), invalid(BuildError::DuplicateAlias { unit: "klick".to_string(), alias: "Klick".to_string() }));
+        assert_eq!(registry.register(def("klick", &[])), Ok(()));
+        assert_eq!(registry.register(def("klick", &[])), taken("klick", "klick"));
+        assert_eq!(registry.register(UnitDef { factor: 0.0, ..def("nothing", &[]) }).unwrap_err().to_string(), "unit 'nothing' can't have a factor of 0");
+        assert_eq!(registry.register(def(" ", &[])), invalid(BuildError::EmptyName));
+        // Disabled units still own their names.
+        let mut restricted = Registry::restricted(&["ton"], None);
+        assert_eq!(restricted.register(def("tonne", &[])), taken("tonne", "ton"));
+    }
+
+    #[test]
+    fn test_builder_rejects_bad_definitions() {
+        let furlong = || UnitBuilder::new("furlong").category(UnitCategory::Length).factor(201.168);
+        let unit = || "furlong".to_string();
+        assert_eq!(UnitBuilder::new("  ").category(UnitCategory::Length).factor(1.0).build(), Err(BuildError::EmptyName));
+        assert_eq!(UnitBuilder::new("furlong").factor(201.168).build(), Err(BuildError::MissingCategory { unit: unit() }));
+        assert_eq!(UnitBuilder::new("furlong").category(UnitCategory::Length).build(), Err(BuildError::MissingFactor { unit: unit() }));
+        assert_eq!(furlong().factor(f64::INFINITY).build(), Err(BuildError::NonFiniteFactor { unit: unit(), factor: f64::INFINITY }));
+        assert_eq!(furlong().factor(0.0).build(), Err(BuildError::ZeroFactor { unit: unit() }));
+        assert!(matches!(furlong().category(UnitCategory::Temperature).offset(f64::NAN).build(), Err(BuildError::NonFiniteOffset { .. })));
+        assert_eq!(
+            furlong().offset(3.0).build(),
+            Err(BuildError::OffsetOutsideTemperature { unit: unit(), category: UnitCategory::Length })
+        );
+        assert_eq!(furlong().alias("furlongs").alias("FURLONGS").build(), Err(BuildError::DuplicateAlias { unit: unit(), alias: "FURLONGS".to_string() }));
+        assert_eq!(furlong().alias("Furlong").build().unwrap_err().to_string(), "unit 'furlong' lists 'Furlong' twice");
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn test_built_units_convert_once_registered() {
+        let def = UnitBuilder::new("furlong").category(UnitCategory::Length).factor(201.168).aliases(["furlongs", "fur"]).build().unwrap();
+        assert_eq!(def, furlong());
+        let rankine = UnitBuilder::new("R").category(UnitCategory::Temperature).factor(5.0 / 9.0).offset(-491.67).singular("degree Rankine").plural("degrees Rankine");
+        let mut registry = Registry::default();
+        registry.register(def).unwrap();
+        registry.register(rankine.build().unwrap()).unwrap();
+        let (fur, m) = (registry.find("fur").unwrap(), registry.find("m").unwrap());
+        assert_eq!(crate::convert_units(fur, m, 10.0), 2011.68);
+        let (r, c) = (registry.find("R").unwrap(), registry.find("C").unwrap());
+        assert!(crate::convert_units(r, c, 491.67_f64).abs() < 1e-12);
+        assert_eq!(r.display_plural, "degrees Rankine");
+    }
+
+    #[test]
+    fn test_registered_units_follow_builtins_in_order() {
+        let mut registry = Registry::default();
+        for name in ["league", "chain", "rod"] {
+            registry.register(def(name, &[])).unwrap();
+        }
+        let names: Vec<&str> = registry.units().map(|u| u.name).collect();
+        assert_eq!(names[..UNITS.len()], UNITS.iter().map(|u| u.name).collect::<Vec<_>>()[..]);
+        assert_eq!(names[UNITS.len()..], ["league", "chain", "rod"]);
+        registry.restrict(&["chain"], None);
+        assert_eq!(registry.units().skip(UNITS.len()).map(|u| u.name).collect::<Vec<_>>(), ["league", "rod"]);
+        assert!(registry.disabled_error("chain").unwrap().to_string().starts_with("unit 'chain' is disabled"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn test_suggestions() {
+        let registry = Registry::default();
+        assert_eq!(edit_distance("celcius", "celsius"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(registry.suggestions("celcius"), ["C"]);
+        assert_eq!(registry.suggestions("Farenheit"), ["F"]);
+        assert_eq!(registry.suggestions("kmz"), ["km"]);
+        assert_eq!(registry.suggestions("x"), [] as [&str; 0]);
+        assert_eq!(registry.suggestions("furlong"), [] as [&str; 0]);
+        assert_eq!(Registry::restricted(&["C"], None).suggestions("celcius"), [] as [&str; 0]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "mass", feature = "volume"))]
+    fn test_cousins() {
+        let registry = Registry::default();
+        let unit = |name| builtin_unit(name).unwrap();
+        assert_eq!(registry.cousin(unit("oz"), UnitCategory::Volume), Some("US fl oz"));
+        assert_eq!(registry.cousin(unit("floz-imp"), UnitCategory::Mass), Some("oz"));
+        assert_eq!(registry.cousin(unit("kg"), UnitCategory::Volume), None);
+        assert_eq!(registry.cousin(unit("oz"), UnitCategory::Mass), None);
+        assert_eq!(Registry::restricted(&["floz-us"], None).cousin(unit("oz"), UnitCategory::Volume), Some("imp fl oz"));
+    }
+
+    #[test]
+    #[cfg(feature = "volume")]
+    fn test_system_resolves_shared_words() {
+        let with = |system| {
+            let mut registry = Registry::default();
+            registry.set_system(system);
+            registry
+        };
+        let name = |registry: &Registry, word| registry.find(word).map(|u| u.name);
+        assert_eq!(name(&with(Some(UnitSystem::Us)), "gallon"), Some("gal-us"));
+        assert_eq!(name(&with(Some(UnitSystem::Us)), "fl oz"), Some("floz-us"));
+        assert_eq!(name(&with(Some(UnitSystem::Imperial)), "Pints"), Some("pt-imp"));
+        assert_eq!(name(&with(Some(UnitSystem::Imperial)), "qt."), Some("qt-imp"));
+        assert_eq!(name(&with(Some(UnitSystem::Us)), "qt"), Some("qt-us"), "the liquid quart, not the dry one");
+        for registry in [with(None), with(Some(UnitSystem::Metric))] {
+            assert_eq!(name(&registry, "gal"), None);
+            assert_eq!(registry.error("gallons"), ConvertError::AmbiguousUnit { input: "gallons".to_string(), choices: ["gal-us", "gal-imp"] });
+        }
+        // A system doesn't bring back a unit the config disabled.
+        let mut disabled = Registry::restricted(&["gal-us"], None);
+        disabled.set_system(Some(UnitSystem::Us));
+        assert!(matches!(disabled.error("gallon"), ConvertError::DisabledUnit { .. }));
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn test_calendar_resolves_year_and_month() {
+        let with = |calendar| {
+            let mut registry = Registry::default();
+            registry.set_calendar(calendar);
+            registry
+        };
+        let name = |registry: &Registry, word| registry.find(word).map(|u| u.name);
+        assert_eq!(name(&Registry::default(), "year"), Some("julian-year"));
+        assert_eq!(name(&with(Calendar::Gregorian), "years"), Some("gregorian-year"));
+        assert_eq!(name(&with(Calendar::Gregorian), "mo"), Some("gregorian-month"));
+        assert_eq!(name(&with(Calendar::Civil), "yr"), Some("civil-year"));
+        assert_eq!(name(&with(Calendar::Civil), "Month"), Some("civil-month"));
+        // The explicit units don't follow the calendar.
+        assert_eq!(name(&with(Calendar::Civil), "julian-year"), Some("julian-year"));
+        assert_eq!(Calendar::parse("Gregorian"), Some(Calendar::Gregorian));
+        assert_eq!(Calendar::parse("lunar"), None);
+    }
+
+    #[test]
+    fn test_unknown_currency_codes() {
+        let currency = |code: &str| UnitDef { name: code.to_string(), aliases: Vec::new(), category: UnitCategory::Currency, ..furlong() };
+        let mut registry = Registry::default();
+        assert_eq!(registry.error("CHF"), ConvertError::UnknownCurrency { code: "CHF".to_string(), known: Vec::new() });
+        assert!(registry.error("CHF").to_string().starts_with("'CHF' looks like a currency code, but no rates are loaded"));
+        assert!(matches!(registry.error("chf"), ConvertError::UnknownUnit { .. }));
+        registry.register(currency("USD")).unwrap();
+        registry.register(currency("EUR")).unwrap();
+        assert_eq!(registry.error("CHF").to_string(), "currency 'CHF' is not in the rates file; use 'USD' or 'EUR'");
+        assert!(is_currency_code("JPY") && !is_currency_code("Jpy") && !is_currency_code("JPYY"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature", feature = "time"))]
+    fn test_trailing_period_and_plural_s() {
+        let name = |input| builtin_unit(input).map(|u| u.name);
+        assert_eq!(name("in."), Some("in"));
+        assert_eq!(name("ft."), Some("ft"));
+        assert_eq!(name("lbs"), Some("lb"));
+        assert_eq!(name("LBS."), Some("lb"));
+        assert_eq!(name("kms"), Some("km"));
+        assert_eq!(name("celsius"), Some("C"));
+        assert_eq!(name("ms"), Some("ms"));
+        assert_eq!(name("mins"), Some("min"));
+        assert_eq!(name("in.."), None);
+        assert_eq!(name("s."), Some("s"));
+        assert_eq!(name("."), None);
+    }
+
+    #[test]
+    fn test_plural_retry_has_no_false_positives() {
+        for word in ["gas", "kgs.s", "is", "as", "us", "yes", "cms2"] {
+            if let Some(unit) = builtin_unit(word) {
+                panic!("'{}' resolved to {}", word, unit.name);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature"))]
+    fn test_enabled_categories() {
+        let registry = registry(&[], Some(vec![UnitCategory::Length, UnitCategory::Mass]));
+        assert!(registry.find("km").is_some());
+        assert!(registry.find("lb").is_some());
+        assert!(registry.find("C").is_none());
+        assert!(registry.units().all(|u| matches!(u.category, UnitCategory::Length | UnitCategory::Mass)));
+        assert_eq!(registry.disabled_error("C").unwrap().to_string(), "unit 'C' is disabled by your configuration");
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass"))]
+    fn test_queries_follow_restrictions() {
+        let mut registry = registry(&["km", "ton"], Some(vec![UnitCategory::Length, UnitCategory::Mass]));
+        assert_eq!(registry.categories(), [UnitCategory::Length, UnitCategory::Mass]);
+        assert_eq!(registry.by_category(UnitCategory::Mass).count(), 6);
+        let names = |registry: &Registry, query| registry.matching(query).into_iter().map(|u| u.name).collect::<Vec<_>>();
+        assert_eq!(names(&registry, "meter"), ["m", "cm", "mm"]);
+        registry.register(furlong()).unwrap();
+        assert_eq!(names(&registry, "FUR"), ["furlong"]);
+        assert!(Registry::empty().categories().is_empty());
+    }
+
+    #[test]
+    fn test_builtin_names_are_unique() {
+        assert_eq!(name_clashes(UNITS), []);
+        for (word, name) in HOMONYMS {
+            let owners: Vec<&str> = UNITS.iter().filter(|u| u.matches(word)).map(|u| u.name).collect();
+            assert!(owners.len() > 1 && owners.contains(name), "'{}' isn't shared, or not by {}: {:?}", word, name, owners);
+        }
+    }
+
+    #[test]
+    fn test_name_clashes() {
+        let unit = |name: &str, aliases: &[&str]| {
+            UnitDef { name: name.to_string(), aliases: aliases.iter().map(|a| a.to_string()).collect(), ..furlong() }.leak()
+        };
+        let units = [unit("mils", &["thou"]), unit("thou", &["MILS", "t"]), unit("ton", &["t", "ton", "T"])];
+        let messages = |clashes: Vec<NameClash>| clashes.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            messages(name_clashes(units)),
+            ["'thou' names both mils and thou", "'MILS' names both mils and thou", "'t' names both thou and ton", "ton lists 'ton' twice"]
+        );
+        assert!(!words_collide(("b", true), ("B", true)) && !words_collide(("mm", true), ("Mm", false)) && words_collide(("Meter", true), ("meter", true)));
+        assert!(words_collide(("km", false), ("KM", false)));
+        assert_eq!(messages(clashes(units, &[("t", "ton")])), ["'thou' names both mils and thou", "'MILS' names both mils and thou", "ton lists 'ton' twice"]);
+    }
+}
@@ -0,0 +1,269 @@
+use std::fmt;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+
+use crate::config::{self, Config};
+use crate::editor::{Completer, Editor, History, ReadLine};
+use crate::history::HistoryEntry;
+use crate::{ConvertError, Options};
+use crate::i18n::tr;
+
+const KEYWORDS: &[&str] = &["list", "precision", "json", "help", "quit", "exit", "on", "off", "length", "temperature", "mass"];
+const HISTORY_FILE: &str = "repl_history";
+
+const HELP: &str = "\
+Commands:
+  <value> <from_unit> <to_unit>   convert, e.g. 5 km mi
+  list [category]                 list units, optionally for one category
+  precision <N | off>             round results to N decimal places
+  json <on | off>                 print results as JSON
+  help                            show this message
+  quit                            leave the REPL (or press Ctrl-D)";
+
+#[derive(Debug)]
+pub struct Session {
+    pub options: Options,
+    pub history_dir: Option<PathBuf>,
+    pub done: bool,
+}
+
+impl Session {
+    pub fn new(options: Options) -> Session {
+        Session { options, history_dir: None, done: false }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ReplError {
+    Usage(String),
+    Convert(ConvertError),
+}
+
+impl fmt::Display for ReplError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplError::Usage(message) => write!(f, "{}", message),
+            ReplError::Convert(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<ConvertError> for ReplError {
+    fn from(e: ConvertError) -> ReplError {
+        ReplError::Convert(e)
+    }
+}
+
+fn usage(message: &str) -> ReplError {
+    ReplError::Usage(message.to_string())
+}
+
+/// Completes unit names, aliases and REPL keywords for the word under the cursor.
+pub struct UnitCompleter;
+
+impl Completer for UnitCompleter {
+    fn complete(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let start = line[..pos].rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let prefix = line[start..pos].to_ascii_lowercase();
+        let mut candidates: Vec<String> = Vec::new();
+        let words = crate::registry::active()
+            .units()
+            .flat_map(|u| std::iter::once(u.name).chain(u.aliases.iter().copied()))
+            .filter(|w| !w.contains(' '))
+            .chain(KEYWORDS.iter().copied());
+        for word in words {
+            if word.to_ascii_lowercase().starts_with(&prefix) && !candidates.iter().any(|c| c == word) {
+                candidates.push(word.to_string());
+            }
+        }
+        (start, candidates)
+    }
+}
+
+/// Evaluates one line of REPL input, returning the text to print.
+pub fn eval_line(session: &mut Session, line: &str) -> Result<String, ReplError> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words.as_slice() {
+        [] => Ok(String::new()),
+        ["quit" | "exit" | "q"] => {
+            session.done = true;
+            Ok(String::new())
+        }
+        ["help" | "?"] => Ok(HELP.to_string()),
+        ["list"] => Ok(crate::units_listing(crate::registry::active(), None).trim_end().to_string()),
+        ["list", category] => {
+            let category = category.parse().map_err(|e: converter::ParseCategoryError| ReplError::Usage(e.to_string()))?;
+            Ok(crate::units_listing(crate::registry::active(), Some(category)).trim_end().to_string())
+        }
+        ["precision", "off"] => {
+            session.options.precision = None;
+            Ok("Precision reset".to_string())
+        }
+        ["precision", n] => {
+            let precision = n
+                .parse()
+                .map_err(|_| ReplError::Usage(format!("'{}' is not a valid precision", n)))?;
+            session.options.precision = Some(precision);
+            Ok(format!("Precision set to {}", precision))
+        }
+        ["precision"] => Err(usage("Usage: precision <N | off>")),
+        ["json", "on"] => {
+            session.options.json = true;
+            Ok("JSON output on".to_string())
+        }
+        ["json", "off"] => {
+            session.options.json = false;
+            Ok("JSON output off".to_string())
+        }
+        ["json", ..] => Err(usage("Usage: json <on | off>")),
+        [value, from, to] => {
+            let converted = crate::convert_checked(value, from, to)?;
+            let mut out = String::new();
+            // With JSON on, the warnings are in the record.
+            if !session.options.json {
+                for warning in &converted.warnings {
+                    out.push_str(&format!("{}: {}\n", tr("warning"), warning));
+                }
+            }
+            out.push_str(&crate::format_record(&converted.record(from, to)?, &session.options));
+            if let Some(dir) = &session.history_dir {
+                let entry = HistoryEntry::now(converted.value, from, to, converted.result);
+                crate::log_history(Some(dir), &entry);
+            }
+            Ok(out)
+        }
+        _ => Err(usage("Expected '<value> <from_unit> <to_unit>' or a command; type 'help'")),
+    }
+}
+
+/// Runs the interactive loop until `quit`, Ctrl-D or Ctrl-C.
+pub fn run(options: &Options, config: &Config) {
+    let mut session = Session::new(options.clone());
+    if options.log_history || config.history {
+        session.history_dir = config::state_dir();
+    }
+    let history = History::load(config::state_dir().map(|d| d.join(HISTORY_FILE)));
+    let interactive = io::stdin().is_terminal();
+    let mut editor = Editor::new(UnitCompleter, history, interactive);
+
+    println!("Unit Converter v3.0.0 interactive mode. Type 'help' for commands, 'quit' to exit.");
+    while !session.done {
+        let line = match editor.read_line("> ") {
+            Ok(ReadLine::Line(line)) => line,
+            Ok(ReadLine::EndOfFile | ReadLine::Interrupted) => break,
+            Err(e) => {
+                eprintln!("{}: {}", tr("error"), e);
+                break;
+            }
+        };
+        if interactive {
+            editor.history.add(&line);
+        }
+        match eval_line(&mut session, &line) {
+            Ok(out) if out.is_empty() => {}
+            Ok(out) => println!("{}", out),
+            Err(e) => eprintln!("{}: {}", tr("error"), e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> Session {
+        Session::new(Options::default())
+    }
+
+    #[test]
+    #[cfg(all(feature = "mass", feature = "temperature"))]
+    fn test_conversion() {
+        let mut s = session();
+        assert_eq!(eval_line(&mut s, "100 C F").unwrap(), "100 C = 212 F");
+        assert_eq!(eval_line(&mut s, "  1 kg g \n").unwrap(), "1 kg = 1000 g");
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_precision_setting_persists() {
+        let mut s = session();
+        assert_eq!(eval_line(&mut s, "precision 3").unwrap(), "Precision set to 3");
+        assert_eq!(eval_line(&mut s, "5 km mi").unwrap(), "5 km = 3.107 mi");
+        assert_eq!(eval_line(&mut s, "1 km mi").unwrap(), "1 km = 0.621 mi");
+        eval_line(&mut s, "precision off").unwrap();
+        assert_eq!(eval_line(&mut s, "1 m cm").unwrap(), "1 m = 100 cm");
+    }
+
+    #[test]
+    #[cfg(feature = "mass")]
+    fn test_list_category() {
+        let mut s = session();
+        let out = eval_line(&mut s, "list mass").unwrap();
+        assert!(out.starts_with("Mass:"));
+        assert!(out.contains("  lb (pound, pounds)"));
+        assert!(!out.contains("Length"));
+        assert!(eval_line(&mut s, "list colour").is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass"))]
+    fn test_errors_do_not_end_session() {
+        let mut s = session();
+        assert_eq!(
+            eval_line(&mut s, "5 km furlong"),
+            Err(ReplError::Convert(ConvertError::UnknownUnit { input: "furlong".to_string(), suggestions: vec![] }))
+        );
+        assert!(matches!(eval_line(&mut s, "5 km kg"), Err(ReplError::Convert(ConvertError::CategoryMismatch { .. }))));
+        assert!(matches!(eval_line(&mut s, "precision x"), Err(ReplError::Usage(_))));
+        assert!(matches!(eval_line(&mut s, "what"), Err(ReplError::Usage(_))));
+        assert!(!s.done);
+        assert_eq!(eval_line(&mut s, "2 kg g").unwrap(), "2 kg = 2000 g");
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_warning_is_included() {
+        let mut s = session();
+        let out = eval_line(&mut s, "-1 m cm").unwrap();
+        assert_eq!(out, "Warning: Negative length doesn't make physical sense\n-1 m = -100 cm");
+    }
+
+    fn candidates(line: &str) -> Vec<String> {
+        UnitCompleter.complete(line, line.len()).1
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "data"))]
+    fn test_complete_unit_prefix() {
+        assert_eq!(
+            candidates("5 kilo"),
+            vec!["kilometer", "kilometers", "kilometre", "kilometres", "kilogram", "kilograms", "kilobit", "kilobits", "kilobyte", "kilobytes"]
+        );
+        assert_eq!(candidates("5 km FEE"), vec!["feet"]);
+    }
+
+    #[test]
+    fn test_complete_keywords_and_misses() {
+        assert_eq!(candidates("pre"), vec!["precision"]);
+        assert_eq!(candidates("list tem"), vec!["temperature"]);
+        assert!(candidates("5 km zz").is_empty());
+        assert!(!candidates("5 km ").iter().any(|c| c.contains(' ')));
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_complete_mid_line() {
+        let (start, found) = UnitCompleter.complete("5 kilom mi", 7);
+        assert_eq!(start, 2);
+        assert_eq!(found.len(), 4);
+    }
+
+    #[test]
+    fn test_quit() {
+        let mut s = session();
+        assert_eq!(eval_line(&mut s, "").unwrap(), "");
+        assert!(!s.done);
+        eval_line(&mut s, "quit").unwrap();
+        assert!(s.done);
+    }
+}
@@ -0,0 +1,157 @@
+//! `roman`: converts between numbers and Roman numerals, whichever way the
+//! input calls for: `roman 1987` prints MCMLXXXVII and `roman MMXXIV` 2024.
+//! Numerals are read strictly, as the shortest standard spelling of their
+//! value; `--lenient` also takes additive spellings such as IIII.
+
+use crate::i18n::{tr, tr_with};
+
+const USAGE: &str = "roman <number | numeral> [--lenient]";
+const LENIENT: &str = "--lenient";
+const MAX: u32 = 3999;
+/// Largest first, with the subtractive pairs in among the letters.
+const NUMERALS: [(&str, u32); 13] = [
+    ("M", 1000),
+    ("CM", 900),
+    ("D", 500),
+    ("CD", 400),
+    ("C", 100),
+    ("XC", 90),
+    ("L", 50),
+    ("XL", 40),
+    ("X", 10),
+    ("IX", 9),
+    ("V", 5),
+    ("IV", 4),
+    ("I", 1),
+];
+/// The additive spellings `--lenient` takes, with the standard ones they
+/// stand for, longest first so VIIII isn't read as V and IIII.
+const ADDITIVE: [(&str, &str); 6] = [("DCCCC", "CM"), ("CCCC", "CD"), ("LXXXX", "XC"), ("XXXX", "XL"), ("VIIII", "IX"), ("IIII", "IV")];
+
+fn out_of_range(input: &str) -> String {
+    tr_with("roman.out_of_range", &[&input, &MAX])
+}
+
+pub fn to_roman(number: u32) -> Result<String, String> {
+    if !(1..=MAX).contains(&number) {
+        return Err(out_of_range(&number.to_string()));
+    }
+    let mut rest = number;
+    let mut numeral = String::new();
+    for (letters, value) in NUMERALS {
+        while rest >= value {
+            numeral.push_str(letters);
+            rest -= value;
+        }
+    }
+    Ok(numeral)
+}
+
+/// The value of `numeral`, ignoring case. It must be the standard
+/// spelling of that value, so IM and IIV are never numerals; `lenient` also
+/// allows the additive spellings such as IIII for IV.
+pub fn from_roman(numeral: &str, lenient: bool) -> Result<u32, String> {
+    let invalid = || tr_with("roman.invalid", &[&numeral]);
+    let upper = numeral.to_ascii_uppercase();
+    let mut rest = upper.as_str();
+    let mut total = 0;
+    while !rest.is_empty() {
+        let (letters, value) = NUMERALS.iter().find(|(letters, _)| rest.starts_with(letters)).ok_or_else(invalid)?;
+        total += value;
+        rest = &rest[letters.len()..];
+    }
+    if total == 0 {
+        return Err(invalid());
+    }
+    if total > MAX {
+        return Err(out_of_range(numeral));
+    }
+    let standard = to_roman(total)?;
+    if standard == upper {
+        return Ok(total);
+    }
+    let respelled = ADDITIVE.iter().fold(upper.clone(), |text, (additive, subtractive)| text.replace(additive, subtractive));
+    match respelled == standard {
+        true if lenient => Ok(total),
+        true => Err(tr_with("roman.not_standard", &[&numeral, &total, &standard, &LENIENT])),
+        false => Err(invalid()),
+    }
+}
+
+/// A number's numeral, or a numeral's number.
+fn convert(input: &str, lenient: bool) -> Result<String, String> {
+    match input.parse::<i64>() {
+        Ok(number) => to_roman(u32::try_from(number).map_err(|_| out_of_range(input))?),
+        Err(_) => from_roman(input, lenient).map(|number| number.to_string()),
+    }
+}
+
+/// Runs the `roman` subcommand, returning the process exit code.
+pub fn run(program: &str, args: &[String]) -> i32 {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let (input, lenient) = match args.as_slice() {
+        [input] => (*input, false),
+        [input, LENIENT] | [LENIENT, input] => (*input, true),
+        _ => {
+            eprintln!("{}: {} {}", tr("usage_label"), program, USAGE);
+            return 1;
+        }
+    };
+    match convert(input, lenient) {
+        Ok(text) => {
+            println!("{}", text);
+            0
+        }
+        Err(e) => {
+            eprintln!("{}: {}", tr("error"), e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_both_directions() {
+        for (number, numeral) in [(1987, "MCMLXXXVII"), (2024, "MMXXIV"), (1999, "MCMXCIX"), (3999, "MMMCMXCIX"), (4, "IV"), (1, "I"), (444, "CDXLIV")] {
+            assert_eq!(convert(&number.to_string(), false), Ok(numeral.to_string()));
+            assert_eq!(convert(numeral, false), Ok(number.to_string()));
+        }
+        assert_eq!(convert("mcmxcix", false), Ok("1999".to_string()));
+    }
+
+    #[test]
+    fn test_every_number_round_trips() {
+        for number in 1..=MAX {
+            assert_eq!(from_roman(&to_roman(number).unwrap(), false), Ok(number));
+        }
+    }
+
+    #[test]
+    fn test_range() {
+        for input in ["0", "4000", "-5"] {
+            assert_eq!(convert(input, false), Err(format!("{} is out of range; Roman numerals run from 1 to 3999", input)));
+        }
+        assert_eq!(from_roman("MMMM", true), Err("MMMM is out of range; Roman numerals run from 1 to 3999".to_string()));
+    }
+
+    #[test]
+    fn test_malformed_numerals() {
+        for numeral in ["IM", "IIV", "VX", "VV", "IXI", "XCX", "IIIII", "ABC", "", "MCMC"] {
+            for lenient in [false, true] {
+                assert_eq!(from_roman(numeral, lenient), Err(format!("'{}' is not a valid Roman numeral", numeral)), "{}", numeral);
+            }
+        }
+    }
+
+    #[test]
+    fn test_additive_spellings_need_lenient() {
+        assert_eq!(from_roman("IIII", false), Err("'IIII' isn't the standard spelling of 4, which is IV; --lenient accepts it".to_string()));
+        assert_eq!(from_roman("IIII", true), Ok(4));
+        assert_eq!(from_roman("VIIII", true), Ok(9));
+        assert_eq!(from_roman("MDCCCCX", true), Ok(1910));
+        assert!(from_roman("XXXX", false).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+//! `shoe`: converts shoe sizes between the US men's, US women's, UK and EU
+//! systems and foot length in centimeters, e.g. `shoe 9.5 us-mens eu`, or
+//! gives every size for a foot with `shoe --foot-length 27`.
+//!
+//! Sizes follow no one formula, so each system is a column of one chart and
+//! a size between two rows is interpolated along it. Sizes come out rounded
+//! to the nearest half; foot lengths don't.
+
+use crate::Options;
+use crate::i18n::{tr, tr_with};
+
+const FOOT_LENGTH: &str = "--foot-length";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum System {
+    UsMens,
+    UsWomens,
+    Uk,
+    Eu,
+    /// Foot length, which is what the other columns are sized for.
+    Cm,
+}
+
+/// Each system's name on the command line, other spellings it accepts, and
+/// its label in output.
+const SYSTEMS: [(System, &str, &[&str], &str); 5] = [
+    (System::UsMens, "us-mens", &["us-men", "us-m", "us"], "US men's"),
+    (System::UsWomens, "us-womens", &["us-women", "us-w"], "US women's"),
+    (System::Uk, "uk", &[], "UK"),
+    (System::Eu, "eu", &[], "EU"),
+    (System::Cm, "cm", &[], "foot length"),
+];
+
+/// US men's, UK, EU and foot length in cm, one row per half US men's size
+/// up to 12 and per size above. Every column rises down the chart.
+const CHART: [[f64; 4]; 24] = [
+    [3.5, 3.0, 35.5, 21.5],
+    [4.0, 3.5, 36.0, 22.0],
+    [4.5, 4.0, 37.0, 22.5],
+    [5.0, 4.5, 37.5, 23.0],
+    [5.5, 5.0, 38.0, 23.5],
+    [6.0, 5.5, 38.5, 24.0],
+    [6.5, 6.0, 39.0, 24.5],
+    [7.0, 6.5, 40.0, 25.0],
+    [7.5, 7.0, 40.5, 25.5],
+    [8.0, 7.5, 41.0, 26.0],
+    [8.5, 8.0, 42.0, 26.5],
+    [9.0, 8.5, 42.5, 27.0],
+    [9.5, 9.0, 43.0, 27.5],
+    [10.0, 9.5, 44.0, 28.0],
+    [10.5, 10.0, 44.5, 28.5],
+    [11.0, 10.5, 45.0, 29.0],
+    [11.5, 11.0, 45.5, 29.5],
+    [12.0, 11.5, 46.0, 30.0],
+    [13.0, 12.5, 47.5, 31.0],
+    [14.0, 13.5, 48.5, 32.0],
+    [15.0, 14.5, 49.5, 33.0],
+    [16.0, 15.5, 50.5, 34.0],
+    [17.0, 16.5, 51.5, 35.0],
+    [18.0, 17.5, 52.5, 36.0],
+];
+
+/// A US women's size is a size and a half above the men's for the same foot.
+const WOMENS_OVER_MENS: f64 = 1.5;
+
+impl System {
+    pub fn parse(name: &str) -> Option<System> {
+        let name = name.to_ascii_lowercase();
+        SYSTEMS.iter().find(|(_, key, others, _)| *key == name || others.contains(&name.as_str())).map(|(system, ..)| *system)
+    }
+
+    fn label(self) -> &'static str {
+        SYSTEMS.iter().find(|(system, ..)| *system == self).map(|(.., label)| *label).expect("every system is listed")
+    }
+
+    fn column(self, row: &[f64; 4]) -> f64 {
+        match self {
+            System::UsMens => row[0],
+            System::UsWomens => row[0] + WOMENS_OVER_MENS,
+            System::Uk => row[1],
+            System::Eu => row[2],
+            System::Cm => row[3],
+        }
+    }
+
+    /// "US men's 9.5", or "27.5 cm" for a foot length.
+    fn describe(self, size: &str) -> String {
+        match self {
+            System::Cm => format!("{} cm", size),
+            _ => format!("{} {}", self.label(), size),
+        }
+    }
+}
+
+/// `size` in `from` as a size in `to`, or the range of `from` the chart covers.
+pub fn convert(size: f64, from: System, to: System) -> Result<f64, String> {
+    let (first, last) = (from.column(&CHART[0]), from.column(&CHART[CHART.len() - 1]));
+    if !(first..=last).contains(&size) {
+        return Err(tr_with(
+            "shoe.outside_chart",
+            &[&from.describe(&size.to_string()), &from.describe(&first.to_string()), &from.describe(&last.to_string())],
+        ));
+    }
+    let row = CHART.windows(2).position(|pair| size <= from.column(&pair[1])).expect("in range");
+    let (a, b) = (&CHART[row], &CHART[row + 1]);
+    let along = (size - from.column(a)) / (from.column(b) - from.column(a));
+    let result = to.column(a) + along * (to.column(b) - to.column(a));
+    Ok(match to {
+        System::Cm => result,
+        _ => (result * 2.0).round() / 2.0,
+    })
+}
+
+fn system(name: &str) -> Result<System, String> {
+    let names: Vec<&str> = SYSTEMS.iter().map(|(_, key, ..)| *key).collect();
+    System::parse(name).ok_or_else(|| tr_with("shoe.unknown_system", &[&name, &names.join(", ")]))
+}
+
+/// The lines `shoe` prints: one conversion, or every system for a foot length.
+fn lines(size: &str, from: &str, to: Option<&str>, options: &Options) -> Result<Vec<String>, String> {
+    let value: f64 = size.parse().ok().filter(|v: &f64| v.is_finite()).ok_or_else(|| tr_with("shoe.invalid_size", &[&size]))?;
+    let from = system(from)?;
+    let targets = match to {
+        Some(to) => vec![system(to)?],
+        None => SYSTEMS.iter().map(|(system, ..)| *system).filter(|&system| system != from).collect(),
+    };
+    targets
+        .into_iter()
+        .map(|to| {
+            let result = crate::format_number(convert(value, from, to)?, options);
+            Ok(format!("{} = {}", from.describe(&crate::format_number(value, options)), to.describe(&result)))
+        })
+        .collect()
+}
+
+/// Runs the `shoe` subcommand, returning the process exit code.
+pub fn run(program: &str, args: &[String], options: &Options) -> i32 {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let (size, from, to) = match args.as_slice() {
+        [FOOT_LENGTH, length] => (*length, "cm", None),
+        [FOOT_LENGTH, length, to] => (*length, "cm", Some(*to)),
+        [size, from, to] => (*size, *from, Some(*to)),
+        _ => {
+            eprintln!("{}: {} shoe <size> <from> <to> | shoe {} <cm> [<to>]", tr("usage_label"), program, FOOT_LENGTH);
+            return 1;
+        }
+    };
+    match lines(size, from, to, options) {
+        Ok(lines) => {
+            for line in lines {
+                println!("{}", line);
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("{}: {}", tr("error"), e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shoe(size: f64, from: &str, to: &str) -> f64 {
+        convert(size, System::parse(from).unwrap(), System::parse(to).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_known_sizes_both_ways() {
+        for (us_mens, uk, eu, cm) in [(9.5, 9.0, 43.0, 27.5), (7.0, 6.5, 40.0, 25.0), (12.0, 11.5, 46.0, 30.0)] {
+            assert_eq!(shoe(us_mens, "us-mens", "uk"), uk);
+            assert_eq!(shoe(us_mens, "us-mens", "eu"), eu);
+            assert_eq!(shoe(us_mens, "us-mens", "cm"), cm);
+            assert_eq!(shoe(eu, "eu", "us-mens"), us_mens);
+            assert_eq!(shoe(uk, "uk", "eu"), eu);
+            assert_eq!(shoe(cm, "cm", "us-mens"), us_mens);
+        }
+        assert_eq!(shoe(8.0, "us-womens", "us-mens"), 6.5);
+        assert_eq!(shoe(40.0, "eu", "us-women"), 8.5);
+    }
+
+    #[test]
+    fn test_between_rows() {
+        // Half-way from 12 to 13 is 12.5, and EU 46.75 rounds to 47.
+        assert_eq!(shoe(12.5, "us-mens", "eu"), 47.0);
+        assert_eq!(shoe(27.2, "cm", "us-mens"), 9.0);
+        assert_eq!(shoe(9.25, "us-mens", "cm"), 27.25);
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        assert_eq!(convert(20.0, System::UsMens, System::Eu), Err("US men's 20 is outside the chart, which runs from US men's 3.5 to US men's 18".to_string()));
+        assert_eq!(convert(10.0, System::Cm, System::Uk), Err("10 cm is outside the chart, which runs from 21.5 cm to 36 cm".to_string()));
+        assert!(convert(3.0, System::UsWomens, System::Uk).is_err());
+    }
+
+    #[test]
+    fn test_foot_length_lists_every_system() {
+        let options = Options::default();
+        assert_eq!(lines("27", "cm", None, &options).unwrap(), ["27 cm = US men's 9", "27 cm = US women's 10.5", "27 cm = UK 8.5", "27 cm = EU 42.5"]);
+        assert_eq!(lines("9", "us-mens", Some("shoe"), &options).unwrap_err(), "unknown sizing system 'shoe'; use us-mens, us-womens, uk, eu, cm");
+        assert_eq!(lines("nine", "us-mens", Some("eu"), &options).unwrap_err(), "'nine' is not a valid shoe size");
+    }
+}
@@ -0,0 +1,125 @@
+//! `sum`: adds quantities given in mixed units, e.g. `sum 5ft 3in 2.5cm --to m`.
+
+use converter::quantity::split_quantity;
+
+use crate::{Options, Unit, UnitCategory};
+use crate::i18n::tr;
+
+fn lookup(unit: &str, argument: &str) -> Result<&'static Unit, String> {
+    let found = crate::find_unit(unit).ok_or_else(|| match crate::unit_error(unit) {
+        explained @ (crate::ConvertError::DisabledUnit { .. } | crate::ConvertError::AmbiguousUnit { .. }) => explained.to_string(),
+        _ => format!("Unknown unit '{}' in '{}'", unit, argument),
+    })?;
+    if found.category == UnitCategory::Temperature {
+        return Err(format!("'{}' is a temperature; adding absolute temperatures is meaningless", argument));
+    }
+    Ok(found)
+}
+
+/// Adds every quantity in base units and returns the total in `to`.
+pub fn sum(quantities: &[(String, f64, String)], to: &str) -> Result<f64, String> {
+    let target = lookup(to, to)?;
+    let mut base = 0.0;
+    for (argument, value, unit) in quantities {
+        let unit = lookup(unit, argument)?;
+        if unit.dimension() != target.dimension() {
+            return Err(format!(
+                "'{}' is a {} quantity, but the total is in {} ({})",
+                argument, unit.category, to, target.category
+            ));
+        }
+        base += unit.to_base(*value);
+    }
+    Ok(target.to_unit(base))
+}
+
+/// Runs the `sum` subcommand, returning the process exit code.
+pub fn run(program: &str, args: &[String], options: &Options) -> i32 {
+    let mut quantities = Vec::new();
+    let mut to = None;
+    let mut iter = args.iter().peekable();
+    while let Some(arg) = iter.next() {
+        if arg == "--to" {
+            to = iter.next().cloned();
+            if to.is_none() {
+                eprintln!("{}: --to requires a unit", tr("error"));
+                return 1;
+            }
+            continue;
+        }
+        // Accept "5 ft" as well as "5ft".
+        let parsed = match (arg.parse::<f64>(), iter.peek()) {
+            (Ok(value), Some(unit)) if *unit != "--to" => {
+                let unit = iter.next().expect("peeked");
+                Some((format!("{} {}", arg, unit), value, unit.clone()))
+            }
+            _ => split_quantity(arg).map(|(value, unit)| (arg.clone(), value, unit.to_string())),
+        };
+        match parsed {
+            Some(quantity) => quantities.push(quantity),
+            None => {
+                eprintln!("{}: '{}' is not a quantity like '5ft' or '2.5kg'", tr("error"), arg);
+                return 1;
+            }
+        }
+    }
+    let Some(to) = to.or_else(|| quantities.first().map(|(_, _, unit)| unit.clone())) else {
+        eprintln!("{}: {} sum <quantity>... [--to <unit>]", tr("usage_label"), program);
+        return 1;
+    };
+
+    match sum(&quantities, &to) {
+        Ok(total) => {
+            let total = crate::format_number(total, options);
+            let terms: Vec<&str> = quantities.iter().map(|(argument, _, _)| argument.as_str()).collect();
+            println!("{} = {} {}", terms.join(" + "), total, crate::unit_label(&to, &total, options));
+            0
+        }
+        Err(e) => {
+            eprintln!("{}: {}", tr("error"), e);
+            1
+        }
+    }
+}
+
+#[cfg(all(test, any(feature = "length", feature = "mass")))]
+mod tests {
+    use super::*;
+
+    fn quantities(args: &[&str]) -> Vec<(String, f64, String)> {
+        args.iter()
+            .map(|a| {
+                let (value, unit) = split_quantity(a).unwrap();
+                (a.to_string(), value, unit.to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_mixed_length_sum() {
+        let total = sum(&quantities(&["5ft", "3in", "2.5cm"]), "m").unwrap();
+        assert!((total - (5.0 * 0.3048 + 3.0 * 0.0254 + 0.025)).abs() < 1e-12);
+    }
+
+    #[test]
+    #[cfg(feature = "mass")]
+    fn test_mass_sum() {
+        let total = sum(&quantities(&["1kg", "500g", "250000mg"]), "g").unwrap();
+        assert!((total - 1750.0).abs() < 1e-9);
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass"))]
+    fn test_category_disagreement_names_argument() {
+        let err = sum(&quantities(&["5ft", "2kg"]), "m").unwrap_err();
+        assert_eq!(err, "'2kg' is a Mass quantity, but the total is in m (Length)");
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn test_temperatures_rejected() {
+        assert!(sum(&quantities(&["20C", "5C"]), "C").unwrap_err().contains("meaningless"));
+        assert!(sum(&quantities(&["5m", "20C"]), "m").unwrap_err().starts_with("'20C' is a temperature"));
+    }
+}
@@ -0,0 +1,276 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Number(f64),
+    Bool(bool),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub key: String,
+    pub value: Value,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub name: String,
+    pub array: bool,
+    pub line: usize,
+    pub entries: Vec<Entry>,
+}
+
+impl Table {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries.iter().find(|e| e.key == key).map(|e| &e.value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    pub tables: Vec<Table>,
+}
+
+impl Document {
+    pub fn root(&self) -> &Table {
+        &self.tables[0]
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.root().get(key)
+    }
+
+    /// Tables nested one level below `prefix`, e.g. `[fav.x]` for prefix "fav".
+    pub fn subtables<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = (&'a str, &'a Table)> {
+        self.tables.iter().filter_map(move |t| {
+            let rest = t.name.strip_prefix(prefix)?.strip_prefix('.')?;
+            Some((rest, t))
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+pub fn parse(input: &str) -> Result<Document, ParseError> {
+    let mut tables = vec![Table { name: String::new(), array: false, line: 0, entries: Vec::new() }];
+
+    for (index, raw) in input.lines().enumerate() {
+        let line = index + 1;
+        let err = |message: String| ParseError { line, message };
+        let text = strip_comment(raw).trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = text.strip_prefix('[') {
+            let (name, array) = match header.strip_prefix('[') {
+                Some(inner) => (inner.strip_suffix("]]"), true),
+                None => (header.strip_suffix(']'), false),
+            };
+            let name = name.ok_or_else(|| err("unterminated table header".to_string()))?.trim();
+            if name.is_empty() {
+                return Err(err("empty table name".to_string()));
+            }
+            let name = name.split('.').map(|part| unquote_key(part.trim())).collect::<Vec<_>>().join(".");
+            if !array && tables.iter().any(|t| t.name == name) {
+                return Err(err(format!("duplicate table [{}]", name)));
+            }
+            tables.push(Table { name, array, line, entries: Vec::new() });
+            continue;
+        }
+
+        let (key, value) = text
+            .split_once('=')
+            .ok_or_else(|| err(format!("expected 'key = value', found '{}'", text)))?;
+        let key = unquote_key(key.trim());
+        if key.is_empty() {
+            return Err(err("empty key".to_string()));
+        }
+        let value = parse_value(value.trim()).map_err(err)?;
+        let table = tables.last_mut().expect("root table");
+        if table.get(&key).is_some() {
+            return Err(err(format!("duplicate key '{}'", key)));
+        }
+        table.entries.push(Entry { key, value, line });
+    }
+
+    Ok(Document { tables })
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn unquote_key(key: &str) -> String {
+    key.strip_prefix('"')
+        .and_then(|k| k.strip_suffix('"'))
+        .unwrap_or(key)
+        .to_string()
+}
+
+fn parse_value(text: &str) -> Result<Value, String> {
+    if let Some(inner) = text.strip_prefix('"') {
+        let inner = inner
+            .strip_suffix('"')
+            .ok_or_else(|| format!("unterminated string {}", text))?;
+        return Ok(Value::String(unescape(inner)));
+    }
+    if let Some(inner) = text.strip_prefix('[') {
+        let inner = inner
+            .strip_suffix(']')
+            .ok_or_else(|| format!("unterminated array {}", text))?;
+        return split_array(inner)
+            .into_iter()
+            .map(|item| parse_value(item.trim()))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array);
+    }
+    match text {
+        "true" => Ok(Value::Bool(true)),
+        "false" => Ok(Value::Bool(false)),
+        _ => text
+            .replace('_', "")
+            .parse()
+            .map(Value::Number)
+            .map_err(|_| format!("invalid value '{}'", text)),
+    }
+}
+
+fn split_array(inner: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in inner.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            ',' if !in_string => {
+                items.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&inner[start..]);
+    items.retain(|item| !item.trim().is_empty());
+    items
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+pub fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_root_keys() {
+        let doc = parse("history = true\nname = \"x # y\" # comment\nn = 1_000\n").unwrap();
+        assert_eq!(doc.get("history"), Some(&Value::Bool(true)));
+        assert_eq!(doc.get("name"), Some(&Value::String("x # y".to_string())));
+        assert_eq!(doc.get("n"), Some(&Value::Number(1000.0)));
+    }
+
+    #[test]
+    fn test_parse_tables_and_arrays() {
+        let doc = parse("[fav.body-temp]\nfrom = \"F\"\ntags = [\"a\", \"b\",]\n\n[[unit]]\nname = \"a\"\n[[unit]]\nname = \"b\"\n").unwrap();
+        let names: Vec<&str> = doc.tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["", "fav.body-temp", "unit", "unit"]);
+        let tags = Value::Array(vec![Value::String("a".to_string()), Value::String("b".to_string())]);
+        assert_eq!(doc.tables[1].get("tags"), Some(&tags));
+        assert!(doc.tables[2].array && doc.tables[3].array);
+        let (name, table) = doc.subtables("fav").next().unwrap();
+        assert_eq!(name, "body-temp");
+        assert_eq!(table.get("from").and_then(Value::as_str), Some("F"));
+    }
+
+    #[test]
+    fn test_quote_round_trip() {
+        let quoted = quote("a \"b\" \\ c");
+        let doc = parse(&format!("k = {}", quoted)).unwrap();
+        assert_eq!(doc.get("k").and_then(Value::as_str), Some("a \"b\" \\ c"));
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        let err = parse("a = 1\nb 2\n").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(parse("a = 1\na = 2\n").unwrap_err().line, 2);
+        assert_eq!(parse("[x\n").unwrap_err().line, 1);
+    }
+}
@@ -0,0 +1,274 @@
+//! Instrumentation for programs that embed the library, in the shape of the
+//! `tracing` crate: spans around work, events inside them, each with a level
+//! and named fields, all handed to a [`Subscriber`] the program installs.
+//! Nothing is recorded, and no field is formatted, until one is.
+//!
+//! A conversion opens a `convert` span with `from`, `to` and, once both
+//! units are known, `category`. Inside it, debug events report a word that
+//! resolved through an alias (`alias`) and a compound unit put together from
+//! its terms (`compound`), and a warn event reports each physical-validity
+//! warning (`warning`).
+//!
+//! ```
+//! use std::sync::{Arc, Mutex};
+//! use converter::trace::{self, Record, Subscriber};
+//!
+//! #[derive(Default)]
+//! struct Names(Mutex<Vec<&'static str>>);
+//!
+//! impl Subscriber for Names {
+//!     fn event(&self, event: &Record, _spans: &[Record]) {
+//!         self.0.lock().unwrap().push(event.name);
+//!     }
+//! }
+//!
+//! # #[cfg(feature = "length")] {
+//! let names = Arc::new(Names::default());
+//! trace::with_default(names.clone(), || converter::convert(-5.0, "kilometers", "mi")).unwrap();
+//! assert_eq!(*names.0.lock().unwrap(), ["alias", "warning"]);
+//! # }
+//! ```
+
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Warn,
+    Info,
+    Debug,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+        })
+    }
+}
+
+/// A span or an event: what happened, and its fields already formatted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub level: Level,
+    pub name: &'static str,
+    pub fields: Vec<(&'static str, String)>,
+}
+
+impl Record {
+    /// The value of the field `name`, if it has one.
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.iter().find(|(field, _)| *field == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// `name{a=1 b=2}`, the way `tracing` prints a span.
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name)?;
+        for (i, (field, value)) in self.fields.iter().enumerate() {
+            write!(f, "{}{}={}", if i == 0 { "{" } else { " " }, field, value)?;
+        }
+        if self.fields.is_empty() { Ok(()) } else { f.write_str("}") }
+    }
+}
+
+pub trait Subscriber: Send + Sync {
+    /// An event, with the spans open on this thread, outermost first.
+    fn event(&self, event: &Record, spans: &[Record]);
+
+    /// A span was opened, with the fields it has so far.
+    fn enter(&self, _span: &Record) {}
+
+    /// A span was closed, with every field recorded on it.
+    fn exit(&self, _span: &Record) {}
+}
+
+/// Prints every event to stderr as `LEVEL span{fields}: event fields`, the
+/// format `-vv` uses.
+pub struct Stderr;
+
+impl Subscriber for Stderr {
+    fn event(&self, event: &Record, spans: &[Record]) {
+        eprintln!("{}", line(event, spans));
+    }
+}
+
+fn line(event: &Record, spans: &[Record]) -> String {
+    let mut line = format!("{:>5} ", event.level);
+    for span in spans {
+        line.push_str(&format!("{}: ", span));
+    }
+    line.push_str(event.name);
+    for (field, value) in &event.fields {
+        line.push_str(&format!(" {}={}", field, value));
+    }
+    line
+}
+
+static GLOBAL: OnceLock<Arc<dyn Subscriber>> = OnceLock::new();
+
+thread_local! {
+    static SCOPED: RefCell<Option<Arc<dyn Subscriber>>> = const { RefCell::new(None) };
+    static SPANS: RefCell<Vec<Record>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Installs `subscriber` for every thread, once; false if one already was.
+pub fn set_global(subscriber: Arc<dyn Subscriber>) -> bool {
+    GLOBAL.set(subscriber).is_ok()
+}
+
+/// Runs `f` with `subscriber` in place of the global one on this thread.
+pub fn with_default<T>(subscriber: Arc<dyn Subscriber>, f: impl FnOnce() -> T) -> T {
+    let previous = SCOPED.with(|scoped| scoped.borrow_mut().replace(subscriber));
+    struct Restore(Option<Arc<dyn Subscriber>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCOPED.with(|scoped| *scoped.borrow_mut() = self.0.take());
+        }
+    }
+    let _restore = Restore(previous);
+    f()
+}
+
+fn current() -> Option<Arc<dyn Subscriber>> {
+    SCOPED.with(|scoped| scoped.borrow().clone()).or_else(|| GLOBAL.get().cloned())
+}
+
+/// Sends an event to the current subscriber; `fields` is only called when
+/// there is one.
+pub(crate) fn event(level: Level, name: &'static str, fields: impl FnOnce() -> Vec<(&'static str, String)>) {
+    if let Some(subscriber) = current() {
+        let event = Record { level, name, fields: fields() };
+        SPANS.with(|spans| subscriber.event(&event, &spans.borrow()));
+    }
+}
+
+/// An open span; it closes when dropped.
+pub(crate) struct Span {
+    subscriber: Option<Arc<dyn Subscriber>>,
+}
+
+/// Opens a span on this thread; `fields` is only called when there is a subscriber.
+pub(crate) fn span(name: &'static str, fields: impl FnOnce() -> Vec<(&'static str, String)>) -> Span {
+    let subscriber = current();
+    if let Some(subscriber) = &subscriber {
+        let span = Record { level: Level::Info, name, fields: fields() };
+        subscriber.enter(&span);
+        SPANS.with(|spans| spans.borrow_mut().push(span));
+    }
+    Span { subscriber }
+}
+
+impl Span {
+    /// Adds a field learned after the span opened.
+    pub(crate) fn record(&self, field: &'static str, value: impl FnOnce() -> String) {
+        if self.subscriber.is_some() {
+            SPANS.with(|spans| spans.borrow_mut().last_mut().map(|span| span.fields.push((field, value()))));
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if let Some(subscriber) = &self.subscriber
+            && let Some(span) = SPANS.with(|spans| spans.borrow_mut().pop())
+        {
+            subscriber.exit(&span);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Every event with the names of the spans around it, and every closed span.
+    #[derive(Default)]
+    struct Capture {
+        events: Mutex<Vec<(Record, Vec<&'static str>)>>,
+        closed: Mutex<Vec<Record>>,
+    }
+
+    impl Subscriber for Capture {
+        fn event(&self, event: &Record, spans: &[Record]) {
+            self.events.lock().unwrap().push((event.clone(), spans.iter().map(|s| s.name).collect()));
+        }
+
+        fn exit(&self, span: &Record) {
+            self.closed.lock().unwrap().push(span.clone());
+        }
+    }
+
+    fn capture<T>(f: impl FnOnce() -> T) -> (T, Arc<Capture>) {
+        let capture = Arc::new(Capture::default());
+        (with_default(capture.clone(), f), capture)
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_convert_span_records_units_and_category() {
+        let (result, capture) = capture(|| crate::convert(5.0, "km", "mi"));
+        assert!(result.is_ok());
+        let closed = capture.closed.lock().unwrap();
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].to_string(), "convert{from=km to=mi category=Length}");
+        assert!(capture.events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_alias_and_warning_events() {
+        let (result, capture) = capture(|| crate::convert_checked("-3", "feet", "Meters"));
+        assert_eq!(result.unwrap().warnings.len(), 1);
+        let events = capture.events.lock().unwrap();
+        let names: Vec<(Level, &str)> = events.iter().map(|(e, _)| (e.level, e.name)).collect();
+        assert_eq!(names, [(Level::Debug, "alias"), (Level::Debug, "alias"), (Level::Warn, "warning")]);
+        assert_eq!(events[0].0.field("input"), Some("feet"));
+        assert_eq!(events[0].0.field("unit"), Some("ft"));
+        assert_eq!(events[1].0.field("input"), Some("Meters"));
+        assert_eq!(events[2].0.field("message"), Some("Negative length doesn't make physical sense"));
+        assert!(events.iter().all(|(_, spans)| spans == &["convert"]));
+        assert_eq!(capture.closed.lock().unwrap()[0].field("category"), Some("Length"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "time"))]
+    fn test_compound_event() {
+        let (result, capture) = capture(|| crate::convert(36.0, "km/h", "cm/s"));
+        assert_eq!(result, Ok(1000.0));
+        let events = capture.events.lock().unwrap();
+        let compounds: Vec<&str> = events.iter().filter(|(e, _)| e.name == "compound").filter_map(|(e, _)| e.field("input")).collect();
+        assert_eq!(compounds, ["km/h", "cm/s"]);
+        assert!(events.iter().all(|(e, _)| e.level == Level::Debug));
+    }
+
+    #[test]
+    fn test_failures_close_the_span() {
+        let (result, capture) = capture(|| crate::convert(1.0, "kg", "furlong"));
+        assert!(result.is_err());
+        assert_eq!(capture.closed.lock().unwrap()[0].to_string(), "convert{from=kg to=furlong}");
+        SPANS.with(|spans| assert!(spans.borrow().is_empty()));
+    }
+
+    #[test]
+    fn test_nothing_without_a_subscriber() {
+        let span = span("convert", || unreachable!("fields of an unobserved span"));
+        span.record("category", || unreachable!());
+        event(Level::Warn, "warning", || unreachable!());
+        drop(span);
+    }
+
+    #[test]
+    fn test_stderr_line() {
+        let span = Record { level: Level::Info, name: "convert", fields: vec![("from", "ft".to_string()), ("to", "m".to_string())] };
+        let event = Record { level: Level::Debug, name: "alias", fields: vec![("input", "feet".to_string()), ("unit", "ft".to_string())] };
+        assert_eq!(line(&event, &[span]), "DEBUG convert{from=ft to=m}: alias input=feet unit=ft");
+        let warning = Record { level: Level::Warn, name: "warning", fields: vec![] };
+        assert_eq!(line(&warning, &[]), " WARN warning");
+    }
+}
@@ -0,0 +1,310 @@
+//! `converter tui`: a full-screen converter with category and unit panes and a
+//! result that updates as the value is typed. `App` holds all of the state and
+//! is driven by `update`, so the behaviour can be tested without a terminal;
+//! `run` only feeds it keys and paints `view`.
+
+use std::io::{self, Write};
+
+use crate::editor::{self, Key, RawMode};
+use crate::{Options, Unit};
+
+const PANE_WIDTH: usize = 26;
+const HELP: &str = "Tab: next pane  Up/Down: select  /: search units  q or Esc: quit";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Category,
+    From,
+    To,
+    Value,
+}
+
+impl Pane {
+    fn next(self) -> Pane {
+        match self {
+            Pane::Category => Pane::From,
+            Pane::From => Pane::To,
+            Pane::To => Pane::Value,
+            Pane::Value => Pane::Category,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct App {
+    pub focus: Pane,
+    pub category: usize,
+    /// Indices into `units()`.
+    pub from: usize,
+    pub to: usize,
+    pub search: String,
+    /// Keys go to the search box rather than the panes.
+    pub searching: bool,
+    pub value: String,
+    pub done: bool,
+    options: Options,
+}
+
+impl App {
+    pub fn new(options: &Options) -> App {
+        App {
+            focus: Pane::Value,
+            category: 0,
+            from: 0,
+            to: 1,
+            search: String::new(),
+            searching: false,
+            value: String::new(),
+            done: false,
+            options: options.clone(),
+        }
+    }
+
+    /// The units of the selected category that match the search, by the same
+    /// names the command line accepts (or a prefix of one).
+    pub fn units(&self) -> Vec<&'static Unit> {
+        let category = converter::categories()[self.category];
+        let search = self.search.to_ascii_lowercase();
+        crate::registry::active()
+            .units()
+            .filter(|u| u.category == category)
+            .filter(|u| {
+                search.is_empty()
+                    || u.matches(&self.search)
+                    || std::iter::once(u.name).chain(u.aliases.iter().copied()).any(|n| n.to_ascii_lowercase().starts_with(&search))
+            })
+            .collect()
+    }
+
+    /// Keeps the unit selections inside the (possibly filtered) list.
+    fn clamp(&mut self) {
+        let last = self.units().len().saturating_sub(1);
+        self.from = self.from.min(last);
+        self.to = self.to.min(last);
+    }
+
+    pub fn update(&mut self, key: Key) {
+        if self.searching {
+            match key {
+                Key::Char(c) => self.search.push(c),
+                Key::Backspace => {
+                    self.search.pop();
+                }
+                Key::Enter => self.searching = false,
+                Key::Escape => {
+                    self.search.clear();
+                    self.searching = false;
+                }
+                Key::Interrupt => self.done = true,
+                _ => {}
+            }
+            self.clamp();
+            return;
+        }
+        match (key, self.focus) {
+            (Key::Interrupt | Key::Escape | Key::EndOfFile, _) => self.done = true,
+            (Key::Tab | Key::Enter, pane) => self.focus = pane.next(),
+            (Key::Char(c), Pane::Value) if c != 'q' || !self.value.is_empty() => self.value.push(c),
+            (Key::Backspace, Pane::Value) => {
+                self.value.pop();
+            }
+            (Key::Char('q'), _) => self.done = true,
+            (Key::Char('/'), Pane::From | Pane::To) => self.searching = true,
+            (Key::Up | Key::Down, pane) => {
+                let down = key == Key::Down;
+                let step = |i: usize, len: usize| if down { (i + 1).min(len.saturating_sub(1)) } else { i.saturating_sub(1) };
+                match pane {
+                    Pane::Category => {
+                        let selected = step(self.category, converter::categories().len());
+                        if selected != self.category {
+                            (self.category, self.from, self.to) = (selected, 0, 1);
+                            self.search.clear();
+                        }
+                    }
+                    Pane::From => self.from = step(self.from, self.units().len()),
+                    Pane::To => self.to = step(self.to, self.units().len()),
+                    Pane::Value => {}
+                }
+            }
+            _ => {}
+        }
+        self.clamp();
+    }
+
+    /// The conversion of the typed value, or why there is none.
+    pub fn result(&self) -> Option<Result<String, String>> {
+        if self.value.trim().is_empty() {
+            return None;
+        }
+        let units = self.units();
+        let (Some(from), Some(to)) = (units.get(self.from), units.get(self.to)) else {
+            return Some(Err("no units match the search".to_string()));
+        };
+        Some(
+            crate::convert_checked(self.value.trim(), from.name, to.name)
+                .map(|c| crate::format_result(c.value, from.name, to.name, c.result, &self.options))
+                .map_err(|e| e.to_string()),
+        )
+    }
+
+    pub fn view(&self) -> Vec<String> {
+        let heading = |pane: Pane, title: &str| if self.focus == pane { format!("[{}]", title) } else { format!(" {} ", title) };
+        let marker = |selected: bool| if selected { "> " } else { "  " };
+        let categories: Vec<String> =
+            converter::categories().iter().enumerate().map(|(i, name)| format!("{}{}", marker(i == self.category), name)).collect();
+        let units = self.units();
+        let column = |selected: usize| -> Vec<String> {
+            units.iter().enumerate().map(|(i, u)| format!("{}{} ({})", marker(i == selected), u.name, u.display_plural)).collect()
+        };
+        let columns = [categories, column(self.from), column(self.to)];
+
+        let mut lines = vec![format!(
+            "{:<w$}{:<w$}{}",
+            heading(Pane::Category, "Category"),
+            heading(Pane::From, "From"),
+            heading(Pane::To, "To"),
+            w = PANE_WIDTH
+        )
+        .trim_end()
+        .to_string()];
+        let rows = columns.iter().map(Vec::len).max().unwrap_or(0);
+        for row in 0..rows {
+            let cell = |c: usize| columns[c].get(row).map_or("", String::as_str);
+            lines.push(format!("{:<w$}{:<w$}{}", cell(0), cell(1), cell(2), w = PANE_WIDTH).trim_end().to_string());
+        }
+        lines.push(String::new());
+        let cursor = |active: bool| if active { "_" } else { "" };
+        lines.push(format!("Search: {}{}", self.search, cursor(self.searching)));
+        lines.push(format!("{}: {}{}", heading(Pane::Value, "Value"), self.value, cursor(self.focus == Pane::Value && !self.searching)));
+        lines.push(match self.result() {
+            None => String::new(),
+            Some(Ok(result)) => result,
+            Some(Err(e)) => format!("Error: {}", e),
+        });
+        lines.push(String::new());
+        lines.push(HELP.to_string());
+        lines
+    }
+}
+
+/// Runs the TUI on the terminal until the user quits.
+pub fn run(options: &Options) -> io::Result<()> {
+    let Some(_raw) = RawMode::enable_polling() else {
+        return Err(io::Error::other("the TUI needs a terminal"));
+    };
+    let mut stdout = io::stdout();
+    let mut app = App::new(options);
+    // Alternate screen, restored on the way out.
+    write!(stdout, "\x1b[?1049h")?;
+    let outcome = event_loop(&mut app, &mut stdout);
+    write!(stdout, "\x1b[?1049l")?;
+    stdout.flush()?;
+    outcome
+}
+
+fn event_loop(app: &mut App, stdout: &mut impl Write) -> io::Result<()> {
+    let mut stdin = io::stdin().lock();
+    while !app.done {
+        write!(stdout, "\x1b[H\x1b[2J{}", app.view().join("\r\n"))?;
+        stdout.flush()?;
+        // A timed-out read just means no key yet.
+        if let Some(key) = editor::read_key(&mut stdin)? {
+            app.update(key);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app() -> App {
+        App::new(&Options::default())
+    }
+
+    fn keys(app: &mut App, keys: &[Key]) {
+        for key in keys {
+            app.update(*key);
+        }
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.update(Key::Char(c));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_live_result_while_typing() {
+        let mut app = app();
+        assert_eq!(app.result(), None);
+        type_text(&mut app, "5");
+        assert_eq!(app.result(), Some(Ok("5 km = 5000 m".to_string())));
+        type_text(&mut app, "0");
+        assert_eq!(app.result(), Some(Ok("50 km = 50000 m".to_string())));
+        keys(&mut app, &[Key::Backspace, Key::Backspace]);
+        assert_eq!(app.result(), None);
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn test_pick_category_and_units() {
+        let mut app = app();
+        // Value -> Category, then down to Temperature.
+        keys(&mut app, &[Key::Tab, Key::Down]);
+        assert_eq!((app.category, app.from, app.to), (1, 0, 1));
+        // From stays C; To moves to K.
+        keys(&mut app, &[Key::Tab, Key::Tab, Key::Down, Key::Down, Key::Down, Key::Tab]);
+        assert_eq!(app.focus, Pane::Value);
+        assert_eq!(app.to, 2);
+        type_text(&mut app, "100");
+        assert_eq!(app.result(), Some(Ok("100 C = 373.15 K".to_string())));
+        type_text(&mut app, "x");
+        assert_eq!(app.result(), Some(Err("'100x' is not a valid number".to_string())));
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_search_filters_units() {
+        let mut app = app();
+        keys(&mut app, &[Key::Tab, Key::Tab, Key::Char('/')]);
+        assert!(app.searching);
+        type_text(&mut app, "mi");
+        let names: Vec<&str> = app.units().iter().map(|u| u.name).collect();
+        assert_eq!(names, ["mm", "mi"]);
+        assert_eq!((app.from, app.to), (0, 1));
+        keys(&mut app, &[Key::Backspace, Key::Backspace]);
+        type_text(&mut app, "feet");
+        assert_eq!(app.units().iter().map(|u| u.name).collect::<Vec<_>>(), ["ft"]);
+        assert_eq!((app.from, app.to), (0, 0));
+        keys(&mut app, &[Key::Escape]);
+        assert!(!app.searching && app.search.is_empty() && !app.done);
+    }
+
+    #[test]
+    fn test_quit_keys() {
+        let mut typed = app();
+        type_text(&mut typed, "5q");
+        assert!(!typed.done, "q is text once a value is being typed");
+        for key in [Key::Char('q'), Key::Escape, Key::Interrupt] {
+            let mut app = app();
+            app.focus = Pane::From;
+            app.update(key);
+            assert!(app.done);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_view_marks_focus_and_selection() {
+        let mut app = app();
+        type_text(&mut app, "1");
+        let view = app.view();
+        assert!(view[0].starts_with(" Category "));
+        assert!(view[1].starts_with("> Length"));
+        assert!(view.iter().any(|l| l == "[Value]: 1_"));
+        assert!(view.iter().any(|l| l == "1 km = 1000 m"));
+    }
+}
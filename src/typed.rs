@@ -0,0 +1,247 @@
+//! Quantities whose category is their type, for programs that would rather
+//! have the compiler catch a mass passed as a length than get a
+//! `CategoryMismatch` at run time. Each keeps its value in the category's
+//! base unit and has a `from_`/`to_` pair for every built-in unit, under its
+//! symbol and its plural name, generated from `units.toml` with the tables:
+//!
+//! ```
+//! # #[cfg(all(feature = "length", feature = "temperature"))] {
+//! use converter::typed::{Length, Temperature};
+//!
+//! let run = Length::from_km(5.0) + Length::from_meters(195.0);
+//! assert!((run.to_miles() - 3.2281).abs() < 1e-4);
+//! assert_eq!(Temperature::from_f(212.0).to_c(), 100.0);
+//! # }
+//! ```
+//!
+//! Lengths and masses add, subtract and scale; temperatures don't, since
+//! adding two of them means nothing. Quantities of different categories
+//! don't mix:
+//!
+//! ```compile_fail
+//! use converter::typed::{Length, Mass};
+//!
+//! let nonsense = Length::from_km(5.0) + Mass::from_kg(1.0);
+//! ```
+//!
+//! A [`Quantity`] of the right category converts with `try_from`, and each
+//! typed quantity converts back into its base unit.
+
+#[cfg(any(feature = "length", feature = "mass"))]
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+use crate::{ConvertError, Quantity, Unit};
+
+macro_rules! typed_quantity {
+    ($(#[$doc:meta])* $name:ident, $table:literal, $module:ident) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+        pub struct $name(f64);
+
+        impl $name {
+            const UNITS: &[Unit] = crate::units::$module::UNITS;
+
+            fn from_unit(value: f64, index: usize) -> $name {
+                $name($name::UNITS[index].to_base(value))
+            }
+
+            fn to_unit(self, index: usize) -> f64 {
+                $name::UNITS[index].to_unit(self.0)
+            }
+        }
+
+        include!(concat!(env!("OUT_DIR"), "/typed_", $table, ".rs"));
+
+        impl TryFrom<Quantity> for $name {
+            type Error = ConvertError;
+
+            fn try_from(quantity: Quantity) -> Result<$name, ConvertError> {
+                let base = &$name::UNITS[$name::BASE];
+                if quantity.unit.category != base.category {
+                    return Err(ConvertError::CategoryMismatch {
+                        from: quantity.unit.category,
+                        to: base.category,
+                        from_unit: quantity.unit.name.to_string(),
+                        to_unit: base.name.to_string(),
+                    });
+                }
+                Ok($name(quantity.unit.to_base(quantity.value)))
+            }
+        }
+
+        impl From<$name> for Quantity {
+            /// The quantity in the category's base unit.
+            fn from(quantity: $name) -> Quantity {
+                Quantity { value: quantity.0, unit: &$name::UNITS[$name::BASE] }
+            }
+        }
+    };
+}
+
+/// Addition and subtraction of two quantities, and scaling by a number.
+#[cfg(any(feature = "length", feature = "mass"))]
+macro_rules! linear_ops {
+    ($name:ident) => {
+        impl Add for $name {
+            type Output = $name;
+
+            fn add(self, other: $name) -> $name {
+                $name(self.0 + other.0)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = $name;
+
+            fn sub(self, other: $name) -> $name {
+                $name(self.0 - other.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, other: $name) {
+                self.0 += other.0;
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, other: $name) {
+                self.0 -= other.0;
+            }
+        }
+
+        impl Neg for $name {
+            type Output = $name;
+
+            fn neg(self) -> $name {
+                $name(-self.0)
+            }
+        }
+
+        impl Mul<f64> for $name {
+            type Output = $name;
+
+            fn mul(self, factor: f64) -> $name {
+                $name(self.0 * factor)
+            }
+        }
+
+        impl Mul<$name> for f64 {
+            type Output = $name;
+
+            fn mul(self, quantity: $name) -> $name {
+                $name(self * quantity.0)
+            }
+        }
+
+        impl Div<f64> for $name {
+            type Output = $name;
+
+            fn div(self, divisor: f64) -> $name {
+                $name(self.0 / divisor)
+            }
+        }
+
+        /// How many times `other` fits in this quantity.
+        impl Div for $name {
+            type Output = f64;
+
+            fn div(self, other: $name) -> f64 {
+                self.0 / other.0
+            }
+        }
+    };
+}
+
+#[cfg(feature = "length")]
+typed_quantity!(
+    /// A length, kept in meters.
+    Length,
+    "length",
+    length
+);
+#[cfg(feature = "length")]
+linear_ops!(Length);
+
+#[cfg(feature = "mass")]
+typed_quantity!(
+    /// A mass, kept in kilograms.
+    Mass,
+    "mass",
+    mass
+);
+#[cfg(feature = "mass")]
+linear_ops!(Mass);
+
+#[cfg(feature = "temperature")]
+typed_quantity!(
+    /// A temperature, kept in degrees Celsius.
+    Temperature,
+    "temperature",
+    temperature
+);
+
+// Every test uses all three types; a build with fewer has fewer to test.
+#[cfg(all(test, feature = "length", feature = "mass", feature = "temperature"))]
+mod tests {
+    use super::*;
+    use crate::UnitCategory;
+
+    fn index(units: &[Unit], unit: &Unit) -> usize {
+        units.iter().position(|u| u.name == unit.name).unwrap()
+    }
+
+    #[test]
+    fn test_accessors_agree_with_the_dynamic_api() {
+        for unit in crate::UNITS {
+            for other in crate::UNITS.iter().filter(|other| other.category == unit.category) {
+                let value = 12.5;
+                let typed = match unit.category {
+                    UnitCategory::Length => Length::try_from(Quantity { value, unit }).unwrap().to_unit(index(Length::UNITS, other)),
+                    UnitCategory::Mass => Mass::try_from(Quantity { value, unit }).unwrap().to_unit(index(Mass::UNITS, other)),
+                    UnitCategory::Temperature => {
+                        Temperature::try_from(Quantity { value, unit }).unwrap().to_unit(index(Temperature::UNITS, other))
+                    }
+                    _ => continue,
+                };
+                assert_eq!(typed, crate::convert(value, unit.name, other.name).unwrap(), "{} -> {}", unit.name, other.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_generated_methods() {
+        assert_eq!(Length::from_km(5.0).to_m(), 5000.0);
+        assert_eq!(Length::from_kilometers(5.0), Length::from_km(5.0));
+        assert_eq!(Length::from_mi(1.0).to_ft(), crate::convert(1.0, "mi", "ft").unwrap());
+        assert_eq!(Length::from_in(12.0).to_feet(), crate::convert(12.0, "in", "ft").unwrap());
+        assert_eq!(Mass::from_pounds(2.0).to_kg(), 0.90718474);
+        assert_eq!(Mass::from_st(1.0).to_stone(), 1.0);
+        assert_eq!(Temperature::from_k(0.0).to_degrees_fahrenheit(), crate::convert(0.0, "K", "F").unwrap());
+        assert_eq!(Temperature::from_degrees_celsius(100.0).to_kelvins(), 373.15);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let mut walk = Length::from_km(1.0) + Length::from_m(500.0) - Length::from_m(100.0);
+        assert_eq!(walk.to_m(), 1400.0);
+        walk += Length::from_m(100.0);
+        walk -= Length::from_m(500.0);
+        assert_eq!(walk, Length::from_km(1.0));
+        assert_eq!((2.0 * walk / 4.0).to_m(), 500.0);
+        assert_eq!(Length::from_mi(1.0) / Length::from_yd(1.0), crate::convert(1.0, "mi", "yd").unwrap());
+        assert_eq!((-Mass::from_kg(2.0) * 3.0).to_g(), -6000.0);
+        assert!(Mass::from_lb(1.0) < Mass::from_kg(1.0));
+    }
+
+    #[test]
+    fn test_quantity_conversions() {
+        let length = Length::try_from(Quantity::parse("3 mi").unwrap()).unwrap();
+        assert_eq!(length.to_mi(), 3.0);
+        let back = Quantity::from(length);
+        assert_eq!((back.value, back.unit.name), (length.to_m(), "m"));
+        assert_eq!(Quantity::from(Temperature::from_f(212.0)).unit.name, "C");
+        let error = Mass::try_from(Quantity::parse("5 km").unwrap()).unwrap_err();
+        assert!(matches!(error, ConvertError::CategoryMismatch { from: UnitCategory::Length, to: UnitCategory::Mass, .. }), "{:?}", error);
+    }
+}
@@ -0,0 +1,342 @@
+//! The system time zone, for `time --local`: a compiled zone file (TZif,
+//! as in `/etc/localtime`) or a POSIX `TZ` rule such as
+//! "EST5EDT,M3.2.0,M11.1.0". A zone file lists its transitions up to some
+//! year and ends with such a rule for the times after them.
+
+const ZONEINFO: &str = "/usr/share/zoneinfo";
+const LOCALTIME: &str = "/etc/localtime";
+/// What a rule's transitions default to: 02:00 local time.
+const DEFAULT_TRANSITION: i64 = 2 * 3600;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Zone {
+    /// When each period starts, in UTC seconds, and its offset from UTC.
+    transitions: Vec<(i64, i32)>,
+    /// The offset before the first transition.
+    initial: i32,
+    /// The times after the last transition.
+    rule: Option<Rule>,
+}
+
+/// A POSIX `TZ` rule: standard time and, if the zone has it, daylight time
+/// and when it starts and ends each year.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    /// Seconds east of UTC, the opposite sign of how POSIX writes them.
+    standard: i32,
+    daylight: Option<(i32, Change, Change)>,
+}
+
+/// "M3.2.0/2": the second Sunday of March at 02:00 local time. Week 5 is
+/// the last week of the month.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Change {
+    month: u32,
+    week: u32,
+    weekday: u32,
+    time: i64,
+}
+
+impl Zone {
+    pub fn utc() -> Zone {
+        Zone { transitions: Vec::new(), initial: 0, rule: None }
+    }
+
+    /// The zone `TZ` names, or `/etc/localtime` without one; UTC when
+    /// neither can be read, as the C library does.
+    pub fn local() -> Zone {
+        let from_file = |path: &str| std::fs::read(path).ok().and_then(|bytes| Zone::parse(&bytes).ok());
+        match std::env::var("TZ") {
+            Ok(tz) if !tz.is_empty() => {
+                let name = tz.strip_prefix(':').unwrap_or(&tz);
+                let path = if name.starts_with('/') { name.to_string() } else { format!("{}/{}", ZONEINFO, name) };
+                from_file(&path).or_else(|| Zone::parse_rule(name))
+            }
+            _ => from_file(LOCALTIME),
+        }
+        .unwrap_or_else(Zone::utc)
+    }
+
+    /// A zone that is a POSIX rule alone, as `TZ` may give one.
+    pub fn parse_rule(text: &str) -> Option<Zone> {
+        Rule::parse(text).map(|rule| Zone { transitions: Vec::new(), initial: rule.standard, rule: Some(rule) })
+    }
+
+    /// Reads a TZif file, using the 64-bit data of version 2 and later.
+    pub fn parse(bytes: &[u8]) -> Result<Zone, String> {
+        let mut reader = Reader { bytes, at: 0 };
+        if reader.take(4)? != b"TZif" {
+            return Err("not a TZif file".to_string());
+        }
+        let version = reader.take(1)?[0];
+        let counts = reader.header()?;
+        if version < b'2' {
+            return reader.data(counts, 4, None);
+        }
+        reader.skip(counts, 4)?;
+        reader.take(5)?;
+        let counts = reader.header()?;
+        let at = reader.at;
+        reader.skip(counts, 8)?;
+        // The footer is the rule between two newlines.
+        let footer = std::str::from_utf8(&bytes[reader.at..]).ok().map(|text| text.trim_matches('\n'));
+        let rule = footer.and_then(Rule::parse);
+        reader.at = at;
+        reader.data(counts, 8, rule)
+    }
+
+    /// Seconds east of UTC at `utc` seconds since the epoch.
+    pub fn offset_at(&self, utc: i64) -> i32 {
+        let index = self.transitions.iter().rposition(|&(start, _)| start <= utc);
+        match (index, &self.rule) {
+            // The rule takes over after the last transition, or from the start if there are none.
+            (_, Some(rule)) if index.map_or(0, |index| index + 1) == self.transitions.len() => rule.offset_at(utc),
+            (None, _) => self.initial,
+            (Some(index), _) => self.transitions[index].1,
+        }
+    }
+
+    /// The UTC time that reads `local` on this zone's clocks. Offsets change
+    /// at most once a day, so it is `local` less the offset a day before or
+    /// a day after; in an overlap that is the earlier time, and in a gap
+    /// the one the earlier offset gives.
+    pub fn to_utc(&self, local: i64) -> i64 {
+        let offsets = [self.offset_at(local - SECONDS_PER_DAY), self.offset_at(local + SECONDS_PER_DAY)];
+        offsets
+            .iter()
+            .map(|&offset| local - offset as i64)
+            .filter(|&utc| self.offset_at(utc) as i64 == local - utc)
+            .min()
+            .unwrap_or(local - offsets[0] as i64)
+    }
+}
+
+/// The counts in a TZif header, in file order.
+#[derive(Clone, Copy)]
+struct Counts {
+    isutcnt: usize,
+    isstdcnt: usize,
+    leapcnt: usize,
+    timecnt: usize,
+    typecnt: usize,
+    charcnt: usize,
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let bytes = self.bytes.get(self.at..self.at + n).ok_or("the zone file is cut short")?;
+        self.at += n;
+        Ok(bytes)
+    }
+
+    fn int(&mut self, size: usize) -> Result<i64, String> {
+        let bytes = self.take(size)?;
+        let sign = if bytes[0] & 0x80 != 0 { -1i64 } else { 0 };
+        Ok(bytes.iter().fold(sign, |value, &byte| (value << 8) | byte as i64))
+    }
+
+    /// The rest of a header after its version byte: 15 reserved bytes, then six counts.
+    fn header(&mut self) -> Result<Counts, String> {
+        self.take(15)?;
+        let mut count = || self.int(4).map(|n| n as usize);
+        Ok(Counts { isutcnt: count()?, isstdcnt: count()?, leapcnt: count()?, timecnt: count()?, typecnt: count()?, charcnt: count()? })
+    }
+
+    fn skip(&mut self, c: Counts, time_size: usize) -> Result<(), String> {
+        let size = c.timecnt * (time_size + 1) + c.typecnt * 6 + c.charcnt + c.leapcnt * (time_size + 4) + c.isstdcnt + c.isutcnt;
+        self.take(size).map(|_| ())
+    }
+
+    fn data(&mut self, c: Counts, time_size: usize, rule: Option<Rule>) -> Result<Zone, String> {
+        let times = (0..c.timecnt).map(|_| self.int(time_size)).collect::<Result<Vec<_>, _>>()?;
+        let indexes = self.take(c.timecnt)?.to_vec();
+        let types = (0..c.typecnt)
+            .map(|_| {
+                let offset = self.int(4)? as i32;
+                self.take(2)?;
+                Ok(offset)
+            })
+            .collect::<Result<Vec<i32>, String>>()?;
+        let offset = |index: u8| types.get(index as usize).copied().ok_or("a transition names a missing type");
+        let transitions = times.into_iter().zip(indexes).map(|(time, index)| Ok((time, offset(index)?))).collect::<Result<Vec<_>, String>>()?;
+        let initial = types.first().copied().unwrap_or(0);
+        Ok(Zone { transitions, initial, rule })
+    }
+}
+
+impl Rule {
+    /// "UTC0", "CET-1CEST,M3.5.0,M10.5.0/3" or "<+0330>-3:30". Rules with
+    /// day-of-year changes ("J60", "60") aren't supported.
+    pub fn parse(text: &str) -> Option<Rule> {
+        let mut rest = text;
+        name(&mut rest)?;
+        let standard = -offset(&mut rest)?;
+        if rest.is_empty() {
+            return Some(Rule { standard, daylight: None });
+        }
+        name(&mut rest)?;
+        let daylight = if rest.starts_with(',') { standard + 3600 } else { -offset(&mut rest)? };
+        let (start, end) = rest.strip_prefix(',')?.split_once(',')?;
+        Some(Rule { standard, daylight: Some((daylight, Change::parse(start)?, Change::parse(end)?)) })
+    }
+
+    fn offset_at(&self, utc: i64) -> i32 {
+        let Some((daylight, start, end)) = self.daylight else { return self.standard };
+        let (year, ..) = crate::epoch::civil(utc.div_euclid(SECONDS_PER_DAY));
+        // Each change is at a local time: the start in standard time, the end in daylight time.
+        let starts = start.local(year) - self.standard as i64;
+        let ends = end.local(year) - daylight as i64;
+        let in_daylight = if starts < ends { starts <= utc && utc < ends } else { utc >= starts || utc < ends };
+        if in_daylight { daylight } else { self.standard }
+    }
+}
+
+impl Change {
+    /// "M3.2.0" or "M11.1.0/01:30".
+    fn parse(text: &str) -> Option<Change> {
+        let (date, time) = match text.split_once('/') {
+            Some((date, time)) => (date, Some(time)),
+            None => (text, None),
+        };
+        let mut fields = date.strip_prefix('M')?.split('.').map(|field| field.parse::<u32>().ok());
+        let (month, week, weekday) = (fields.next()??, fields.next()??, fields.next()??);
+        if fields.next().is_some() || !(1..=12).contains(&month) || !(1..=5).contains(&week) || weekday > 6 {
+            return None;
+        }
+        let time = match time {
+            Some(time) => {
+                let mut rest = time;
+                let seconds = offset(&mut rest)?;
+                rest.is_empty().then_some(seconds as i64)?
+            }
+            None => DEFAULT_TRANSITION,
+        };
+        Some(Change { month, week, weekday, time })
+    }
+
+    /// When this change happens in `year`, in local seconds since the epoch.
+    fn local(self, year: i64) -> i64 {
+        let first = crate::epoch::days_from_civil(year, self.month, 1);
+        let first_weekday = crate::epoch::weekday(first);
+        let mut day = (self.weekday as i64 - first_weekday as i64).rem_euclid(7) + (self.week as i64 - 1) * 7;
+        let next_month = if self.month == 12 { crate::epoch::days_from_civil(year + 1, 1, 1) } else { crate::epoch::days_from_civil(year, self.month + 1, 1) };
+        while first + day >= next_month {
+            day -= 7;
+        }
+        (first + day) * SECONDS_PER_DAY + self.time
+    }
+}
+
+/// Skips a zone abbreviation: letters, or anything between '<' and '>'.
+fn name(rest: &mut &str) -> Option<()> {
+    let len = match rest.strip_prefix('<') {
+        Some(quoted) => quoted.find('>')? + 2,
+        None => rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len()),
+    };
+    if len < 3 {
+        return None;
+    }
+    *rest = &rest[len..];
+    Some(())
+}
+
+/// Reads "[+-]hh[:mm[:ss]]" as seconds.
+fn offset(rest: &mut &str) -> Option<i32> {
+    let (sign, unsigned) = match rest.as_bytes().first() {
+        Some(b'-') => (-1, &rest[1..]),
+        Some(b'+') => (1, &rest[1..]),
+        _ => (1, *rest),
+    };
+    let len = unsigned.find(|c: char| !(c.is_ascii_digit() || c == ':')).unwrap_or(unsigned.len());
+    let mut seconds = 0;
+    let mut parts = 0;
+    for (part, scale) in unsigned[..len].split(':').zip([3600, 60, 1]) {
+        seconds += part.parse::<i32>().ok()? * scale;
+        parts += 1;
+    }
+    if parts == 0 || unsigned[..len].split(':').count() > 3 {
+        return None;
+    }
+    *rest = &unsigned[len..];
+    Some(sign * seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A version 2 zone file with the given transitions and footer; version
+    /// 1 data is left empty, as `zic -b slim` writes it.
+    fn tzif(transitions: &[(i64, u8)], types: &[i32], footer: &str) -> Vec<u8> {
+        let mut bytes = b"TZif2".to_vec();
+        let header = |bytes: &mut Vec<u8>, times: usize, types: usize| {
+            bytes.extend([0; 15]);
+            for count in [0, 0, 0, times, types, 4] {
+                bytes.extend((count as u32).to_be_bytes());
+            }
+        };
+        header(&mut bytes, 0, 1);
+        bytes.extend([0; 6]);
+        bytes.extend(b"UTC\0");
+        bytes.extend(b"TZif2");
+        header(&mut bytes, transitions.len(), types.len());
+        for (time, _) in transitions {
+            bytes.extend(time.to_be_bytes());
+        }
+        bytes.extend(transitions.iter().map(|(_, index)| index));
+        for offset in types {
+            bytes.extend(offset.to_be_bytes());
+            bytes.extend([0, 0]);
+        }
+        bytes.extend(b"ABC\0");
+        bytes.extend(format!("\n{}\n", footer).bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_rules() {
+        let new_york = Rule::parse("EST5EDT,M3.2.0,M11.1.0").unwrap();
+        // 2023-03-12T06:59:59Z is 01:59:59 EST; a second later clocks read 03:00 EDT.
+        assert_eq!(new_york.offset_at(1678604399), -5 * 3600);
+        assert_eq!(new_york.offset_at(1678604400), -4 * 3600);
+        // 2023-11-05T05:59:59Z is 01:59:59 EDT, then 01:00 EST again.
+        assert_eq!(new_york.offset_at(1699163999), -4 * 3600);
+        assert_eq!(new_york.offset_at(1699164000), -5 * 3600);
+        // In the south, daylight time spans the new year.
+        let sydney = Rule::parse("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        assert_eq!(sydney.offset_at(1704067200), 11 * 3600);
+        assert_eq!(sydney.offset_at(1719792000), 10 * 3600);
+        assert_eq!(Rule::parse("<+0530>-5:30"), Some(Rule { standard: 19800, daylight: None }));
+        assert_eq!(Rule::parse("UTC0"), Some(Rule { standard: 0, daylight: None }));
+        assert_eq!(Rule::parse("EST5EDT,J60,J300"), None);
+        assert_eq!(Rule::parse("E5"), None);
+    }
+
+    #[test]
+    fn test_zone_files() {
+        let bytes = tzif(&[(-100, 1), (1000, 0)], &[3600, 7200], "CET-1CEST,M3.5.0,M10.5.0/3");
+        let zone = Zone::parse(&bytes).unwrap();
+        assert_eq!(zone.offset_at(-200), 3600);
+        assert_eq!(zone.offset_at(0), 7200);
+        // After the last transition the footer's rule decides: 2024-07-01 is summer.
+        assert_eq!(zone.offset_at(1719792000), 7200);
+        assert_eq!(zone.offset_at(1704067200), 3600);
+        assert_eq!(Zone::parse(b"TZif2"), Err("the zone file is cut short".to_string()));
+        assert_eq!(Zone::parse(b"PK\x03\x04"), Err("not a TZif file".to_string()));
+    }
+
+    #[test]
+    fn test_local_to_utc() {
+        let zone = Zone::parse_rule("EST5EDT,M3.2.0,M11.1.0").unwrap();
+        // 2023-11-14T17:13:20 in New York is 22:13:20Z.
+        assert_eq!(zone.to_utc(1700000000 - 5 * 3600), 1700000000);
+        // 2023-07-01T12:00:00 EDT.
+        assert_eq!(zone.to_utc(1688212800 - 4 * 3600), 1688212800);
+    }
+}
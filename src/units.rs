@@ -0,0 +1,321 @@
+//! How many base units each linear built-in unit is, as constants for
+//! `convert_linear` in const contexts:
+//!
+//! ```
+//! use converter::{convert_linear, units};
+//!
+//! const MILE_IN_FEET: f64 = convert_linear(1.0, units::MI_TO_M, units::FT_TO_M);
+//! assert!((MILE_IN_FEET - 5280.0).abs() < 1e-9);
+//! ```
+//!
+//! Each equals the factor of its unit in `UNITS`. Temperatures other than
+//! Celsius have an offset, so they have no constant.
+//!
+//! The units themselves are written in `units.toml` at the root of the
+//! repository, from which `build.rs` generates a table per category; a bad
+//! row fails the build with its line. Each table is a module here, and only
+//! the categories whose feature is on are compiled in: `length`, `mass`,
+//! `temperature`, `time`, `volume`, `speed`, `data` and `angle`, or all of them with
+//! `full`.
+
+/// A [`Unit`](crate::Unit) in one line: its category and symbol, its factor
+/// to the base unit (a number or a fraction such as `1.0 / 100.0`), and its
+/// aliases. The first two aliases are the singular and plural display
+/// names unless those are given before the aliases as `"second" / "seconds"`.
+/// A unit with an offset is `affine`, with its offset after the factor.
+/// Its symbols match only as spelled unless `any case` follows the symbol.
+///
+/// ```
+/// use converter::{Conversion, unit};
+///
+/// const KM: converter::Unit = unit!(Length: "km" any case, 1000.0, ["kilometer", "kilometers", "kilometre"]);
+/// const F: converter::Unit = unit!(Temperature: "F", affine 5.0 / 9.0, offset -32.0, "degree Fahrenheit" / "degrees Fahrenheit", ["fahrenheit"]);
+/// assert_eq!((KM.display_singular, KM.display_plural), ("kilometer", "kilometers"));
+/// assert!(KM.matches("KM") && !F.matches("f"));
+/// assert_eq!(F.conversion, Conversion::Affine { factor: (5.0, 9.0), offset: -32.0 });
+/// ```
+///
+/// A zero factor, an empty symbol and a unit without display names don't
+/// compile:
+///
+/// ```compile_fail
+/// let nowhere = converter::unit!(Length: "nw", 1.0 / 0.0, ["nowhere", "nowheres"]);
+/// ```
+///
+/// ```compile_fail
+/// let unnamed = converter::unit!(Length: "", 1.0, ["thing", "things"]);
+/// ```
+///
+/// ```compile_fail
+/// let bare = converter::unit!(Length: "bare", 1.0, ["bare"]);
+/// ```
+#[macro_export]
+macro_rules! unit {
+    (@denominator) => { 1.0 };
+    (@denominator $denominator:literal) => { $denominator };
+    (@factor $numerator:literal $(/ $denominator:literal)?) => {{
+        const { assert!($numerator != 0.0 && $crate::unit!(@denominator $($denominator)?) != 0.0, "a unit's factor can't be zero") };
+        ($numerator, $crate::unit!(@denominator $($denominator)?))
+    }};
+    (@unit $category:ident, $name:literal, $conversion:expr, $singular:literal / $plural:literal, [$($alias:literal),* $(,)?]) => {{
+        const { assert!(!$name.is_empty(), "a unit needs a symbol") };
+        $crate::Unit {
+            name: $name,
+            aliases: &[$($alias),*],
+            case_sensitive: true,
+            display_singular: $singular,
+            display_plural: $plural,
+            category: $crate::UnitCategory::$category,
+            conversion: $conversion,
+        }
+    }};
+    (@unit $category:ident, $name:literal, $conversion:expr, [$singular:literal, $plural:literal $(, $alias:literal)* $(,)?]) => {
+        $crate::unit!(@unit $category, $name, $conversion, $singular / $plural, [$singular, $plural $(, $alias)*])
+    };
+    ($category:ident: $name:literal any case, $($rest:tt)+) => {
+        $crate::Unit { case_sensitive: false, ..$crate::unit!($category: $name, $($rest)+) }
+    };
+    ($category:ident: $name:literal, affine $numerator:literal $(/ $denominator:literal)?, offset $offset:literal, $($rest:tt)+) => {
+        $crate::unit!(@unit $category, $name, $crate::Conversion::Affine {
+            factor: $crate::unit!(@factor $numerator $(/ $denominator)?),
+            offset: $offset,
+        }, $($rest)+)
+    };
+    ($category:ident: $name:literal, $numerator:literal $(/ $denominator:literal)?, $($rest:tt)+) => {
+        $crate::unit!(@unit $category, $name, $crate::Conversion::Linear {
+            factor: $crate::unit!(@factor $numerator $(/ $denominator)?),
+        }, $($rest)+)
+    };
+}
+
+#[cfg(feature = "length")]
+pub(crate) mod length;
+#[cfg(feature = "mass")]
+pub(crate) mod mass;
+#[cfg(feature = "temperature")]
+pub(crate) mod temperature;
+#[cfg(feature = "time")]
+pub(crate) mod time;
+#[cfg(feature = "volume")]
+pub(crate) mod volume;
+#[cfg(feature = "speed")]
+pub(crate) mod speed;
+#[cfg(feature = "data")]
+pub(crate) mod data;
+#[cfg(feature = "angle")]
+pub(crate) mod angle;
+
+pub const KM_TO_M: f64 = 1000.0;
+pub const CM_TO_M: f64 = 0.01;
+pub const MM_TO_M: f64 = 0.001;
+pub const MI_TO_M: f64 = 1609.344;
+pub const YD_TO_M: f64 = 0.9144;
+pub const FT_TO_M: f64 = 0.3048;
+pub const IN_TO_M: f64 = 0.0254;
+
+pub const G_TO_KG: f64 = 0.001;
+pub const MG_TO_KG: f64 = 0.000001;
+pub const LB_TO_KG: f64 = 0.45359237;
+pub const OZ_TO_KG: f64 = 0.028349523125;
+pub const ST_TO_KG: f64 = 6.35029318;
+pub const TON_TO_KG: f64 = 1000.0;
+
+pub const MS_TO_S: f64 = 0.001;
+pub const MIN_TO_S: f64 = 60.0;
+pub const H_TO_S: f64 = 3600.0;
+pub const D_TO_S: f64 = 86400.0;
+pub const WK_TO_S: f64 = 604800.0;
+/// 365.25 days.
+pub const JULIAN_YEAR_TO_S: f64 = 31557600.0;
+/// 365.2425 days, the average over the 400-year Gregorian cycle.
+pub const GREGORIAN_YEAR_TO_S: f64 = 31556952.0;
+/// 365 days.
+pub const CIVIL_YEAR_TO_S: f64 = 31536000.0;
+pub const JULIAN_MONTH_TO_S: f64 = 2629800.0;
+pub const GREGORIAN_MONTH_TO_S: f64 = 2629746.0;
+pub const CIVIL_MONTH_TO_S: f64 = 2628000.0;
+
+pub const L_TO_M3: f64 = 0.001;
+pub const ML_TO_M3: f64 = 0.000001;
+/// The US fluid ounce.
+pub const FLOZ_TO_M3: f64 = 0.0000295735295625;
+pub const FLOZ_IMP_TO_M3: f64 = 0.0000284130625;
+pub const PT_US_TO_M3: f64 = 0.000473176473;
+pub const PT_IMP_TO_M3: f64 = 0.00056826125;
+pub const QT_US_TO_M3: f64 = 0.000946352946;
+pub const QT_IMP_TO_M3: f64 = 0.0011365225;
+pub const GAL_US_TO_M3: f64 = 0.003785411784;
+pub const GAL_IMP_TO_M3: f64 = 0.00454609;
+pub const DRY_QT_TO_M3: f64 = 0.001101220942715;
+pub const DRY_GAL_TO_M3: f64 = 0.00440488377086;
+pub const PECK_TO_M3: f64 = 0.00880976754172;
+pub const BUSHEL_TO_M3: f64 = 0.03523907016688;
+
+pub const MPH_TO_MPS: f64 = 0.44704;
+pub const MACH_TO_MPS: f64 = 340.29;
+pub const C_TO_MPS: f64 = 299792458.0;
+
+pub const BYTE_TO_BIT: f64 = 8.0;
+pub const KBIT_TO_BIT: f64 = 1000.0;
+pub const MBIT_TO_BIT: f64 = 1000000.0;
+pub const GBIT_TO_BIT: f64 = 1000000000.0;
+pub const TBIT_TO_BIT: f64 = 1000000000000.0;
+pub const KBYTE_TO_BIT: f64 = 8000.0;
+pub const MBYTE_TO_BIT: f64 = 8000000.0;
+pub const GBYTE_TO_BIT: f64 = 8000000000.0;
+pub const TBYTE_TO_BIT: f64 = 8000000000000.0;
+pub const KIBIT_TO_BIT: f64 = 1024.0;
+pub const MIBIT_TO_BIT: f64 = 1048576.0;
+pub const GIBIT_TO_BIT: f64 = 1073741824.0;
+pub const TIBIT_TO_BIT: f64 = 1099511627776.0;
+pub const KIBYTE_TO_BIT: f64 = 8192.0;
+pub const MIBYTE_TO_BIT: f64 = 8388608.0;
+pub const GIBYTE_TO_BIT: f64 = 8589934592.0;
+pub const TIBYTE_TO_BIT: f64 = 8796093022208.0;
+
+pub const ARCMIN_TO_DEG: f64 = 1.0 / 60.0;
+pub const ARCSEC_TO_DEG: f64 = 1.0 / 3600.0;
+pub const RAD_TO_DEG: f64 = 57.29577951308232;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Conversion, UNITS, builtin_unit};
+
+    const CONSTANTS: [(&str, f64); 61] = [
+        ("km", KM_TO_M),
+        ("cm", CM_TO_M),
+        ("mm", MM_TO_M),
+        ("mi", MI_TO_M),
+        ("yd", YD_TO_M),
+        ("ft", FT_TO_M),
+        ("in", IN_TO_M),
+        ("g", G_TO_KG),
+        ("mg", MG_TO_KG),
+        ("lb", LB_TO_KG),
+        ("oz", OZ_TO_KG),
+        ("st", ST_TO_KG),
+        ("ton", TON_TO_KG),
+        ("ms", MS_TO_S),
+        ("min", MIN_TO_S),
+        ("h", H_TO_S),
+        ("d", D_TO_S),
+        ("wk", WK_TO_S),
+        ("julian-year", JULIAN_YEAR_TO_S),
+        ("gregorian-year", GREGORIAN_YEAR_TO_S),
+        ("civil-year", CIVIL_YEAR_TO_S),
+        ("julian-month", JULIAN_MONTH_TO_S),
+        ("gregorian-month", GREGORIAN_MONTH_TO_S),
+        ("civil-month", CIVIL_MONTH_TO_S),
+        ("L", L_TO_M3),
+        ("mL", ML_TO_M3),
+        ("floz-us", FLOZ_TO_M3),
+        ("floz-imp", FLOZ_IMP_TO_M3),
+        ("pt-us", PT_US_TO_M3),
+        ("pt-imp", PT_IMP_TO_M3),
+        ("qt-us", QT_US_TO_M3),
+        ("qt-imp", QT_IMP_TO_M3),
+        ("gal-us", GAL_US_TO_M3),
+        ("gal-imp", GAL_IMP_TO_M3),
+        ("dry-qt", DRY_QT_TO_M3),
+        ("dry-gal", DRY_GAL_TO_M3),
+        ("peck", PECK_TO_M3),
+        ("bushel", BUSHEL_TO_M3),
+        ("mi/h", MPH_TO_MPS),
+        ("mach", MACH_TO_MPS),
+        ("c", C_TO_MPS),
+        ("B", BYTE_TO_BIT),
+        ("kb", KBIT_TO_BIT),
+        ("Mb", MBIT_TO_BIT),
+        ("Gb", GBIT_TO_BIT),
+        ("Tb", TBIT_TO_BIT),
+        ("kB", KBYTE_TO_BIT),
+        ("MB", MBYTE_TO_BIT),
+        ("GB", GBYTE_TO_BIT),
+        ("TB", TBYTE_TO_BIT),
+        ("Kib", KIBIT_TO_BIT),
+        ("Mib", MIBIT_TO_BIT),
+        ("Gib", GIBIT_TO_BIT),
+        ("Tib", TIBIT_TO_BIT),
+        ("KiB", KIBYTE_TO_BIT),
+        ("MiB", MIBYTE_TO_BIT),
+        ("GiB", GIBYTE_TO_BIT),
+        ("TiB", TIBYTE_TO_BIT),
+        ("arcmin", ARCMIN_TO_DEG),
+        ("arcsec", ARCSEC_TO_DEG),
+        ("rad", RAD_TO_DEG),
+    ];
+
+    #[test]
+    fn test_constants_match_the_table() {
+        // Constants of categories left out of the build have no unit to match.
+        let mut built = 0;
+        for (name, constant) in CONSTANTS {
+            let Some(unit) = builtin_unit(name) else { continue };
+            let (numerator, denominator) = unit.conversion.factor();
+            assert_eq!(numerator / denominator, constant, "{}", name);
+            built += 1;
+        }
+        let linear = UNITS.iter().filter(|u| u.conversion.offset() == 0.0);
+        let scaled = linear.filter(|u| u.conversion.factor() != (1.0, 1.0)).count();
+        assert_eq!(built, scaled, "every linear unit but the base units has a constant");
+    }
+
+    /// `{:?}` of every built-in unit as written out before the `unit!` macro.
+    const TABLE_BEFORE_MACRO: &str = include_str!("../tests/fixtures/builtin_units.txt");
+
+    #[test]
+    fn test_macro_table_matches_the_written_out_one() {
+        let compiled: Vec<String> = UNITS.iter().map(|unit| format!("{:?}", unit)).collect();
+        let expected: Vec<&str> = TABLE_BEFORE_MACRO
+            .lines()
+            .filter(|line| UNITS.iter().any(|unit| line.contains(&format!("category: {:?},", unit.category))))
+            .collect();
+        assert_eq!(compiled, expected);
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature", feature = "time", feature = "volume", feature = "speed", feature = "data", feature = "angle"))]
+    fn test_generated_tables() {
+        use crate::UnitCategory;
+
+        let count = |category| UNITS.iter().filter(|u| u.category == category).count();
+        assert_eq!(count(UnitCategory::Length), 8);
+        assert_eq!(count(UnitCategory::Temperature), 3);
+        assert_eq!(count(UnitCategory::Mass), 7);
+        assert_eq!(count(UnitCategory::Time), 12);
+        assert_eq!(count(UnitCategory::Volume), 15);
+        assert_eq!(count(UnitCategory::Speed), 4);
+        assert_eq!(count(UnitCategory::Data), 18);
+        assert_eq!(count(UnitCategory::Angle), 4);
+        let conversion = |name| builtin_unit(name).map(|u| u.conversion);
+        assert_eq!(conversion("cm"), Some(Conversion::Linear { factor: (1.0, 100.0) }));
+        assert_eq!(conversion("F"), Some(Conversion::Affine { factor: (5.0, 9.0), offset: -32.0 }));
+        assert_eq!(conversion("oz"), Some(Conversion::Linear { factor: (0.028349523125, 1.0) }));
+        assert_eq!(builtin_unit("s").map(|u| (u.display_singular, u.display_plural)), Some(("second", "seconds")));
+    }
+
+    #[test]
+    fn test_display_names_default_to_the_first_two_aliases() {
+        let unit = unit!(Mass: "gr", 0.00006479891, ["grain", "grains", "gr."]);
+        assert_eq!((unit.display_singular, unit.display_plural, unit.aliases.len()), ("grain", "grains", 3));
+        assert_eq!(unit.conversion, Conversion::Linear { factor: (0.00006479891, 1.0) });
+        let unit = unit!(Temperature: "R", affine 5.0 / 9.0, offset -491.67, "degree Rankine" / "degrees Rankine", []);
+        assert_eq!(unit.conversion.offset(), -491.67);
+        assert!(unit.aliases.is_empty());
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass"))]
+    fn test_const_conversions_match_runtime() {
+        use crate::{convert_linear, convert_units};
+
+        const MILES: f64 = convert_linear(5.0, KM_TO_M, MI_TO_M);
+        const KILOGRAMS: f64 = convert_linear(10.0, LB_TO_KG, 1.0);
+        const SECONDS: [u8; convert_linear(0.01, H_TO_S, 1.0) as usize] = [0; 36];
+        let unit = |name| builtin_unit(name).unwrap();
+        assert_eq!(MILES, convert_units(unit("km"), unit("mi"), 5.0));
+        assert_eq!(KILOGRAMS, convert_units(unit("lb"), unit("kg"), 10.0));
+        assert_eq!(SECONDS.len(), 36);
+    }
+}
@@ -0,0 +1,5 @@
+//! Units of angle, in degrees.
+
+use crate::Unit;
+
+pub(crate) const UNITS: &[Unit] = include!(concat!(env!("OUT_DIR"), "/angle.rs"));
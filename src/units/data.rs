@@ -0,0 +1,5 @@
+//! Units of data, in bits.
+
+use crate::Unit;
+
+pub(crate) const UNITS: &[Unit] = include!(concat!(env!("OUT_DIR"), "/data.rs"));
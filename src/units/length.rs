@@ -0,0 +1,5 @@
+//! Units of length, in meters.
+
+use crate::Unit;
+
+pub(crate) const UNITS: &[Unit] = include!(concat!(env!("OUT_DIR"), "/length.rs"));
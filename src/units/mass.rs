@@ -0,0 +1,5 @@
+//! Units of mass, in kilograms.
+
+use crate::Unit;
+
+pub(crate) const UNITS: &[Unit] = include!(concat!(env!("OUT_DIR"), "/mass.rs"));
@@ -0,0 +1,5 @@
+//! Units of speed, in meters per second.
+
+use crate::Unit;
+
+pub(crate) const UNITS: &[Unit] = include!(concat!(env!("OUT_DIR"), "/speed.rs"));
@@ -0,0 +1,5 @@
+//! Temperatures, in degrees Celsius. Every unit but Celsius has an offset.
+
+use crate::Unit;
+
+pub(crate) const UNITS: &[Unit] = include!(concat!(env!("OUT_DIR"), "/temperature.rs"));
@@ -0,0 +1,5 @@
+//! Units of time, in seconds.
+
+use crate::Unit;
+
+pub(crate) const UNITS: &[Unit] = include!(concat!(env!("OUT_DIR"), "/time.rs"));
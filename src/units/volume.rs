@@ -0,0 +1,5 @@
+//! Units of volume, in cubic meters.
+
+use crate::Unit;
+
+pub(crate) const UNITS: &[Unit] = include!(concat!(env!("OUT_DIR"), "/volume.rs"));
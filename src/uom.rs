@@ -0,0 +1,132 @@
+//! Conversions between a [`Quantity`] and the `uom` crate's quantities, for
+//! programs that already keep their values in uom's types. A quantity of the
+//! matching category converts into `uom::si::f64::Length`, `Mass`,
+//! `ThermodynamicTemperature`, `Time`, `Volume`, `Velocity`, `Information` or
+//! `Angle` with `try_from`, and each of those converts back into a
+//! `Quantity` in its SI unit. Each pair is there when its category is.
+//!
+//! A quantity of another category is a `CategoryMismatch`, as converting it
+//! would be. uom's temperatures are absolute, so 25 °C is 298.15 K:
+//!
+//! ```
+//! # #[cfg(all(feature = "length", feature = "temperature"))] {
+//! use converter::Quantity;
+//! use uom::si::f64::ThermodynamicTemperature;
+//! use uom::si::length::meter;
+//! use uom::si::thermodynamic_temperature::kelvin;
+//!
+//! assert_eq!(converter::uom::to_uom_length(Quantity::parse("5 km")?)?.get::<meter>(), 5000.0);
+//! let room = ThermodynamicTemperature::try_from(Quantity::parse("25 C")?)?;
+//! assert!((room.get::<kelvin>() - 298.15).abs() < 1e-9);
+//! # }
+//! # Ok::<(), converter::ConvertError>(())
+//! ```
+
+use ::uom::si;
+
+use crate::{ConvertError, Quantity, Unit};
+
+/// The built-in unit uom keeps a category's quantities in.
+fn si_unit(name: &str) -> &'static Unit {
+    crate::builtin_unit(name).expect("the SI unit of a built-in category")
+}
+
+macro_rules! uom_quantity {
+    ($feature:literal, $name:ident, $unit:literal, $uom_unit:ty) => {
+        #[cfg(feature = $feature)]
+        impl TryFrom<Quantity> for si::f64::$name {
+            type Error = ConvertError;
+
+            fn try_from(quantity: Quantity) -> Result<si::f64::$name, ConvertError> {
+                let unit = si_unit($unit);
+                if quantity.unit.category != unit.category {
+                    return Err(ConvertError::CategoryMismatch {
+                        from: quantity.unit.category,
+                        to: unit.category,
+                        from_unit: quantity.unit.name.to_string(),
+                        to_unit: unit.name.to_string(),
+                    });
+                }
+                Ok(si::f64::$name::new::<$uom_unit>(crate::convert_units(quantity.unit, unit, quantity.value)))
+            }
+        }
+
+        #[cfg(feature = $feature)]
+        impl From<si::f64::$name> for Quantity {
+            #[doc = concat!("The quantity in `", $unit, "`.")]
+            fn from(quantity: si::f64::$name) -> Quantity {
+                Quantity { value: quantity.get::<$uom_unit>(), unit: si_unit($unit) }
+            }
+        }
+    };
+}
+
+uom_quantity!("length", Length, "m", si::length::meter);
+uom_quantity!("mass", Mass, "kg", si::mass::kilogram);
+uom_quantity!("temperature", ThermodynamicTemperature, "K", si::thermodynamic_temperature::kelvin);
+uom_quantity!("time", Time, "s", si::time::second);
+uom_quantity!("volume", Volume, "m3", si::volume::cubic_meter);
+uom_quantity!("speed", Velocity, "m/s", si::velocity::meter_per_second);
+uom_quantity!("data", Information, "b", si::information::bit);
+uom_quantity!("angle", Angle, "deg", si::angle::degree);
+
+/// `quantity` as a uom length, or a `CategoryMismatch` when it isn't one.
+#[cfg(feature = "length")]
+pub fn to_uom_length(quantity: Quantity) -> Result<si::f64::Length, ConvertError> {
+    si::f64::Length::try_from(quantity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_length_round_trip() {
+        let length = to_uom_length(Quantity::parse("5 km").unwrap()).unwrap();
+        assert_eq!(length, si::f64::Length::new::<si::length::kilometer>(5.0));
+        let back = Quantity::from(length);
+        assert_eq!(back.unit.name, "m");
+        assert_eq!(back.to("km").unwrap(), Quantity::parse("5 km").unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn test_temperature_offsets_match_uom() {
+        use si::f64::ThermodynamicTemperature;
+        use si::thermodynamic_temperature::{degree_celsius, degree_fahrenheit, kelvin};
+
+        for (text, theirs) in [
+            ("25 C", ThermodynamicTemperature::new::<degree_celsius>(25.0)),
+            ("-40 C", ThermodynamicTemperature::new::<degree_celsius>(-40.0)),
+            ("98.6 F", ThermodynamicTemperature::new::<degree_fahrenheit>(98.6)),
+            ("0 K", ThermodynamicTemperature::new::<kelvin>(0.0)),
+        ] {
+            let ours = ThermodynamicTemperature::try_from(Quantity::parse(text).unwrap()).unwrap();
+            assert!((ours.get::<kelvin>() - theirs.get::<kelvin>()).abs() < 1e-9, "{}", text);
+        }
+        let boiling = Quantity::from(ThermodynamicTemperature::new::<degree_fahrenheit>(212.0));
+        assert!((boiling.to("C").unwrap().value - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "time", feature = "volume"))]
+    fn test_category_mismatch_is_an_error() {
+        let error = to_uom_length(Quantity::parse("5 kg").unwrap()).unwrap_err();
+        assert!(
+            matches!(error, ConvertError::CategoryMismatch { from: crate::UnitCategory::Mass, to: crate::UnitCategory::Length, .. }),
+            "{:?}",
+            error
+        );
+        assert!(si::f64::Time::try_from(Quantity::parse("1 L").unwrap()).is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "mass", feature = "angle"))]
+    fn test_other_categories() {
+        let mass = si::f64::Mass::try_from(Quantity::parse("2 lb").unwrap()).unwrap();
+        assert!((mass.get::<si::mass::kilogram>() - 0.90718474).abs() < 1e-12);
+        let half_turn = si::f64::Angle::try_from(Quantity::parse("180 deg").unwrap()).unwrap();
+        assert!((half_turn.get::<si::angle::radian>() - std::f64::consts::PI).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,60 @@
+//! The exports a web page calls, through wasm-bindgen. Each is a thin
+//! wrapper over a plain function that the native tests cover; an error
+//! reaches JavaScript as a thrown `Error` carrying the `ConvertError`
+//! message, which the page shows as is.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{info, registry};
+
+/// `value` in `from` expressed in `to`; throws the error's message.
+#[wasm_bindgen]
+pub fn convert(value: f64, from: &str, to: &str) -> Result<f64, JsValue> {
+    convert_value(value, from, to).map_err(|message| js_sys::Error::new(&message).into())
+}
+
+/// Every available unit as an array of objects, in the `--list --json` format.
+#[wasm_bindgen(js_name = listUnits)]
+pub fn list_units() -> JsValue {
+    js_sys::JSON::parse(&units_json()).expect("the units are valid JSON")
+}
+
+/// Unit names close to `input`, best first, for "did you mean" hints.
+#[wasm_bindgen]
+pub fn suggest(input: &str) -> Vec<String> {
+    registry::active().suggestions(input).into_iter().map(String::from).collect()
+}
+
+fn convert_value(value: f64, from: &str, to: &str) -> Result<f64, String> {
+    crate::convert(value, from, to).map_err(|e| e.to_string())
+}
+
+fn units_json() -> String {
+    info::units_json(registry::active().units()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::{self, Json};
+
+    #[test]
+    fn test_convert() {
+        assert_eq!(convert_value(100.0, "C", "F"), Ok(212.0));
+        assert_eq!(convert_value(1.0, "furlong", "m"), Err("Unknown unit 'furlong'".to_string()));
+        assert_eq!(convert_value(1.0, "celcius", "F"), Err("Unknown unit 'celcius'; did you mean 'C'?".to_string()));
+    }
+
+    #[test]
+    fn test_list_units() {
+        let Json::Array(units) = json::parse(&units_json()).unwrap() else { panic!("not an array") };
+        assert_eq!(units.len(), crate::UNITS.len());
+        assert_eq!(units[0].get("name").and_then(Json::as_str), Some("km"));
+    }
+
+    #[test]
+    fn test_suggest() {
+        assert_eq!(suggest("kmz"), ["km"]);
+        assert!(suggest("x").is_empty());
+    }
+}
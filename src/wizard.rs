@@ -0,0 +1,119 @@
+//! A guided prompt flow for `converter` run with no arguments on a terminal:
+//! pick a category, the two units and a value, then go again or stop.
+
+use std::io::{self, BufRead, Write};
+
+use crate::{Options, Unit};
+
+/// Prompts for `prompt` until a line arrives; `None` at end of input.
+fn ask(input: &mut impl BufRead, output: &mut impl Write, prompt: &str) -> io::Result<Option<String>> {
+    write!(output, "{}", prompt)?;
+    output.flush()?;
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        writeln!(output)?;
+        return Ok(None);
+    }
+    Ok(Some(line.trim().to_string()))
+}
+
+/// Shows a numbered menu and returns the index picked, re-asking on bad input.
+fn choose(input: &mut impl BufRead, output: &mut impl Write, title: &str, items: &[String]) -> io::Result<Option<usize>> {
+    writeln!(output, "{}:", title)?;
+    for (i, item) in items.iter().enumerate() {
+        writeln!(output, "  {}) {}", i + 1, item)?;
+    }
+    loop {
+        let Some(answer) = ask(input, output, "> ")? else {
+            return Ok(None);
+        };
+        match answer.parse::<usize>() {
+            Ok(n) if (1..=items.len()).contains(&n) => return Ok(Some(n - 1)),
+            _ => writeln!(output, "Please enter a number from 1 to {}", items.len())?,
+        }
+    }
+}
+
+fn menu_entry(unit: &Unit) -> String {
+    format!("{} ({})", unit.name, unit.display_plural)
+}
+
+/// Runs the wizard until the user declines another conversion or input ends.
+pub fn run(input: &mut impl BufRead, output: &mut impl Write, options: &Options) -> io::Result<()> {
+    writeln!(output, "Unit Converter v3.0.0. Answer the prompts to convert a value (Ctrl-D quits).")?;
+    let categories = converter::categories();
+    loop {
+        let names: Vec<String> = categories.iter().map(ToString::to_string).collect();
+        let Some(category) = choose(input, output, "Category", &names)? else {
+            return Ok(());
+        };
+        let units: Vec<&Unit> = converter::units_by_category(categories[category]).collect();
+        let entries: Vec<String> = units.iter().map(|u| menu_entry(u)).collect();
+        let Some(from) = choose(input, output, "From", &entries)? else {
+            return Ok(());
+        };
+        let Some(to) = choose(input, output, "To", &entries)? else {
+            return Ok(());
+        };
+        let (from, to) = (units[from].name, units[to].name);
+        loop {
+            let Some(value) = ask(input, output, &format!("Value in {}: ", from))? else {
+                return Ok(());
+            };
+            match crate::convert_checked(&value, from, to) {
+                Ok(converted) => {
+                    for warning in &converted.warnings {
+                        writeln!(output, "Warning: {}", warning)?;
+                    }
+                    writeln!(output, "{}", crate::format_result(converted.value, from, to, converted.result, options))?;
+                    break;
+                }
+                Err(e) => writeln!(output, "Error: {}", e)?,
+            }
+        }
+        match ask(input, output, "Convert another? [Y/n] ")? {
+            Some(answer) if answer.is_empty() || answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case("yes") => {}
+            _ => return Ok(()),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "length"))]
+mod tests {
+    use super::*;
+
+    fn script(input: &str) -> String {
+        let mut output = Vec::new();
+        run(&mut input.as_bytes(), &mut output, &Options::default()).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn test_single_conversion() {
+        // Length, mi -> km, 5, then stop.
+        let out = script("1\n5\n1\n5\nn\n");
+        assert!(out.contains("  5) mi (miles)"));
+        assert!(out.contains("5 mi = 8.04672 km\n"));
+        assert!(out.ends_with("Convert another? [Y/n] "));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature", feature = "time", feature = "volume", feature = "speed", feature = "data", feature = "angle"))]
+    fn test_bad_answers_are_asked_again() {
+        let out = script("9\nx\n2\n1\n2\nwarm\n100\nno\n");
+        assert_eq!(out.matches("Please enter a number from 1 to 8").count(), 2);
+        assert!(out.contains("Error: 'warm' is not a valid number"));
+        assert!(out.contains("100 C = 212 F"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "mass", feature = "temperature"))]
+    fn test_go_again_and_end_of_input() {
+        let out = script("3\n1\n4\n1\n\n1\n1\n2\n1000");
+        assert!(out.contains("1 kg = 2.20462262184878 lb"));
+        assert!(out.contains("1000 km = 1000000 m"));
+        // Input ended at the "Convert another?" prompt.
+        assert!(out.ends_with("Convert another? [Y/n] \n"));
+    }
+}
@@ -0,0 +1,53 @@
+//! A build with only the `length` category, as someone trimming the table
+//! for size would make: units of the other categories must be unknown, not
+//! a panic, and listings must leave their categories out.
+
+use std::process::{Command, Output};
+
+fn cargo(args: &[&str]) -> Output {
+    let output = Command::new(env!("CARGO"))
+        .args(args)
+        .args(["--quiet", "--no-default-features", "--features", "std,length"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .env("CARGO_TARGET_DIR", concat!(env!("CARGO_MANIFEST_DIR"), "/target/categories"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    output
+}
+
+#[test]
+fn test_length_only() {
+    cargo(&["test", "--lib"]);
+    cargo(&["build", "--bin", "converter"]);
+    let binary = concat!(env!("CARGO_MANIFEST_DIR"), "/target/categories/debug/converter");
+    // A home without a config, and English, as in tests/cli.rs.
+    let home = std::env::temp_dir().join(format!("converter-categories-{}", std::process::id()));
+    let run = |args: &[&str]| {
+        Command::new(binary)
+            .args(args)
+            .env("HOME", &home)
+            .env("XDG_CONFIG_HOME", &home)
+            .env("XDG_STATE_HOME", &home)
+            .env("LC_ALL", "C")
+            .env_remove("LC_MESSAGES")
+            .env_remove("LANG")
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&["1", "kg", "g"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown unit 'kg'"));
+
+    let output = run(&["1", "km", "m"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "1 km = 1000 m");
+
+    let listing = String::from_utf8(run(&["--list"]).stdout).unwrap();
+    assert!(listing.contains("Length:\n  km"), "{}", listing);
+    assert!(!listing.contains("Mass:"), "{}", listing);
+    let output = run(&["--list", "--category", "mass"]);
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("  "));
+}
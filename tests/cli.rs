@@ -0,0 +1,467 @@
+//! The binary end to end: what a shell user sees on stdout, stderr and in the
+//! exit code.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Runs the binary with a config and state directory that don't exist and an
+/// English locale, so the user's own setup can't change the output.
+fn run(args: &[&str]) -> Output {
+    run_in(&std::env::temp_dir().join(format!("converter-cli-{}", std::process::id())), args)
+}
+
+/// Runs the binary with `config` as its config file.
+fn run_with_config(name: &str, config: &str, args: &[&str]) -> Output {
+    let home = std::env::temp_dir().join(format!("converter-cli-{}-{}", std::process::id(), name));
+    std::fs::create_dir_all(home.join("converter")).unwrap();
+    std::fs::write(home.join("converter").join("config.toml"), config).unwrap();
+    let output = run_in(&home, args);
+    std::fs::remove_dir_all(&home).unwrap();
+    output
+}
+
+fn run_in(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_converter"))
+        .args(args)
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home)
+        .env("XDG_STATE_HOME", home)
+        .env("LC_ALL", "C")
+        .env_remove("LC_MESSAGES")
+        .env_remove("LANG")
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+#[cfg(all(feature = "length", feature = "mass", feature = "temperature"))]
+fn test_converts() {
+    let output = run(&["5", "km", "mi"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "5 km = 3.10685596118667 mi\n");
+    assert_eq!(stdout(&run(&["--precision", "1", "100", "C", "F"])), "100 C = 212.0 F\n");
+    assert_eq!(stdout(&run(&["1", "m", "cm"])), "1 m = 100 cm\n");
+    assert_eq!(stdout(&run(&["1", "g", "mg"])), "1 g = 1000 mg\n");
+}
+
+#[test]
+#[cfg(feature = "mass")]
+fn test_multi_word_units() {
+    assert_eq!(stdout(&run(&["5", "metric ton", "kg"])), "5 ton = 5000 kg\n");
+    assert_eq!(stdout(&run(&["5", "metric", "ton", "kg"])), "5 ton = 5000 kg\n");
+    assert_eq!(stdout(&run(&["5", "metric-ton", "kg"])), "5 ton = 5000 kg\n");
+    let output = run(&["5", "km", "mi", "furlong"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("Error: Expected 3 arguments, got 4\n"), "{}", stderr(&output));
+}
+
+#[test]
+#[cfg(all(feature = "length", feature = "time"))]
+fn test_units_print_as_symbols_unless_echoed() {
+    assert_eq!(stdout(&run(&["05.000", "KILOMETRES", "MILE"])), "5 km = 3.10685596118667 mi\n");
+    assert_eq!(stdout(&run(&["--echo-input", "05.000", "KILOMETRES", "MILE"])), "5 kilometers = 3.10685596118667 miles\n");
+    assert_eq!(stdout(&run(&["--echo-input", "36", "KM/H", "m/s"])), "36 KM/H = 10 m/s\n");
+}
+
+#[test]
+fn test_unknown_unit_fails() {
+    let output = run(&["5", "furlong", "mi"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).starts_with("Error: Unknown unit 'furlong'\n"), "{}", stderr(&output));
+}
+
+#[test]
+#[cfg(feature = "temperature")]
+fn test_typo_gets_a_suggestion() {
+    let output = run(&["20", "celcius", "F"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "Error: Unknown unit 'celcius'; did you mean 'C'?\n");
+}
+
+#[test]
+#[cfg(feature = "length")]
+fn test_non_finite_values_fail() {
+    for value in ["NaN", "inf", "-inf"] {
+        let output = run(&[value, "km", "mi"]);
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(stdout(&output), "");
+        assert_eq!(stderr(&output), format!("Error: '{}' is not allowed: the value must be a finite number\n", value));
+    }
+    let output = run(&["1e308", "km", "mm"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "Error: 1e308 km in mm: result too large to represent\n");
+}
+
+#[test]
+#[cfg(all(feature = "mass", feature = "temperature", feature = "time"))]
+fn test_strict_turns_warnings_into_errors() {
+    let output = run(&["-5", "kg", "lb"]);
+    assert!(output.status.success());
+    assert_eq!(stderr(&output), "Warning: Negative mass doesn't make physical sense\n");
+    let output = run(&["--strict", "-5", "kg", "lb"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(stderr(&output), "Error: Negative mass doesn't make physical sense\n");
+    let output = run(&["--strict", "-40", "C", "F"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "-40 C = -40 F\n");
+    let output = run(&["--strict", "1e-308", "mg/d", "ton/ms"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "Error: The result is too small for a double and came out as 0\n");
+}
+
+#[test]
+#[cfg(all(feature = "length", feature = "mass"))]
+fn test_category_mismatch_fails() {
+    let output = run(&["1", "kg", "m"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stderr(&output),
+        "Error: Cannot convert between different unit categories (M¹ vs L¹)\n  kg is a Mass unit\n  m is a Length unit\n"
+    );
+}
+
+#[test]
+#[cfg(all(feature = "length", feature = "mass", feature = "time", feature = "volume"))]
+fn test_mismatch_hint_and_via() {
+    let output = run(&["5", "kg", "L"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).ends_with("\n  L is a Volume unit\n  hint: convert through a density, e.g. water ≈ 1 g/mL\n"), "{}", stderr(&output));
+    let output = run(&["--via", "1g/mL", "5", "kg", "L"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "5 kg = 5 L\n");
+    let output = run(&["--via", "5km/h", "--json", "2", "h", "mi"]);
+    assert!(stdout(&output).starts_with("{\"value\":2,\"from\":\"h\",\"to\":\"mi\",\"result\":6.21371192237334,\"category\":\"length\","), "{}", stdout(&output));
+    let output = run(&["--via", "1g/mL", "5", "kg", "m"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).ends_with("  m is a Length unit\n"), "{}", stderr(&output));
+}
+
+#[test]
+#[cfg(feature = "length")]
+fn test_json_output() {
+    let output = run(&["--json", "5", "km", "mi"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "{\"value\":5,\"from\":\"km\",\"to\":\"mi\",\"result\":3.10685596118667,\"category\":\"length\",\"factor\":0.621371192237334,\"warnings\":[]}\n"
+    );
+}
+
+#[test]
+#[cfg(feature = "length")]
+fn test_json_and_daemon_share_the_record() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let output = run(&["--json", "-1", "m", "cm"]);
+    assert!(output.status.success());
+    assert_eq!(stderr(&output), "", "warnings ride along in the record");
+    let record = stdout(&output);
+
+    let mut daemon = Command::new(env!("CARGO_BIN_EXE_converter"))
+        .arg("--serve-stdio")
+        .env("LC_ALL", "C")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    daemon.stdin.take().unwrap().write_all(b"{\"value\":-1,\"from\":\"m\",\"to\":\"cm\"}\n").unwrap();
+    let response = stdout(&daemon.wait_with_output().unwrap());
+    assert_eq!(response.strip_prefix("{\"ok\":true,"), record.strip_prefix('{'));
+}
+
+#[test]
+#[cfg(all(feature = "length", feature = "mass"))]
+fn test_json_errors() {
+    let error = |args: &[&str]| {
+        let output = run(args);
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(stdout(&output), "");
+        converter::json::parse(&stderr(&output)).unwrap()
+    };
+    let text = |json: &converter::json::Json, key: &str| json.get(key).and_then(|v| v.as_str()).map(str::to_string);
+
+    let unknown = error(&["--json", "5", "kmz", "mi"]);
+    assert_eq!(unknown.get("ok"), Some(&converter::json::Json::from(false)));
+    assert_eq!(text(&unknown, "error").as_deref(), Some("unknown_unit"));
+    assert_eq!(text(&unknown, "message").as_deref(), Some("Unknown unit 'kmz'; did you mean 'km'?"));
+    assert_eq!(text(&unknown, "input").as_deref(), Some("kmz"));
+    let suggestions: Vec<&str> = unknown.get("suggestions").and_then(|s| s.as_array()).unwrap().iter().filter_map(|s| s.as_str()).collect();
+    assert_eq!(suggestions, ["km"]);
+
+    let mismatch = error(&["--json", "--lang", "de", "1", "kg", "m"]);
+    assert_eq!(text(&mismatch, "error").as_deref(), Some("category_mismatch"));
+    assert!(text(&mismatch, "message").unwrap().starts_with("Cannot convert between different unit categories"));
+    let fields = ["from", "to", "from_category", "to_category"].map(|key| text(&mismatch, key).unwrap());
+    assert_eq!(fields, ["kg", "m", "mass", "length"]);
+}
+
+#[test]
+fn test_lang_translates_errors() {
+    let output = run(&["--lang", "es", "5", "furlong", "mi"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("Error: Unidad desconocida 'furlong'\n"), "{}", stderr(&output));
+    let output = run(&["--lang", "de", "roman", "4000"]);
+    assert_eq!(stderr(&output), "Fehler: 4000 liegt außerhalb des Bereichs; römische Zahlen reichen von 1 bis 3999\n");
+    let output = run(&["--lang", "es", "base", "12a"]);
+    assert_eq!(stderr(&output), "Error: 'a' no es un dígito en base 10\n");
+    let output = run(&["--lang", "de", "paper", "a4", "--in", "letter"]);
+    assert!(stdout(&output).ends_with("\nA4 ist 5.9 mm schmaler und 17.6 mm höher als Letter\n"), "{}", stdout(&output));
+    let output = run(&["--lang", "es", "shoe", "9", "us-mens", "shoe"]);
+    assert_eq!(stderr(&output), "Error: sistema de tallas desconocido 'shoe'; usa us-mens, us-womens, uk, eu, cm\n");
+}
+
+#[test]
+#[cfg(feature = "temperature")]
+fn test_list_one_category() {
+    let output = run(&["--list", "--category", "temperature"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "Supported units:\n\nTemperature:\n  C (celsius, centigrade)\n  F (fahrenheit)\n  K (kelvin)\n\n");
+    let output = run(&["--list", "--category", "energy"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "Error: unknown category 'energy'; use length, temperature, mass, time, volume, speed, data, angle or currency\n");
+}
+
+#[test]
+#[cfg(feature = "length")]
+fn test_list_detailed() {
+    let output = run(&["--list", "--detailed"]);
+    assert!(output.status.success());
+    let listing = stdout(&output);
+    assert!(listing.contains("\n  mi  mile        1 mi = 1609.344 m\n"), "{}", listing);
+    let config = "[[unit]]\nname = \"furlong\"\ncategory = \"length\"\nfactor = 201.168\n";
+    let listing = stdout(&run_with_config("detailed", config, &["--list", "--category", "length", "--detailed"]));
+    assert!(listing.contains("  m        meter\n  furlong  furlong     1 furlong = 201.168 m\n  km "), "{}", listing);
+}
+
+#[test]
+#[cfg(all(feature = "length", feature = "mass", feature = "temperature", feature = "time", feature = "volume", feature = "speed", feature = "data", feature = "angle"))]
+fn test_every_example_converts() {
+    let output = run(&["--examples"]);
+    assert!(output.status.success());
+    let listing = stdout(&output);
+    let examples: Vec<&str> = listing.lines().filter_map(|line| line.trim().strip_prefix("converter ")).collect();
+    assert_eq!(examples.len(), 24, "{}", listing);
+    for example in examples {
+        let output = run(&example.split(' ').collect::<Vec<_>>());
+        assert!(output.status.success(), "{}: {}", example, stderr(&output));
+    }
+    let output = run(&["--examples", "mass"]);
+    assert_eq!(stdout(&output), "Mass:\n    converter 5 kg lb\n    converter 5 lb kg\n    converter 5 kilograms pounds\n\n");
+    assert_eq!(run(&["--examples", "energy"]).status.code(), Some(1));
+}
+
+#[test]
+#[cfg(all(feature = "length", feature = "temperature"))]
+fn test_info() {
+    let output = run(&["info", "kilometres"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("  Equals:   1 km = 0.621371192237334 mi = 1000 m\n"), "{}", stdout(&output));
+    let output = run(&["info", "F", "--json"]);
+    let info = converter::json::parse(&stdout(&output)).unwrap();
+    assert_eq!(info.get("name").and_then(|n| n.as_str()), Some("F"));
+    assert_eq!(info.get("offset").and_then(|o| o.as_f64()), Some(-32.0));
+    let output = run(&["info", "kmm"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "Error: Unknown unit 'kmm'; did you mean 'km' or 'mm'?\n");
+}
+
+#[test]
+#[cfg(feature = "length")]
+fn test_config_defines_units() {
+    let config = "[[unit]]\nname = \"furlong\"\naliases = [\"furlongs\"]\ncategory = \"length\"\nfactor = 201.168\nplural = \"furlongs\"\n";
+    let output = run_with_config("units", config, &["2", "furlongs", "m"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "2 furlong = 402.336 m\n");
+    let listing = stdout(&run_with_config("listing", config, &["--list", "--category", "length"]));
+    assert!(listing.ends_with("  in (inch, inches)\n  furlong (furlongs)\n\n"), "{}", listing);
+}
+
+#[test]
+#[cfg(feature = "temperature")]
+fn test_registered_scale_stops_at_absolute_zero() {
+    // Delisle counts down: 100 °C is 0 °De, and absolute zero is 559.725 °De.
+    let config = "[[unit]]\nname = \"De\"\ncategory = \"temperature\"\nfactor = -0.6666666666666666\noffset = -150\n";
+    let output = run_with_config("delisle", config, &["600", "De", "K"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("Error: 600 De is below absolute zero (559.725 De)\n"), "{}", stderr(&output));
+    let output = run_with_config("delisle-ok", config, &["559.725", "De", "K"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+}
+
+#[test]
+#[cfg(feature = "mass")]
+fn test_list_json() {
+    let output = run(&["--list", "--json", "--category", "mass"]);
+    assert!(output.status.success());
+    let listing = stdout(&output);
+    assert!(listing.starts_with("[{\"name\":\"kg\",\"aliases\":[\"kilogram\",\"kilograms\"],\"case_sensitive\":false,\"category\":\"mass\",\"factor\":1,"), "{}", listing);
+    assert_eq!(listing.matches("\"name\":").count(), 7);
+}
+
+#[test]
+#[cfg(all(feature = "length", feature = "mass", feature = "temperature", feature = "time", feature = "volume"))]
+fn test_compound_units() {
+    assert_eq!(stdout(&run(&["90", "km/h", "m/s"])), "90 km/h = 25 m/s\n");
+    assert_eq!(stdout(&run(&["--precision", "3", "1", "g/cm^3", "kg/m^3"])), "1 g/cm^3 = 1000.000 kg/m^3\n");
+    assert_eq!(stdout(&run(&["--precision", "4", "2", "m2", "ft2"])), "2 m^2 = 21.5278 ft^2\n");
+    assert_eq!(stdout(&run(&["1", "m3", "L"])), "1 m3 = 1000 L\n");
+    let output = run(&["1", "km/fortnight", "m/s"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("Error: Unknown unit 'fortnight'\n"), "{}", stderr(&output));
+    let output = run(&["1", "C/s", "F/s"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "Error: 'C/s' can't include C: temperatures and other units with an offset only convert on their own\n");
+}
+
+#[test]
+#[cfg(all(feature = "length", feature = "temperature", feature = "time", feature = "speed"))]
+fn test_mach_and_the_speed_of_light() {
+    assert_eq!(stdout(&run(&["0.5", "c", "km/s"])), "0.5 c = 149896.229 km/s\n");
+    assert_eq!(stdout(&run(&["2.5", "mach", "km/h"])), "2.5 mach = 3062.61 km/h\n");
+    assert_eq!(stdout(&run(&["1", "lightspeed", "mi/h"])), "1 c = 670616629.384395 mi/h\n");
+    // A walking pace is a tiny fraction of c, and c a huge number of mm/h.
+    assert_eq!(stdout(&run(&["5", "km/h", "c"])), "5 km/h = 4.63283465552989e-9 c\n");
+    assert_eq!(stdout(&run(&["1", "c", "mm/h"])), "1 c = 1.0792528488e15 mm/h\n");
+    let output = run(&["100", "c", "F"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("\n  c is a Speed unit; did you mean 'C' (Temperature)?\n"), "{}", stderr(&output));
+}
+
+#[test]
+#[cfg(feature = "volume")]
+fn test_system_decides_shared_volume_words() {
+    assert_eq!(stdout(&run(&["1", "gal-imp", "gal-us"])), "1 gal-imp = 1.20094992550485 gal-us\n");
+    assert_eq!(stdout(&run(&["--system", "us", "1", "gallon", "L"])), "1 gal-us = 3.785411784 L\n");
+    assert_eq!(stdout(&run(&["--system", "imperial", "2", "pints", "L"])), "2 pt-imp = 1.1365225 L\n");
+    assert_eq!(stdout(&run(&["--system", "imperial", "5", "fl", "oz", "mL"])), "5 floz-imp = 142.0653125 mL\n");
+    let output = run(&["1", "quart", "L"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("Error: 'quart' means a different amount in the US and imperial systems; use 'qt-us' or 'qt-imp', or choose a system with --system us|imperial"), "{}", stderr(&output));
+    let config = "system = \"imperial\"\n";
+    assert_eq!(stdout(&run_with_config("system", config, &["1", "gallon", "L"])), "1 gal-imp = 4.54609 L\n");
+    assert_eq!(stdout(&run_with_config("system", config, &["--system", "us", "1", "gallon", "L"])), "1 gal-us = 3.785411784 L\n");
+    assert!(stderr(&run(&["--system", "nautical", "1", "gallon", "L"])).contains("unknown system 'nautical'; use us, imperial or metric"));
+}
+
+#[test]
+#[cfg(all(feature = "length", feature = "time", feature = "speed"))]
+fn test_running_pace() {
+    assert_eq!(stdout(&run(&["4:30", "min/km", "min/mi"])), "4:30 min/km = 7:15 min/mi\n");
+    assert_eq!(stdout(&run(&["4:30", "min/km", "km/h"])), "4:30 min/km = 13.3333333333333 km/h\n");
+    assert_eq!(stdout(&run(&["7.5", "mi/h", "min/mi"])), "7.5 mi/h = 8:00 min/mi\n");
+    assert_eq!(stdout(&run(&["--json", "6:00", "min/km", "km/h"])), "{\"value\":6,\"from\":\"min/km\",\"to\":\"km/h\",\"result\":10,\"category\":\"speed\",\"factor\":null,\"warnings\":[]}\n");
+    let output = run(&["4:75", "min/km", "km/h"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("'4:75' is not a valid number"), "{}", stderr(&output));
+    assert_eq!(stdout(&run(&["4:30", "min/km", "mph"])), "4:30 min/km = 8.28494922983112 mi/h\n");
+    assert_eq!(stdout(&run(&["4:59.9", "min/km", "km/h"])), "4:59.9 min/km = 12.0040013337779 km/h\n");
+    let output = run(&["0.0", "mph", "min/mi"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "Error: 0.0 mph: a speed of zero has no pace\n");
+}
+
+#[test]
+#[cfg(feature = "time")]
+fn test_calendar_decides_year_and_month() {
+    assert_eq!(stdout(&run(&["1", "year", "d"])), "1 julian-year = 365.25 d\n");
+    assert_eq!(stdout(&run(&["--calendar", "gregorian", "1", "year", "d"])), "1 gregorian-year = 365.2425 d\n");
+    assert_eq!(stdout(&run(&["--calendar", "civil", "6", "months", "d"])), "6 civil-month = 182.5 d\n");
+    assert_eq!(stdout(&run(&["--calendar", "civil", "1", "gregorian-year", "d"])), "1 gregorian-year = 365.2425 d\n");
+    let config = "calendar = \"gregorian\"\n";
+    assert_eq!(stdout(&run_with_config("calendar", config, &["1", "month", "s"])), "1 gregorian-month = 2629746 s\n");
+    assert_eq!(stdout(&run_with_config("calendar", config, &["--calendar", "julian", "1", "month", "s"])), "1 julian-month = 2629800 s\n");
+    assert!(stderr(&run(&["--calendar", "lunar", "1", "year", "d"])).contains("unknown calendar 'lunar'; use julian, gregorian or civil"));
+}
+
+#[test]
+#[cfg(all(feature = "length", feature = "angle"))]
+fn test_degrees_minutes_and_seconds() {
+    assert_eq!(stdout(&run(&["1", "rad", "deg", "--dms"])), "1 rad = 57°17′44.81″\n");
+    assert_eq!(stdout(&run(&["--dms", "--precision", "0", "1", "rad", "deg"])), "1 rad = 57°17′45″\n");
+    assert_eq!(stdout(&run(&["45d30m15s", "deg", "arcsec"])), "45.50416666666667 deg = 163815 arcsec\n");
+    assert_eq!(stdout(&run(&["-12°30′", "deg", "arcmin", "--dms"])), "-12.5 deg = -12°30′00″\n");
+    let output = run(&["1", "km", "m", "--dms"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "Error: --dms writes angles; 'm' is not one\n");
+}
+
+#[test]
+#[cfg(feature = "data")]
+fn test_data_symbols_are_case_sensitive() {
+    let output = run(&["1", "MB", "Mb"]);
+    assert_eq!(stdout(&output), "1 MB = 8 Mb\n");
+    assert_eq!(stderr(&output), "Note: data symbols are case-sensitive: MB = megabyte, Mb = megabit\n");
+    assert_eq!(stdout(&run(&["2", "KiB", "Kib"])), "2 KiB = 16 Kib\n");
+    let output = run(&["1", "megabytes", "megabits"]);
+    assert_eq!(stdout(&output), "1 MB = 8 Mb\n");
+    assert_eq!(stderr(&output), "");
+    assert_eq!(stdout(&run(&["1", "MEGABYTES", "kilobits"])), "1 MB = 8000 kb\n");
+    assert!(stderr(&run(&["1", "mb", "kb"])).contains("Unknown unit 'mb'"));
+}
+
+#[test]
+#[cfg(all(feature = "length", feature = "volume", feature = "speed"))]
+fn test_search() {
+    let output = run(&["search", "meter"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "km (kilometer, kilometers, kilometre, kilometres)\nm (meter, meters, metre, metres)\n\
+         cm (centimeter, centimeters, centimetre, centimetres)\nmm (millimeter, millimeters, millimetre, millimetres)\n\
+         m3 (cubic meter, cubic meters, cubic metre, cubic metres)\n\
+         m/s (meter per second, meters per second, metre per second, metres per second)\n"
+    );
+    let output = run(&["search", "furlong"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "Error: no unit matches 'furlong'\n");
+}
+
+#[test]
+#[cfg(all(feature = "length", feature = "temperature"))]
+fn test_negative_values_are_not_flags() {
+    assert_eq!(stdout(&run(&["-40", "C", "F"])), "-40 C = -40 F\n");
+    let output = run(&["--", "-5", "km", "mi"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "-5 km = -3.10685596118667 mi\n");
+    let output = run(&["--", "-h"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("Error: Expected 3 arguments, got 1\n"), "{}", stderr(&output));
+}
+
+#[test]
+#[cfg(all(feature = "length", feature = "mass", feature = "temperature", feature = "time", feature = "volume", feature = "speed", feature = "data", feature = "angle"))]
+fn test_help() {
+    let output = run(&["--help"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("USAGE:"));
+    assert_eq!(stdout(&run(&["-h"])), stdout(&output));
+    assert!(stdout(&output).contains("only list this category:\n                         length, temperature, mass, time, volume, speed, data, angle\n"), "{}", stdout(&output));
+}
+
+#[test]
+fn test_currency_rates() {
+    let rates = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/rates/usd.toml");
+    let output = run(&["--rates", rates, "100", "USD", "EUR"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "100 USD = 93.42 EUR\n");
+    assert!(stderr(&output).starts_with(&format!("Note: rates from {} dated 2024-05-01, ", rates)), "{}", stderr(&output));
+    let output = run_with_config("rates", &format!("rates = \"{}\"\n", rates), &["100", "GBP", "JPY"]);
+    assert_eq!(stdout(&output), "100 GBP = 19747.22 JPY\n");
+    let output = run(&["--rates", "missing.toml", "100", "USD", "EUR"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "Error: rates file missing.toml not found\n");
+    let output = run(&["--rates", rates, "100", "USD", "CHF"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("Error: currency 'CHF' is not in the rates file; use 'USD', 'EUR', 'GBP' or 'JPY'"), "{}", stderr(&output));
+}
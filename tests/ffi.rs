@@ -0,0 +1,107 @@
+//! The C interface as a C program sees it: declared from the header's
+//! signatures and called through the C ABI.
+
+use std::ffi::{CStr, c_char};
+use std::ptr;
+
+use converter::ffi;
+
+unsafe extern "C" {
+    fn converter_convert(value: f64, from: *const c_char, to: *const c_char, out: *mut f64) -> i32;
+    fn converter_last_error_message() -> *const c_char;
+    fn converter_last_error_suggestions_json() -> *mut c_char;
+    fn converter_list_units_json() -> *mut c_char;
+    fn converter_free_string(text: *mut c_char);
+}
+
+fn convert(value: f64, from: &CStr, to: &CStr) -> (i32, f64) {
+    let mut out = f64::NAN;
+    let code = unsafe { converter_convert(value, from.as_ptr(), to.as_ptr(), &mut out) };
+    (code, out)
+}
+
+fn last_error() -> String {
+    let message = unsafe { converter_last_error_message() };
+    assert!(!message.is_null());
+    unsafe { CStr::from_ptr(message) }.to_str().unwrap().to_string()
+}
+
+fn take_string(text: *mut c_char) -> String {
+    let owned = unsafe { CStr::from_ptr(text) }.to_str().unwrap().to_string();
+    unsafe { converter_free_string(text) };
+    owned
+}
+
+#[test]
+#[cfg(all(feature = "length", feature = "temperature", feature = "time"))]
+fn test_converts() {
+    assert_eq!(convert(100.0, c"C", c"F"), (ffi::CONVERTER_OK, 212.0));
+    assert_eq!(convert(90.0, c"km/h", c"m/s"), (ffi::CONVERTER_OK, 25.0));
+}
+
+#[test]
+#[cfg(all(feature = "length", feature = "mass", feature = "temperature", feature = "time"))]
+fn test_errors_have_codes_and_messages() {
+    assert!(unsafe { converter_last_error_message() }.is_null());
+    let (code, out) = convert(1.0, c"celcius", c"F");
+    assert_eq!(code, ffi::CONVERTER_UNKNOWN_UNIT);
+    assert!(out.is_nan());
+    assert_eq!(last_error(), "Unknown unit 'celcius'; did you mean 'C'?");
+    assert_eq!(take_string(unsafe { converter_last_error_suggestions_json() }), r#"["C"]"#);
+    assert_eq!(convert(1.0, c"kg", c"m").0, ffi::CONVERTER_CATEGORY_MISMATCH);
+    assert!(last_error().starts_with("Cannot convert between different unit categories (M¹ vs L¹)\n"));
+    assert_eq!(take_string(unsafe { converter_last_error_suggestions_json() }), "[]");
+    assert_eq!(convert(0.0, c"km/h", c"s/m").0, ffi::CONVERTER_ZERO_SPEED);
+    assert_eq!(last_error(), "0 km/h: a speed of zero has no pace");
+}
+
+#[test]
+#[cfg(feature = "volume")]
+fn test_ambiguous_units_have_their_own_code() {
+    let (code, out) = convert(1.0, c"gallon", c"L");
+    assert_eq!(code, ffi::CONVERTER_AMBIGUOUS_UNIT);
+    assert!(out.is_nan());
+    assert!(last_error().starts_with("'gallon' means a different amount in the US and imperial systems; use 'gal-us' or 'gal-imp', "), "{}", last_error());
+    assert_eq!(convert(1.0, c"gal-us", c"L").0, ffi::CONVERTER_OK);
+}
+
+#[test]
+#[cfg(feature = "length")]
+fn test_wire_gauges_out_of_range_have_their_own_code() {
+    let (code, out) = convert(12.0, c"awg", c"mm");
+    assert_eq!(code, ffi::CONVERTER_OK);
+    assert!((out - 2.0525).abs() < 1e-4, "{}", out);
+    let (code, out) = convert(41.0, c"awg", c"mm");
+    assert_eq!(code, ffi::CONVERTER_NO_GAUGE);
+    assert!(out.is_nan());
+    assert_eq!(last_error(), "'41' is not a wire gauge from 4/0 to 40");
+    assert_eq!(convert(20.0, c"mm", c"awg").0, ffi::CONVERTER_NO_GAUGE);
+}
+
+#[test]
+fn test_currencies_have_their_own_code() {
+    let (code, out) = convert(1.0, c"CHF", c"EUR");
+    assert_eq!(code, ffi::CONVERTER_UNKNOWN_CURRENCY);
+    assert!(out.is_nan());
+    assert!(last_error().starts_with("'CHF' looks like a currency code, but no rates are loaded"), "{}", last_error());
+    assert_eq!(take_string(unsafe { converter_last_error_suggestions_json() }), "[]");
+}
+
+#[test]
+fn test_bad_pointers_and_text_are_refused() {
+    let mut out = 0.0;
+    assert_eq!(unsafe { converter_convert(1.0, ptr::null(), c"m".as_ptr(), &mut out) }, ffi::CONVERTER_NULL_POINTER);
+    assert_eq!(last_error(), "'from' is a null pointer");
+    assert_eq!(unsafe { converter_convert(1.0, c"km".as_ptr(), c"m".as_ptr(), ptr::null_mut()) }, ffi::CONVERTER_NULL_POINTER);
+    assert_eq!(convert(1.0, c"km", c"\xff\xfe").0, ffi::CONVERTER_INVALID_UTF8);
+    assert_eq!(last_error(), "'to' is not valid UTF-8");
+    unsafe { converter_free_string(ptr::null_mut()) };
+}
+
+#[test]
+fn test_list_units_json() {
+    let text = take_string(unsafe { converter_list_units_json() });
+    #[cfg(feature = "length")]
+    assert!(text.starts_with("[{\"name\":\"km\","), "{}", text);
+    assert_eq!(text.matches("\"name\":").count(), converter::UNITS.len());
+}
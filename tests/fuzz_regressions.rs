@@ -0,0 +1,51 @@
+//! The fuzz targets' properties, replayed on their seed corpus and on any
+//! input that once broke them (fuzz/regressions), so they hold without
+//! cargo-fuzz too.
+
+use std::path::Path;
+
+use converter::UNITS;
+
+/// Everything fuzz/fuzz_targets/parse.rs does with `input`; a panic fails the test.
+fn parse(input: &str) {
+    let _ = converter::Quantity::parse(input);
+    let _ = converter::find_unit(input);
+    let _ = converter::duration::parse(input);
+    if let Some(ratio) = converter::rational::Ratio::parse_decimal(input) {
+        let _ = ratio.to_decimal();
+    }
+    let _ = converter::convert_checked(input, "km", "mi");
+    let _ = converter::convert_delta(input, "C", "F");
+    let _ = converter::convert_checked("1", input, "m");
+}
+
+fn inputs(dir: &str) -> Vec<String> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz").join(dir);
+    let Ok(entries) = std::fs::read_dir(&dir) else { return Vec::new() };
+    let paths = entries.map(|e| e.unwrap().path()).filter(|path| path.extension().is_none_or(|ext| ext != "md"));
+    paths.map(|path| std::fs::read(path).unwrap()).map(|bytes| String::from_utf8_lossy(&bytes).into_owned()).collect()
+}
+
+#[test]
+fn test_parse_corpus_and_regressions() {
+    let corpus = inputs("corpus/parse");
+    assert!(corpus.len() >= 10, "the seed corpus is missing");
+    for input in corpus.iter().chain(&inputs("regressions")) {
+        parse(input);
+    }
+}
+
+#[test]
+fn test_conversions_are_finite() {
+    let values = [0.0, -0.0, 1.0, -1.0, -459.67, -273.15, 1e300, -1e300, 5e-324, f64::MAX, f64::MIN, f64::INFINITY, f64::NEG_INFINITY];
+    for value in values {
+        for from in UNITS {
+            for to in UNITS {
+                if let Ok(result) = converter::convert(value, from.name, to.name) {
+                    assert!(result.is_finite(), "{} {} is {} in {}", value, from.name, result, to.name);
+                }
+            }
+        }
+    }
+    assert!(converter::convert(f64::NAN, "km", "mi").is_err());
+}
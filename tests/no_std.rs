@@ -0,0 +1,17 @@
+//! The library without its default `std` feature, as embedded users build it:
+//! it must still compile as `no_std`, here with only the `length` units.
+//! `cargo test --lib --no-default-features --features full` runs the unit
+//! tests that cover what is left.
+
+use std::process::Command;
+
+#[test]
+fn test_builds_without_std() {
+    let output = Command::new(env!("CARGO"))
+        .args(["build", "--lib", "--no-default-features", "--features", "length", "--quiet"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .env("CARGO_TARGET_DIR", concat!(env!("CARGO_MANIFEST_DIR"), "/target/no-std"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
@@ -0,0 +1,37 @@
+//! The JavaScript exports as a page sees them. These run on wasm32 only:
+//! `wasm-pack test --node -- --features wasm`.
+
+#![cfg(target_arch = "wasm32")]
+
+use converter::wasm::{convert, list_units, suggest};
+use js_sys::{Array, Error, Reflect};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn test_convert() {
+    assert_eq!(convert(100.0, "C", "F").unwrap(), 212.0);
+    assert_eq!(convert(5.0, "km", "m").unwrap(), 5000.0);
+}
+
+#[wasm_bindgen_test]
+fn test_errors_are_thrown_as_errors() {
+    let error: Error = convert(1.0, "celcius", "F").unwrap_err().dyn_into().expect("an Error");
+    assert_eq!(String::from(error.message()), "Unknown unit 'celcius'; did you mean 'C'?");
+    let error: Error = convert(1.0, "kg", "m").unwrap_err().dyn_into().expect("an Error");
+    assert!(String::from(error.message()).starts_with("Cannot convert between different unit categories"));
+}
+
+#[wasm_bindgen_test]
+fn test_list_units() {
+    let units: Array = list_units().dyn_into().expect("an array");
+    assert_eq!(units.length() as usize, converter::UNITS.len());
+    let name = Reflect::get(&units.get(0), &"name".into()).unwrap();
+    assert_eq!(name.as_string().as_deref(), Some("km"));
+}
+
+#[wasm_bindgen_test]
+fn test_suggest() {
+    assert_eq!(suggest("kmz"), ["km"]);
+    assert!(suggest("x").is_empty());
+}